@@ -1,13 +1,17 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
-use crate::{compile_error::CompileError, typesystem::DataTypeInformation};
+use crate::{compile_error::CompileError, index::Index, typesystem::DataTypeInformation};
 use std::{
+    collections::HashMap,
     fmt::{Debug, Display, Formatter, Result},
     iter,
     ops::Range,
     result, unimplemented,
 };
+mod namespace_resolver;
 mod pre_processor;
 
+pub use namespace_resolver::{collect_declarations, resolve_namespaces};
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Dimension {
     pub start_offset: i32,
@@ -30,9 +34,13 @@ pub struct Pou {
     pub return_type: Option<DataTypeDeclaration>,
     pub location: SourceRange,
     pub poly_mode: Option<PolymorphismMode>,
+    /// the name of the POU this POU extends (`EXTENDS`), if any
+    pub super_class: Option<String>,
+    /// the names of the interfaces this POU implements (`IMPLEMENTS`)
+    pub interfaces: Vec<String>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PolymorphismMode {
     None,
     Abstract,
@@ -49,18 +57,23 @@ pub enum DirectAccess {
 
 impl DirectAccess {
     /// Returns true if the current index is in the range for the given type
-    pub fn is_in_range(&self, index: u32, data_type: &DataTypeInformation) -> bool {
-        self.to_bits(index) < data_type.get_size()
+    pub fn is_in_range(
+        &self,
+        access_index: u32,
+        data_type: &DataTypeInformation,
+        index: &Index,
+    ) -> bool {
+        self.to_bits(access_index) < data_type.get_size(index)
     }
 
     /// Returns the range from 0 for the given data type
-    pub fn get_range(&self, data_type: &DataTypeInformation) -> Range<u32> {
-        0..((data_type.get_size() / self.get_bit_witdh()) - 1)
+    pub fn get_range(&self, data_type: &DataTypeInformation, index: &Index) -> Range<u32> {
+        0..((data_type.get_size(index) / self.get_bit_witdh()) - 1)
     }
 
     /// Returns true if the direct access can be used for the given type
-    pub fn is_compatible(&self, data_type: &DataTypeInformation) -> bool {
-        data_type.get_size() > self.get_bit_witdh()
+    pub fn is_compatible(&self, data_type: &DataTypeInformation, index: &Index) -> bool {
+        data_type.get_size(index) > self.get_bit_witdh()
     }
 
     /// Returns the size of the bitaccess result
@@ -79,6 +92,94 @@ impl DirectAccess {
     }
 }
 
+/// the process image a located variable is mapped to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HardwareArea {
+    /// `%I`
+    Input,
+    /// `%Q`
+    Output,
+    /// `%M`
+    Memory,
+}
+
+/// the address of a located variable (e.g. `%IX0.3` or `%QW10`)
+///
+/// bits are addressed with `<byte>.<bit>`, all other sizes with the offset of their first byte
+#[derive(Clone, Debug, PartialEq)]
+pub struct HardwareAddress {
+    pub area: HardwareArea,
+    pub access: DirectAccess,
+    pub address: Vec<u32>,
+    pub location: SourceRange,
+}
+
+impl HardwareAddress {
+    /// returns the name of a located variable that was declared without a name
+    /// (e.g. `__QW10` for `AT %QW10 : WORD`)
+    pub fn get_variable_name(&self) -> String {
+        let address = self
+            .address
+            .iter()
+            .map(|it| it.to_string())
+            .collect::<Vec<_>>();
+        format!(
+            "__{}{}{}",
+            self.get_area_prefix(),
+            self.get_access_prefix(),
+            address.join("_")
+        )
+    }
+
+    /// returns the bits the address occupies in its process image or None if the address
+    /// is malformed (e.g. a bit without its byte)
+    pub fn get_bit_range(&self) -> Option<Range<u32>> {
+        let start = match (&self.access, self.address.as_slice()) {
+            (DirectAccess::Bit, [byte, bit]) if *bit < 8 => {
+                byte.checked_mul(8)?.checked_add(*bit)?
+            }
+            (DirectAccess::Bit, _) => return None,
+            (_, [byte]) => byte.checked_mul(8)?,
+            _ => return None,
+        };
+        Some(start..start.checked_add(self.access.get_bit_witdh())?)
+    }
+
+    fn get_area_prefix(&self) -> char {
+        match self.area {
+            HardwareArea::Input => 'I',
+            HardwareArea::Output => 'Q',
+            HardwareArea::Memory => 'M',
+        }
+    }
+
+    fn get_access_prefix(&self) -> char {
+        match self.access {
+            DirectAccess::Bit => 'X',
+            DirectAccess::Byte => 'B',
+            DirectAccess::Word => 'W',
+            DirectAccess::DWord => 'D',
+        }
+    }
+}
+
+impl Display for HardwareAddress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let address = self
+            .address
+            .iter()
+            .map(|it| it.to_string())
+            .collect::<Vec<_>>();
+        write!(
+            f,
+            "%{}{}{}",
+            self.get_area_prefix(),
+            self.get_access_prefix(),
+            address.join(".")
+        )
+    }
+}
+
 impl Debug for Pou {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         f.debug_struct("POU")
@@ -96,10 +197,27 @@ impl Pou {
     }
 
     pub fn calc_return_name(pou_name: &str) -> &str {
-        pou_name.split('.').last().unwrap_or_default()
+        let name = pou_name.split('.').last().unwrap_or_default();
+        // a property's getter returns its value through the property's name
+        name.strip_prefix(PROPERTY_GETTER_PREFIX).unwrap_or(name)
     }
 }
 
+/// the prefix of the method that reads a property (e.g. `__get_x` for property `x`)
+pub const PROPERTY_GETTER_PREFIX: &str = "__get_";
+/// the prefix of the method that writes a property (e.g. `__set_x` for property `x`)
+pub const PROPERTY_SETTER_PREFIX: &str = "__set_";
+
+/// returns the name of the method that reads the given property
+pub fn get_property_getter_name(property: &str) -> String {
+    format!("{}{}", PROPERTY_GETTER_PREFIX, property)
+}
+
+/// returns the name of the method that writes the given property
+pub fn get_property_setter_name(property: &str) -> String {
+    format!("{}{}", PROPERTY_SETTER_PREFIX, property)
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Implementation {
     pub name: String,
@@ -118,7 +236,7 @@ pub enum LinkageType {
     External,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum AccessModifier {
     Private,
     Public,
@@ -133,6 +251,7 @@ pub enum PouType {
     FunctionBlock,
     Action,
     Class,
+    Interface,
     Method { owner_class: String },
 }
 
@@ -153,6 +272,31 @@ pub struct CompilationUnit {
     pub units: Vec<Pou>,
     pub implementations: Vec<Implementation>,
     pub types: Vec<UserTypeDeclaration>,
+    pub configurations: Vec<Configuration>,
+    /// the namespace scopes of all declarations that were declared inside a `NAMESPACE` or
+    /// that import namespaces with `USING`, keyed by their lowercase qualified name
+    pub namespace_scopes: HashMap<String, NamespaceScope>,
+}
+
+/// the namespace a declaration was declared in and the namespaces it imports
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NamespaceScope {
+    /// the qualified name of the enclosing namespace (e.g. `A.B`), if any
+    pub namespace: Option<String>,
+    /// the qualified names of the namespaces imported with `USING`
+    pub usings: Vec<String>,
+}
+
+impl NamespaceScope {
+    /// returns true if this scope neither declares a namespace nor imports one
+    pub fn is_global(&self) -> bool {
+        self.namespace.is_none() && self.usings.is_empty()
+    }
+}
+
+/// returns the given name qualified with the given namespace (e.g. `A.B.MyFb`)
+pub fn get_qualified_name(namespace: &str, name: &str) -> String {
+    format!("{}.{}", namespace, name)
 }
 
 impl CompilationUnit {
@@ -167,6 +311,8 @@ impl CompilationUnit {
         self.units.extend(other.units);
         self.implementations.extend(other.implementations);
         self.types.extend(other.types);
+        self.configurations.extend(other.configurations);
+        self.namespace_scopes.extend(other.namespace_scopes);
     }
 }
 
@@ -177,10 +323,52 @@ impl Default for CompilationUnit {
             units: Vec::new(),
             implementations: Vec::new(),
             types: Vec::new(),
+            configurations: Vec::new(),
+            namespace_scopes: HashMap::new(),
         }
     }
 }
 
+/// a `CONFIGURATION` with the resources it consists of
+#[derive(Debug, PartialEq)]
+pub struct Configuration {
+    pub name: String,
+    pub resources: Vec<Resource>,
+    pub location: SourceRange,
+}
+
+/// a `RESOURCE` with its tasks and the program instances they execute
+#[derive(Debug, PartialEq)]
+pub struct Resource {
+    pub name: String,
+    /// the processing unit the resource runs on (`ON PLC`)
+    pub processing_unit: Option<String>,
+    pub tasks: Vec<Task>,
+    pub programs: Vec<ProgramInstance>,
+    pub location: SourceRange,
+}
+
+/// a `TASK name(INTERVAL := ..., PRIORITY := ...)` declaration
+#[derive(Debug, PartialEq)]
+pub struct Task {
+    pub name: String,
+    /// the time between two cycles of the task
+    pub interval: Option<AstStatement>,
+    pub priority: Option<AstStatement>,
+    pub location: SourceRange,
+}
+
+/// a `PROGRAM inst WITH task : prg` declaration
+#[derive(Debug, PartialEq)]
+pub struct ProgramInstance {
+    pub name: String,
+    /// the task that executes the program, if any
+    pub task: Option<String>,
+    /// the name of the instantiated program
+    pub program: String,
+    pub location: SourceRange,
+}
+
 #[derive(Debug, Copy, PartialEq, Clone)]
 pub enum VariableBlockType {
     Local,
@@ -189,6 +377,8 @@ pub enum VariableBlockType {
     Output,
     Global,
     InOut,
+    /// references a variable declared in a `VAR_GLOBAL` block
+    External,
 }
 
 #[derive(PartialEq)]
@@ -214,23 +404,24 @@ pub struct Variable {
     pub name: String,
     pub data_type: DataTypeDeclaration,
     pub initializer: Option<AstStatement>,
+    /// the address of a located variable (`x AT %IX0.3 : BOOL`)
+    pub address: Option<HardwareAddress>,
     pub location: SourceRange,
 }
 
 impl Debug for Variable {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut variable = f.debug_struct("Variable");
+        variable
+            .field("name", &self.name)
+            .field("data_type", &self.data_type);
         if self.initializer.is_some() {
-            f.debug_struct("Variable")
-                .field("name", &self.name)
-                .field("data_type", &self.data_type)
-                .field("initializer", &self.initializer)
-                .finish()
-        } else {
-            f.debug_struct("Variable")
-                .field("name", &self.name)
-                .field("data_type", &self.data_type)
-                .finish()
+            variable.field("initializer", &self.initializer);
         }
+        if let Some(address) = &self.address {
+            variable.field("address", &address.to_string());
+        }
+        variable.finish()
     }
 }
 
@@ -353,9 +544,18 @@ pub enum DataType {
         name: Option<String>, //maybe None for inline structs
         variables: Vec<Variable>,
     },
+    /// a union's members share the same memory, they all start at offset 0
+    UnionType {
+        name: Option<String>, //maybe None for inline unions
+        variables: Vec<Variable>,
+    },
     EnumType {
         name: Option<String>, //maybe empty for inline enums
+        numeric_type: String,
         elements: Vec<String>,
+        /// the explicitly assigned values of the elements (e.g. `Run := 10`),
+        /// an element without a value is one greater than its predecessor
+        values: Vec<Option<AstStatement>>,
     },
     SubRangeType {
         name: Option<String>,
@@ -367,9 +567,17 @@ pub enum DataType {
         bounds: AstStatement,
         referenced_type: Box<DataTypeDeclaration>,
     },
+    /// an array with unknown bounds (e.g. `ARRAY[*, *] OF INT`), only allowed for parameters
+    VariableLengthArrayType {
+        name: Option<String>,
+        dimensions: usize,
+        referenced_type: Box<DataTypeDeclaration>,
+    },
     PointerType {
         name: Option<String>,
         referenced_type: Box<DataTypeDeclaration>,
+        is_ref_to: bool,  //REF_TO does not support pointer arithmetic
+        auto_deref: bool, //REFERENCE TO
     },
     StringType {
         name: Option<String>,
@@ -389,10 +597,22 @@ impl Debug for DataType {
                 .field("name", name)
                 .field("variables", variables)
                 .finish(),
-            DataType::EnumType { name, elements } => f
+            DataType::UnionType { name, variables } => f
+                .debug_struct("UnionType")
+                .field("name", name)
+                .field("variables", variables)
+                .finish(),
+            DataType::EnumType {
+                name,
+                numeric_type,
+                elements,
+                values,
+            } => f
                 .debug_struct("EnumType")
                 .field("name", name)
+                .field("numeric_type", numeric_type)
                 .field("elements", elements)
+                .field("values", values)
                 .finish(),
             DataType::SubRangeType {
                 name,
@@ -414,13 +634,27 @@ impl Debug for DataType {
                 .field("bounds", bounds)
                 .field("referenced_type", referenced_type)
                 .finish(),
+            DataType::VariableLengthArrayType {
+                name,
+                dimensions,
+                referenced_type,
+            } => f
+                .debug_struct("VariableLengthArrayType")
+                .field("name", name)
+                .field("dimensions", dimensions)
+                .field("referenced_type", referenced_type)
+                .finish(),
             DataType::PointerType {
                 name,
                 referenced_type,
+                is_ref_to,
+                auto_deref,
             } => f
                 .debug_struct("PointerType")
                 .field("name", name)
                 .field("referenced_type", referenced_type)
+                .field("is_ref_to", is_ref_to)
+                .field("auto_deref", auto_deref)
                 .finish(),
             DataType::StringType {
                 name,
@@ -444,9 +678,11 @@ impl DataType {
     pub fn set_name(&mut self, new_name: String) {
         match self {
             DataType::StructType { name, variables: _ } => *name = Some(new_name),
-            DataType::EnumType { name, elements: _ } => *name = Some(new_name),
+            DataType::UnionType { name, .. } => *name = Some(new_name),
+            DataType::EnumType { name, .. } => *name = Some(new_name),
             DataType::SubRangeType { name, .. } => *name = Some(new_name),
             DataType::ArrayType { name, .. } => *name = Some(new_name),
+            DataType::VariableLengthArrayType { name, .. } => *name = Some(new_name),
             DataType::PointerType { name, .. } => *name = Some(new_name),
             DataType::StringType { name, .. } => *name = Some(new_name),
             DataType::VarArgs { .. } => {} //No names on varargs
@@ -456,8 +692,10 @@ impl DataType {
     pub fn get_name(&self) -> Option<&str> {
         match self {
             DataType::StructType { name, variables: _ } => name.as_ref().map(|x| x.as_str()),
-            DataType::EnumType { name, elements: _ } => name.as_ref().map(|x| x.as_str()),
+            DataType::UnionType { name, .. } => name.as_ref().map(|x| x.as_str()),
+            DataType::EnumType { name, .. } => name.as_ref().map(|x| x.as_str()),
             DataType::ArrayType { name, .. } => name.as_ref().map(|x| x.as_str()),
+            DataType::VariableLengthArrayType { name, .. } => name.as_ref().map(|x| x.as_str()),
             DataType::PointerType { name, .. } => name.as_ref().map(|x| x.as_str()),
             DataType::StringType { name, .. } => name.as_ref().map(|x| x.as_str()),
             DataType::SubRangeType { name, .. } => name.as_ref().map(|x| x.as_str()),
@@ -474,6 +712,9 @@ impl DataType {
         match self {
             DataType::ArrayType {
                 referenced_type, ..
+            }
+            | DataType::VariableLengthArrayType {
+                referenced_type, ..
             } => replace_reference(referenced_type, type_name, location),
             DataType::PointerType {
                 referenced_type, ..
@@ -530,6 +771,7 @@ pub enum AstStatement {
         year: i32,
         month: u32,
         day: u32,
+        is_long: bool,
         location: SourceRange,
         id: AstId,
     },
@@ -540,7 +782,8 @@ pub enum AstStatement {
         hour: u32,
         min: u32,
         sec: u32,
-        milli: u32,
+        nano: u32,
+        is_long: bool,
         location: SourceRange,
         id: AstId,
     },
@@ -548,7 +791,8 @@ pub enum AstStatement {
         hour: u32,
         min: u32,
         sec: u32,
-        milli: u32,
+        nano: u32,
+        is_long: bool,
         location: SourceRange,
         id: AstId,
     },
@@ -561,6 +805,7 @@ pub enum AstStatement {
         micro: f64,
         nano: u32,
         negative: bool,
+        is_long: bool,
         location: SourceRange,
         id: AstId,
     },
@@ -655,6 +900,12 @@ pub enum AstStatement {
         right: Box<AstStatement>,
         id: AstId,
     },
+    // ReferenceAssignment (REF=), rebinds a REFERENCE TO variable
+    ReferenceAssignment {
+        left: Box<AstStatement>,
+        right: Box<AstStatement>,
+        id: AstId,
+    },
     //Call Statement
     CallStatement {
         operator: Box<AstStatement>,
@@ -729,12 +980,17 @@ impl Debug for AstStatement {
                 .field("value", value)
                 .finish(),
             AstStatement::LiteralDate {
-                year, month, day, ..
+                year,
+                month,
+                day,
+                is_long,
+                ..
             } => f
                 .debug_struct("LiteralDate")
                 .field("year", year)
                 .field("month", month)
                 .field("day", day)
+                .field("is_long", is_long)
                 .finish(),
             AstStatement::LiteralDateAndTime {
                 year,
@@ -743,7 +999,8 @@ impl Debug for AstStatement {
                 hour,
                 min,
                 sec,
-                milli,
+                nano,
+                is_long,
                 ..
             } => f
                 .debug_struct("LiteralDateAndTime")
@@ -753,20 +1010,23 @@ impl Debug for AstStatement {
                 .field("hour", hour)
                 .field("min", min)
                 .field("sec", sec)
-                .field("milli", milli)
+                .field("nano", nano)
+                .field("is_long", is_long)
                 .finish(),
             AstStatement::LiteralTimeOfDay {
                 hour,
                 min,
                 sec,
-                milli,
+                nano,
+                is_long,
                 ..
             } => f
                 .debug_struct("LiteralTimeOfDay")
                 .field("hour", hour)
                 .field("min", min)
                 .field("sec", sec)
-                .field("milli", milli)
+                .field("nano", nano)
+                .field("is_long", is_long)
                 .finish(),
             AstStatement::LiteralTime {
                 day,
@@ -777,6 +1037,7 @@ impl Debug for AstStatement {
                 micro,
                 nano,
                 negative,
+                is_long,
                 ..
             } => f
                 .debug_struct("LiteralTime")
@@ -788,6 +1049,7 @@ impl Debug for AstStatement {
                 .field("micro", micro)
                 .field("nano", nano)
                 .field("negative", negative)
+                .field("is_long", is_long)
                 .finish(),
             AstStatement::LiteralReal { value, .. } => {
                 f.debug_struct("LiteralReal").field("value", value).finish()
@@ -848,6 +1110,11 @@ impl Debug for AstStatement {
                 .field("left", left)
                 .field("right", right)
                 .finish(),
+            AstStatement::ReferenceAssignment { left, right, .. } => f
+                .debug_struct("ReferenceAssignment")
+                .field("left", left)
+                .field("right", right)
+                .finish(),
             AstStatement::CallStatement {
                 operator,
                 parameters,
@@ -1009,6 +1276,11 @@ impl AstStatement {
                 let right_loc = right.get_location();
                 SourceRange::new(left_loc.range.start..right_loc.range.end)
             }
+            AstStatement::ReferenceAssignment { left, right, .. } => {
+                let left_loc = left.get_location();
+                let right_loc = right.get_location();
+                SourceRange::new(left_loc.range.start..right_loc.range.end)
+            }
             AstStatement::CallStatement { location, .. } => location.clone(),
             AstStatement::IfStatement { location, .. } => location.clone(),
             AstStatement::ForLoopStatement { location, .. } => location.clone(),
@@ -1058,6 +1330,7 @@ impl AstStatement {
             AstStatement::RangeStatement { id, .. } => *id,
             AstStatement::Assignment { id, .. } => *id,
             AstStatement::OutputAssignment { id, .. } => *id,
+            AstStatement::ReferenceAssignment { id, .. } => *id,
             AstStatement::CallStatement { id, .. } => *id,
             AstStatement::IfStatement { id, .. } => *id,
             AstStatement::ForLoopStatement { id, .. } => *id,
@@ -1078,6 +1351,7 @@ pub enum Operator {
     Plus,
     Minus,
     Multiplication,
+    Exponentiation,
     Division,
     Equal,
     NotEqual,
@@ -1099,9 +1373,20 @@ impl Display for Operator {
             Operator::Plus => "+",
             Operator::Minus => "-",
             Operator::Multiplication => "*",
+            Operator::Exponentiation => "**",
             Operator::Division => "/",
             Operator::Equal => "=",
-            _ => unimplemented!(),
+            Operator::NotEqual => "<>",
+            Operator::Modulo => "MOD",
+            Operator::Less => "<",
+            Operator::Greater => ">",
+            Operator::LessOrEqual => "<=",
+            Operator::GreaterOrEqual => ">=",
+            Operator::Not => "NOT",
+            Operator::And => "AND",
+            Operator::Or => "OR",
+            Operator::Xor => "XOR",
+            Operator::Address => "&",
         };
         f.write_str(symbol)
     }
@@ -0,0 +1,549 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+
+//! Declarations inside a `NAMESPACE` are qualified with the namespace's name by the parser
+//! (e.g. `A.B.MyFb`). This module rewrites all references to such declarations so they
+//! use the qualified name as well. A name is looked up in the following order:
+//! 1. in the enclosing namespaces from the innermost to the outermost one
+//! 2. as a global declaration or in one of the namespaces imported with `USING`.
+//!    If more than one of these candidates exists, the reference is ambiguous.
+use std::collections::{HashMap, HashSet};
+
+use crate::Diagnostic;
+
+use super::{
+    get_qualified_name, AstStatement, CompilationUnit, DataType, DataTypeDeclaration,
+    NamespaceScope, Pou, PouType, SourceRange, Variable, VariableBlockType,
+};
+
+/// the qualified names of all POUs, types and global variables that may be referenced
+pub struct NamespaceDeclarations {
+    /// maps the lowercase qualified names to the names as they were declared
+    declarations: HashMap<String, String>,
+}
+
+/// collects the declarations of all given units
+pub fn collect_declarations<'a>(
+    units: impl Iterator<Item = &'a CompilationUnit>,
+) -> NamespaceDeclarations {
+    let mut declarations = HashMap::new();
+    for unit in units {
+        let pous = unit
+            .units
+            .iter()
+            .filter(|it| !matches!(it.pou_type, PouType::Method { .. } | PouType::Action))
+            .map(|it| it.name.as_str());
+        let types = unit.types.iter().filter_map(|it| it.data_type.get_name());
+        let globals = unit
+            .global_vars
+            .iter()
+            .flat_map(|it| it.variables.iter())
+            .map(|it| it.name.as_str());
+
+        for name in pous.chain(types).chain(globals) {
+            declarations.insert(name.to_lowercase(), name.to_string());
+        }
+    }
+    NamespaceDeclarations { declarations }
+}
+
+/// qualifies all references to namespace declarations in the given unit and returns
+/// the diagnostics for ambiguous references
+pub fn resolve_namespaces(
+    unit: &mut CompilationUnit,
+    declarations: &NamespaceDeclarations,
+) -> Vec<Diagnostic> {
+    let mut resolver = NamespaceResolver {
+        declarations,
+        diagnostics: vec![],
+    };
+    let scopes = &unit.namespace_scopes;
+    let global_scope = NamespaceScope::default();
+    let get_scope = |name: &str| scopes.get(&name.to_lowercase()).unwrap_or(&global_scope);
+    let no_locals = HashSet::new();
+
+    //the local variables of all POUs shadow declarations of the same name
+    let locals: HashMap<String, HashSet<String>> = unit
+        .units
+        .iter()
+        .map(|it| (it.name.to_lowercase(), get_local_names(it)))
+        .collect();
+
+    for pou in unit.units.iter_mut() {
+        let scope = get_scope(&pou.name);
+        let pou_locals = locals.get(&pou.name.to_lowercase()).unwrap_or(&no_locals);
+        let location = pou.location.clone();
+        if let Some(super_class) = pou.super_class.as_mut() {
+            resolver.resolve_name(super_class, scope, &location);
+        }
+        for interface in pou.interfaces.iter_mut() {
+            resolver.resolve_name(interface, scope, &location);
+        }
+        if let Some(return_type) = pou.return_type.as_mut() {
+            resolver.resolve_data_type_declaration(return_type, scope);
+        }
+        for block in pou.variable_blocks.iter_mut() {
+            let is_external = block.variable_block_type == VariableBlockType::External;
+            for variable in block.variables.iter_mut() {
+                if is_external {
+                    //an external variable refers to a global variable
+                    resolver.resolve_name(&mut variable.name, scope, &variable.location);
+                }
+                resolver.resolve_variable(variable, scope, &[pou_locals]);
+            }
+        }
+    }
+
+    for implementation in unit.implementations.iter_mut() {
+        let scope = get_scope(&implementation.name);
+        let mut implementation_locals = vec![];
+        implementation_locals.extend(locals.get(&implementation.type_name.to_lowercase()));
+        if let PouType::Method { owner_class } = &implementation.pou_type {
+            implementation_locals.extend(locals.get(&owner_class.to_lowercase()));
+        }
+        for statement in implementation.statements.iter_mut() {
+            resolver.resolve_statement(statement, scope, &implementation_locals);
+        }
+    }
+
+    for user_type in unit.types.iter_mut() {
+        let scope = get_scope(user_type.data_type.get_name().unwrap_or_default());
+        resolver.resolve_data_type(&mut user_type.data_type, scope);
+        if let Some(initializer) = user_type.initializer.as_mut() {
+            resolver.resolve_initializer(initializer, scope, &[]);
+        }
+    }
+
+    for variable in unit
+        .global_vars
+        .iter_mut()
+        .flat_map(|it| it.variables.iter_mut())
+    {
+        let scope = get_scope(&variable.name);
+        resolver.resolve_variable(variable, scope, &[]);
+    }
+
+    for configuration in unit.configurations.iter_mut() {
+        let scope = get_scope(&configuration.name);
+        for program in configuration
+            .resources
+            .iter_mut()
+            .flat_map(|it| it.programs.iter_mut())
+        {
+            resolver.resolve_name(&mut program.program, scope, &program.location);
+        }
+    }
+
+    resolver.diagnostics
+}
+
+/// returns the lowercase names of the given POU's variables and its return variable.
+/// External variables are no locals, they refer to global variables
+fn get_local_names(pou: &Pou) -> HashSet<String> {
+    pou.variable_blocks
+        .iter()
+        .filter(|it| it.variable_block_type != VariableBlockType::External)
+        .flat_map(|it| it.variables.iter())
+        .map(|it| it.name.to_lowercase())
+        .chain(std::iter::once(pou.get_return_name().to_lowercase()))
+        .collect()
+}
+
+struct NamespaceResolver<'d> {
+    declarations: &'d NamespaceDeclarations,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'d> NamespaceResolver<'d> {
+    /// replaces the given name with the qualified name of the declaration it refers to.
+    /// returns false if the name does not refer to a known declaration
+    fn resolve_name(
+        &mut self,
+        name: &mut String,
+        scope: &NamespaceScope,
+        location: &SourceRange,
+    ) -> bool {
+        //look into the enclosing namespaces first
+        let mut namespace = scope.namespace.as_deref();
+        while let Some(current) = namespace {
+            if let Some(declaration) = self.find_declaration(&get_qualified_name(current, name)) {
+                *name = declaration.to_string();
+                return true;
+            }
+            namespace = current.rfind('.').map(|separator| &current[..separator]);
+        }
+
+        //a global declaration competes with the declarations of all imported namespaces
+        let mut candidates: Vec<&str> = vec![];
+        let imported = scope
+            .usings
+            .iter()
+            .map(|it| self.find_declaration(&get_qualified_name(it, name)));
+        for candidate in std::iter::once(self.find_declaration(name))
+            .chain(imported)
+            .flatten()
+        {
+            if !candidates.contains(&candidate) {
+                candidates.push(candidate);
+            }
+        }
+
+        match candidates.as_slice() {
+            [] => false,
+            [declaration] => {
+                if !declaration.eq_ignore_ascii_case(name) {
+                    *name = declaration.to_string();
+                }
+                true
+            }
+            _ => {
+                self.diagnostics.push(Diagnostic::ambiguous_reference(
+                    name,
+                    candidates.as_slice(),
+                    location.clone(),
+                ));
+                false
+            }
+        }
+    }
+
+    fn find_declaration(&self, name: &str) -> Option<&'d str> {
+        self.declarations
+            .declarations
+            .get(&name.to_lowercase())
+            .map(|it| it.as_str())
+    }
+
+    fn resolve_variable(
+        &mut self,
+        variable: &mut Variable,
+        scope: &NamespaceScope,
+        locals: &[&HashSet<String>],
+    ) {
+        self.resolve_data_type_declaration(&mut variable.data_type, scope);
+        if let Some(initializer) = variable.initializer.as_mut() {
+            self.resolve_initializer(initializer, scope, locals);
+        }
+    }
+
+    fn resolve_data_type_declaration(
+        &mut self,
+        declaration: &mut DataTypeDeclaration,
+        scope: &NamespaceScope,
+    ) {
+        match declaration {
+            DataTypeDeclaration::DataTypeReference {
+                referenced_type,
+                location,
+            } => {
+                self.resolve_name(referenced_type, scope, location);
+            }
+            DataTypeDeclaration::DataTypeDefinition { data_type, .. } => {
+                self.resolve_data_type(data_type, scope)
+            }
+        }
+    }
+
+    fn resolve_data_type(&mut self, data_type: &mut DataType, scope: &NamespaceScope) {
+        match data_type {
+            DataType::StructType { variables, .. } | DataType::UnionType { variables, .. } => {
+                for variable in variables.iter_mut() {
+                    self.resolve_variable(variable, scope, &[]);
+                }
+            }
+            DataType::SubRangeType {
+                referenced_type,
+                bounds,
+                ..
+            } => {
+                let location = bounds
+                    .as_ref()
+                    .map_or_else(SourceRange::undefined, |it| it.get_location());
+                self.resolve_name(referenced_type, scope, &location);
+                if let Some(bounds) = bounds.as_mut() {
+                    self.resolve_statement(bounds, scope, &[]);
+                }
+            }
+            DataType::ArrayType {
+                bounds,
+                referenced_type,
+                ..
+            } => {
+                self.resolve_statement(bounds, scope, &[]);
+                self.resolve_data_type_declaration(referenced_type, scope);
+            }
+            DataType::VariableLengthArrayType {
+                referenced_type, ..
+            }
+            | DataType::PointerType {
+                referenced_type, ..
+            } => self.resolve_data_type_declaration(referenced_type, scope),
+            DataType::StringType { size, .. } => {
+                if let Some(size) = size.as_mut() {
+                    self.resolve_statement(size, scope, &[]);
+                }
+            }
+            DataType::VarArgs { referenced_type } => {
+                if let Some(referenced_type) = referenced_type.as_mut() {
+                    self.resolve_data_type_declaration(referenced_type, scope);
+                }
+            }
+            DataType::EnumType { .. } => {}
+        }
+    }
+
+    /// resolves an initializer. the left side of its assignments name struct members
+    fn resolve_initializer(
+        &mut self,
+        initializer: &mut AstStatement,
+        scope: &NamespaceScope,
+        locals: &[&HashSet<String>],
+    ) {
+        match initializer {
+            AstStatement::ExpressionList { expressions, .. } => {
+                for expression in expressions.iter_mut() {
+                    self.resolve_initializer(expression, scope, locals);
+                }
+            }
+            AstStatement::LiteralArray {
+                elements: Some(elements),
+                ..
+            } => self.resolve_initializer(elements, scope, locals),
+            AstStatement::MultipliedStatement { element, .. } => {
+                self.resolve_initializer(element, scope, locals)
+            }
+            AstStatement::Assignment { right, .. } => {
+                self.resolve_initializer(right, scope, locals)
+            }
+            _ => self.resolve_statement(initializer, scope, locals),
+        }
+    }
+
+    /// resolves a call's argument. the left side of its assignments name parameters
+    fn resolve_argument(
+        &mut self,
+        argument: &mut AstStatement,
+        scope: &NamespaceScope,
+        locals: &[&HashSet<String>],
+    ) {
+        match argument {
+            AstStatement::ExpressionList { expressions, .. } => {
+                for expression in expressions.iter_mut() {
+                    self.resolve_argument(expression, scope, locals);
+                }
+            }
+            AstStatement::Assignment { right, .. }
+            | AstStatement::OutputAssignment { right, .. } => {
+                self.resolve_statement(right, scope, locals)
+            }
+            _ => self.resolve_statement(argument, scope, locals),
+        }
+    }
+
+    fn resolve_statement(
+        &mut self,
+        statement: &mut AstStatement,
+        scope: &NamespaceScope,
+        locals: &[&HashSet<String>],
+    ) {
+        match statement {
+            AstStatement::Reference { name, location, .. } if !is_local(name, locals) => {
+                self.resolve_name(name, scope, location);
+            }
+            AstStatement::QualifiedReference { elements, .. } => {
+                self.resolve_qualified_reference(elements, scope, locals);
+                if elements.len() == 1 {
+                    //the whole reference named a single declaration (e.g. `A.B.foo`)
+                    *statement = elements.remove(0);
+                }
+            }
+            AstStatement::CallStatement {
+                operator,
+                parameters,
+                ..
+            } => {
+                self.resolve_statement(operator, scope, locals);
+                if let Some(parameters) = parameters.as_mut() {
+                    self.resolve_argument(parameters, scope, locals);
+                }
+            }
+            AstStatement::CastStatement {
+                type_name,
+                location,
+                ..
+            } => {
+                self.resolve_name(type_name, scope, location);
+            }
+            AstStatement::ArrayAccess {
+                reference, access, ..
+            } => {
+                self.resolve_statement(reference, scope, locals);
+                self.resolve_statement(access, scope, locals);
+            }
+            AstStatement::PointerAccess { reference, .. } => {
+                self.resolve_statement(reference, scope, locals)
+            }
+            AstStatement::LiteralArray {
+                elements: Some(elements),
+                ..
+            } => self.resolve_statement(elements, scope, locals),
+            AstStatement::MultipliedStatement { element, .. } => {
+                self.resolve_statement(element, scope, locals)
+            }
+            AstStatement::UnaryExpression { value, .. } => {
+                self.resolve_statement(value, scope, locals)
+            }
+            AstStatement::CaseCondition { condition, .. } => {
+                self.resolve_statement(condition, scope, locals)
+            }
+            AstStatement::BinaryExpression { left, right, .. }
+            | AstStatement::Assignment { left, right, .. }
+            | AstStatement::OutputAssignment { left, right, .. }
+            | AstStatement::ReferenceAssignment { left, right, .. }
+            | AstStatement::RangeStatement {
+                start: left,
+                end: right,
+                ..
+            } => {
+                self.resolve_statement(left, scope, locals);
+                self.resolve_statement(right, scope, locals);
+            }
+            AstStatement::ExpressionList { expressions, .. } => {
+                for expression in expressions.iter_mut() {
+                    self.resolve_statement(expression, scope, locals);
+                }
+            }
+            AstStatement::IfStatement {
+                blocks, else_block, ..
+            } => {
+                for block in blocks.iter_mut() {
+                    self.resolve_statement(&mut block.condition, scope, locals);
+                    self.resolve_statements(&mut block.body, scope, locals);
+                }
+                self.resolve_statements(else_block, scope, locals);
+            }
+            AstStatement::CaseStatement {
+                selector,
+                case_blocks,
+                else_block,
+                ..
+            } => {
+                self.resolve_statement(selector, scope, locals);
+                for block in case_blocks.iter_mut() {
+                    self.resolve_statement(&mut block.condition, scope, locals);
+                    self.resolve_statements(&mut block.body, scope, locals);
+                }
+                self.resolve_statements(else_block, scope, locals);
+            }
+            AstStatement::ForLoopStatement {
+                counter,
+                start,
+                end,
+                by_step,
+                body,
+                ..
+            } => {
+                self.resolve_statement(counter, scope, locals);
+                self.resolve_statement(start, scope, locals);
+                self.resolve_statement(end, scope, locals);
+                if let Some(by_step) = by_step.as_mut() {
+                    self.resolve_statement(by_step, scope, locals);
+                }
+                self.resolve_statements(body, scope, locals);
+            }
+            AstStatement::WhileLoopStatement {
+                condition, body, ..
+            }
+            | AstStatement::RepeatLoopStatement {
+                condition, body, ..
+            } => {
+                self.resolve_statement(condition, scope, locals);
+                self.resolve_statements(body, scope, locals);
+            }
+            _ => {}
+        }
+    }
+
+    fn resolve_statements(
+        &mut self,
+        statements: &mut [AstStatement],
+        scope: &NamespaceScope,
+        locals: &[&HashSet<String>],
+    ) {
+        for statement in statements.iter_mut() {
+            self.resolve_statement(statement, scope, locals);
+        }
+    }
+
+    /// merges the longest leading elements naming a declaration (e.g. `A.B.foo` in
+    /// `A.B.foo.x`) into a single reference. the remaining elements name members and
+    /// only their array-access expressions are resolved
+    fn resolve_qualified_reference(
+        &mut self,
+        elements: &mut Vec<AstStatement>,
+        scope: &NamespaceScope,
+        locals: &[&HashSet<String>],
+    ) {
+        let mut names = vec![];
+        for element in elements.iter() {
+            if let AstStatement::Reference { name, .. } = element {
+                names.push(name.as_str());
+            } else {
+                break;
+            }
+        }
+
+        let mut qualified_prefix = None;
+        if !names.first().map_or(true, |it| is_local(it, locals)) {
+            for len in (2..=names.len()).rev() {
+                let mut name = names[..len].join(".");
+                let location = SourceRange::new(
+                    elements[0].get_location().get_start()
+                        ..elements[len - 1].get_location().get_end(),
+                );
+                if self.resolve_name(&mut name, scope, &location) {
+                    qualified_prefix = Some((len, name, location));
+                    break;
+                }
+            }
+        }
+
+        if let Some((len, name, location)) = qualified_prefix {
+            let id = elements[len - 1].get_id();
+            let members = elements.split_off(len);
+            *elements = std::iter::once(AstStatement::Reference { name, location, id })
+                .chain(members)
+                .collect();
+        } else if let Some(first) = elements.first_mut() {
+            self.resolve_statement(first, scope, locals);
+        }
+
+        for member in elements.iter_mut().skip(1) {
+            self.resolve_member(member, scope, locals);
+        }
+    }
+
+    fn resolve_member(
+        &mut self,
+        member: &mut AstStatement,
+        scope: &NamespaceScope,
+        locals: &[&HashSet<String>],
+    ) {
+        match member {
+            AstStatement::ArrayAccess {
+                reference, access, ..
+            } => {
+                self.resolve_member(reference, scope, locals);
+                self.resolve_statement(access, scope, locals);
+            }
+            AstStatement::PointerAccess { reference, .. } => {
+                self.resolve_member(reference, scope, locals)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_local(name: &str, locals: &[&HashSet<String>]) -> bool {
+    let name = name.to_lowercase();
+    locals.iter().any(|it| it.contains(&name))
+}
@@ -38,7 +38,9 @@ pub fn pre_process(unit: &mut CompilationUnit) {
     //process all variables in dataTypes
     let mut new_types = vec![];
     for dt in unit.types.iter_mut() {
-        if let DataType::StructType { name, variables } = &mut dt.data_type {
+        if let DataType::StructType { name, variables } | DataType::UnionType { name, variables } =
+            &mut dt.data_type
+        {
             variables
                 .iter_mut()
                 .filter(|it| should_generate_implicit_type(it))
@@ -0,0 +1,377 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+use crate::typesystem::{self, *};
+
+/// the functions of the standard library that are built into the compiler. A call of
+/// a builtin function is typed by the `TypeAnnotator` and generated inline by the
+/// `ExpressionCodeGenerator`. A user-defined POU with the same name takes precedence.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BuiltIn {
+    /// `EXPT(IN1, IN2)` raises `IN1` to the power of `IN2` (same as `IN1 ** IN2`)
+    Expt,
+    /// `__ISVALIDREF(REF)` returns TRUE if the given reference or pointer is not NULL
+    IsValidRef,
+    /// `LOWER_BOUND(ARR, DIM)` returns the lower bound of the given dimension (starting at 1)
+    LowerBound,
+    /// `UPPER_BOUND(ARR, DIM)` returns the upper bound of the given dimension (starting at 1)
+    UpperBound,
+    /// `MIN(IN1, IN2, ...)` returns the smallest of the given values
+    Min,
+    /// `MAX(IN1, IN2, ...)` returns the biggest of the given values
+    Max,
+    /// `LIMIT(MN, IN, MX)` returns `IN` limited to the range from `MN` to `MX`
+    Limit,
+    /// `SEL(G, IN0, IN1)` returns `IN0` if `G` is FALSE and `IN1` if `G` is TRUE
+    Sel,
+    /// `MUX(K, IN0, IN1, ...)` returns the input selected by `K`
+    Mux,
+    /// `MOVE(IN)` returns `IN`
+    Move,
+    /// `SHL(IN, N)` shifts the bits of `IN` by `N` to the left, filling in zeros
+    Shl,
+    /// `SHR(IN, N)` shifts the bits of `IN` by `N` to the right, filling in zeros
+    Shr,
+    /// `ROL(IN, N)` rotates the bits of `IN` by `N` to the left
+    Rol,
+    /// `ROR(IN, N)` rotates the bits of `IN` by `N` to the right
+    Ror,
+    /// `ABS(IN)` returns the absolute value of the number `IN`
+    Abs,
+    /// `SQRT(IN)` returns the square root of `IN`
+    Sqrt,
+    /// `LN(IN)` returns the natural logarithm of `IN`
+    Ln,
+    /// `LOG(IN)` returns the logarithm to base 10 of `IN`
+    Log,
+    /// `EXP(IN)` returns e raised to the power of `IN`
+    Exp,
+    /// `SIN(IN)` returns the sine of `IN` (in radians)
+    Sin,
+    /// `COS(IN)` returns the cosine of `IN` (in radians)
+    Cos,
+    /// `TAN(IN)` returns the tangent of `IN` (in radians)
+    Tan,
+    /// `ASIN(IN)` returns the arc sine of `IN`
+    Asin,
+    /// `ACOS(IN)` returns the arc cosine of `IN`
+    Acos,
+    /// `ATAN(IN)` returns the arc tangent of `IN`
+    Atan,
+    /// `ATAN2(Y, X)` returns the arc tangent of `Y / X` using the signs of both to find the quadrant
+    Atan2,
+    /// `ROUND(IN)` rounds `IN` to the nearest integral value, halfway cases away from zero
+    Round,
+    /// `LEN(IN)` returns the number of characters of the string `IN`
+    Len,
+    /// `LEFT(IN, L)` returns the first `L` characters of `IN`
+    Left,
+    /// `RIGHT(IN, L)` returns the last `L` characters of `IN`
+    Right,
+    /// `MID(IN, L, P)` returns `L` characters of `IN` starting at the `P`-th character
+    Mid,
+    /// `CONCAT(IN1, IN2, ...)` returns the concatenation of the given strings
+    Concat,
+    /// `INSERT(IN1, IN2, P)` returns `IN1` with `IN2` inserted after the `P`-th character
+    Insert,
+    /// `DELETE(IN, L, P)` returns `IN` without the `L` characters starting at the `P`-th character
+    Delete,
+    /// `REPLACE(IN1, IN2, L, P)` returns `IN1` with the `L` characters starting at the `P`-th
+    /// character replaced by `IN2`
+    Replace,
+    /// `FIND(IN1, IN2)` returns the position of the first occurrence of `IN2` in `IN1` or 0
+    Find,
+    /// `<SOURCE>_TO_<TARGET>(IN)` and `TO_<TARGET>(IN)` convert `IN` into the target type,
+    /// real numbers are rounded to the nearest integer
+    Conversion {
+        source: Option<&'static str>,
+        target: &'static str,
+    },
+    /// `TRUNC(IN)` and `<SOURCE>_TRUNC_<TARGET>(IN)` convert the real number `IN` into an
+    /// integer by cutting off its fraction
+    Trunc {
+        source: Option<&'static str>,
+        target: &'static str,
+    },
+}
+
+impl BuiltIn {
+    /// returns true for the numeric functions that calculate a REAL or LREAL result from real
+    /// inputs (e.g. `SQRT` or `ATAN2`)
+    pub fn is_real_function(&self) -> bool {
+        matches!(
+            self,
+            BuiltIn::Sqrt
+                | BuiltIn::Ln
+                | BuiltIn::Log
+                | BuiltIn::Exp
+                | BuiltIn::Sin
+                | BuiltIn::Cos
+                | BuiltIn::Tan
+                | BuiltIn::Asin
+                | BuiltIn::Acos
+                | BuiltIn::Atan
+                | BuiltIn::Atan2
+                | BuiltIn::Round
+        )
+    }
+
+    /// returns the number of inputs of a real function (e.g. 2 for `ATAN2`)
+    pub fn get_real_function_arity(&self) -> usize {
+        if self == &BuiltIn::Atan2 {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// returns the minimum and the maximum number of inputs the function accepts, functions
+    /// with a variable number of inputs (e.g. `MAX`) have no maximum
+    pub fn get_input_count(&self) -> (usize, Option<usize>) {
+        match self {
+            BuiltIn::Min | BuiltIn::Max | BuiltIn::Mux => (2, None),
+            BuiltIn::Concat => (1, None),
+            BuiltIn::Replace => (4, Some(4)),
+            BuiltIn::Limit | BuiltIn::Sel | BuiltIn::Mid | BuiltIn::Insert | BuiltIn::Delete => {
+                (3, Some(3))
+            }
+            BuiltIn::Expt
+            | BuiltIn::LowerBound
+            | BuiltIn::UpperBound
+            | BuiltIn::Shl
+            | BuiltIn::Shr
+            | BuiltIn::Rol
+            | BuiltIn::Ror
+            | BuiltIn::Left
+            | BuiltIn::Right
+            | BuiltIn::Find => (2, Some(2)),
+            builtin if builtin.is_real_function() => {
+                let arity = builtin.get_real_function_arity();
+                (arity, Some(arity))
+            }
+            _ => (1, Some(1)),
+        }
+    }
+
+    /// returns the builtin function with the given name (ignoring case)
+    pub fn find(name: &str) -> Option<BuiltIn> {
+        let name = name.to_uppercase();
+        match name.as_str() {
+            "EXPT" => Some(BuiltIn::Expt),
+            "__ISVALIDREF" => Some(BuiltIn::IsValidRef),
+            "LOWER_BOUND" => Some(BuiltIn::LowerBound),
+            "UPPER_BOUND" => Some(BuiltIn::UpperBound),
+            "MIN" => Some(BuiltIn::Min),
+            "MAX" => Some(BuiltIn::Max),
+            "LIMIT" => Some(BuiltIn::Limit),
+            "SEL" => Some(BuiltIn::Sel),
+            "MUX" => Some(BuiltIn::Mux),
+            "MOVE" => Some(BuiltIn::Move),
+            "SHL" => Some(BuiltIn::Shl),
+            "SHR" => Some(BuiltIn::Shr),
+            "ROL" => Some(BuiltIn::Rol),
+            "ROR" => Some(BuiltIn::Ror),
+            "ABS" => Some(BuiltIn::Abs),
+            "SQRT" => Some(BuiltIn::Sqrt),
+            "LN" => Some(BuiltIn::Ln),
+            "LOG" => Some(BuiltIn::Log),
+            "EXP" => Some(BuiltIn::Exp),
+            "SIN" => Some(BuiltIn::Sin),
+            "COS" => Some(BuiltIn::Cos),
+            "TAN" => Some(BuiltIn::Tan),
+            "ASIN" => Some(BuiltIn::Asin),
+            "ACOS" => Some(BuiltIn::Acos),
+            "ATAN" => Some(BuiltIn::Atan),
+            "ATAN2" => Some(BuiltIn::Atan2),
+            "ROUND" => Some(BuiltIn::Round),
+            "LEN" => Some(BuiltIn::Len),
+            "LEFT" => Some(BuiltIn::Left),
+            "RIGHT" => Some(BuiltIn::Right),
+            "MID" => Some(BuiltIn::Mid),
+            "CONCAT" => Some(BuiltIn::Concat),
+            "INSERT" => Some(BuiltIn::Insert),
+            "DELETE" => Some(BuiltIn::Delete),
+            "REPLACE" => Some(BuiltIn::Replace),
+            "FIND" => Some(BuiltIn::Find),
+            "TRUNC" => Some(BuiltIn::Trunc {
+                source: None,
+                target: DINT_TYPE,
+            }),
+            _ => find_conversion(&name),
+        }
+    }
+
+    /// returns the name of the builtin function
+    pub fn get_name(&self) -> String {
+        match self {
+            BuiltIn::Expt => "EXPT".into(),
+            BuiltIn::IsValidRef => "__ISVALIDREF".into(),
+            BuiltIn::LowerBound => "LOWER_BOUND".into(),
+            BuiltIn::UpperBound => "UPPER_BOUND".into(),
+            BuiltIn::Min => "MIN".into(),
+            BuiltIn::Max => "MAX".into(),
+            BuiltIn::Limit => "LIMIT".into(),
+            BuiltIn::Sel => "SEL".into(),
+            BuiltIn::Mux => "MUX".into(),
+            BuiltIn::Move => "MOVE".into(),
+            BuiltIn::Shl => "SHL".into(),
+            BuiltIn::Shr => "SHR".into(),
+            BuiltIn::Rol => "ROL".into(),
+            BuiltIn::Ror => "ROR".into(),
+            BuiltIn::Abs => "ABS".into(),
+            BuiltIn::Sqrt => "SQRT".into(),
+            BuiltIn::Ln => "LN".into(),
+            BuiltIn::Log => "LOG".into(),
+            BuiltIn::Exp => "EXP".into(),
+            BuiltIn::Sin => "SIN".into(),
+            BuiltIn::Cos => "COS".into(),
+            BuiltIn::Tan => "TAN".into(),
+            BuiltIn::Asin => "ASIN".into(),
+            BuiltIn::Acos => "ACOS".into(),
+            BuiltIn::Atan => "ATAN".into(),
+            BuiltIn::Atan2 => "ATAN2".into(),
+            BuiltIn::Round => "ROUND".into(),
+            BuiltIn::Len => "LEN".into(),
+            BuiltIn::Left => "LEFT".into(),
+            BuiltIn::Right => "RIGHT".into(),
+            BuiltIn::Mid => "MID".into(),
+            BuiltIn::Concat => "CONCAT".into(),
+            BuiltIn::Insert => "INSERT".into(),
+            BuiltIn::Delete => "DELETE".into(),
+            BuiltIn::Replace => "REPLACE".into(),
+            BuiltIn::Find => "FIND".into(),
+            BuiltIn::Conversion {
+                source: Some(source),
+                target,
+            } => format!("{}_TO_{}", source, target),
+            BuiltIn::Conversion {
+                source: None,
+                target,
+            } => format!("TO_{}", target),
+            BuiltIn::Trunc {
+                source: Some(source),
+                target,
+            } => format!("{}_TRUNC_{}", source, target),
+            BuiltIn::Trunc { source: None, .. } => "TRUNC".into(),
+        }
+    }
+}
+
+/// the elementary types that can be converted into each other
+const CONVERTIBLE_TYPES: &[&str] = &[
+    BOOL_TYPE,
+    BYTE_TYPE,
+    WORD_TYPE,
+    DWORD_TYPE,
+    LWORD_TYPE,
+    SINT_TYPE,
+    USINT_TYPE,
+    INT_TYPE,
+    UINT_TYPE,
+    DINT_TYPE,
+    UDINT_TYPE,
+    LINT_TYPE,
+    ULINT_TYPE,
+    REAL_TYPE,
+    LREAL_TYPE,
+    CHAR_TYPE,
+    WCHAR_TYPE,
+    TIME_TYPE,
+    LTIME_TYPE,
+    DATE_TYPE,
+    LDATE_TYPE,
+    TIME_OF_DAY_TYPE,
+    LTIME_OF_DAY_TYPE,
+    DATE_AND_TIME_TYPE,
+    LDATE_AND_TIME_TYPE,
+    STRING_TYPE,
+    WSTRING_TYPE,
+];
+
+/// the groups of elementary types that share the same conversion rules
+#[derive(PartialEq)]
+enum ConversionGroup {
+    Bool,
+    Integer,
+    Real,
+    Character,
+    DateTime,
+    String,
+}
+
+fn get_conversion_group(type_name: &str) -> ConversionGroup {
+    match type_name {
+        BOOL_TYPE => ConversionGroup::Bool,
+        REAL_TYPE | LREAL_TYPE => ConversionGroup::Real,
+        CHAR_TYPE | WCHAR_TYPE => ConversionGroup::Character,
+        STRING_TYPE | WSTRING_TYPE => ConversionGroup::String,
+        _ if typesystem::get_date_time_kind(type_name).is_some() => ConversionGroup::DateTime,
+        _ => ConversionGroup::Integer,
+    }
+}
+
+/// returns the elementary type with the given name, the short names of the date and time types
+/// (e.g. `DT` or `LTOD`) are accepted as well
+fn find_convertible_type(name: &str) -> Option<&'static str> {
+    let name = match name {
+        SHORT_DATE_AND_TIME_TYPE => DATE_AND_TIME_TYPE,
+        SHORT_TIME_OF_DAY_TYPE => TIME_OF_DAY_TYPE,
+        SHORT_LDATE_AND_TIME_TYPE => LDATE_AND_TIME_TYPE,
+        SHORT_LTIME_OF_DAY_TYPE => LTIME_OF_DAY_TYPE,
+        _ => name,
+    };
+    CONVERTIBLE_TYPES.iter().find(|it| **it == name).copied()
+}
+
+/// returns true if a value of the source type can be converted into the target type:
+/// - BOOL, integers and real numbers convert into each other
+/// - CHAR and WCHAR convert into each other and into integers
+/// - date and time types convert into numbers and into related date and time types
+/// - every type converts into STRING and WSTRING and back
+pub fn is_valid_conversion(source: &str, target: &str) -> bool {
+    use ConversionGroup::*;
+    if find_convertible_type(source).is_none() || find_convertible_type(target).is_none() {
+        return false;
+    }
+    match (get_conversion_group(source), get_conversion_group(target)) {
+        (String, _) | (_, String) => true,
+        (DateTime, DateTime) => typesystem::is_valid_date_time_conversion(source, target),
+        (Character, other) | (other, Character) => matches!(other, Character | Integer),
+        (Bool, DateTime) | (DateTime, Bool) => false,
+        _ => true,
+    }
+}
+
+/// returns the conversion function with the given (uppercase) name, e.g. `INT_TO_REAL`,
+/// `TO_REAL` or `LREAL_TRUNC_DINT`
+fn find_conversion(name: &str) -> Option<BuiltIn> {
+    if let Some(target) = name.strip_prefix("TO_") {
+        return find_convertible_type(target).map(|target| BuiltIn::Conversion {
+            source: None,
+            target,
+        });
+    }
+    if let Some(position) = name.find("_TRUNC_") {
+        let source = find_convertible_type(&name[..position])?;
+        let target = find_convertible_type(&name[position + "_TRUNC_".len()..])?;
+        return if get_conversion_group(source) == ConversionGroup::Real
+            && get_conversion_group(target) == ConversionGroup::Integer
+        {
+            Some(BuiltIn::Trunc {
+                source: Some(source),
+                target,
+            })
+        } else {
+            None
+        };
+    }
+    let position = name.find("_TO_")?;
+    let source = find_convertible_type(&name[..position])?;
+    let target = find_convertible_type(&name[position + "_TO_".len()..])?;
+    if is_valid_conversion(source, target) {
+        Some(BuiltIn::Conversion {
+            source: Some(source),
+            target,
+        })
+    } else {
+        None
+    }
+}
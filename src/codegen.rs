@@ -3,10 +3,10 @@
 /// module to generate llvm intermediate representation for a CompilationUnit
 use self::{
     generators::{
-        data_type_generator,
+        data_type_generator, io_generator,
         llvm::Llvm,
         pou_generator::{self, PouGenerator},
-        variable_generator,
+        struct_generator, task_generator, variable_generator,
     },
     llvm_index::LlvmTypedIndex,
 };
@@ -78,7 +78,7 @@ impl<'ink> CodeGen<'ink> {
         let mut index = LlvmTypedIndex::new();
         //Generate types index, and any global variables associated with them.
         let llvm_type_index =
-            data_type_generator::generate_data_types(&llvm, global_index, annotations)?;
+            data_type_generator::generate_data_types(module, &llvm, global_index, annotations)?;
         index.merge(llvm_type_index);
         //Generate global variables
         let llvm_gv_index = variable_generator::generate_global_variables(
@@ -99,6 +99,11 @@ impl<'ink> CodeGen<'ink> {
             &index,
         )?;
         index.merge(llvm_impl_index);
+        //Generate the vtables of classes implementing interfaces and the method tables of classes with overridden methods
+        let llvm = Llvm::new(self.context, self.context.create_builder());
+        let llvm_vtable_index =
+            struct_generator::generate_vtables(module, &llvm, global_index, &index)?;
+        index.merge(llvm_vtable_index);
         Ok(index)
     }
 
@@ -119,9 +124,23 @@ impl<'ink> CodeGen<'ink> {
         for implementation in &unit.implementations {
             //Don't generate external functions
             if implementation.linkage != LinkageType::External {
-                pou_generator.generate_implementation(implementation)?;
+                pou_generator.generate_implementation(implementation, &self.module)?;
             }
         }
+
+        //Generate the process images of all located variables
+        let llvm = Llvm::new(self.context, self.context.create_builder());
+        io_generator::generate_process_images(&self.module, &llvm, global_index, &llvm_index)?;
+
+        //Generate the task table once all programs are known
+        let llvm = Llvm::new(self.context, self.context.create_builder());
+        task_generator::generate_task_table(
+            &self.module,
+            &llvm,
+            global_index,
+            annotations,
+            &llvm_index,
+        )?;
         Ok(self.module.print_to_string().to_string())
     }
 }
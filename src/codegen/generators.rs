@@ -1,7 +1,9 @@
 pub mod data_type_generator;
 pub mod expression_generator;
+pub mod io_generator;
 pub mod llvm;
 pub mod pou_generator;
 pub mod statement_generator;
 pub mod struct_generator;
+pub mod task_generator;
 pub mod variable_generator;
@@ -2,6 +2,7 @@
 
 /// the data_type_generator generates user defined data-types
 /// - Structures
+/// - Unions
 /// - Enum types
 /// - SubRange types
 /// - Alias types
@@ -21,13 +22,17 @@ use crate::{
     typesystem::DataType,
 };
 use inkwell::{
+    module::Module,
+    targets::TargetData,
     types::{ArrayType, BasicType, BasicTypeEnum},
     values::BasicValueEnum,
     AddressSpace,
 };
 
 use super::{
-    expression_generator::ExpressionCodeGenerator, llvm::Llvm, struct_generator::StructGenerator,
+    expression_generator::ExpressionCodeGenerator,
+    llvm::Llvm,
+    struct_generator::{self, StructGenerator},
 };
 
 pub struct DataTypeGenerator<'ink, 'b> {
@@ -35,16 +40,22 @@ pub struct DataTypeGenerator<'ink, 'b> {
     index: &'b Index,
     annotations: &'b AnnotationMap,
     types_index: LlvmTypedIndex<'ink>,
+    /// the layout of the module's target, used to size unions and array descriptors
+    target_data: TargetData,
 }
 
 /// generates the llvm-type for the given data-type and registers it at the index
 /// this function may create and register a ...
 /// - Struct type for a STRUCT
+/// - Struct type for a UNION, sized to hold its biggest member
 /// - global variables for enum-elements
 /// - an alias index entry for sub-range types
 /// - Array type for arrays
+/// - Struct type holding a pointer and the bounds for variable-length arrays
 /// - array type for sized Strings
+/// - the method tables referenced by the instances of extended classes
 pub fn generate_data_types<'ink>(
+    module: &Module<'ink>,
     llvm: &Llvm<'ink>,
     index: &Index,
     annotations: &AnnotationMap,
@@ -54,12 +65,16 @@ pub fn generate_data_types<'ink>(
         index,
         annotations,
         types_index: LlvmTypedIndex::new(),
+        target_data: TargetData::create(&module.get_data_layout().as_str().to_string_lossy()),
     };
 
     let types = generator.index.get_types();
     for (name, user_type) in types {
         if let DataTypeInformation::Struct {
             name: struct_name, ..
+        }
+        | DataTypeInformation::Union {
+            name: struct_name, ..
         } = user_type.get_type_information()
         {
             generator
@@ -71,6 +86,20 @@ pub fn generate_data_types<'ink>(
         let gen_type = generator.create_type(name, user_type)?;
         generator.types_index.associate_type(name, gen_type)?
     }
+    for user_type in types.values() {
+        generator.expand_union_type(user_type)?;
+    }
+    for user_type in types.values() {
+        let class_name = user_type.get_name();
+        if index.find_method_table_owner(class_name).is_some() {
+            let method_table =
+                struct_generator::declare_method_table(module, llvm, index, class_name);
+            generator.types_index.associate_global(
+                &struct_generator::get_method_table_name(class_name),
+                method_table,
+            )?;
+        }
+    }
     for (name, user_type) in types {
         generator.expand_opaque_types(user_type)?;
         if let Some(initial_value) = generator.generate_initial_value(user_type) {
@@ -111,8 +140,111 @@ impl<'ink, 'b> DataTypeGenerator<'ink, 'b> {
         Ok(())
     }
 
+    /// sets the body of an opaque union type
+    ///
+    /// all members of a union start at offset 0, so the union is represented as a struct
+    /// holding the member with the strictest alignment followed by enough padding bytes
+    /// to fit the biggest member
+    fn expand_union_type(&self, data_type: &DataType) -> Result<(), CompileError> {
+        if let DataTypeInformation::Union { member_names, .. } = data_type.get_type_information() {
+            let union_type = self
+                .types_index
+                .get_associated_type(data_type.get_name())?
+                .into_struct_type();
+            let members = self.get_union_members(data_type.get_name(), member_names);
+            let (union_size, union_alignment) = get_union_size_and_alignment(&members);
+            //the member with the strictest alignment determines the alignment of the union
+            let mut body = vec![];
+            if let Some((type_name, size, _)) = members
+                .iter()
+                .max_by_key(|(_, size, alignment)| (*alignment, *size))
+            {
+                body.push(self.types_index.get_associated_type(type_name)?);
+                let padding = round_up(union_size, union_alignment) - size;
+                if padding > 0 {
+                    body.push(self.llvm.context.i8_type().array_type(padding).into());
+                }
+            }
+            union_type.set_body(&body, false);
+        }
+        Ok(())
+    }
+
+    /// returns the size and the alignment of the given type in bytes
+    ///
+    /// structs, unions and arrays are measured by their members because their llvm types
+    /// may not have a body yet, all other types are measured by the module's target data
+    fn get_size_and_alignment(&self, type_name: &str) -> (u32, u32) {
+        match self.index.get_type_information_or_void(type_name) {
+            DataTypeInformation::Array {
+                inner_type_name,
+                dimensions,
+                ..
+            } => {
+                let (size, alignment) = self.get_size_and_alignment(inner_type_name);
+                let length: u32 = dimensions.iter().map(Dimension::get_length).product();
+                (size * length, alignment)
+            }
+            DataTypeInformation::Struct {
+                name, member_names, ..
+            } => {
+                let mut size = 0;
+                let mut struct_alignment = 1;
+                for member in member_names
+                    .iter()
+                    .filter_map(|member| self.index.find_member(name, member))
+                    .filter(|member| !member.is_return())
+                {
+                    let (member_size, alignment) =
+                        self.get_size_and_alignment(member.get_type_name());
+                    size = round_up(size, alignment) + member_size;
+                    struct_alignment = struct_alignment.max(alignment);
+                }
+                (round_up(size, struct_alignment), struct_alignment)
+            }
+            DataTypeInformation::Union { name, member_names } => {
+                let members = self.get_union_members(name, member_names);
+                let (size, alignment) = get_union_size_and_alignment(&members);
+                (round_up(size, alignment), alignment)
+            }
+            DataTypeInformation::SubRange {
+                referenced_type, ..
+            }
+            | DataTypeInformation::Alias {
+                referenced_type, ..
+            } => self.get_size_and_alignment(referenced_type),
+            DataTypeInformation::Void => (0, 1),
+            _ => self
+                .types_index
+                .find_associated_type(type_name)
+                .map(|it| {
+                    (
+                        self.target_data.get_abi_size(&it) as u32,
+                        self.target_data.get_abi_alignment(&it),
+                    )
+                })
+                .unwrap_or((0, 1)),
+        }
+    }
+
+    /// returns the type-name, the size and the alignment of every member of the given union
+    fn get_union_members(
+        &self,
+        union_name: &str,
+        member_names: &[String],
+    ) -> Vec<(&str, u32, u32)> {
+        member_names
+            .iter()
+            .filter_map(|member| self.index.find_member(union_name, member))
+            .map(|member| {
+                let (size, alignment) = self.get_size_and_alignment(member.get_type_name());
+                (member.get_type_name(), size, alignment)
+            })
+            .collect()
+    }
+
     /// Creates an llvm type to be associated with the given data type.
-    /// Generates only an opaque type for structs.
+    /// Generates only an opaque type for structs and unions.
     /// Eagerly generates but does not associate nested array and referenced aliased types
     fn create_type(
         &self,
@@ -121,7 +253,7 @@ impl<'ink, 'b> DataTypeGenerator<'ink, 'b> {
     ) -> Result<BasicTypeEnum<'ink>, CompileError> {
         let information = data_type.get_type_information();
         match information {
-            DataTypeInformation::Struct { .. } => {
+            DataTypeInformation::Struct { .. } | DataTypeInformation::Union { .. } => {
                 self.types_index.get_associated_type(data_type.get_name())
             }
             DataTypeInformation::Array {
@@ -140,12 +272,31 @@ impl<'ink, 'b> DataTypeGenerator<'ink, 'b> {
                     .into();
                 Ok(array_type)
             }
+            DataTypeInformation::VariableLengthArray {
+                inner_type_name,
+                dimensions,
+                ..
+            } => {
+                let inner_type =
+                    self.create_type(inner_type_name, self.index.get_type(inner_type_name)?)?;
+                let bounds_type = self.llvm.i32_type().array_type(2 * *dimensions as u32);
+                Ok(self
+                    .llvm
+                    .context
+                    .struct_type(
+                        &[
+                            inner_type.ptr_type(AddressSpace::Generic).into(),
+                            bounds_type.into(),
+                        ],
+                        false,
+                    )
+                    .into())
+            }
             DataTypeInformation::Integer { size, .. } => {
                 get_llvm_int_type(self.llvm.context, *size, name).map(|it| it.into())
             }
-            DataTypeInformation::Enum { name, .. } => {
-                let enum_size = information.get_size();
-                get_llvm_int_type(self.llvm.context, enum_size, name).map(|it| it.into())
+            DataTypeInformation::Enum { numeric_type, .. } => {
+                self.create_type(name, self.index.get_type(numeric_type)?)
             }
             DataTypeInformation::Float { size, .. } => {
                 get_llvm_float_type(self.llvm.context, *size, name).map(|it| it.into())
@@ -188,6 +339,7 @@ impl<'ink, 'b> DataTypeGenerator<'ink, 'b> {
         let information = data_type.get_type_information();
         match information {
             DataTypeInformation::Struct { .. } => None, //Done elsewhere
+            DataTypeInformation::Union { .. } => None,
             DataTypeInformation::Array { .. } => self
                 .generate_array_initializer(
                     data_type,
@@ -197,7 +349,9 @@ impl<'ink, 'b> DataTypeGenerator<'ink, 'b> {
                 )
                 .unwrap(),
             DataTypeInformation::Integer { .. } => None,
-            DataTypeInformation::Enum { .. } => None,
+            DataTypeInformation::Enum { elements, .. } => {
+                self.generate_enum_initial_value(data_type, elements)
+            }
             DataTypeInformation::Float { .. } => None,
             DataTypeInformation::String { .. } => self
                 .generate_array_initializer(
@@ -216,6 +370,7 @@ impl<'ink, 'b> DataTypeGenerator<'ink, 'b> {
             // Void types are not basic type enums, so we return an int here
             DataTypeInformation::Void => None, //get_llvm_int_type(llvm.context, 32, "Void").map(Into::into),
             DataTypeInformation::Pointer { .. } => None,
+            DataTypeInformation::VariableLengthArray { .. } => None,
         }
     }
 
@@ -246,6 +401,29 @@ impl<'ink, 'b> DataTypeGenerator<'ink, 'b> {
         }
     }
 
+    /// generates the initial value of an enum, which is the value of its first element
+    fn generate_enum_initial_value(
+        &self,
+        data_type: &DataType,
+        elements: &[String],
+    ) -> Option<BasicValueEnum<'ink>> {
+        let first_element = elements
+            .first()
+            .and_then(|it| self.index.find_enum_element(data_type.get_name(), it))?;
+        let generator = ExpressionCodeGenerator::new_context_free(
+            self.llvm,
+            self.index,
+            self.annotations,
+            &self.types_index,
+            Some(data_type.get_type_information().clone()),
+        );
+        first_element
+            .initial_value
+            .as_ref()
+            .and_then(|it| generator.generate_literal(it).ok())
+            .map(|(_, value)| value)
+    }
+
     /// generates and associates the given array-datatype (used for arrays and strings)
     fn generate_array_initializer(
         &self,
@@ -311,3 +489,19 @@ impl<'ink, 'b> DataTypeGenerator<'ink, 'b> {
         result.unwrap()
     }
 }
+
+/// rounds the given offset up to the next multiple of the alignment
+fn round_up(offset: u32, alignment: u32) -> u32 {
+    (offset + alignment - 1) / alignment * alignment
+}
+
+/// returns the size of the biggest member and the strictest alignment of the given
+/// `(type_name, size, alignment)` members
+fn get_union_size_and_alignment(members: &[(&str, u32, u32)]) -> (u32, u32) {
+    members.iter().fold(
+        (0, 1),
+        |(size, alignment), (_, member_size, member_alignment)| {
+            (size.max(*member_size), alignment.max(*member_alignment))
+        },
+    )
+}
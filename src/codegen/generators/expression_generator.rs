@@ -1,13 +1,16 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
 use crate::{
     ast::{Pou, SourceRange},
-    index::{ImplementationType, Index},
+    index::{ImplementationType, Index, SUPER_CLASS_MEMBER_NAME, SUPER_POINTER_NAME},
     resolver::{AnnotationMap, StatementAnnotation},
-    typesystem::{StringEncoding, DINT_TYPE, LINT_TYPE, LREAL_TYPE},
+    typesystem::{
+        StringEncoding, DATE_AND_TIME_TYPE, DATE_TYPE, DINT_TYPE, LDATE_AND_TIME_TYPE, LDATE_TYPE,
+        LREAL_TYPE, LTIME_OF_DAY_TYPE, LTIME_TYPE, TIME_OF_DAY_TYPE, TIME_TYPE,
+    },
 };
 use inkwell::{
     basic_block::BasicBlock,
-    types::BasicTypeEnum,
+    types::{BasicType, BasicTypeEnum},
     values::{
         ArrayValue, BasicValue, BasicValueEnum, FloatValue, IntValue, PointerValue, StructValue,
         VectorValue,
@@ -24,7 +27,7 @@ use crate::{
         TypeAndPointer, TypeAndValue,
     },
     compile_error::CompileError,
-    index::VariableIndexEntry,
+    index::{ImplementationIndexEntry, VariableIndexEntry},
     typesystem::{DataType, DataTypeInformation},
 };
 
@@ -32,6 +35,17 @@ use super::{llvm::Llvm, statement_generator::FunctionContext, struct_generator};
 
 use chrono::{LocalResult, TimeZone, Utc};
 
+mod arrays;
+mod bit_shifts;
+mod builtins;
+mod date_time;
+mod numeric;
+mod pointers;
+mod selection;
+mod string_conversions;
+mod strings;
+mod trigonometry;
+
 /// the generator for expressions
 pub struct ExpressionCodeGenerator<'a, 'b> {
     llvm: &'b Llvm<'a>,
@@ -41,7 +55,7 @@ pub struct ExpressionCodeGenerator<'a, 'b> {
     /// an optional type hint for generating literals
     type_hint: Option<DataTypeInformation>,
     /// the current function to create blocks in
-    function_context: Option<&'b FunctionContext<'a>>,
+    function_context: Option<&'b FunctionContext<'a, 'b>>,
 
     /// the string-prefix to use for temporary variables
     pub temp_variable_prefix: String,
@@ -71,7 +85,7 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
         annotations: &'b AnnotationMap,
         llvm_index: &'b LlvmTypedIndex<'a>,
         type_hint: Option<DataTypeInformation>,
-        function_context: &'b FunctionContext<'a>,
+        function_context: &'b FunctionContext<'a, 'b>,
     ) -> ExpressionCodeGenerator<'a, 'b> {
         ExpressionCodeGenerator {
             llvm,
@@ -127,7 +141,7 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
     fn get_function_context(
         &self,
         statement: &AstStatement,
-    ) -> Result<&'b FunctionContext<'a>, CompileError> {
+    ) -> Result<&'b FunctionContext<'a, 'b>, CompileError> {
         self.function_context
             .ok_or_else(|| CompileError::missing_function(statement.get_location()))
     }
@@ -145,6 +159,11 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
         let builder = &self.llvm.builder;
         match expression {
             AstStatement::Reference { name, .. } => {
+                if let Some((pointer_type, instance)) =
+                    self.generate_instance_pointer(name, expression)?
+                {
+                    return Ok((pointer_type.get_type_information().clone(), instance.into()));
+                }
                 let load_name = format!(
                     "{}{}{}",
                     self.temp_variable_prefix, name, self.temp_variable_suffix
@@ -182,6 +201,18 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
                         return self
                             .generate_short_circuit_boolean_expression(operator, left, right)
                     }
+                    Operator::Exponentiation => {
+                        return self.generate_exponentiation(left, right, expression)
+                    }
+                    _ if self.is_date_time_operation(left, right) => {
+                        return self.generate_date_time_binary_expression(
+                            operator, left, right, expression,
+                        )
+                    }
+                    _ if self.is_pointer_operation(left, right) => {
+                        return self
+                            .generate_pointer_binary_expression(operator, left, right, expression)
+                    }
                     _ => {}
                 }
 
@@ -232,6 +263,73 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
         }
     }
 
+    /// generates the given expression to be assigned to a variable of the given `target_type`
+    ///
+    /// an instance of a class or function block that is assigned to a variable of an
+    /// interface-type is converted to a reference to the instance and its vtable
+    pub fn generate_assignable_expression(
+        &self,
+        target_type: &DataType,
+        expression: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let interface = target_type.get_name();
+        if self.index.is_interface(interface) {
+            let source_type = self.annotations.get_type_or_void(expression, self.index);
+            if !self.index.is_interface(source_type.get_name()) {
+                return self.generate_interface_reference(interface, expression);
+            }
+        }
+        self.generate_expression(expression)
+    }
+
+    /// generates a reference of the given interface to the instance the given expression
+    /// points to. The reference consists of a pointer to the instance and a pointer to the
+    /// vtable of the instance's class for the interface.
+    fn generate_interface_reference(
+        &self,
+        interface: &str,
+        expression: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let builder = &self.llvm.builder;
+        let instance = self.generate_element_pointer(expression)?;
+        let class_name = instance.type_entry.get_name();
+        let vtable = self
+            .llvm_index
+            .find_associated_variable_value(&struct_generator::get_vtable_name(
+                class_name, interface,
+            ))
+            .map(BasicValueEnum::into_pointer_value)
+            .ok_or_else(|| {
+                CompileError::codegen_error(
+                    format!("{} does not implement {}", class_name, interface),
+                    expression.get_location(),
+                )
+            })?;
+
+        let void_ptr_type = self.llvm.context.i8_type().ptr_type(AddressSpace::Generic);
+        let interface_type = self.index.get_type(interface)?;
+        let reference = self.llvm.create_local_variable(
+            "",
+            &self
+                .llvm_index
+                .get_associated_type(interface_type.get_name())?,
+        );
+        let instance_member = builder.build_struct_gep(reference, 0, "").unwrap();
+        builder.build_store(
+            instance_member,
+            builder.build_pointer_cast(instance.ptr_value, void_ptr_type, ""),
+        );
+        let vtable_member = builder.build_struct_gep(reference, 1, "").unwrap();
+        builder.build_store(
+            vtable_member,
+            builder.build_pointer_cast(vtable, void_ptr_type, ""),
+        );
+        Ok((
+            interface_type.clone_type_information(),
+            builder.build_load(reference, ""),
+        ))
+    }
+
     fn generate_directaccess(
         &self,
         elements: &[AstStatement],
@@ -341,8 +439,17 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
         operator: &AstStatement,
         parameters: &Option<AstStatement>,
     ) -> Result<TypeAndValue<'a>, CompileError> {
+        if let Some(builtin) = self.find_builtin(operator) {
+            return self.generate_builtin_call(builtin, operator, parameters);
+        }
         let function_context = self.get_function_context(operator)?;
         let instance_and_index_entry = match operator {
+            AstStatement::Reference { name, .. }
+                if self.find_instance_method(function_context, name).is_some() =>
+            {
+                //a (possibly inherited) method of the current instance, called without THIS^
+                self.generate_instance_method_call_target(function_context, name, operator)
+            }
             AstStatement::Reference { name, .. } => {
                 //Get associated Variable or generate a variable for the type with the same name
                 let variable = self.index.find_callable_instance_variable(
@@ -393,44 +500,62 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
                     }
                 };
 
-                Ok((None, callable_reference, implementation))
+                Ok((None, callable_reference, implementation, false))
             }
-            AstStatement::QualifiedReference { .. } => {
-                let loaded_value = self.generate_element_pointer_for_rec(None, operator);
-                loaded_value.map(
-                    |TypeAndPointer {
-                         type_entry,
-                         ptr_value,
-                     }| {
-                        self.index
-                            .find_implementation(type_entry.get_name())
-                            .map(|implementation| {
-                                let (class_struct, method_struct) = if matches!(
-                                    implementation.get_implementation_type(),
-                                    &ImplementationType::Method
-                                ) {
-                                    (
-                                        Some(ptr_value),
-                                        self.allocate_function_struct_instance(
-                                            implementation.get_call_name(),
-                                            operator,
-                                        )
-                                        .unwrap(),
-                                    )
-                                } else {
-                                    (None, ptr_value)
-                                };
-                                (class_struct, method_struct, implementation)
-                            })
-                            .ok_or_else(|| CompileError::CodeGenError {
-                                message: format!(
-                                    "cannot generate call statement for {:?}",
-                                    operator
-                                ),
-                                location: operator.get_location(),
-                            })
-                    },
-                )?
+            AstStatement::QualifiedReference { elements, .. } => {
+                let TypeAndPointer {
+                    type_entry,
+                    ptr_value,
+                } = self.generate_element_pointer_for_rec(None, operator)?;
+                let implementation = self
+                    .index
+                    .find_implementation(type_entry.get_name())
+                    .ok_or_else(|| CompileError::CodeGenError {
+                        message: format!("cannot generate call statement for {:?}", operator),
+                        location: operator.get_location(),
+                    })?;
+                if matches!(
+                    implementation.get_implementation_type(),
+                    &ImplementationType::Method
+                ) {
+                    match self.find_virtual_method_declaration(implementation, elements) {
+                        //the call is dispatched through the instance's method table
+                        Some(declaration) => {
+                            let class_struct = self.generate_super_class_pointer(
+                                ptr_value,
+                                implementation
+                                    .get_associated_class_name()
+                                    .map(String::as_str)
+                                    .unwrap_or_default(),
+                                declaration
+                                    .get_associated_class_name()
+                                    .map(String::as_str)
+                                    .unwrap_or_default(),
+                                &operator.get_location(),
+                            )?;
+                            Ok((
+                                Some(class_struct),
+                                self.allocate_function_struct_instance(
+                                    declaration.get_call_name(),
+                                    operator,
+                                )?,
+                                declaration,
+                                true,
+                            ))
+                        }
+                        None => Ok((
+                            Some(ptr_value),
+                            self.allocate_function_struct_instance(
+                                implementation.get_call_name(),
+                                operator,
+                            )?,
+                            implementation,
+                            false,
+                        )),
+                    }
+                } else {
+                    Ok((None, ptr_value, implementation, false))
+                }
             }
             _ => Err(CompileError::CodeGenError {
                 message: format!("cannot generate call statement for {:?}", operator),
@@ -438,7 +563,7 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
             }),
         };
 
-        let (class_struct, instance, index_entry) = instance_and_index_entry?;
+        let (class_struct, instance, index_entry, is_dispatched) = instance_and_index_entry?;
         let function_name = index_entry.get_call_name();
         //Create parameters for input and output blocks
         let current_f = function_context.function;
@@ -470,9 +595,14 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
             .map(VariableIndexEntry::get_type_name)
             .or(Some("__VOID"))
             .and_then(|it| self.index.find_type_information(it));
+        let call_name = if is_dispatched {
+            struct_generator::get_dispatcher_name(function_name)
+        } else {
+            function_name.to_string()
+        };
         let function = self
             .llvm_index
-            .find_associated_implementation(function_name) //using the non error option to control the output error
+            .find_associated_implementation(&call_name) //using the non error option to control the output error
             .ok_or_else(|| CompileError::CodeGenError {
                 message: format!(
                     "No callable implementation associated to {:?}",
@@ -502,6 +632,110 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
         Ok((return_type.unwrap(), value))
     }
 
+    /// returns the class or function block the current implementation works on and its
+    /// (possibly inherited) method `name`, unless `name` refers to a callable variable
+    fn find_instance_method(
+        &self,
+        function_context: &FunctionContext,
+        name: &str,
+    ) -> Option<(&'b str, &'b ImplementationIndexEntry)> {
+        let linking_context = &function_context.linking_context;
+        if self
+            .index
+            .find_callable_instance_variable(
+                Some(linking_context.get_type_name()),
+                &[name.to_string()],
+            )
+            .is_some()
+        {
+            return None;
+        }
+        let class_name = self
+            .index
+            .find_instance_class(linking_context.get_call_name())?;
+        self.index
+            .find_method(class_name, name)
+            .filter(|it| it.get_implementation_type() == &ImplementationType::Method)
+            .map(|method| (class_name, method))
+    }
+
+    /// returns the class-struct, the method-struct and the called implementation for a call of
+    /// the method `name` on the instance the current implementation works on.
+    /// The instance is passed as the first parameter to methods and function blocks
+    fn generate_instance_method_call_target(
+        &self,
+        function_context: &FunctionContext<'a, '_>,
+        name: &str,
+        operator: &AstStatement,
+    ) -> Result<
+        (
+            Option<PointerValue<'a>>,
+            PointerValue<'a>,
+            &'b ImplementationIndexEntry,
+            bool,
+        ),
+        CompileError,
+    > {
+        let (class_name, method) = self
+            .find_instance_method(function_context, name)
+            .ok_or_else(|| CompileError::invalid_reference(name, operator.get_location()))?;
+        let instance = function_context
+            .function
+            .get_first_param()
+            .map(BasicValueEnum::into_pointer_value)
+            .ok_or_else(|| CompileError::missing_function(operator.get_location()))?;
+        let (called_method, is_dispatched) = match self.find_virtual_method_declaration(method, &[])
+        {
+            //the call is dispatched through the instance's method table
+            Some(declaration) => (declaration, true),
+            None => (method, false),
+        };
+        let class_struct = self.generate_super_class_pointer(
+            instance,
+            class_name,
+            called_method
+                .get_associated_class_name()
+                .map(String::as_str)
+                .unwrap_or_default(),
+            &operator.get_location(),
+        )?;
+        Ok((
+            Some(class_struct),
+            self.allocate_function_struct_instance(called_method.get_call_name(), operator)?,
+            called_method,
+            is_dispatched,
+        ))
+    }
+
+    /// returns the declaration of the given method if a call to it is dispatched through the
+    /// method table of the called instance. Calls of a super class' method via `SUPER^` are
+    /// bound statically
+    ///
+    /// - `method` the called method
+    /// - `operator_elements` the elements of the call's qualified operator
+    fn find_virtual_method_declaration(
+        &self,
+        method: &'b ImplementationIndexEntry,
+        operator_elements: &[AstStatement],
+    ) -> Option<&'b ImplementationIndexEntry> {
+        let is_super_call = operator_elements
+            .iter()
+            .rev()
+            .nth(1)
+            .map(|it| {
+                matches!(it, AstStatement::PointerAccess { reference, .. }
+                    if matches!(reference.as_ref(), AstStatement::Reference { name, .. }
+                        if name.eq_ignore_ascii_case(SUPER_POINTER_NAME)))
+            })
+            .unwrap_or(false);
+        if is_super_call {
+            return None;
+        }
+        let class_name = method.get_associated_class_name()?;
+        let method_name = method.get_call_name().rsplit('.').next()?;
+        self.index.find_virtual_method(class_name, method_name)
+    }
+
     /// generates a new instance of a function called `function_name` and returns a PointerValue to it
     ///
     /// - `function_name` the name of the function as registered in the index
@@ -643,14 +877,22 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
             let pointer_to_param = builder
                 .build_struct_gep(parameter_struct, index as u32, "")
                 .unwrap();
-            let parameter = parameter_type
+            let parameter_data_type = parameter_type
                 .or_else(|| {
                     self.index
                         .find_input_parameter(function_name, index as u32)
                         .and_then(|var| self.index.find_type(var.get_type_name()))
                 })
-                .map(|var| var.get_type_information())
                 .unwrap();
+            let parameter = parameter_data_type.get_type_information();
+            if parameter.is_variable_length_array() {
+                self.generate_variable_length_array_argument(
+                    pointer_to_param,
+                    parameter,
+                    assignment_statement,
+                )?;
+                return Ok(None);
+            }
             let (value_type, generated_exp) = if let DataTypeInformation::Pointer {
                 auto_deref: true,
                 ..
@@ -674,7 +916,7 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
                         )
                     })?
             } else {
-                self.generate_expression(assignment_statement)?
+                self.generate_assignable_expression(parameter_data_type, assignment_statement)?
             };
             let value = cast_if_needed(
                 self.llvm,
@@ -706,6 +948,15 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
         builder.position_at_end(*output_block);
         if let AstStatement::Reference { name, .. } = &*left {
             let parameter = self.index.find_member(function_name, name).unwrap();
+            //the parameter may be inherited from a super class
+            let declaring_pou = get_declaring_container(parameter);
+            let parameter_struct = self.generate_super_class_pointer(
+                parameter_struct,
+                function_name,
+                declaring_pou,
+                &left.get_location(),
+            )?;
+            let function_name = declaring_pou;
             let index = parameter.get_location_in_parent();
             let param_type = self
                 .index
@@ -752,6 +1003,15 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
         builder.position_at_end(*input_block);
         if let AstStatement::Reference { name, .. } = &*left {
             let parameter = self.index.find_member(function_name, name).unwrap();
+            //the parameter may be inherited from a super class
+            let declaring_pou = get_declaring_container(parameter);
+            let parameter_struct = self.generate_super_class_pointer(
+                parameter_struct,
+                function_name,
+                declaring_pou,
+                &left.get_location(),
+            )?;
+            let function_name = declaring_pou;
             let index = parameter.get_location_in_parent();
             let param_type = self.index.find_type(parameter.get_type_name());
             self.generate_single_parameter(
@@ -801,6 +1061,15 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
         })
     }
 
+    /// generates a gep for the given reference without dereferencing it automatically, so a
+    /// `REFERENCE TO` or VAR_IN_OUT variable yields the pointer to the reference itself
+    pub fn generate_reference_pointer(
+        &self,
+        reference_statement: &AstStatement,
+    ) -> Result<TypeAndPointer<'a, '_>, CompileError> {
+        self.generate_element_pointer_for_rec(None, reference_statement)
+    }
+
     /// geneartes a gep for the given reference with an optional qualifier
     ///
     /// - `qualifier` an optional qualifier for a reference (e.g. myStruct.x where myStruct is the qualifier for x)
@@ -829,8 +1098,27 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
             //.unwrap();
             let member_data_type = member.map(|it| it.get_type_name()).unwrap();
             let member_type = self.index.get_type(member_data_type)?;
-            let gep = self.llvm.get_member_pointer_from_struct(
+            //all members of a union start at offset 0, so we only need to reinterpret its pointer
+            if let DataTypeInformation::Union { .. } = l_value.type_entry.get_type_information() {
+                let member_ptr_type = self
+                    .llvm_index
+                    .get_associated_type(member_data_type)?
+                    .ptr_type(AddressSpace::Generic);
+                let member_ptr =
+                    self.llvm
+                        .builder
+                        .build_pointer_cast(l_value.ptr_value, member_ptr_type, name);
+                return Ok(TypeAndPointer::new(member_type, member_ptr));
+            }
+            //inherited members are accessed via the embedded super class instance
+            let struct_ptr = self.generate_super_class_pointer(
                 l_value.ptr_value,
+                qualifier_name,
+                member.map(get_declaring_container).unwrap(),
+                offset,
+            )?;
+            let gep = self.llvm.get_member_pointer_from_struct(
+                struct_ptr,
                 member_location,
                 name,
                 offset,
@@ -1006,6 +1294,42 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
                     let internal_type = self.index.get_type(inner_type_name)?; //TODO this is WRONG!!! typename is not correct
                     return Ok(TypeAndPointer::new(internal_type, pointer));
                 }
+                if let DataTypeInformation::VariableLengthArray {
+                    inner_type_name,
+                    dimensions,
+                    ..
+                } = lvalue.get_type_information()
+                {
+                    return self.generate_element_pointer_for_variable_length_array(
+                        lvalue.ptr_value,
+                        inner_type_name,
+                        *dimensions,
+                        access,
+                    );
+                }
+                if let DataTypeInformation::String { encoding, .. } = lvalue.get_type_information()
+                {
+                    //a string is accessed character by character, starting at 1
+                    let char_type = self.index.get_type(encoding.get_char_type_name())?;
+                    let char_pointer = self.llvm.builder.build_pointer_cast(
+                        lvalue.ptr_value,
+                        self.llvm_index
+                            .get_associated_type(char_type.get_name())?
+                            .ptr_type(AddressSpace::Generic),
+                        "",
+                    );
+                    let (_, index) = self.generate_expression(access)?;
+                    let index = index.into_int_value();
+                    let offset = self.llvm.builder.build_int_sub(
+                        index,
+                        index.get_type().const_int(1, false),
+                        "",
+                    );
+                    let pointer =
+                        self.llvm
+                            .load_array_element(char_pointer, &[offset], "tmpVar")?;
+                    return Ok(TypeAndPointer::new(char_type, pointer));
+                }
                 Err(CompileError::codegen_error(
                     "Invalid array access".to_string(),
                     access.get_location(),
@@ -1043,13 +1367,21 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
             }
             AstStatement::Reference { name, .. } => {
                 if let Some(qualifier) = qualifier {
-                    //Find if there is an action with the current name
-                    let qualified_name = format!("{}.{}", qualifier.type_entry.get_name(), name);
-                    let implementation = self.index.find_implementation(&qualified_name);
-                    if implementation.is_some() {
+                    //Find if there is an action or a (possibly inherited) method with the current name
+                    let qualifier_name = qualifier.type_entry.get_name();
+                    if let Some(implementation) = self.index.find_method(qualifier_name, name) {
+                        let declaring_class = implementation
+                            .get_associated_class_name()
+                            .map(String::as_str)
+                            .unwrap_or(qualifier_name);
                         let result = TypeAndPointer {
-                            type_entry: self.index.get_type(&qualified_name)?,
-                            ptr_value: qualifier.ptr_value,
+                            type_entry: self.index.get_type(implementation.get_call_name())?,
+                            ptr_value: self.generate_super_class_pointer(
+                                qualifier.ptr_value,
+                                qualifier_name,
+                                declaring_class,
+                                &reference.get_location(),
+                            )?,
                         };
                         return Ok(result);
                     }
@@ -1060,7 +1392,35 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
             AstStatement::ArrayAccess {
                 reference, access, ..
             } => self.generate_element_pointer_for_array(qualifier, reference, access),
+            AstStatement::CallStatement { .. } if qualifier.is_none() => {
+                //the result of a call (e.g. a property's getter) is stored in a temporary
+                //variable, so its members can be accessed
+                let (_, value) = self.generate_expression(reference)?;
+                let result_type = self.annotations.get_type_or_void(reference, self.index);
+                let llvm_type = self
+                    .llvm_index
+                    .get_associated_type(result_type.get_name())?;
+                let temp = self.llvm.create_local_variable("", &llvm_type);
+                self.llvm.builder.build_store(temp, value);
+                Ok(TypeAndPointer::new(result_type, temp))
+            }
             AstStatement::PointerAccess { reference, .. } => {
+                if let (None, AstStatement::Reference { name, .. }) =
+                    (qualifier, reference.as_ref())
+                {
+                    //THIS^ and SUPER^ refer to the current instance
+                    if let Some((pointer_type, instance)) =
+                        self.generate_instance_pointer(name, reference)?
+                    {
+                        if let DataTypeInformation::Pointer {
+                            inner_type_name, ..
+                        } = pointer_type.get_type_information()
+                        {
+                            let instance_type = self.index.get_type(inner_type_name)?;
+                            return Ok(TypeAndPointer::new(instance_type, instance));
+                        }
+                    }
+                }
                 let pointer = self.generate_element_pointer_for_rec(qualifier, reference)?;
                 self.deref(pointer.type_entry, pointer.ptr_value, reference)
             }
@@ -1071,6 +1431,80 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
         }
     }
 
+    /// returns the type and the value of the implicit `THIS` or `SUPER` pointer if `name`
+    /// refers to one of them inside the current method or function block
+    fn generate_instance_pointer(
+        &self,
+        name: &str,
+        context: &AstStatement,
+    ) -> Result<Option<(&'b DataType, PointerValue<'a>)>, CompileError> {
+        let function_context = match self.function_context {
+            Some(it) => it,
+            None => return Ok(None),
+        };
+        let pou_name = function_context.linking_context.get_call_name();
+        let pointer_type = match self.index.find_instance_pointer_type(pou_name, name) {
+            Some(it) => it,
+            None => return Ok(None),
+        };
+        //the instance is passed as the first parameter
+        let instance = function_context
+            .function
+            .get_first_param()
+            .map(BasicValueEnum::into_pointer_value)
+            .ok_or_else(|| CompileError::missing_function(context.get_location()))?;
+        let instance = if name.eq_ignore_ascii_case(SUPER_POINTER_NAME) {
+            let class_name = self.index.find_instance_class(pou_name).unwrap_or_default();
+            let super_class = self
+                .index
+                .find_super_class_name(class_name)
+                .unwrap_or_default();
+            self.generate_super_class_pointer(
+                instance,
+                class_name,
+                super_class,
+                &context.get_location(),
+            )?
+        } else {
+            instance
+        };
+        Ok(Some((pointer_type, instance)))
+    }
+
+    /// returns a pointer to the instance of `super_class` that is embedded in the given
+    /// `instance` of `class_name` by following the `__SUPER` members of the class hierarchy.
+    /// returns the given `instance` if `super_class` equals `class_name`
+    fn generate_super_class_pointer(
+        &self,
+        instance: PointerValue<'a>,
+        class_name: &str,
+        super_class: &str,
+        offset: &SourceRange,
+    ) -> Result<PointerValue<'a>, CompileError> {
+        let mut ptr = instance;
+        for class in self.index.get_class_hierarchy(class_name) {
+            if class.eq_ignore_ascii_case(super_class) {
+                break;
+            }
+            let super_member = self
+                .index
+                .find_local_member(class, SUPER_CLASS_MEMBER_NAME)
+                .ok_or_else(|| {
+                    CompileError::invalid_reference(
+                        &format!("{}.{}", class_name, super_class),
+                        offset.clone(),
+                    )
+                })?;
+            ptr = self.llvm.get_member_pointer_from_struct(
+                ptr,
+                super_member.get_location_in_parent(),
+                SUPER_CLASS_MEMBER_NAME,
+                offset,
+            )?;
+        }
+        Ok(ptr)
+    }
+
     /// generates the result of an int/bool binary-expression (+, -, *, /, %, ==)
     ///
     /// - `operator` the binary operator
@@ -1318,10 +1752,12 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
                 year,
                 month,
                 day,
+                is_long,
                 location,
                 ..
             } => self.create_const_int(
-                calculate_date_time(*year, *month, *day, 0, 0, 0, 0)
+                if *is_long { LDATE_TYPE } else { DATE_TYPE },
+                calculate_date_time(*year, *month, *day, 0, 0, 0, 0, *is_long)
                     .map_err(|op| CompileError::codegen_error(op, location.clone()))?,
             ),
             AstStatement::LiteralDateAndTime {
@@ -1331,22 +1767,34 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
                 hour,
                 min,
                 sec,
-                milli,
+                nano,
+                is_long,
                 location,
                 ..
             } => self.create_const_int(
-                calculate_date_time(*year, *month, *day, *hour, *min, *sec, *milli)
+                if *is_long {
+                    LDATE_AND_TIME_TYPE
+                } else {
+                    DATE_AND_TIME_TYPE
+                },
+                calculate_date_time(*year, *month, *day, *hour, *min, *sec, *nano, *is_long)
                     .map_err(|op| CompileError::codegen_error(op, location.clone()))?,
             ),
             AstStatement::LiteralTimeOfDay {
                 hour,
                 min,
                 sec,
-                milli,
+                nano,
+                is_long,
                 location,
                 ..
             } => self.create_const_int(
-                calculate_date_time(1970, 1, 1, *hour, *min, *sec, *milli)
+                if *is_long {
+                    LTIME_OF_DAY_TYPE
+                } else {
+                    TIME_OF_DAY_TYPE
+                },
+                calculate_date_time(1970, 1, 1, *hour, *min, *sec, *nano, *is_long)
                     .map_err(|op| CompileError::codegen_error(op, location.clone()))?,
             ),
             AstStatement::LiteralTime {
@@ -1358,14 +1806,18 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
                 micro,
                 nano,
                 negative,
+                is_long,
                 ..
-            } => self.create_const_int(calculate_time_nano(
-                *negative,
-                calculate_dhm_time_seconds(*day, *hour, *min, *sec),
-                *milli,
-                *micro,
-                *nano,
-            )),
+            } => self.create_const_int(
+                if *is_long { LTIME_TYPE } else { TIME_TYPE },
+                calculate_time_nano(
+                    *negative,
+                    calculate_dhm_time_seconds(*day, *hour, *min, *sec),
+                    *milli,
+                    *micro,
+                    *nano,
+                ),
+            ),
             AstStatement::LiteralReal { value, .. } => {
                 let type_context = self.get_type_hint_for(literal_statement, LREAL_TYPE)?;
                 let value = self.llvm.create_const_numeric(
@@ -1383,7 +1835,17 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
                     .annotations
                     .get_type_or_void(literal_statement, self.index)
                     .get_type_information();
-                if let DataTypeInformation::String { encoding, .. } = expected_type {
+                if expected_type.is_character() {
+                    //a character literal (e.g. CHAR#'A') is the code of its only character
+                    let code = value.chars().next().map(|it| it as u64).unwrap_or_default();
+                    let llvm_type = self
+                        .llvm_index
+                        .get_associated_type(expected_type.get_name())?;
+                    Ok((
+                        expected_type.clone(),
+                        llvm_type.into_int_type().const_int(code, false).into(),
+                    ))
+                } else if let DataTypeInformation::String { encoding, .. } = expected_type {
                     match encoding {
                         StringEncoding::Utf8 => self.llvm.create_const_utf8_string(value.as_str()),
                         StringEncoding::Utf16 => {
@@ -1412,7 +1874,17 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
             AstStatement::Assignment { .. } => {
                 self.generate_literal_struct(literal_statement, &literal_statement.get_location())
             }
-            AstStatement::CastStatement { target, .. } => self.generate_expression(target),
+            AstStatement::CastStatement {
+                target, type_name, ..
+            } => match (self.index.find_type_information(type_name), target.as_ref()) {
+                (
+                    Some(DataTypeInformation::Enum {
+                        name: enum_name, ..
+                    }),
+                    AstStatement::Reference { name, location, .. },
+                ) => self.generate_enum_element(enum_name, name, location),
+                _ => self.generate_expression(target),
+            },
             _ => Err(CompileError::codegen_error(
                 format!("Cannot generate Literal for {:?}", literal_statement),
                 literal_statement.get_location(),
@@ -1420,6 +1892,31 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
         }
     }
 
+    /// generates the value of the element `element_name` qualified with its enum (e.g. `Color#Red`).
+    /// The element is identified by its enum, so two enums may declare elements with the same name
+    fn generate_enum_element(
+        &self,
+        enum_name: &str,
+        element_name: &str,
+        location: &SourceRange,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let element = self
+            .index
+            .find_enum_element(enum_name, element_name)
+            .ok_or_else(|| CompileError::InvalidReference {
+                reference: format!("{}#{}", enum_name, element_name),
+                location: location.clone(),
+            })?;
+        let value = element.initial_value.as_ref().ok_or_else(|| {
+            CompileError::codegen_error(
+                format!("Cannot generate value for {}#{}", enum_name, element_name),
+                location.clone(),
+            )
+        })?;
+        let enum_type = self.index.get_type_information(enum_name)?;
+        self.morph_to_typed(&enum_type).generate_literal(value)
+    }
+
     /// returns the data type associated to the given statement using the following strategy:
     /// - 1st try: fetch the type associated via the `self.annotations`
     /// - 2nd try: fetch the type associated with the given `default_type_name`
@@ -1701,7 +2198,7 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
 
         builder.position_at_end(continue_branch);
         //Generate phi
-        let target_type = if left_type.get_size() > right_type.get_size() {
+        let target_type = if left_type.get_size(self.index) > right_type.get_size(self.index) {
             left_type
         } else {
             right_type
@@ -1718,10 +2215,14 @@ impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
         Ok((target_type, phi_value.as_basic_value()))
     }
 
-    fn create_const_int(&self, value: i64) -> Result<TypeAndValue<'a>, CompileError> {
-        let type_info = self.index.get_type_information(LINT_TYPE)?;
+    fn create_const_int(
+        &self,
+        type_name: &str,
+        value: i64,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let type_info = self.index.get_type_information(type_name)?;
         let value = self.llvm.create_const_numeric(
-            &self.llvm_index.get_associated_type(LINT_TYPE)?,
+            &self.llvm_index.get_associated_type(type_name)?,
             value.to_string().as_str(),
         )?;
         Ok((type_info, value))
@@ -1750,8 +2251,9 @@ fn calculate_time_nano(negative: bool, sec: f64, milli: f64, micro: f64, nano: u
     }
 }
 
-/// calculates the milliseconds since 1970-01-01-00:00:00 for the given
-/// point in time
+/// calculates the milliseconds (or nanoseconds for the long date types) since
+/// 1970-01-01-00:00:00 for the given point in time
+#[allow(clippy::too_many_arguments)]
 fn calculate_date_time(
     year: i32,
     month: u32,
@@ -1759,20 +2261,40 @@ fn calculate_date_time(
     hour: u32,
     min: u32,
     sec: u32,
-    milli: u32,
+    nano: u32,
+    is_long: bool,
 ) -> Result<i64, String> {
     if let LocalResult::Single(date_time) = Utc
         .ymd_opt(year, month, day)
-        .and_hms_milli_opt(hour, min, sec, milli)
+        .and_hms_nano_opt(hour, min, sec, nano)
     {
-        return Ok(date_time.timestamp_millis());
+        if !is_long {
+            return Ok(date_time.timestamp_millis());
+        }
+        //the nanoseconds of the long types cover the years 1677 to 2262
+        if let Some(nanos) = date_time
+            .timestamp()
+            .checked_mul(1_000_000_000)
+            .and_then(|it| it.checked_add(i64::from(date_time.timestamp_subsec_nanos())))
+        {
+            return Ok(nanos);
+        }
     }
     Err(format!(
-        "Invalid Date {}-{}-{}-{}:{}:{}.{}",
-        year, month, day, hour, min, sec, milli
+        "Invalid Date {}-{}-{}-{}:{}:{}.{:09}",
+        year, month, day, hour, min, sec, nano
     ))
 }
 
+/// returns the name of the pou or struct that declares the given member
+fn get_declaring_container(member: &VariableIndexEntry) -> &str {
+    let qualified_name = member.get_qualified_name();
+    qualified_name
+        .rfind('.')
+        .map(|it| &qualified_name[..it])
+        .unwrap_or(qualified_name)
+}
+
 /// Returns true if the current statement has a return access.
 fn has_direct_access(statement: &AstStatement) -> bool {
     if let AstStatement::QualifiedReference { elements, .. } = statement {
@@ -0,0 +1,301 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+
+//! generates variable-length arrays (`ARRAY[*] OF ...`), they are passed as a struct holding a
+//! pointer to the array's first element and the lower and upper bound of every dimension
+use inkwell::{
+    values::{IntValue, PointerValue},
+    AddressSpace,
+};
+
+use super::ExpressionCodeGenerator;
+use crate::{
+    ast::AstStatement,
+    builtins::BuiltIn,
+    codegen::{llvm_typesystem::cast_if_needed, TypeAndPointer, TypeAndValue},
+    compile_error::CompileError,
+    typesystem::{DataTypeInformation, DINT_TYPE},
+};
+
+/// the member of a variable-length array's struct pointing to the first element
+const DATA_MEMBER: u32 = 0;
+/// the member of a variable-length array's struct holding the bounds as (lower, upper) pairs
+const BOUNDS_MEMBER: u32 = 1;
+
+impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
+    /// stores the array passed for a variable-length array parameter into the parameter's struct.
+    /// A fixed array passes a pointer to its first element and its constant bounds, a
+    /// variable-length array is passed on as it is
+    ///
+    /// - `parameter_pointer` the pointer to the parameter's struct
+    /// - `parameter_type` the type of the variable-length array parameter
+    /// - `argument` the array passed for the parameter
+    pub(super) fn generate_variable_length_array_argument(
+        &self,
+        parameter_pointer: PointerValue<'a>,
+        parameter_type: &DataTypeInformation,
+        argument: &AstStatement,
+    ) -> Result<(), CompileError> {
+        let builder = &self.llvm.builder;
+        let argument_pointer = self.generate_element_pointer(argument)?;
+        match (parameter_type, argument_pointer.get_type_information()) {
+            (
+                DataTypeInformation::VariableLengthArray {
+                    inner_type_name,
+                    dimensions,
+                    ..
+                },
+                DataTypeInformation::VariableLengthArray {
+                    inner_type_name: argument_inner_type_name,
+                    dimensions: argument_dimensions,
+                    ..
+                },
+            ) if dimensions == argument_dimensions
+                && self.is_same_element_type(inner_type_name, argument_inner_type_name)? =>
+            {
+                let value = builder.build_load(argument_pointer.ptr_value, "");
+                builder.build_store(parameter_pointer, value);
+                Ok(())
+            }
+            (
+                DataTypeInformation::VariableLengthArray {
+                    inner_type_name,
+                    dimensions,
+                    ..
+                },
+                DataTypeInformation::Array {
+                    inner_type_name: argument_inner_type_name,
+                    dimensions: argument_dimensions,
+                    ..
+                },
+            ) if *dimensions == argument_dimensions.len()
+                && self.is_same_element_type(inner_type_name, argument_inner_type_name)? =>
+            {
+                let location = argument.get_location();
+                let element_pointer_type = self
+                    .llvm_index
+                    .get_associated_type(inner_type_name)?
+                    .ptr_type(AddressSpace::Generic);
+                let first_element = builder.build_pointer_cast(
+                    argument_pointer.ptr_value,
+                    element_pointer_type,
+                    "",
+                );
+                let data_pointer = self.llvm.get_member_pointer_from_struct(
+                    parameter_pointer,
+                    DATA_MEMBER,
+                    "",
+                    &location,
+                )?;
+                builder.build_store(data_pointer, first_element);
+
+                let i32_type = self.llvm.i32_type();
+                let bounds: Vec<IntValue> = argument_dimensions
+                    .iter()
+                    .flat_map(|it| vec![it.start_offset, it.end_offset])
+                    .map(|it| i32_type.const_int(it as u64, true))
+                    .collect();
+                let bounds_pointer = self.llvm.get_member_pointer_from_struct(
+                    parameter_pointer,
+                    BOUNDS_MEMBER,
+                    "",
+                    &location,
+                )?;
+                builder.build_store(bounds_pointer, i32_type.const_array(&bounds));
+                Ok(())
+            }
+            (_, argument_type) => Err(CompileError::codegen_error(
+                format!(
+                    "Invalid argument, cannot pass {} as {}",
+                    argument_type.get_name(),
+                    parameter_type.get_name()
+                ),
+                argument.get_location(),
+            )),
+        }
+    }
+
+    /// generates a gep for an element of a variable-length array. The index is calculated at
+    /// runtime using the bounds passed with the array, the elements are stored in row-major order
+    ///
+    /// - `array_pointer` the pointer to the variable-length array's struct
+    /// - `inner_type_name` the name of the array's element type
+    /// - `dimensions` the number of the array's dimensions
+    /// - `access` the accessor expression (the expression between the brackets: reference[access])
+    pub(super) fn generate_element_pointer_for_variable_length_array(
+        &self,
+        array_pointer: PointerValue<'a>,
+        inner_type_name: &str,
+        dimensions: usize,
+        access: &AstStatement,
+    ) -> Result<TypeAndPointer<'a, 'b>, CompileError> {
+        let statements = access.get_as_list();
+        if statements.is_empty() || statements.len() != dimensions {
+            return Err(CompileError::codegen_error(
+                format!(
+                    "Mismatched array access : {} -> {} ",
+                    statements.len(),
+                    dimensions
+                ),
+                access.get_location(),
+            ));
+        }
+        let location = access.get_location();
+        let builder = &self.llvm.builder;
+        let i32_type = self.llvm.i32_type();
+        let dint_type = self.index.get_type_information(DINT_TYPE)?;
+        let bounds_pointer = self.llvm.get_member_pointer_from_struct(
+            array_pointer,
+            BOUNDS_MEMBER,
+            "",
+            &location,
+        )?;
+
+        let mut offset: Option<IntValue> = None;
+        for (dimension, statement) in statements.iter().enumerate() {
+            let (access_type, access_value) = self.generate_expression(statement)?;
+            let access_value = cast_if_needed(
+                self.llvm,
+                self.index,
+                &dint_type,
+                access_value,
+                &access_type,
+                statement,
+            )?
+            .into_int_value();
+            let lower_bound = self.load_bound(
+                bounds_pointer,
+                i32_type.const_int(2 * dimension as u64, false),
+            )?;
+            let index = builder.build_int_sub(access_value, lower_bound, "");
+            offset = Some(if let Some(offset) = offset {
+                //row-major order: skip `offset` rows of this dimension's length
+                let upper_bound = self.load_bound(
+                    bounds_pointer,
+                    i32_type.const_int(2 * dimension as u64 + 1, false),
+                )?;
+                let length = builder.build_int_add(
+                    builder.build_int_sub(upper_bound, lower_bound, ""),
+                    i32_type.const_int(1, false),
+                    "",
+                );
+                builder.build_int_add(builder.build_int_mul(offset, length, ""), index, "")
+            } else {
+                index
+            });
+        }
+
+        let data_pointer =
+            self.llvm
+                .get_member_pointer_from_struct(array_pointer, DATA_MEMBER, "", &location)?;
+        let data = builder.build_load(data_pointer, "").into_pointer_value();
+        let pointer = self
+            .llvm
+            .load_array_element(data, &[offset.unwrap()], "tmpVar")?;
+        Ok(TypeAndPointer::new(
+            self.index.get_type(inner_type_name)?,
+            pointer,
+        ))
+    }
+
+    /// generates `LOWER_BOUND(array, dimension)` or `UPPER_BOUND(array, dimension)`, the
+    /// dimensions are counted starting at 1. The bounds of a fixed array are known at compile
+    /// time, the bounds of a variable-length array are loaded from its struct
+    pub(super) fn generate_array_bound(
+        &self,
+        builtin: BuiltIn,
+        array: &AstStatement,
+        dimension: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let dint_type = self.index.get_type_information(DINT_TYPE)?;
+        let i32_type = self.llvm.i32_type();
+        let is_upper_bound = builtin == BuiltIn::UpperBound;
+        match self
+            .annotations
+            .get_type_or_void(array, self.index)
+            .get_type_information()
+        {
+            DataTypeInformation::Array { dimensions, .. } => {
+                let dimension = match dimension {
+                    AstStatement::LiteralInteger { value, .. }
+                        if *value >= 1 && *value as usize <= dimensions.len() =>
+                    {
+                        &dimensions[*value as usize - 1]
+                    }
+                    _ => {
+                        return Err(CompileError::codegen_error(
+                            format!(
+                                "{} expects a constant dimension between 1 and {}",
+                                builtin.get_name(),
+                                dimensions.len()
+                            ),
+                            dimension.get_location(),
+                        ))
+                    }
+                };
+                let bound = if is_upper_bound {
+                    dimension.end_offset
+                } else {
+                    dimension.start_offset
+                };
+                Ok((dint_type, i32_type.const_int(bound as u64, true).into()))
+            }
+            DataTypeInformation::VariableLengthArray { .. } => {
+                let builder = &self.llvm.builder;
+                let array_pointer = self.generate_element_pointer(array)?;
+                let (dimension_type, dimension_value) = self.generate_expression(dimension)?;
+                let dimension_value = cast_if_needed(
+                    self.llvm,
+                    self.index,
+                    &dint_type,
+                    dimension_value,
+                    &dimension_type,
+                    dimension,
+                )?
+                .into_int_value();
+                //the bounds of the n-th dimension are stored at 2 * (n - 1) and 2 * (n - 1) + 1
+                let index = builder.build_int_mul(
+                    builder.build_int_sub(dimension_value, i32_type.const_int(1, false), ""),
+                    i32_type.const_int(2, false),
+                    "",
+                );
+                let index = if is_upper_bound {
+                    builder.build_int_add(index, i32_type.const_int(1, false), "")
+                } else {
+                    index
+                };
+                let bounds_pointer = self.llvm.get_member_pointer_from_struct(
+                    array_pointer.ptr_value,
+                    BOUNDS_MEMBER,
+                    "",
+                    &array.get_location(),
+                )?;
+                let bound = self.load_bound(bounds_pointer, index)?;
+                Ok((dint_type, bound.into()))
+            }
+            _ => Err(CompileError::codegen_error(
+                format!("{} expects an array", builtin.get_name()),
+                array.get_location(),
+            )),
+        }
+    }
+
+    /// loads the bound at the given index from the bounds of a variable-length array
+    fn load_bound(
+        &self,
+        bounds_pointer: PointerValue<'a>,
+        index: IntValue<'a>,
+    ) -> Result<IntValue<'a>, CompileError> {
+        let pointer = self.llvm.load_array_element(
+            bounds_pointer,
+            &[self.llvm.i32_type().const_zero(), index],
+            "",
+        )?;
+        Ok(self.llvm.builder.build_load(pointer, "").into_int_value())
+    }
+
+    /// returns true if both element types are represented by the same llvm type
+    fn is_same_element_type(&self, left: &str, right: &str) -> Result<bool, CompileError> {
+        Ok(self.llvm_index.get_associated_type(left)?
+            == self.llvm_index.get_associated_type(right)?)
+    }
+}
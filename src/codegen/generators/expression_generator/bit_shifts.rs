@@ -0,0 +1,146 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+
+//! generates the bit-string functions SHL, SHR, ROL and ROR
+use inkwell::{
+    types::BasicTypeEnum,
+    values::{BasicValueEnum, IntValue},
+    IntPredicate,
+};
+
+use super::ExpressionCodeGenerator;
+use crate::{
+    ast::AstStatement,
+    builtins::BuiltIn,
+    codegen::{llvm_typesystem::cast_if_needed, TypeAndValue},
+    compile_error::CompileError,
+    typesystem::{self, DataTypeInformation},
+};
+
+impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
+    /// generates `SHL(IN, N)`, `SHR(IN, N)`, `ROL(IN, N)` or `ROR(IN, N)` on the bits of `IN`,
+    /// the result has the type of `IN`
+    ///
+    /// - shifting by the bit width of `IN` or more results in 0
+    /// - rotations use the llvm `fshl` and `fshr` intrinsics, they rotate by `N` modulo the
+    ///   bit width
+    pub(super) fn generate_bit_shift(
+        &self,
+        builtin: BuiltIn,
+        input: &AstStatement,
+        count: &AstStatement,
+        context: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let (input_type, input_value) = self.generate_expression(input)?;
+        let input_type = self.get_intrinsic_type(input_type);
+        let value = match (input_value, &input_type) {
+            (BasicValueEnum::IntValue(value), DataTypeInformation::Integer { size, .. })
+                if *size > 1 && !typesystem::is_date_or_time_type(&input_type) =>
+            {
+                value
+            }
+            _ => {
+                return Err(CompileError::codegen_error(
+                    format!(
+                        "invalid type, {} expects a bit-string but found {}",
+                        builtin.get_name(),
+                        input_type.get_name()
+                    ),
+                    input.get_location(),
+                ))
+            }
+        };
+        let (count_type, count_value) = self.generate_expression(count)?;
+        let count_type = self.get_intrinsic_type(count_type);
+        let narrowed_count = cast_if_needed(
+            self.llvm,
+            self.index,
+            &input_type,
+            count_value,
+            &count_type,
+            count,
+        )?
+        .into_int_value();
+
+        let value = match builtin {
+            BuiltIn::Shl | BuiltIn::Shr => {
+                //the count is range-checked before it is narrowed to the type of IN,
+                //otherwise SHL(BYTE#1, 256) would shift by 0
+                let unchecked_count = match (count_value, &count_type) {
+                    (BasicValueEnum::IntValue(wide_count), DataTypeInformation::Integer { .. })
+                        if count_type.get_size(self.index) > input_type.get_size(self.index) =>
+                    {
+                        wide_count
+                    }
+                    _ => narrowed_count,
+                };
+                self.generate_shift(builtin, value, narrowed_count, unchecked_count)
+            }
+            //the bit widths are powers of 2, so narrowing the count keeps it modulo the bit width
+            _ => self.generate_rotation(builtin, value, narrowed_count, context)?,
+        };
+        Ok((input_type, value))
+    }
+
+    /// generates a shift with `shl` or `lshr`. Llvm does not define shifts by the bit width or
+    /// more, so they are replaced by 0. The range is checked on `unchecked_count`, the count
+    /// before it was narrowed to the type of the value
+    fn generate_shift(
+        &self,
+        builtin: BuiltIn,
+        value: IntValue<'a>,
+        count: IntValue<'a>,
+        unchecked_count: IntValue<'a>,
+    ) -> BasicValueEnum<'a> {
+        let builder = &self.llvm.builder;
+        let shifted = if builtin == BuiltIn::Shl {
+            builder.build_left_shift(value, count, "tmpVar")
+        } else {
+            builder.build_right_shift(value, count, false, "tmpVar")
+        };
+        let int_type = value.get_type();
+        let count_type = unchecked_count.get_type();
+        let bit_width = count_type.const_int(int_type.get_bit_width() as u64, false);
+        let is_in_range =
+            builder.build_int_compare(IntPredicate::ULT, unchecked_count, bit_width, "");
+        builder.build_select(is_in_range, shifted, int_type.const_zero(), "tmpVar")
+    }
+
+    /// generates a rotation as a funnel shift of the value with itself
+    fn generate_rotation(
+        &self,
+        builtin: BuiltIn,
+        value: IntValue<'a>,
+        count: IntValue<'a>,
+        context: &AstStatement,
+    ) -> Result<BasicValueEnum<'a>, CompileError> {
+        let int_type = value.get_type();
+        let intrinsic = format!(
+            "llvm.{}.i{}",
+            if builtin == BuiltIn::Rol {
+                "fshl"
+            } else {
+                "fshr"
+            },
+            int_type.get_bit_width()
+        );
+        let parameter_type: BasicTypeEnum = int_type.into();
+        let function_type =
+            int_type.fn_type(&[parameter_type, parameter_type, parameter_type], false);
+        let function = self.get_intrinsic(&intrinsic, function_type, context)?;
+        self.llvm
+            .builder
+            .build_call(
+                function,
+                &[value.into(), value.into(), count.into()],
+                "tmpVar",
+            )
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| {
+                CompileError::codegen_error(
+                    format!("{} did not return a value", intrinsic),
+                    context.get_location(),
+                )
+            })
+    }
+}
@@ -0,0 +1,654 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+
+//! generates the calls of builtin functions inline
+use inkwell::{
+    module::Linkage,
+    types::{BasicTypeEnum, FunctionType},
+    values::{BasicValueEnum, FloatValue, FunctionValue},
+    FloatPredicate, IntPredicate,
+};
+
+use super::ExpressionCodeGenerator;
+use crate::{
+    ast::{flatten_expression_list, AstStatement},
+    builtins::{self, BuiltIn},
+    codegen::{llvm_typesystem::cast_if_needed, TypeAndValue},
+    compile_error::CompileError,
+    resolver::StatementAnnotation,
+    typesystem::{
+        self, DataTypeInformation, BOOL_TYPE, DINT_TYPE, LINT_TYPE, LREAL_TYPE, USINT_TYPE,
+    },
+};
+
+/// the name of the helper function that raises a LINT to the power of a LINT
+const INTEGER_EXPONENTIATION_HELPER: &str = "__expt_lint";
+
+impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
+    /// returns the builtin function called by the given operator or None if the operator
+    /// refers to a declared POU
+    pub(super) fn find_builtin(&self, operator: &AstStatement) -> Option<BuiltIn> {
+        match self.annotations.get(operator) {
+            Some(StatementAnnotation::Function { qualified_name, .. })
+                if self.index.find_implementation(qualified_name).is_none() =>
+            {
+                BuiltIn::find(qualified_name)
+            }
+            _ => None,
+        }
+    }
+
+    /// generates the call of a builtin function with the given parameters
+    pub(super) fn generate_builtin_call(
+        &self,
+        builtin: BuiltIn,
+        operator: &AstStatement,
+        parameters: &Option<AstStatement>,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let parameters = parameters
+            .as_ref()
+            .map(flatten_expression_list)
+            .unwrap_or_default();
+        match (builtin, parameters.as_slice()) {
+            (BuiltIn::Expt, [base, exponent]) => {
+                self.generate_exponentiation(base, exponent, operator)
+            }
+            (BuiltIn::IsValidRef, [reference]) => self.generate_is_valid_reference(reference),
+            (BuiltIn::LowerBound, [array, dimension])
+            | (BuiltIn::UpperBound, [array, dimension]) => {
+                self.generate_array_bound(builtin, array, dimension)
+            }
+            (BuiltIn::Min, inputs) | (BuiltIn::Max, inputs) if inputs.len() >= 2 => {
+                self.generate_min_max(builtin, inputs, operator)
+            }
+            (BuiltIn::Limit, [minimum, input, maximum]) => {
+                self.generate_limit(minimum, input, maximum, operator)
+            }
+            (BuiltIn::Sel, [selector, input0, input1]) => {
+                self.generate_sel(selector, input0, input1, operator)
+            }
+            (BuiltIn::Mux, [selector, inputs @ ..]) if !inputs.is_empty() => {
+                self.generate_mux(selector, inputs, operator)
+            }
+            (BuiltIn::Move, [input]) => self.generate_expression(input),
+            (BuiltIn::Shl, [input, count])
+            | (BuiltIn::Shr, [input, count])
+            | (BuiltIn::Rol, [input, count])
+            | (BuiltIn::Ror, [input, count]) => {
+                self.generate_bit_shift(builtin, input, count, operator)
+            }
+            (BuiltIn::Abs, [input]) => self.generate_abs(input, operator),
+            (builtin, inputs)
+                if builtin.is_real_function()
+                    && inputs.len() == builtin.get_real_function_arity() =>
+            {
+                self.generate_real_function(builtin, inputs, operator)
+            }
+            (BuiltIn::Len, [_])
+            | (BuiltIn::Left, [_, _])
+            | (BuiltIn::Right, [_, _])
+            | (BuiltIn::Mid, [_, _, _])
+            | (BuiltIn::Insert, [_, _, _])
+            | (BuiltIn::Delete, [_, _, _])
+            | (BuiltIn::Replace, [_, _, _, _])
+            | (BuiltIn::Find, [_, _]) => {
+                self.generate_string_function(builtin, parameters.as_slice(), operator)
+            }
+            (BuiltIn::Concat, inputs) if !inputs.is_empty() => {
+                self.generate_string_function(builtin, inputs, operator)
+            }
+            (BuiltIn::Conversion { source, target }, [argument]) => {
+                self.generate_conversion(source, target, false, argument, operator)
+            }
+            (BuiltIn::Trunc { source, target }, [argument]) => {
+                self.generate_conversion(source, target, true, argument, operator)
+            }
+            _ => Err(CompileError::codegen_error(
+                format!(
+                    "Invalid number of parameters ({}) for {}",
+                    parameters.len(),
+                    builtin.get_name()
+                ),
+                operator.get_location(),
+            )),
+        }
+    }
+
+    /// generates `base ** exponent` using the llvm `pow` and `powi` intrinsics
+    ///
+    /// the result has the bigger type of the two operands, an integer raised to the power of
+    /// an integer is calculated by multiplying integers, integer bases with a real exponent are
+    /// raised as an LREAL and converted back to the resulting integer type
+    pub(super) fn generate_exponentiation(
+        &self,
+        base: &AstStatement,
+        exponent: &AstStatement,
+        context: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let (base_type, base_value) = self.generate_expression(base)?;
+        let (exponent_type, exponent_value) = self.generate_expression(exponent)?;
+        let base_type = self.get_intrinsic_type(base_type);
+        let exponent_type = self.get_intrinsic_type(exponent_type);
+        if !base_type.is_numerical() || !exponent_type.is_numerical() {
+            return Err(CompileError::codegen_error(
+                format!(
+                    "invalid types, cannot raise {} to the power of {}",
+                    base_type.get_name(),
+                    exponent_type.get_name()
+                ),
+                context.get_location(),
+            ));
+        }
+        let result_type = typesystem::get_bigger_type(&base_type, &exponent_type, self.index);
+        if base_type.is_int() && exponent_type.is_int() {
+            return self.generate_integer_exponentiation(
+                result_type,
+                (base_type, base_value),
+                (exponent_type, exponent_value),
+                context,
+            );
+        }
+        let float_type = if result_type.is_float() {
+            result_type.clone()
+        } else {
+            self.index.get_type_information(LREAL_TYPE)?
+        };
+
+        let base_value = cast_if_needed(
+            self.llvm,
+            self.index,
+            &float_type,
+            base_value,
+            &base_type,
+            base,
+        )?
+        .into_float_value();
+        let float_llvm_type = base_value.get_type();
+        let suffix = match float_type {
+            DataTypeInformation::Float { size: 32, .. } => "f32",
+            _ => "f64",
+        };
+        let (intrinsic, exponent_value, exponent_llvm_type): (_, _, BasicTypeEnum) =
+            if exponent_type.is_int() {
+                //powi expects the exponent as an i32
+                let dint_type = self.index.get_type_information(DINT_TYPE)?;
+                let exponent_value = cast_if_needed(
+                    self.llvm,
+                    self.index,
+                    &dint_type,
+                    exponent_value,
+                    &exponent_type,
+                    exponent,
+                )?;
+                (
+                    format!("llvm.powi.{}", suffix),
+                    exponent_value,
+                    self.llvm.i32_type().into(),
+                )
+            } else {
+                let exponent_value = cast_if_needed(
+                    self.llvm,
+                    self.index,
+                    &float_type,
+                    exponent_value,
+                    &exponent_type,
+                    exponent,
+                )?;
+                (
+                    format!("llvm.pow.{}", suffix),
+                    exponent_value,
+                    float_llvm_type.into(),
+                )
+            };
+
+        let function_type =
+            float_llvm_type.fn_type(&[float_llvm_type.into(), exponent_llvm_type], false);
+        let function = self.get_intrinsic(&intrinsic, function_type, context)?;
+        let value = self
+            .llvm
+            .builder
+            .build_call(function, &[base_value.into(), exponent_value], "tmpVar")
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| {
+                CompileError::codegen_error(
+                    format!("{} did not return a value", intrinsic),
+                    context.get_location(),
+                )
+            })?;
+        let value = cast_if_needed(
+            self.llvm,
+            self.index,
+            &result_type,
+            value,
+            &float_type,
+            context,
+        )?;
+        Ok((result_type, value))
+    }
+
+    /// generates `base ** exponent` for two integers. Both are raised as a LINT by the
+    /// `__expt_lint` helper and truncated to the result type, so an overflow wraps around like
+    /// a multiplication. A negative exponent results in 0 unless the base is 1 or -1
+    fn generate_integer_exponentiation(
+        &self,
+        result_type: DataTypeInformation,
+        (base_type, base_value): TypeAndValue<'a>,
+        (exponent_type, exponent_value): TypeAndValue<'a>,
+        context: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let builder = &self.llvm.builder;
+        let lint_type = self.index.get_type_information(LINT_TYPE)?;
+        let i64_type = self.llvm.context.i64_type();
+        let base_value = cast_if_needed(
+            self.llvm, self.index, &lint_type, base_value, &base_type, context,
+        )?
+        .into_int_value();
+        let exponent_value = cast_if_needed(
+            self.llvm,
+            self.index,
+            &lint_type,
+            exponent_value,
+            &exponent_type,
+            context,
+        )?
+        .into_int_value();
+
+        let is_negative = if exponent_type.is_signed_int() {
+            Some(builder.build_int_compare(
+                IntPredicate::SLT,
+                exponent_value,
+                i64_type.const_zero(),
+                "",
+            ))
+        } else {
+            None
+        };
+        //the helper expects the exponent's magnitude, it is treated as unsigned
+        let magnitude = match is_negative {
+            Some(is_negative) => builder
+                .build_select(
+                    is_negative,
+                    builder.build_int_neg(exponent_value, ""),
+                    exponent_value,
+                    "",
+                )
+                .into_int_value(),
+            None => exponent_value,
+        };
+        let function = self.get_integer_exponentiation_helper(context)?;
+        let power = builder
+            .build_call(function, &[base_value.into(), magnitude.into()], "")
+            .try_as_basic_value()
+            .left()
+            .map(BasicValueEnum::into_int_value)
+            .ok_or_else(|| {
+                CompileError::codegen_error(
+                    format!("{} did not return a value", INTEGER_EXPONENTIATION_HELPER),
+                    context.get_location(),
+                )
+            })?;
+
+        //base ** -exponent = 1 / (base ** exponent) is only an integer for the bases 1 and -1
+        let power = match is_negative {
+            Some(is_negative) => {
+                let is_unit = builder.build_or(
+                    builder.build_int_compare(
+                        IntPredicate::EQ,
+                        base_value,
+                        i64_type.const_int(1, false),
+                        "",
+                    ),
+                    builder.build_int_compare(
+                        IntPredicate::EQ,
+                        base_value,
+                        i64_type.const_all_ones(),
+                        "",
+                    ),
+                    "",
+                );
+                let is_fraction =
+                    builder.build_and(is_negative, builder.build_not(is_unit, ""), "");
+                builder
+                    .build_select(is_fraction, i64_type.const_zero(), power, "")
+                    .into_int_value()
+            }
+            None => power,
+        };
+        let value = cast_if_needed(
+            self.llvm,
+            self.index,
+            &result_type,
+            power.into(),
+            &lint_type,
+            context,
+        )?;
+        Ok((result_type, value))
+    }
+
+    /// returns the helper `__expt_lint(base, exponent)` of the current module, it is generated
+    /// when it is used for the first time
+    fn get_integer_exponentiation_helper(
+        &self,
+        context: &AstStatement,
+    ) -> Result<FunctionValue<'a>, CompileError> {
+        let module = self.get_function_context(context)?.module;
+        if let Some(function) = module.get_function(INTEGER_EXPONENTIATION_HELPER) {
+            return Ok(function);
+        }
+        let i64_type = self.llvm.context.i64_type();
+        let function = module.add_function(
+            INTEGER_EXPONENTIATION_HELPER,
+            i64_type.fn_type(&[i64_type.into(), i64_type.into()], false),
+            Some(Linkage::Internal),
+        );
+
+        let current_block = self.llvm.builder.get_insert_block();
+        self.generate_integer_exponentiation_helper(function);
+        if let Some(block) = current_block {
+            self.llvm.builder.position_at_end(block);
+        }
+        Ok(function)
+    }
+
+    /// generates the body of `__expt_lint(base, exponent)` which raises the base to the power
+    /// of the (unsigned) exponent by repeated squaring
+    fn generate_integer_exponentiation_helper(&self, function: FunctionValue<'a>) {
+        let context = self.llvm.context;
+        let builder = &self.llvm.builder;
+        let base = function.get_nth_param(0).unwrap().into_int_value();
+        let exponent = function.get_nth_param(1).unwrap().into_int_value();
+        let i64_type = context.i64_type();
+        let one = i64_type.const_int(1, false);
+
+        let entry = context.append_basic_block(function, "entry");
+        let condition = context.append_basic_block(function, "condition");
+        let multiply = context.append_basic_block(function, "multiply");
+        let exit = context.append_basic_block(function, "exit");
+
+        builder.position_at_end(entry);
+        builder.build_unconditional_branch(condition);
+
+        //while exponent <> 0, multiply the result by the factors of the exponent's set bits
+        builder.position_at_end(condition);
+        let result_phi = builder.build_phi(i64_type, "result");
+        let factor_phi = builder.build_phi(i64_type, "factor");
+        let exponent_phi = builder.build_phi(i64_type, "exponent");
+        let result = result_phi.as_basic_value().into_int_value();
+        let factor = factor_phi.as_basic_value().into_int_value();
+        let rest = exponent_phi.as_basic_value().into_int_value();
+        let is_done = builder.build_int_compare(IntPredicate::EQ, rest, i64_type.const_zero(), "");
+        builder.build_conditional_branch(is_done, exit, multiply);
+
+        builder.position_at_end(multiply);
+        let is_bit_set = builder.build_int_compare(
+            IntPredicate::NE,
+            builder.build_and(rest, one, ""),
+            i64_type.const_zero(),
+            "",
+        );
+        let next_result = builder
+            .build_select(
+                is_bit_set,
+                builder.build_int_mul(result, factor, ""),
+                result,
+                "",
+            )
+            .into_int_value();
+        let next_factor = builder.build_int_mul(factor, factor, "");
+        let next_rest = builder.build_right_shift(rest, one, false, "");
+        builder.build_unconditional_branch(condition);
+        result_phi.add_incoming(&[(&one, entry), (&next_result, multiply)]);
+        factor_phi.add_incoming(&[(&base, entry), (&next_factor, multiply)]);
+        exponent_phi.add_incoming(&[(&exponent, entry), (&next_rest, multiply)]);
+
+        builder.position_at_end(exit);
+        builder.build_return(Some(&result));
+    }
+
+    /// generates `__ISVALIDREF(reference)` which checks that the given reference or pointer is
+    /// not NULL. A `REFERENCE TO` is not dereferenced, so its own address is checked
+    fn generate_is_valid_reference(
+        &self,
+        reference: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let reference_pointer = self.generate_reference_pointer(reference)?;
+        if !matches!(
+            reference_pointer.get_type_information(),
+            DataTypeInformation::Pointer { .. }
+        ) {
+            return Err(CompileError::codegen_error(
+                format!(
+                    "{} expects a reference or a pointer",
+                    BuiltIn::IsValidRef.get_name()
+                ),
+                reference.get_location(),
+            ));
+        }
+        let (_, address) = self.llvm.load_pointer(&reference_pointer, "");
+        let value = self
+            .llvm
+            .builder
+            .build_is_not_null(address.into_pointer_value(), "tmpVar");
+        Ok((self.index.get_type_information(BOOL_TYPE)?, value.into()))
+    }
+
+    /// generates the conversion of the given argument into the target type. If the conversion
+    /// names its source type (e.g. `INT_TO_REAL`) the argument is casted to the source type first
+    ///
+    /// - real numbers are rounded to the nearest integer unless `truncate` is set
+    /// - a number converts into TRUE if it is not 0
+    /// - date and time values convert to and from numbers in milliseconds (nanoseconds for the
+    ///   long types)
+    /// - a DATE_AND_TIME converts into its date or into its time of day
+    fn generate_conversion(
+        &self,
+        source: Option<&str>,
+        target: &str,
+        truncate: bool,
+        argument: &AstStatement,
+        context: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let (value_type, value) = self.generate_expression(argument)?;
+        let value_type = self.get_intrinsic_type(value_type);
+        //strings keep their own size and encoding, the string conversion reads both
+        let is_string = matches!(value_type, DataTypeInformation::String { .. });
+        let (source_type, value) = match source {
+            Some(source) if !is_string => {
+                let source_type = self.index.get_type_information(source)?;
+                let value = cast_if_needed(
+                    self.llvm,
+                    self.index,
+                    &source_type,
+                    value,
+                    &value_type,
+                    argument,
+                )?;
+                (source_type, value)
+            }
+            _ => (value_type, value),
+        };
+        let target_type = self.index.get_type_information(target)?;
+        if !builtins::is_valid_conversion(source_type.get_name(), target_type.get_name())
+            || (truncate && !source_type.is_float())
+        {
+            return Err(CompileError::codegen_error(
+                format!(
+                    "invalid types, cannot convert {} to {}",
+                    source_type.get_name(),
+                    target_type.get_name()
+                ),
+                context.get_location(),
+            ));
+        }
+        if source_type.get_name() == target_type.get_name() {
+            return Ok((source_type, value));
+        }
+        if matches!(source_type, DataTypeInformation::String { .. })
+            || matches!(target_type, DataTypeInformation::String { .. })
+        {
+            return self.generate_string_conversion(
+                (source_type, value),
+                target_type,
+                argument,
+                context,
+            );
+        }
+
+        let builder = &self.llvm.builder;
+        if target_type.get_name() == BOOL_TYPE {
+            let value = match value {
+                BasicValueEnum::FloatValue(value) => builder.build_float_compare(
+                    FloatPredicate::ONE,
+                    value,
+                    value.get_type().const_zero(),
+                    "tmpVar",
+                ),
+                _ => {
+                    let value = value.into_int_value();
+                    builder.build_int_compare(
+                        IntPredicate::NE,
+                        value,
+                        value.get_type().const_zero(),
+                        "tmpVar",
+                    )
+                }
+            };
+            return Ok((target_type, value.into()));
+        }
+        //BOOL is a signed i1, extend it to an unsigned type so TRUE converts into 1
+        let (source_type, value) = if source_type.get_name() == BOOL_TYPE {
+            let usint_type = self.index.get_type_information(USINT_TYPE)?;
+            let value =
+                builder.build_int_z_extend(value.into_int_value(), self.llvm.context.i8_type(), "");
+            (usint_type, value.into())
+        } else {
+            (source_type, value)
+        };
+        let value = match value {
+            BasicValueEnum::FloatValue(float_value) if !truncate && !target_type.is_float() => {
+                self.generate_rounded_float(float_value, &source_type, context)?
+            }
+            _ => value,
+        };
+
+        let source_resolution = typesystem::get_date_time_resolution(&source_type);
+        let target_resolution = typesystem::get_date_time_resolution(&target_type);
+        let value = match (source_resolution, target_resolution) {
+            (Some(source_resolution), Some(target_resolution)) => {
+                let value = value.into_int_value();
+                let nanos_per_day = 86_400_000_000_000 / source_resolution;
+                let day = value.get_type().const_int(nanos_per_day as u64, true);
+                let value = match typesystem::get_date_time_kind(target_type.get_name()) {
+                    Some((typesystem::DateTimeKind::Date, _)) => {
+                        let time_of_day = builder.build_int_signed_rem(value, day, "");
+                        builder.build_int_sub(value, time_of_day, "")
+                    }
+                    Some((typesystem::DateTimeKind::TimeOfDay, _)) => {
+                        builder.build_int_signed_rem(value, day, "")
+                    }
+                    _ => value,
+                };
+                self.convert_date_time_resolution(value, source_resolution, target_resolution)
+                    .into()
+            }
+            (Some(source_resolution), None) => {
+                let conversion_resolution =
+                    typesystem::get_date_time_conversion_resolution(&source_type)
+                        .unwrap_or(source_resolution);
+                let value = self.convert_date_time_resolution(
+                    value.into_int_value(),
+                    source_resolution,
+                    conversion_resolution,
+                );
+                let lint_type = self.index.get_type_information(LINT_TYPE)?;
+                cast_if_needed(
+                    self.llvm,
+                    self.index,
+                    &target_type,
+                    value.into(),
+                    &lint_type,
+                    context,
+                )?
+            }
+            (None, Some(target_resolution)) => {
+                let conversion_resolution =
+                    typesystem::get_date_time_conversion_resolution(&target_type)
+                        .unwrap_or(target_resolution);
+                let lint_type = self.index.get_type_information(LINT_TYPE)?;
+                let value = cast_if_needed(
+                    self.llvm,
+                    self.index,
+                    &lint_type,
+                    value,
+                    &source_type,
+                    context,
+                )?;
+                self.convert_date_time_resolution(
+                    value.into_int_value(),
+                    conversion_resolution,
+                    target_resolution,
+                )
+                .into()
+            }
+            (None, None) => cast_if_needed(
+                self.llvm,
+                self.index,
+                &target_type,
+                value,
+                &source_type,
+                context,
+            )?,
+        };
+        Ok((target_type, value))
+    }
+
+    /// rounds the given real number to the nearest integer (halfway cases away from zero) using
+    /// the llvm `round` intrinsic
+    fn generate_rounded_float(
+        &self,
+        value: FloatValue<'a>,
+        value_type: &DataTypeInformation,
+        context: &AstStatement,
+    ) -> Result<BasicValueEnum<'a>, CompileError> {
+        let float_llvm_type = value.get_type();
+        let intrinsic = match value_type {
+            DataTypeInformation::Float { size: 32, .. } => "llvm.round.f32",
+            _ => "llvm.round.f64",
+        };
+        let function_type = float_llvm_type.fn_type(&[float_llvm_type.into()], false);
+        let function = self.get_intrinsic(intrinsic, function_type, context)?;
+        self.llvm
+            .builder
+            .build_call(function, &[value.into()], "")
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| {
+                CompileError::codegen_error(
+                    format!("{} did not return a value", intrinsic),
+                    context.get_location(),
+                )
+            })
+    }
+
+    /// returns the intrinsic type of the given type (e.g. the numeric type of an enum)
+    pub(super) fn get_intrinsic_type(&self, data_type: DataTypeInformation) -> DataTypeInformation {
+        self.index
+            .find_intrinsic_type_information(&data_type)
+            .cloned()
+            .unwrap_or(data_type)
+    }
+
+    /// returns the llvm intrinsic with the given name, it is declared
+    /// in the current module when it is used for the first time
+    pub(super) fn get_intrinsic(
+        &self,
+        name: &str,
+        function_type: FunctionType<'a>,
+        context: &AstStatement,
+    ) -> Result<FunctionValue<'a>, CompileError> {
+        let module = self.get_function_context(context)?.module;
+        Ok(module
+            .get_function(name)
+            .unwrap_or_else(|| module.add_function(name, function_type, None)))
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+
+//! generates arithmetic and comparisons on date and time values
+use inkwell::values::{BasicValueEnum, IntValue};
+
+use super::ExpressionCodeGenerator;
+use crate::{
+    ast::{AstStatement, Operator},
+    codegen::{llvm_typesystem::cast_if_needed, TypeAndValue},
+    compile_error::CompileError,
+    typesystem::{self, DataTypeInformation, LREAL_TYPE},
+};
+
+impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
+    /// returns true if one of the given operands is a date or time value
+    pub(super) fn is_date_time_operation(&self, left: &AstStatement, right: &AstStatement) -> bool {
+        [left, right].iter().any(|it| {
+            let data_type = self
+                .annotations
+                .get_type_or_void(it, self.index)
+                .get_type_information();
+            typesystem::is_date_or_time_type(data_type)
+        })
+    }
+
+    /// generates a binary expression with at least one date or time operand
+    ///
+    /// the operands are converted to the finer resolution of the two before they are added,
+    /// subtracted or compared (e.g. `DT - DT` is calculated in milliseconds and converted to a
+    /// TIME in nanoseconds afterwards)
+    pub(super) fn generate_date_time_binary_expression(
+        &self,
+        operator: &Operator,
+        left: &AstStatement,
+        right: &AstStatement,
+        expression: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let (left_type, left_value) = self.generate_expression(left)?;
+        let (right_type, right_value) = self.generate_expression(right)?;
+        let left_type = self.get_intrinsic_type(left_type);
+        let right_type = self.get_intrinsic_type(right_type);
+
+        if matches!(operator, Operator::Multiplication | Operator::Division) {
+            return if typesystem::is_date_or_time_type(&left_type) {
+                self.generate_scaled_time(
+                    operator,
+                    (left_type, left_value),
+                    (right_type, right_value),
+                    expression,
+                )
+            } else {
+                self.generate_scaled_time(
+                    operator,
+                    (right_type, right_value),
+                    (left_type, left_value),
+                    expression,
+                )
+            };
+        }
+
+        let left_resolution = typesystem::get_date_time_resolution(&left_type);
+        let right_resolution = typesystem::get_date_time_resolution(&right_type);
+        let (working_type, working_resolution) = match (left_resolution, right_resolution) {
+            (Some(left_resolution), Some(right_resolution))
+                if right_resolution < left_resolution =>
+            {
+                (right_type.clone(), right_resolution)
+            }
+            (Some(left_resolution), _) => (left_type.clone(), left_resolution),
+            (None, Some(right_resolution)) => (right_type.clone(), right_resolution),
+            (None, None) => unreachable!("one of the operands is a date or time value"),
+        };
+
+        let left_value = self.convert_to_working_resolution(
+            left_value,
+            &left_type,
+            &working_type,
+            working_resolution,
+            left,
+        )?;
+        let right_value = self.convert_to_working_resolution(
+            right_value,
+            &right_type,
+            &working_type,
+            working_resolution,
+            right,
+        )?;
+        let (result_type, result_value) = self.create_llvm_int_binary_expression(
+            operator,
+            left_value.into(),
+            right_value.into(),
+            &working_type,
+        );
+
+        if matches!(operator, Operator::Plus | Operator::Minus) {
+            let target_type = self.get_intrinsic_type(
+                self.annotations
+                    .get_type_or_void(expression, self.index)
+                    .get_type_information()
+                    .clone(),
+            );
+            if let Some(target_resolution) = typesystem::get_date_time_resolution(&target_type) {
+                let value = self.convert_date_time_resolution(
+                    result_value.into_int_value(),
+                    working_resolution,
+                    target_resolution,
+                );
+                return Ok((target_type, value.into()));
+            }
+        }
+        Ok((result_type, result_value))
+    }
+
+    /// generates `time * factor` or `time / factor`. Float factors are applied to the time
+    /// as an LREAL, the result is converted back to the time's type
+    fn generate_scaled_time(
+        &self,
+        operator: &Operator,
+        (time_type, time_value): TypeAndValue<'a>,
+        (factor_type, factor_value): TypeAndValue<'a>,
+        context: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        if factor_type.is_float() {
+            let float_type = self.index.get_type_information(LREAL_TYPE)?;
+            let time_value = cast_if_needed(
+                self.llvm,
+                self.index,
+                &float_type,
+                time_value,
+                &time_type,
+                context,
+            )?;
+            let factor_value = cast_if_needed(
+                self.llvm,
+                self.index,
+                &float_type,
+                factor_value,
+                &factor_type,
+                context,
+            )?;
+            let (_, value) = self.create_llvm_float_binary_expression(
+                operator,
+                time_value,
+                factor_value,
+                &float_type,
+            );
+            let value = cast_if_needed(
+                self.llvm,
+                self.index,
+                &time_type,
+                value,
+                &float_type,
+                context,
+            )?;
+            Ok((time_type, value))
+        } else {
+            let factor_value = cast_if_needed(
+                self.llvm,
+                self.index,
+                &time_type,
+                factor_value,
+                &factor_type,
+                context,
+            )?;
+            Ok(self.create_llvm_int_binary_expression(
+                operator,
+                time_value,
+                factor_value,
+                &time_type,
+            ))
+        }
+    }
+
+    /// converts the given operand to the working type of a date or time operation. Date or time
+    /// values are scaled to the working resolution, other values are casted to the working type
+    fn convert_to_working_resolution(
+        &self,
+        value: BasicValueEnum<'a>,
+        value_type: &DataTypeInformation,
+        working_type: &DataTypeInformation,
+        working_resolution: i64,
+        statement: &AstStatement,
+    ) -> Result<IntValue<'a>, CompileError> {
+        if let Some(resolution) = typesystem::get_date_time_resolution(value_type) {
+            Ok(self.convert_date_time_resolution(
+                value.into_int_value(),
+                resolution,
+                working_resolution,
+            ))
+        } else {
+            cast_if_needed(
+                self.llvm,
+                self.index,
+                working_type,
+                value,
+                value_type,
+                statement,
+            )
+            .map(BasicValueEnum::into_int_value)
+        }
+    }
+
+    /// converts a date or time value between two resolutions given in nanoseconds per unit
+    pub(super) fn convert_date_time_resolution(
+        &self,
+        value: IntValue<'a>,
+        from: i64,
+        to: i64,
+    ) -> IntValue<'a> {
+        if from > to {
+            let factor = value.get_type().const_int((from / to) as u64, true);
+            self.llvm.builder.build_int_mul(value, factor, "")
+        } else if from < to {
+            let divisor = value.get_type().const_int((to / from) as u64, true);
+            self.llvm.builder.build_int_signed_div(value, divisor, "")
+        } else {
+            value
+        }
+    }
+}
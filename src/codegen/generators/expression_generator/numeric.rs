@@ -0,0 +1,170 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+
+//! generates the numeric functions ABS, SQRT, LN, LOG, EXP, ROUND, SIN and COS
+use inkwell::{
+    types::BasicTypeEnum,
+    values::{BasicValueEnum, FloatValue},
+    IntPredicate,
+};
+
+use super::ExpressionCodeGenerator;
+use crate::{
+    ast::AstStatement,
+    builtins::BuiltIn,
+    codegen::{llvm_typesystem::cast_if_needed, TypeAndValue},
+    compile_error::CompileError,
+    typesystem::{self, DataTypeInformation, REAL_TYPE},
+};
+
+impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
+    /// generates `ABS(IN)`, real numbers use the llvm `fabs` intrinsic, signed integers are
+    /// negated if they are negative
+    pub(super) fn generate_abs(
+        &self,
+        input: &AstStatement,
+        context: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let (input_type, input_value) = self.generate_expression(input)?;
+        let input_type = self.get_intrinsic_type(input_type);
+        let value = match (input_value, &input_type) {
+            (BasicValueEnum::FloatValue(value), DataTypeInformation::Float { size, .. }) => {
+                let intrinsic = format!("llvm.fabs.f{}", size);
+                self.call_float_function(&intrinsic, &[value], context)?
+            }
+            (
+                BasicValueEnum::IntValue(value),
+                DataTypeInformation::Integer {
+                    signed: true, size, ..
+                },
+            ) if *size > 1 => {
+                let builder = &self.llvm.builder;
+                let is_negative = builder.build_int_compare(
+                    IntPredicate::SLT,
+                    value,
+                    value.get_type().const_zero(),
+                    "",
+                );
+                let negated = builder.build_int_neg(value, "");
+                builder.build_select(is_negative, negated, value, "tmpVar")
+            }
+            (BasicValueEnum::IntValue(_), _) if input_type.is_numerical() => input_value,
+            _ => {
+                return Err(CompileError::codegen_error(
+                    format!(
+                        "invalid type, {} expects a number but found {}",
+                        BuiltIn::Abs.get_name(),
+                        input_type.get_name()
+                    ),
+                    input.get_location(),
+                ))
+            }
+        };
+        Ok((input_type, value))
+    }
+
+    /// generates a real function (e.g. `SQRT(IN)` or `ATAN2(Y, X)`). The inputs are converted to
+    /// REAL, or LREAL if one of them does not fit into a REAL. LLVM has no intrinsics for TAN,
+    /// ASIN, ACOS, ATAN and ATAN2, they are calculated inline (see `trigonometry.rs`)
+    pub(super) fn generate_real_function(
+        &self,
+        builtin: BuiltIn,
+        inputs: &[&AstStatement],
+        context: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let mut result_type = self.index.get_type_information(REAL_TYPE)?;
+        let mut values = Vec::new();
+        for input in inputs {
+            let (input_type, input_value) = self.generate_expression(input)?;
+            let input_type = self.get_intrinsic_type(input_type);
+            if !input_type.is_numerical() {
+                return Err(CompileError::codegen_error(
+                    format!(
+                        "invalid type, {} expects a number but found {}",
+                        builtin.get_name(),
+                        input_type.get_name()
+                    ),
+                    input.get_location(),
+                ));
+            }
+            result_type = typesystem::get_bigger_type(&result_type, &input_type, self.index);
+            values.push((input_type, input_value, *input));
+        }
+        let values = values
+            .into_iter()
+            .map(|(input_type, input_value, input)| {
+                cast_if_needed(
+                    self.llvm,
+                    self.index,
+                    &result_type,
+                    input_value,
+                    &input_type,
+                    input,
+                )
+                .map(BasicValueEnum::into_float_value)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if matches!(
+            builtin,
+            BuiltIn::Tan | BuiltIn::Asin | BuiltIn::Acos | BuiltIn::Atan | BuiltIn::Atan2
+        ) {
+            let value = self.generate_trigonometric_function(builtin, &values, context)?;
+            return Ok((result_type, value));
+        }
+
+        let suffix = if matches!(result_type, DataTypeInformation::Float { size: 32, .. }) {
+            "f32"
+        } else {
+            "f64"
+        };
+        let function_name = match builtin {
+            BuiltIn::Sqrt => format!("llvm.sqrt.{}", suffix),
+            BuiltIn::Ln => format!("llvm.log.{}", suffix),
+            BuiltIn::Log => format!("llvm.log10.{}", suffix),
+            BuiltIn::Exp => format!("llvm.exp.{}", suffix),
+            BuiltIn::Sin => format!("llvm.sin.{}", suffix),
+            BuiltIn::Cos => format!("llvm.cos.{}", suffix),
+            BuiltIn::Round => format!("llvm.round.{}", suffix),
+            _ => {
+                return Err(CompileError::codegen_error(
+                    format!("{} is no real function", builtin.get_name()),
+                    context.get_location(),
+                ))
+            }
+        };
+        let value = self.call_float_function(&function_name, &values, context)?;
+        Ok((result_type, value))
+    }
+
+    /// calls the intrinsic with the given name that takes the given real numbers and returns a
+    /// real number of the same type, the intrinsic is declared when it is used for the first time
+    pub(super) fn call_float_function(
+        &self,
+        function_name: &str,
+        arguments: &[FloatValue<'a>],
+        context: &AstStatement,
+    ) -> Result<BasicValueEnum<'a>, CompileError> {
+        let float_llvm_type = arguments.first().map(FloatValue::get_type).ok_or_else(|| {
+            CompileError::codegen_error(
+                format!("{} expects at least one argument", function_name),
+                context.get_location(),
+            )
+        })?;
+        let parameter_types: Vec<BasicTypeEnum> =
+            arguments.iter().map(|it| it.get_type().into()).collect();
+        let function_type = float_llvm_type.fn_type(&parameter_types, false);
+        let function = self.get_intrinsic(function_name, function_type, context)?;
+        let arguments: Vec<BasicValueEnum> = arguments.iter().map(|it| (*it).into()).collect();
+        self.llvm
+            .builder
+            .build_call(function, &arguments, "tmpVar")
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| {
+                CompileError::codegen_error(
+                    format!("{} did not return a value", function_name),
+                    context.get_location(),
+                )
+            })
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+
+//! generates pointer arithmetic and comparisons of pointers
+use inkwell::{
+    values::{BasicValueEnum, PointerValue},
+    IntPredicate,
+};
+
+use super::ExpressionCodeGenerator;
+use crate::{
+    ast::{AstStatement, Operator},
+    codegen::{llvm_typesystem::cast_if_needed, TypeAndValue},
+    compile_error::CompileError,
+    typesystem::{DataTypeInformation, BOOL_TYPE, LINT_TYPE},
+};
+
+impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
+    /// returns true if one of the given operands is a pointer (e.g. `REF_TO INT`)
+    pub(super) fn is_pointer_operation(&self, left: &AstStatement, right: &AstStatement) -> bool {
+        [left, right].iter().any(|it| {
+            self.annotations
+                .get_type_or_void(it, self.index)
+                .get_type_information()
+                .is_pointer()
+        })
+    }
+
+    /// generates a binary expression with at least one pointer operand
+    ///
+    /// - `ptr + n`, `n + ptr` and `ptr - n` move the pointer by `n` elements
+    /// - `ptr1 - ptr2` results in the number of elements between the two pointers as a LINT
+    /// - comparisons compare the addresses of the two pointers
+    pub(super) fn generate_pointer_binary_expression(
+        &self,
+        operator: &Operator,
+        left: &AstStatement,
+        right: &AstStatement,
+        expression: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let (left_type, left_value) = self.generate_expression(left)?;
+        let (right_type, right_value) = self.generate_expression(right)?;
+        let builder = &self.llvm.builder;
+        match (left_value, right_value) {
+            (
+                BasicValueEnum::PointerValue(left_pointer),
+                BasicValueEnum::PointerValue(right_pointer),
+            ) if operator == &Operator::Minus => {
+                let value = builder.build_ptr_diff(left_pointer, right_pointer, "tmpVar");
+                Ok((self.index.get_type_information(LINT_TYPE)?, value.into()))
+            }
+            (
+                BasicValueEnum::PointerValue(left_pointer),
+                BasicValueEnum::PointerValue(right_pointer),
+            ) => {
+                let predicate = match operator {
+                    Operator::Equal => IntPredicate::EQ,
+                    Operator::NotEqual => IntPredicate::NE,
+                    Operator::Less => IntPredicate::ULT,
+                    Operator::Greater => IntPredicate::UGT,
+                    Operator::LessOrEqual => IntPredicate::ULE,
+                    Operator::GreaterOrEqual => IntPredicate::UGE,
+                    _ => {
+                        return Err(CompileError::codegen_error(
+                            format!("invalid operator {} for pointers", operator),
+                            expression.get_location(),
+                        ))
+                    }
+                };
+                //icmp compares the addresses directly, so the target's pointer size is used
+                let right_pointer =
+                    builder.build_pointer_cast(right_pointer, left_pointer.get_type(), "");
+                let value =
+                    builder.build_int_compare(predicate, left_pointer, right_pointer, "tmpVar");
+                Ok((self.index.get_type_information(BOOL_TYPE)?, value.into()))
+            }
+            (BasicValueEnum::PointerValue(pointer), offset) => self.generate_pointer_offset(
+                operator,
+                (left_type, pointer),
+                (right_type, offset),
+                right,
+            ),
+            (offset, BasicValueEnum::PointerValue(pointer)) => self.generate_pointer_offset(
+                operator,
+                (right_type, pointer),
+                (left_type, offset),
+                left,
+            ),
+            _ => Err(CompileError::codegen_error(
+                format!(
+                    "invalid types, cannot generate binary expression for {} and {}",
+                    left_type.get_name(),
+                    right_type.get_name()
+                ),
+                expression.get_location(),
+            )),
+        }
+    }
+
+    /// moves the given pointer by `offset` elements of its inner type, the element size is
+    /// taken into account by the GEP instruction
+    fn generate_pointer_offset(
+        &self,
+        operator: &Operator,
+        (pointer_type, pointer): (DataTypeInformation, PointerValue<'a>),
+        (offset_type, offset): TypeAndValue<'a>,
+        offset_statement: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let offset_target_type = self.index.get_type_information(LINT_TYPE)?;
+        let offset = cast_if_needed(
+            self.llvm,
+            self.index,
+            &offset_target_type,
+            offset,
+            &offset_type,
+            offset_statement,
+        )?
+        .into_int_value();
+        let offset = if operator == &Operator::Minus {
+            self.llvm.builder.build_int_neg(offset, "")
+        } else {
+            offset
+        };
+        let value = unsafe {
+            self.llvm
+                .builder
+                .build_in_bounds_gep(pointer, &[offset], "tmpVar")
+        };
+        Ok((pointer_type, value.into()))
+    }
+}
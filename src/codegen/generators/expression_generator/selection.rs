@@ -0,0 +1,266 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+
+//! generates the selection functions MIN, MAX, LIMIT, SEL and MUX as selects and branches
+use inkwell::{
+    values::{BasicValueEnum, IntValue},
+    FloatPredicate, IntPredicate,
+};
+
+use super::ExpressionCodeGenerator;
+use crate::{
+    ast::AstStatement,
+    builtins::BuiltIn,
+    codegen::{llvm_typesystem::cast_if_needed, TypeAndValue},
+    compile_error::CompileError,
+    typesystem::{self, DataTypeInformation, BOOL_TYPE, DINT_TYPE},
+};
+
+impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
+    /// generates `MIN(IN1, IN2, ...)` or `MAX(IN1, IN2, ...)`. Every input replaces the result
+    /// so far if it is smaller (or bigger) than the result
+    pub(super) fn generate_min_max(
+        &self,
+        builtin: BuiltIn,
+        inputs: &[&AstStatement],
+        context: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let result_type = self.get_common_type(inputs, context)?;
+        let mut values = self.generate_values_as(inputs, &result_type)?.into_iter();
+        let mut result = values.next().ok_or_else(|| {
+            CompileError::codegen_error(
+                format!("{} expects at least one input", builtin.get_name()),
+                context.get_location(),
+            )
+        })?;
+        for value in values {
+            let is_selected = if builtin == BuiltIn::Min {
+                self.generate_less_than(value, result, &result_type, context)?
+            } else {
+                self.generate_less_than(result, value, &result_type, context)?
+            };
+            result = self
+                .llvm
+                .builder
+                .build_select(is_selected, value, result, "tmpVar");
+        }
+        Ok((result_type, result))
+    }
+
+    /// generates `LIMIT(MN, IN, MX)` as `MIN(MAX(IN, MN), MX)`
+    pub(super) fn generate_limit(
+        &self,
+        minimum: &AstStatement,
+        input: &AstStatement,
+        maximum: &AstStatement,
+        context: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let result_type = self.get_common_type(&[minimum, input, maximum], context)?;
+        let values = self.generate_values_as(&[minimum, input, maximum], &result_type)?;
+        let (minimum, value, maximum) = (values[0], values[1], values[2]);
+        let builder = &self.llvm.builder;
+        let is_below = self.generate_less_than(value, minimum, &result_type, context)?;
+        let value = builder.build_select(is_below, minimum, value, "tmpVar");
+        let is_above = self.generate_less_than(maximum, value, &result_type, context)?;
+        let value = builder.build_select(is_above, maximum, value, "tmpVar");
+        Ok((result_type, value))
+    }
+
+    /// generates `SEL(G, IN0, IN1)` as a select on `G`
+    pub(super) fn generate_sel(
+        &self,
+        selector: &AstStatement,
+        input0: &AstStatement,
+        input1: &AstStatement,
+        context: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let (selector_type, selector_value) = self.generate_expression(selector)?;
+        let bool_type = self.index.get_type_information(BOOL_TYPE)?;
+        let selector_value = cast_if_needed(
+            self.llvm,
+            self.index,
+            &bool_type,
+            selector_value,
+            &selector_type,
+            selector,
+        )?
+        .into_int_value();
+        let result_type = self.get_common_type(&[input0, input1], context)?;
+        let values = self.generate_values_as(&[input0, input1], &result_type)?;
+        let value = self
+            .llvm
+            .builder
+            .build_select(selector_value, values[1], values[0], "tmpVar");
+        Ok((result_type, value))
+    }
+
+    /// generates `MUX(K, IN0, IN1, ...)` as a switch on `K` with one block per input, only the
+    /// selected input is evaluated. A `K` outside of the inputs selects the last input
+    pub(super) fn generate_mux(
+        &self,
+        selector: &AstStatement,
+        inputs: &[&AstStatement],
+        context: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let result_type = self.get_common_type(inputs, context)?;
+        let (selector_type, selector_value) = self.generate_expression(selector)?;
+        let dint_type = self.index.get_type_information(DINT_TYPE)?;
+        let selector_value = cast_if_needed(
+            self.llvm,
+            self.index,
+            &dint_type,
+            selector_value,
+            &selector_type,
+            selector,
+        )?
+        .into_int_value();
+
+        let builder = &self.llvm.builder;
+        let function = self.get_function_context(context)?.function;
+        let input_blocks: Vec<_> = inputs
+            .iter()
+            .map(|_| self.llvm.context.append_basic_block(function, "mux"))
+            .collect();
+        let continue_block = self.llvm.context.append_basic_block(function, "continue");
+        let cases: Vec<_> = input_blocks
+            .iter()
+            .enumerate()
+            .map(|(index, block)| {
+                (
+                    selector_value.get_type().const_int(index as u64, true),
+                    *block,
+                )
+            })
+            .collect();
+        let else_block = *input_blocks.last().ok_or_else(|| {
+            CompileError::codegen_error(
+                format!("{} expects at least one input", BuiltIn::Mux.get_name()),
+                context.get_location(),
+            )
+        })?;
+        builder.build_switch(selector_value, else_block, &cases);
+
+        let mut incoming = Vec::new();
+        for (input, block) in inputs.iter().zip(input_blocks) {
+            builder.position_at_end(block);
+            let (input_type, input_value) = self.generate_expression(input)?;
+            let input_value = cast_if_needed(
+                self.llvm,
+                self.index,
+                &result_type,
+                input_value,
+                &input_type,
+                input,
+            )?;
+            //the input may have created additional blocks (e.g. for a call)
+            incoming.push((input_value, builder.get_insert_block().unwrap()));
+            builder.build_unconditional_branch(continue_block);
+        }
+
+        builder.position_at_end(continue_block);
+        let phi_value = builder.build_phi(
+            self.llvm_index
+                .get_associated_type(result_type.get_name())?,
+            "tmpVar",
+        );
+        for (value, block) in &incoming {
+            phi_value.add_incoming(&[(value, *block)]);
+        }
+        Ok((result_type, phi_value.as_basic_value()))
+    }
+
+    /// returns the type the given inputs are converted to before one of them is selected.
+    /// Numbers are converted to the bigger type of all inputs, other inputs must share the
+    /// same type
+    fn get_common_type(
+        &self,
+        inputs: &[&AstStatement],
+        context: &AstStatement,
+    ) -> Result<DataTypeInformation, CompileError> {
+        let types: Vec<DataTypeInformation> = inputs
+            .iter()
+            .map(|it| {
+                self.get_intrinsic_type(
+                    self.annotations
+                        .get_type_or_void(it, self.index)
+                        .get_type_information()
+                        .clone(),
+                )
+            })
+            .collect();
+        let invalid_types = || {
+            CompileError::codegen_error(
+                format!(
+                    "invalid types, cannot select one of {}",
+                    types
+                        .iter()
+                        .map(DataTypeInformation::get_name)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                context.get_location(),
+            )
+        };
+        let (first, others) = types.split_first().ok_or_else(invalid_types)?;
+        if types.iter().all(DataTypeInformation::is_numerical) {
+            Ok(others.iter().fold(first.clone(), |result_type, it| {
+                typesystem::get_bigger_type(&result_type, it, self.index)
+            }))
+        } else if others.iter().all(|it| it.get_name() == first.get_name()) {
+            Ok(first.clone())
+        } else {
+            Err(invalid_types())
+        }
+    }
+
+    /// generates the given inputs and casts them to the given type
+    fn generate_values_as(
+        &self,
+        inputs: &[&AstStatement],
+        target_type: &DataTypeInformation,
+    ) -> Result<Vec<BasicValueEnum<'a>>, CompileError> {
+        inputs
+            .iter()
+            .map(|input| {
+                let (input_type, input_value) = self.generate_expression(input)?;
+                cast_if_needed(
+                    self.llvm,
+                    self.index,
+                    target_type,
+                    input_value,
+                    &input_type,
+                    input,
+                )
+            })
+            .collect()
+    }
+
+    /// generates `left < right` for two values of the given type, unsigned integers (and BOOL)
+    /// are compared without their sign
+    fn generate_less_than(
+        &self,
+        left: BasicValueEnum<'a>,
+        right: BasicValueEnum<'a>,
+        data_type: &DataTypeInformation,
+        context: &AstStatement,
+    ) -> Result<IntValue<'a>, CompileError> {
+        let builder = &self.llvm.builder;
+        match (left, right) {
+            (BasicValueEnum::FloatValue(left), BasicValueEnum::FloatValue(right)) => {
+                Ok(builder.build_float_compare(FloatPredicate::OLT, left, right, "tmpVar"))
+            }
+            (BasicValueEnum::IntValue(left), BasicValueEnum::IntValue(right)) => {
+                let predicate = match data_type {
+                    DataTypeInformation::Integer {
+                        signed: true, size, ..
+                    } if *size > 1 => IntPredicate::SLT,
+                    _ => IntPredicate::ULT,
+                };
+                Ok(builder.build_int_compare(predicate, left, right, "tmpVar"))
+            }
+            _ => Err(CompileError::codegen_error(
+                format!("invalid types, cannot compare {}", data_type.get_name()),
+                context.get_location(),
+            )),
+        }
+    }
+}
@@ -0,0 +1,2368 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+
+//! generates the conversions from and into STRING and WSTRING values (e.g. `INT_TO_STRING`,
+//! `STRING_TO_LREAL` or `TO_WSTRING`)
+//!
+//! a value is written as its literal: numbers in decimal (e.g. `-42` or `2.5`), real numbers
+//! rounded to 7 (REAL) or 15 (LREAL) significant digits and in exponent notation if they are
+//! very small or big (e.g. `1.5E20`), BOOL values as `TRUE` or `FALSE` and date and time values
+//! with their prefix (e.g. `T#1h30m`, `D#2021-10-17`, `TOD#12:30:15.500` or
+//! `DT#2021-10-17-12:30:15`). A CHAR converts into a string of the single character and a string
+//! converts into its first character.
+//!
+//! a string converts into a number if it starts with one (leading spaces are skipped), the
+//! characters behind the number are ignored. It converts into TRUE if it is `TRUE` (ignoring
+//! case) or a number other than 0. Date and time values are read behind the `#` of their prefix.
+//!
+//! the text is written and read by helper functions on STRINGs, a WSTRING is converted into a
+//! STRING first (or afterwards), its characters are converted like `WCHAR_TO_CHAR`. The helpers
+//! are generated into the module when they are used for the first time:
+//! - `__wstring_from_string(dest, dest_capacity, src, src_capacity)` and
+//!   `__string_from_wstring(dest, dest_capacity, src, src_capacity)` copy the characters of
+//!   `src` into `dest` and return the length of `dest`
+//! - `__string_append_integer(dest, capacity, length, value, is_negative, digits)` appends the
+//!   unsigned `value` with at least `digits` digits (preceded by a `-` if `is_negative`) to `dest`
+//!   of the given `length` and returns its new length
+//! - `__string_append_real(dest, capacity, length, value, precision)` appends the LREAL `value`
+//!   rounded to `precision` significant digits
+//! - `__string_append_duration(dest, capacity, length, value)` appends the duration of `value`
+//!   nanoseconds (e.g. `1d2h30m`)
+//! - `__string_append_date(dest, capacity, length, value, units_per_second)` and
+//!   `__string_append_time_of_day(dest, capacity, length, value, units_per_second, digits)` append
+//!   the date (`2021-10-17`) or the time of day (`12:30:15.500`) of the date and time `value`
+//! - `__string_parse_integer(str, capacity, index)` reads the decimal digits starting at
+//!   `*index`, moves the index behind them and returns their value
+//! - `__string_to_integer(str, capacity)`, `__string_to_real(str, capacity)` and
+//!   `__string_to_bool(str, capacity)` read the value of the whole string
+//! - `__string_to_duration(str, capacity, index)`, `__string_to_date(str, capacity, index,
+//!   units_per_second)` and `__string_to_time_of_day(str, capacity, index, units_per_second)` read
+//!   a date or time value starting at `*index` and move the index behind it
+use inkwell::{
+    basic_block::BasicBlock,
+    module::Linkage,
+    types::{BasicType, BasicTypeEnum, FunctionType},
+    values::{BasicValueEnum, FloatValue, FunctionValue, IntValue, PointerValue},
+    AddressSpace, FloatPredicate, IntPredicate,
+};
+
+use super::{
+    strings::{get_parameter, get_string_size, StringArgument, StringHelper},
+    ExpressionCodeGenerator,
+};
+use crate::{
+    ast::AstStatement,
+    codegen::{llvm_typesystem::cast_if_needed, TypeAndValue},
+    compile_error::CompileError,
+    typesystem::{
+        self, DataTypeInformation, DateTimeKind, StringEncoding, BOOL_TYPE, CHAR_TYPE, LINT_TYPE,
+        LREAL_TYPE, WCHAR_TYPE,
+    },
+};
+
+/// the units of a duration with their length in nanoseconds, from the biggest to the smallest
+const DURATION_UNITS: [(&str, u64); 7] = [
+    ("d", 86_400_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+/// the number of characters of the digit buffer of `__string_append_integer`, enough for the
+/// 20 digits of an ULINT preceded by a `-`
+const INTEGER_BUFFER_SIZE: u64 = 22;
+
+/// the helper functions the string conversions are composed of
+#[derive(Clone, Copy)]
+enum ConversionHelper {
+    Widen,
+    Narrow,
+    AppendInteger,
+    AppendReal,
+    AppendDuration,
+    AppendDate,
+    AppendTimeOfDay,
+    ParseInteger,
+    ToInteger,
+    ToReal,
+    ToBool,
+    ToDuration,
+    ToDate,
+    ToTimeOfDay,
+}
+
+impl ConversionHelper {
+    fn get_name(&self) -> &'static str {
+        match self {
+            ConversionHelper::Widen => "__wstring_from_string",
+            ConversionHelper::Narrow => "__string_from_wstring",
+            ConversionHelper::AppendInteger => "__string_append_integer",
+            ConversionHelper::AppendReal => "__string_append_real",
+            ConversionHelper::AppendDuration => "__string_append_duration",
+            ConversionHelper::AppendDate => "__string_append_date",
+            ConversionHelper::AppendTimeOfDay => "__string_append_time_of_day",
+            ConversionHelper::ParseInteger => "__string_parse_integer",
+            ConversionHelper::ToInteger => "__string_to_integer",
+            ConversionHelper::ToReal => "__string_to_real",
+            ConversionHelper::ToBool => "__string_to_bool",
+            ConversionHelper::ToDuration => "__string_to_duration",
+            ConversionHelper::ToDate => "__string_to_date",
+            ConversionHelper::ToTimeOfDay => "__string_to_time_of_day",
+        }
+    }
+}
+
+impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
+    /// generates the conversion of the given value from or into a STRING or WSTRING
+    pub(super) fn generate_string_conversion(
+        &self,
+        (source_type, value): TypeAndValue<'a>,
+        target_type: DataTypeInformation,
+        argument: &AstStatement,
+        context: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        match (&source_type, &target_type) {
+            (DataTypeInformation::String { .. }, DataTypeInformation::String { encoding, .. }) => {
+                let string = self.create_string_argument(source_type.clone(), value, argument)?;
+                let (result, buffer) =
+                    self.create_string_buffer(target_type.clone(), encoding.clone());
+                self.recode_string(&result, &string, context)?;
+                Ok((target_type, self.llvm.builder.build_load(buffer, "")))
+            }
+            (DataTypeInformation::String { .. }, _) => {
+                let string = self.create_string_argument(source_type, value, argument)?;
+                self.generate_conversion_from_string(string, target_type, context)
+            }
+            (_, DataTypeInformation::String { encoding, .. }) => {
+                let (result, buffer) =
+                    self.create_string_buffer(target_type.clone(), encoding.clone());
+                if source_type.is_character() {
+                    //a string of the single character
+                    let character_type = self.index.get_type_information(match encoding {
+                        StringEncoding::Utf8 => CHAR_TYPE,
+                        StringEncoding::Utf16 => WCHAR_TYPE,
+                    })?;
+                    let character = cast_if_needed(
+                        self.llvm,
+                        self.index,
+                        &character_type,
+                        value,
+                        &source_type,
+                        context,
+                    )?;
+                    self.llvm.builder.build_store(result.pointer, character);
+                } else if let StringEncoding::Utf16 = encoding {
+                    let (text, _) = self.create_string_buffer(
+                        DataTypeInformation::String {
+                            size: get_string_size(&target_type),
+                            encoding: StringEncoding::Utf8,
+                        },
+                        StringEncoding::Utf8,
+                    );
+                    self.write_text(&text, (source_type, value), context)?;
+                    self.recode_string(&result, &text, context)?;
+                } else {
+                    self.write_text(&result, (source_type, value), context)?;
+                }
+                Ok((target_type, self.llvm.builder.build_load(buffer, "")))
+            }
+            _ => Err(CompileError::codegen_error(
+                format!(
+                    "invalid types, cannot convert {} to {}",
+                    source_type.get_name(),
+                    target_type.get_name()
+                ),
+                context.get_location(),
+            )),
+        }
+    }
+
+    /// reads a value of the target type from the given string
+    fn generate_conversion_from_string(
+        &self,
+        string: StringArgument<'a>,
+        target_type: DataTypeInformation,
+        context: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        if target_type.is_character() {
+            //the first character of the string
+            let character_type = self.index.get_type_information(match string.encoding {
+                StringEncoding::Utf8 => CHAR_TYPE,
+                StringEncoding::Utf16 => WCHAR_TYPE,
+            })?;
+            let character = self.llvm.builder.build_load(string.pointer, "");
+            let value = cast_if_needed(
+                self.llvm,
+                self.index,
+                &target_type,
+                character,
+                &character_type,
+                context,
+            )?;
+            return Ok((target_type, value));
+        }
+
+        let string = self.narrow_string(string, context)?;
+        let arguments: [BasicValueEnum; 2] = [string.pointer.into(), string.capacity.into()];
+        if target_type.get_name() == BOOL_TYPE {
+            let value =
+                self.call_conversion_helper(ConversionHelper::ToBool, &arguments, context)?;
+            return Ok((target_type, value));
+        }
+        if let Some((kind, _)) = typesystem::get_date_time_kind(target_type.get_name()) {
+            let value = self.read_date_time(&string, kind, &target_type, context)?;
+            return Ok((target_type, value.into()));
+        }
+
+        let (helper, value_type) = if target_type.is_float() {
+            (ConversionHelper::ToReal, LREAL_TYPE)
+        } else {
+            (ConversionHelper::ToInteger, LINT_TYPE)
+        };
+        let value = self.call_conversion_helper(helper, &arguments, context)?;
+        let value = cast_if_needed(
+            self.llvm,
+            self.index,
+            &target_type,
+            value,
+            &self.index.get_type_information(value_type)?,
+            context,
+        )?;
+        Ok((target_type, value))
+    }
+
+    /// reads a date or time value of the given kind behind the `#` of the string's prefix (or
+    /// from its start if it has no prefix)
+    fn read_date_time(
+        &self,
+        string: &StringArgument<'a>,
+        kind: DateTimeKind,
+        target_type: &DataTypeInformation,
+        context: &AstStatement,
+    ) -> Result<IntValue<'a>, CompileError> {
+        let builder = &self.llvm.builder;
+        let units_per_second = self.get_units_per_second(target_type);
+        let (hash, hash_capacity) = self.create_text("#");
+        let start = self.call_string_helper(
+            StringHelper::Find,
+            &StringEncoding::Utf8,
+            &[
+                string.pointer.into(),
+                string.capacity.into(),
+                hash.into(),
+                hash_capacity.into(),
+            ],
+            context,
+        )?;
+        //the 1-based position of the `#` is the 0-based index of the character behind it
+        let index = builder.build_alloca(self.llvm.context.i32_type(), "");
+        builder.build_store(index, start);
+
+        let read = |helper: ConversionHelper| {
+            let mut arguments: Vec<BasicValueEnum> =
+                vec![string.pointer.into(), string.capacity.into(), index.into()];
+            if !matches!(helper, ConversionHelper::ToDuration) {
+                arguments.push(units_per_second.into());
+            }
+            self.call_conversion_helper(helper, &arguments, context)
+                .map(BasicValueEnum::into_int_value)
+        };
+        Ok(match kind {
+            DateTimeKind::Time => read(ConversionHelper::ToDuration)?,
+            DateTimeKind::Date => read(ConversionHelper::ToDate)?,
+            DateTimeKind::TimeOfDay => read(ConversionHelper::ToTimeOfDay)?,
+            DateTimeKind::DateAndTime => {
+                let date = read(ConversionHelper::ToDate)?;
+                self.skip_character(index);
+                let time_of_day = read(ConversionHelper::ToTimeOfDay)?;
+                builder.build_int_add(date, time_of_day, "")
+            }
+        })
+    }
+
+    /// writes the text of the given value into the given STRING
+    fn write_text(
+        &self,
+        string: &StringArgument<'a>,
+        (value_type, value): TypeAndValue<'a>,
+        context: &AstStatement,
+    ) -> Result<(), CompileError> {
+        let builder = &self.llvm.builder;
+        let i32_type = self.llvm.context.i32_type();
+        let i64_type = self.llvm.context.i64_type();
+        let zero = i32_type.const_zero();
+
+        if value_type.get_name() == BOOL_TYPE {
+            let (true_text, true_capacity) = self.create_text("TRUE");
+            let (false_text, false_capacity) = self.create_text("FALSE");
+            let is_true = value.into_int_value();
+            let text = builder.build_select(is_true, true_text, false_text, "");
+            let capacity = builder.build_select(is_true, true_capacity, false_capacity, "");
+            self.call_string_helper(
+                StringHelper::Append,
+                &StringEncoding::Utf8,
+                &[
+                    string.pointer.into(),
+                    string.capacity.into(),
+                    zero.into(),
+                    text,
+                    capacity,
+                    zero.into(),
+                    capacity,
+                ],
+                context,
+            )?;
+            return Ok(());
+        }
+
+        let destination: [BasicValueEnum; 2] = [string.pointer.into(), string.capacity.into()];
+        if let Some((kind, is_long)) = typesystem::get_date_time_kind(value_type.get_name()) {
+            let value = value.into_int_value();
+            let units_per_second = self.get_units_per_second(&value_type);
+            let fraction_digits = i32_type.const_int(if is_long { 9 } else { 3 }, false);
+            let prefix = get_date_time_prefix(kind, is_long);
+            let length = self.append_text(
+                &destination,
+                zero,
+                prefix,
+                i32_type.const_int(prefix.len() as u64, false),
+                context,
+            )?;
+            let append_date = |length: IntValue<'a>| {
+                self.call_conversion_helper(
+                    ConversionHelper::AppendDate,
+                    &[
+                        destination[0],
+                        destination[1],
+                        length.into(),
+                        value.into(),
+                        units_per_second.into(),
+                    ],
+                    context,
+                )
+                .map(BasicValueEnum::into_int_value)
+            };
+            let append_time_of_day = |length: IntValue<'a>| {
+                self.call_conversion_helper(
+                    ConversionHelper::AppendTimeOfDay,
+                    &[
+                        destination[0],
+                        destination[1],
+                        length.into(),
+                        value.into(),
+                        units_per_second.into(),
+                        fraction_digits.into(),
+                    ],
+                    context,
+                )
+            };
+            match kind {
+                DateTimeKind::Time => {
+                    self.call_conversion_helper(
+                        ConversionHelper::AppendDuration,
+                        &[destination[0], destination[1], length.into(), value.into()],
+                        context,
+                    )?;
+                }
+                DateTimeKind::Date => {
+                    append_date(length)?;
+                }
+                DateTimeKind::TimeOfDay => {
+                    append_time_of_day(length)?;
+                }
+                DateTimeKind::DateAndTime => {
+                    let length = append_date(length)?;
+                    let length = self.append_text(
+                        &destination,
+                        length,
+                        "-",
+                        i32_type.const_int(1, false),
+                        context,
+                    )?;
+                    append_time_of_day(length)?;
+                }
+            }
+            return Ok(());
+        }
+
+        match value {
+            BasicValueEnum::FloatValue(value) => {
+                let double_type = self.llvm.context.f64_type();
+                let (value, precision) = if value.get_type() == double_type {
+                    (value, 15)
+                } else {
+                    (builder.build_float_ext(value, double_type, ""), 7)
+                };
+                self.call_conversion_helper(
+                    ConversionHelper::AppendReal,
+                    &[
+                        destination[0],
+                        destination[1],
+                        zero.into(),
+                        value.into(),
+                        i32_type.const_int(precision, false).into(),
+                    ],
+                    context,
+                )?;
+            }
+            BasicValueEnum::IntValue(value) => {
+                let (value, is_negative) = if value_type.is_unsigned_int() {
+                    (
+                        builder.build_int_z_extend_or_bit_cast(value, i64_type, ""),
+                        self.llvm.context.bool_type().const_zero(),
+                    )
+                } else {
+                    let value = builder.build_int_s_extend_or_bit_cast(value, i64_type, "");
+                    let is_negative = builder.build_int_compare(
+                        IntPredicate::SLT,
+                        value,
+                        i64_type.const_zero(),
+                        "",
+                    );
+                    (value, is_negative)
+                };
+                let magnitude =
+                    builder.build_select(is_negative, builder.build_int_neg(value, ""), value, "");
+                self.call_conversion_helper(
+                    ConversionHelper::AppendInteger,
+                    &[
+                        destination[0],
+                        destination[1],
+                        zero.into(),
+                        magnitude,
+                        is_negative.into(),
+                        i32_type.const_int(1, false).into(),
+                    ],
+                    context,
+                )?;
+            }
+            _ => {
+                return Err(CompileError::codegen_error(
+                    format!(
+                        "invalid types, cannot convert {} to a string",
+                        value_type.get_name()
+                    ),
+                    context.get_location(),
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// copies the characters of the given source into the given result of the other encoding
+    fn recode_string(
+        &self,
+        result: &StringArgument<'a>,
+        source: &StringArgument<'a>,
+        context: &AstStatement,
+    ) -> Result<(), CompileError> {
+        let helper = match result.encoding {
+            StringEncoding::Utf8 => ConversionHelper::Narrow,
+            StringEncoding::Utf16 => ConversionHelper::Widen,
+        };
+        self.call_conversion_helper(
+            helper,
+            &[
+                result.pointer.into(),
+                result.capacity.into(),
+                source.pointer.into(),
+                source.capacity.into(),
+            ],
+            context,
+        )?;
+        Ok(())
+    }
+
+    /// returns the given string as a STRING, a WSTRING is copied into a STRING of the same length
+    fn narrow_string(
+        &self,
+        string: StringArgument<'a>,
+        context: &AstStatement,
+    ) -> Result<StringArgument<'a>, CompileError> {
+        if let StringEncoding::Utf8 = string.encoding {
+            return Ok(string);
+        }
+        let (result, _) = self.create_string_buffer(
+            DataTypeInformation::String {
+                size: get_string_size(&string.data_type),
+                encoding: StringEncoding::Utf8,
+            },
+            StringEncoding::Utf8,
+        );
+        self.recode_string(&result, &string, context)?;
+        Ok(result)
+    }
+
+    /// returns the number of units of the given date or time type per second as an LINT
+    fn get_units_per_second(&self, data_type: &DataTypeInformation) -> IntValue<'a> {
+        let resolution = typesystem::get_date_time_resolution(data_type).unwrap_or(1);
+        self.llvm
+            .context
+            .i64_type()
+            .const_int((1_000_000_000 / resolution) as u64, false)
+    }
+
+    /// copies the given text into a local STRING and returns a pointer to its first character
+    /// and its capacity
+    fn create_text(&self, text: &str) -> (PointerValue<'a>, IntValue<'a>) {
+        let i8_type = self.llvm.context.i8_type();
+        let characters: Vec<IntValue> = text
+            .bytes()
+            .chain(std::iter::once(0))
+            .map(|it| i8_type.const_int(it as u64, false))
+            .collect();
+        let llvm_type = i8_type.array_type(characters.len() as u32);
+        let buffer = self.llvm.create_local_variable("", &llvm_type.into());
+        self.llvm
+            .builder
+            .build_store(buffer, i8_type.const_array(&characters));
+        let pointer = self.llvm.builder.build_pointer_cast(
+            buffer,
+            i8_type.ptr_type(AddressSpace::Generic),
+            "",
+        );
+        let capacity = self
+            .llvm
+            .context
+            .i32_type()
+            .const_int(characters.len() as u64, false);
+        (pointer, capacity)
+    }
+
+    /// appends up to `count` characters of the given text to the STRING `[dest, capacity]` of
+    /// the given length and returns its new length
+    fn append_text(
+        &self,
+        destination: &[BasicValueEnum<'a>; 2],
+        length: IntValue<'a>,
+        text: &str,
+        count: IntValue<'a>,
+        context: &AstStatement,
+    ) -> Result<IntValue<'a>, CompileError> {
+        let (text, capacity) = self.create_text(text);
+        self.call_string_helper(
+            StringHelper::Append,
+            &StringEncoding::Utf8,
+            &[
+                destination[0],
+                destination[1],
+                length.into(),
+                text.into(),
+                capacity.into(),
+                self.llvm.context.i32_type().const_zero().into(),
+                count.into(),
+            ],
+            context,
+        )
+    }
+
+    /// returns the given count if the condition is true and 0 otherwise
+    fn select_count(&self, condition: IntValue<'a>, count: IntValue<'a>) -> IntValue<'a> {
+        let zero = self.llvm.context.i32_type().const_zero();
+        self.llvm
+            .builder
+            .build_select(condition, count, zero, "")
+            .into_int_value()
+    }
+
+    /// increments the index behind the given pointer to skip a separator (e.g. the `:` of a time)
+    fn skip_character(&self, index: PointerValue<'a>) {
+        let builder = &self.llvm.builder;
+        let position = builder.build_load(index, "").into_int_value();
+        let one = self.llvm.context.i32_type().const_int(1, false);
+        builder.build_store(index, builder.build_int_add(position, one, ""));
+    }
+
+    /// calls the given conversion helper, it is generated when it is used for the first time
+    fn call_conversion_helper(
+        &self,
+        helper: ConversionHelper,
+        arguments: &[BasicValueEnum<'a>],
+        context: &AstStatement,
+    ) -> Result<BasicValueEnum<'a>, CompileError> {
+        let function = self.get_conversion_helper(helper, context)?;
+        self.llvm
+            .builder
+            .build_call(function, arguments, "")
+            .try_as_basic_value()
+            .left()
+            .ok_or_else(|| {
+                CompileError::codegen_error(
+                    format!("{} did not return a value", helper.get_name()),
+                    context.get_location(),
+                )
+            })
+    }
+
+    /// returns the given conversion helper of the current module, it is generated when it is
+    /// used for the first time
+    fn get_conversion_helper(
+        &self,
+        helper: ConversionHelper,
+        context: &AstStatement,
+    ) -> Result<FunctionValue<'a>, CompileError> {
+        let module = self.get_function_context(context)?.module;
+        if let Some(function) = module.get_function(helper.get_name()) {
+            return Ok(function);
+        }
+        let function = module.add_function(
+            helper.get_name(),
+            self.get_conversion_helper_type(helper),
+            Some(Linkage::Internal),
+        );
+
+        let current_block = self.llvm.builder.get_insert_block();
+        let generated = match helper {
+            ConversionHelper::Widen => {
+                self.generate_recode_helper(function, &StringEncoding::Utf16);
+                Ok(())
+            }
+            ConversionHelper::Narrow => {
+                self.generate_recode_helper(function, &StringEncoding::Utf8);
+                Ok(())
+            }
+            ConversionHelper::AppendInteger => {
+                self.generate_append_integer_helper(function, context)
+            }
+            ConversionHelper::AppendReal => self.generate_append_real_helper(function, context),
+            ConversionHelper::AppendDuration => {
+                self.generate_append_duration_helper(function, context)
+            }
+            ConversionHelper::AppendDate => self.generate_append_date_helper(function, context),
+            ConversionHelper::AppendTimeOfDay => {
+                self.generate_append_time_of_day_helper(function, context)
+            }
+            ConversionHelper::ParseInteger => {
+                self.generate_parse_integer_helper(function);
+                Ok(())
+            }
+            ConversionHelper::ToInteger => self.generate_to_integer_helper(function, context),
+            ConversionHelper::ToReal => self.generate_to_real_helper(function, context),
+            ConversionHelper::ToBool => self.generate_to_bool_helper(function, context),
+            ConversionHelper::ToDuration => self.generate_to_duration_helper(function, context),
+            ConversionHelper::ToDate => self.generate_to_date_helper(function, context),
+            ConversionHelper::ToTimeOfDay => self.generate_to_time_of_day_helper(function, context),
+        };
+        if let Some(block) = current_block {
+            self.llvm.builder.position_at_end(block);
+        }
+        generated.map(|_| function)
+    }
+
+    /// returns the signature of the given conversion helper
+    fn get_conversion_helper_type(&self, helper: ConversionHelper) -> FunctionType<'a> {
+        let context = self.llvm.context;
+        let string: BasicTypeEnum = context.i8_type().ptr_type(AddressSpace::Generic).into();
+        let wide_string: BasicTypeEnum = context.i16_type().ptr_type(AddressSpace::Generic).into();
+        let index: BasicTypeEnum = context.i32_type().ptr_type(AddressSpace::Generic).into();
+        let int: BasicTypeEnum = context.i32_type().into();
+        let long: BasicTypeEnum = context.i64_type().into();
+        let (return_type, parameters): (BasicTypeEnum, Vec<BasicTypeEnum>) = match helper {
+            ConversionHelper::Widen => (int, vec![wide_string, int, string, int]),
+            ConversionHelper::Narrow => (int, vec![string, int, wide_string, int]),
+            ConversionHelper::AppendInteger => (
+                int,
+                vec![string, int, int, long, context.bool_type().into(), int],
+            ),
+            ConversionHelper::AppendReal => {
+                (int, vec![string, int, int, context.f64_type().into(), int])
+            }
+            ConversionHelper::AppendDuration => (int, vec![string, int, int, long]),
+            ConversionHelper::AppendDate => (int, vec![string, int, int, long, long]),
+            ConversionHelper::AppendTimeOfDay => (int, vec![string, int, int, long, long, int]),
+            ConversionHelper::ParseInteger | ConversionHelper::ToDuration => {
+                (long, vec![string, int, index])
+            }
+            ConversionHelper::ToInteger => (long, vec![string, int]),
+            ConversionHelper::ToReal => (context.f64_type().into(), vec![string, int]),
+            ConversionHelper::ToBool => (context.bool_type().into(), vec![string, int]),
+            ConversionHelper::ToDate | ConversionHelper::ToTimeOfDay => {
+                (long, vec![string, int, index, long])
+            }
+        };
+        return_type.fn_type(&parameters, false)
+    }
+
+    /// generates the body of `__wstring_from_string(dest, dest_capacity, src, src_capacity)` or
+    /// `__string_from_wstring(dest, dest_capacity, src, src_capacity)` for the given encoding of
+    /// `dest`, it never writes more than `dest_capacity - 1` characters followed by the terminator
+    fn generate_recode_helper(&self, function: FunctionValue<'a>, encoding: &StringEncoding) {
+        let context = self.llvm.context;
+        let builder = &self.llvm.builder;
+        let destination = get_parameter(function, 0).into_pointer_value();
+        let destination_capacity = get_parameter(function, 1).into_int_value();
+        let source = get_parameter(function, 2).into_pointer_value();
+        let source_capacity = get_parameter(function, 3).into_int_value();
+        let i32_type = context.i32_type();
+        let character_type = self.get_character_type(encoding);
+
+        let entry = context.append_basic_block(function, "entry");
+        let condition = context.append_basic_block(function, "condition");
+        let copy = context.append_basic_block(function, "copy");
+        let exit = context.append_basic_block(function, "exit");
+
+        builder.position_at_end(entry);
+        let destination_limit =
+            builder.build_int_sub(destination_capacity, i32_type.const_int(1, false), "");
+        builder.build_unconditional_branch(condition);
+
+        //while index < dest_capacity - 1 && index < src_capacity && src[index] <> 0
+        builder.position_at_end(condition);
+        let index_phi = builder.build_phi(i32_type, "index");
+        let index = index_phi.as_basic_value().into_int_value();
+        let is_in_bounds = builder.build_and(
+            builder.build_int_compare(IntPredicate::SLT, index, destination_limit, ""),
+            builder.build_int_compare(IntPredicate::SLT, index, source_capacity, ""),
+            "",
+        );
+        builder.build_conditional_branch(is_in_bounds, copy, exit);
+
+        builder.position_at_end(copy);
+        let source_pointer = unsafe { builder.build_in_bounds_gep(source, &[index], "") };
+        let character = builder.build_load(source_pointer, "").into_int_value();
+        let converted = match encoding {
+            StringEncoding::Utf8 => builder.build_int_truncate(character, character_type, ""),
+            StringEncoding::Utf16 => builder.build_int_z_extend(character, character_type, ""),
+        };
+        let destination_pointer = unsafe { builder.build_in_bounds_gep(destination, &[index], "") };
+        builder.build_store(destination_pointer, converted);
+        let is_terminator = builder.build_int_compare(
+            IntPredicate::EQ,
+            character,
+            character.get_type().const_zero(),
+            "",
+        );
+        let next_index = builder.build_int_add(index, i32_type.const_int(1, false), "");
+        builder.build_conditional_branch(is_terminator, exit, condition);
+        index_phi.add_incoming(&[(&i32_type.const_zero(), entry), (&next_index, copy)]);
+
+        builder.position_at_end(exit);
+        let terminator_pointer = unsafe { builder.build_in_bounds_gep(destination, &[index], "") };
+        builder.build_store(terminator_pointer, character_type.const_zero());
+        builder.build_return(Some(&index));
+    }
+
+    /// generates the body of
+    /// `__string_append_integer(dest, capacity, length, value, is_negative, digits)`, the digits
+    /// are written into a local buffer from its end and appended afterwards
+    fn generate_append_integer_helper(
+        &self,
+        function: FunctionValue<'a>,
+        context: &AstStatement,
+    ) -> Result<(), CompileError> {
+        let append =
+            self.get_string_helper(StringHelper::Append, &StringEncoding::Utf8, context)?;
+        let llvm_context = self.llvm.context;
+        let builder = &self.llvm.builder;
+        let destination = get_parameter(function, 0);
+        let capacity = get_parameter(function, 1);
+        let length = get_parameter(function, 2);
+        let value = get_parameter(function, 3).into_int_value();
+        let is_negative = get_parameter(function, 4).into_int_value();
+        let digits = get_parameter(function, 5).into_int_value();
+        let i8_type = llvm_context.i8_type();
+        let i32_type = llvm_context.i32_type();
+        let i64_type = llvm_context.i64_type();
+        let end = i32_type.const_int(INTEGER_BUFFER_SIZE - 1, false);
+        let ten = i64_type.const_int(10, false);
+
+        let entry = llvm_context.append_basic_block(function, "entry");
+        let condition = llvm_context.append_basic_block(function, "condition");
+        let digit = llvm_context.append_basic_block(function, "digit");
+        let exit = llvm_context.append_basic_block(function, "exit");
+
+        builder.position_at_end(entry);
+        let buffer = builder.build_alloca(i8_type.array_type(INTEGER_BUFFER_SIZE as u32), "");
+        let buffer =
+            builder.build_pointer_cast(buffer, i8_type.ptr_type(AddressSpace::Generic), "");
+        builder.build_unconditional_branch(condition);
+
+        //while (rest <> 0 || count < digits) && position > 1
+        builder.position_at_end(condition);
+        let position_phi = builder.build_phi(i32_type, "position");
+        let rest_phi = builder.build_phi(i64_type, "rest");
+        let count_phi = builder.build_phi(i32_type, "count");
+        let position = position_phi.as_basic_value().into_int_value();
+        let rest = rest_phi.as_basic_value().into_int_value();
+        let count = count_phi.as_basic_value().into_int_value();
+        let has_digits = builder.build_or(
+            builder.build_int_compare(IntPredicate::NE, rest, i64_type.const_zero(), ""),
+            builder.build_int_compare(IntPredicate::SLT, count, digits, ""),
+            "",
+        );
+        let has_space = builder.build_int_compare(
+            IntPredicate::SGT,
+            position,
+            i32_type.const_int(1, false),
+            "",
+        );
+        builder.build_conditional_branch(builder.build_and(has_digits, has_space, ""), digit, exit);
+
+        //buffer[position - 1] := '0' + rest MOD 10
+        builder.position_at_end(digit);
+        let next_position = builder.build_int_sub(position, i32_type.const_int(1, false), "");
+        let character = builder.build_int_add(
+            builder.build_int_truncate(builder.build_int_unsigned_rem(rest, ten, ""), i8_type, ""),
+            i8_type.const_int(b'0' as u64, false),
+            "",
+        );
+        let character_pointer =
+            unsafe { builder.build_in_bounds_gep(buffer, &[next_position], "") };
+        builder.build_store(character_pointer, character);
+        let next_rest = builder.build_int_unsigned_div(rest, ten, "");
+        let next_count = builder.build_int_add(count, i32_type.const_int(1, false), "");
+        builder.build_unconditional_branch(condition);
+        position_phi.add_incoming(&[(&end, entry), (&next_position, digit)]);
+        rest_phi.add_incoming(&[(&value, entry), (&next_rest, digit)]);
+        count_phi.add_incoming(&[(&i32_type.const_zero(), entry), (&next_count, digit)]);
+
+        //the sign is written in front of the digits, it is skipped for positive values
+        builder.position_at_end(exit);
+        let sign_position = builder.build_int_sub(position, i32_type.const_int(1, false), "");
+        let sign_pointer = unsafe { builder.build_in_bounds_gep(buffer, &[sign_position], "") };
+        builder.build_store(sign_pointer, i8_type.const_int(b'-' as u64, false));
+        let start = builder
+            .build_select(is_negative, sign_position, position, "")
+            .into_int_value();
+        let text_length = builder.build_int_sub(end, start, "");
+        let new_length = builder
+            .build_call(
+                append,
+                &[
+                    destination,
+                    capacity,
+                    length,
+                    buffer.into(),
+                    i32_type.const_int(INTEGER_BUFFER_SIZE, false).into(),
+                    start.into(),
+                    text_length.into(),
+                ],
+                "",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap_or(length);
+        builder.build_return(Some(&new_length));
+        Ok(())
+    }
+
+    /// generates the body of `__string_append_real(dest, capacity, length, value, precision)`
+    ///
+    /// the value is rounded to an integer of `precision` digits (the significand) and its
+    /// decimal exponent. Numbers from 1E-5 to 1E`precision` are written without an exponent,
+    /// trailing zeros of the fraction are left out
+    fn generate_append_real_helper(
+        &self,
+        function: FunctionValue<'a>,
+        context: &AstStatement,
+    ) -> Result<(), CompileError> {
+        let append_integer =
+            self.get_conversion_helper(ConversionHelper::AppendInteger, context)?;
+        let llvm_context = self.llvm.context;
+        let builder = &self.llvm.builder;
+        let destination = [get_parameter(function, 0), get_parameter(function, 1)];
+        let length = get_parameter(function, 2).into_int_value();
+        let value = get_parameter(function, 3).into_float_value();
+        let precision = get_parameter(function, 4).into_int_value();
+        let i32_type = llvm_context.i32_type();
+        let i64_type = llvm_context.i64_type();
+        let double_type = llvm_context.f64_type();
+        let one = i32_type.const_int(1, false);
+        let ten = double_type.const_float(10.0);
+        let call_intrinsic = |name: &str, arguments: &[FloatValue<'a>]| {
+            self.call_float_function(name, arguments, context)
+                .map(BasicValueEnum::into_float_value)
+        };
+        let power_of_ten = |exponent: IntValue<'a>| {
+            let exponent = builder.build_signed_int_to_float(exponent, double_type, "");
+            call_intrinsic("llvm.pow.f64", &[ten, exponent])
+        };
+
+        let entry = llvm_context.append_basic_block(function, "entry");
+        let special = llvm_context.append_basic_block(function, "special");
+        let finite = llvm_context.append_basic_block(function, "finite");
+        let trim = llvm_context.append_basic_block(function, "trim");
+        let format = llvm_context.append_basic_block(function, "format");
+
+        builder.position_at_end(entry);
+        let magnitude = call_intrinsic("llvm.fabs.f64", &[value])?;
+        let is_nan = builder.build_float_compare(FloatPredicate::UNO, value, value, "");
+        let is_infinite = builder.build_float_compare(
+            FloatPredicate::OEQ,
+            magnitude,
+            double_type.const_float(f64::INFINITY),
+            "",
+        );
+        let is_zero = builder.build_float_compare(
+            FloatPredicate::OEQ,
+            magnitude,
+            double_type.const_zero(),
+            "",
+        );
+        let is_negative =
+            builder.build_float_compare(FloatPredicate::OLT, value, double_type.const_zero(), "");
+        let is_special = builder.build_or(builder.build_or(is_nan, is_infinite, ""), is_zero, "");
+        builder.build_conditional_branch(is_special, special, finite);
+
+        //NaN, INF, -INF or 0.0
+        builder.position_at_end(special);
+        let (nan_text, nan_capacity) = self.create_text("NaN");
+        let (infinity_text, infinity_capacity) = self.create_text("-INF");
+        let (zero_text, zero_capacity) = self.create_text("0.0");
+        let select = |then_value: BasicValueEnum<'a>, else_value: BasicValueEnum<'a>| {
+            builder.build_select(
+                is_nan,
+                then_value,
+                builder.build_select(is_infinite, else_value, zero_text.into(), ""),
+                "",
+            )
+        };
+        let text = select(nan_text.into(), infinity_text.into());
+        let select_capacity = |then_value: IntValue<'a>, else_value: IntValue<'a>| {
+            builder.build_select(
+                is_nan,
+                then_value,
+                builder
+                    .build_select(is_infinite, else_value, zero_capacity, "")
+                    .into_int_value(),
+                "",
+            )
+        };
+        let text_capacity = select_capacity(nan_capacity, infinity_capacity);
+        let is_positive_infinity =
+            builder.build_and(is_infinite, builder.build_not(is_negative, ""), "");
+        let start = self.select_count(is_positive_infinity, one);
+        let special_length = self.call_string_helper(
+            StringHelper::Append,
+            &StringEncoding::Utf8,
+            &[
+                destination[0],
+                destination[1],
+                length.into(),
+                text,
+                text_capacity,
+                start.into(),
+                text_capacity,
+            ],
+            context,
+        )?;
+        builder.build_return(Some(&special_length));
+
+        //the exponent of the highest digit, corrected if log10 is off by one
+        builder.position_at_end(finite);
+        let estimate = builder.build_float_to_signed_int(
+            call_intrinsic(
+                "llvm.floor.f64",
+                &[call_intrinsic("llvm.log10.f64", &[magnitude])?],
+            )?,
+            i32_type,
+            "",
+        );
+        let power = power_of_ten(estimate)?;
+        let is_below = builder.build_float_compare(FloatPredicate::OLT, magnitude, power, "");
+        let is_above = builder.build_float_compare(
+            FloatPredicate::OGE,
+            magnitude,
+            builder.build_float_mul(power, ten, ""),
+            "",
+        );
+        let exponent = builder
+            .build_select(
+                is_below,
+                builder.build_int_sub(estimate, one, ""),
+                builder
+                    .build_select(
+                        is_above,
+                        builder.build_int_add(estimate, one, ""),
+                        estimate,
+                        "",
+                    )
+                    .into_int_value(),
+                "",
+            )
+            .into_int_value();
+        //scale the value to `precision` digits in two steps, one power of ten may overflow
+        let shift = builder.build_int_sub(builder.build_int_sub(precision, one, ""), exponent, "");
+        let half_shift = builder.build_int_signed_div(shift, i32_type.const_int(2, false), "");
+        let scaled = builder.build_float_mul(
+            builder.build_float_mul(magnitude, power_of_ten(half_shift)?, ""),
+            power_of_ten(builder.build_int_sub(shift, half_shift, ""))?,
+            "",
+        );
+        let rounded = builder.build_float_to_unsigned_int(
+            call_intrinsic("llvm.round.f64", &[scaled])?,
+            i64_type,
+            "",
+        );
+        //rounding up may add a digit (e.g. 9.9999999 -> 10.00000)
+        let limit = builder.build_float_to_unsigned_int(power_of_ten(precision)?, i64_type, "");
+        let is_overflow = builder.build_int_compare(IntPredicate::UGE, rounded, limit, "");
+        let significand = builder
+            .build_select(
+                is_overflow,
+                builder.build_int_unsigned_div(rounded, i64_type.const_int(10, false), ""),
+                rounded,
+                "",
+            )
+            .into_int_value();
+        let exponent = builder
+            .build_select(
+                is_overflow,
+                builder.build_int_add(exponent, one, ""),
+                exponent,
+                "",
+            )
+            .into_int_value();
+        builder.build_unconditional_branch(trim);
+
+        //remove the trailing zeros of the significand
+        builder.position_at_end(trim);
+        let digits_phi = builder.build_phi(i32_type, "digits");
+        let significand_phi = builder.build_phi(i64_type, "significand");
+        let digits = digits_phi.as_basic_value().into_int_value();
+        let trimmed = significand_phi.as_basic_value().into_int_value();
+        let ten_integer = i64_type.const_int(10, false);
+        let is_zero_digit = builder.build_int_compare(
+            IntPredicate::EQ,
+            builder.build_int_unsigned_rem(trimmed, ten_integer, ""),
+            i64_type.const_zero(),
+            "",
+        );
+        let can_trim = builder.build_and(
+            is_zero_digit,
+            builder.build_int_compare(IntPredicate::SGT, digits, one, ""),
+            "",
+        );
+        let next_digits = builder.build_int_sub(digits, one, "");
+        let next_significand = builder.build_int_unsigned_div(trimmed, ten_integer, "");
+        builder.build_conditional_branch(can_trim, trim, format);
+        digits_phi.add_incoming(&[(&precision, finite), (&next_digits, trim)]);
+        significand_phi.add_incoming(&[(&significand, finite), (&next_significand, trim)]);
+
+        builder.position_at_end(format);
+        let (digit_text, digit_capacity) = self.create_text(&"0".repeat(23));
+        builder.build_call(
+            append_integer,
+            &[
+                digit_text.into(),
+                digit_capacity.into(),
+                i32_type.const_zero().into(),
+                trimmed.into(),
+                llvm_context.bool_type().const_zero().into(),
+                digits.into(),
+            ],
+            "",
+        );
+        let append_digits = |length: IntValue<'a>, start: IntValue<'a>, count: IntValue<'a>| {
+            self.call_string_helper(
+                StringHelper::Append,
+                &StringEncoding::Utf8,
+                &[
+                    destination[0],
+                    destination[1],
+                    length.into(),
+                    digit_text.into(),
+                    digit_capacity.into(),
+                    start.into(),
+                    count.into(),
+                ],
+                context,
+            )
+        };
+        let is_fixed = builder.build_and(
+            builder.build_int_compare(
+                IntPredicate::SGE,
+                exponent,
+                i32_type.const_int(-5i64 as u64, true),
+                "",
+            ),
+            builder.build_int_compare(IntPredicate::SLT, exponent, precision, ""),
+            "",
+        );
+        let is_negative_exponent =
+            builder.build_int_compare(IntPredicate::SLT, exponent, i32_type.const_zero(), "");
+        let is_small = builder.build_and(is_fixed, is_negative_exponent, "");
+        //the digits in front of the point, small numbers start with 0.000
+        let integer_digits = builder
+            .build_select(
+                is_fixed,
+                builder
+                    .build_select(
+                        is_negative_exponent,
+                        i32_type.const_zero(),
+                        builder.build_int_add(exponent, one, ""),
+                        "",
+                    )
+                    .into_int_value(),
+                one,
+                "",
+            )
+            .into_int_value();
+        let length = self.append_text(
+            &destination,
+            length,
+            "-",
+            self.select_count(is_negative, one),
+            context,
+        )?;
+        let length = self.append_text(
+            &destination,
+            length,
+            "0.",
+            self.select_count(is_small, i32_type.const_int(2, false)),
+            context,
+        )?;
+        let leading_zeros = builder.build_int_sub(builder.build_int_neg(exponent, ""), one, "");
+        let length = self.append_text(
+            &destination,
+            length,
+            "0000",
+            self.select_count(is_small, leading_zeros),
+            context,
+        )?;
+        let length = append_digits(length, i32_type.const_zero(), integer_digits)?;
+        let length = self.append_text(
+            &destination,
+            length,
+            &"0".repeat(23),
+            builder.build_int_sub(integer_digits, digits, ""),
+            context,
+        )?;
+        //the fraction, at least one digit behind the point
+        let has_point =
+            builder.build_int_compare(IntPredicate::SGT, integer_digits, i32_type.const_zero(), "");
+        let length = self.append_text(
+            &destination,
+            length,
+            ".",
+            self.select_count(has_point, one),
+            context,
+        )?;
+        let length = append_digits(
+            length,
+            integer_digits,
+            builder.build_int_sub(digits, integer_digits, ""),
+        )?;
+        let has_no_fraction = builder.build_and(
+            has_point,
+            builder.build_int_compare(IntPredicate::SLE, digits, integer_digits, ""),
+            "",
+        );
+        let length = self.append_text(
+            &destination,
+            length,
+            "0",
+            self.select_count(has_no_fraction, one),
+            context,
+        )?;
+        //the exponent of very small or big numbers
+        let is_exponential = builder.build_not(is_fixed, "");
+        let length = self.append_text(
+            &destination,
+            length,
+            "E",
+            self.select_count(is_exponential, one),
+            context,
+        )?;
+        let exponent_magnitude = builder.build_int_z_extend(
+            builder
+                .build_select(
+                    is_negative_exponent,
+                    builder.build_int_neg(exponent, ""),
+                    exponent,
+                    "",
+                )
+                .into_int_value(),
+            i64_type,
+            "",
+        );
+        let length = builder
+            .build_call(
+                append_integer,
+                &[
+                    destination[0],
+                    destination[1],
+                    length.into(),
+                    builder.build_select(
+                        is_exponential,
+                        exponent_magnitude,
+                        i64_type.const_zero(),
+                        "",
+                    ),
+                    builder
+                        .build_and(is_exponential, is_negative_exponent, "")
+                        .into(),
+                    self.select_count(is_exponential, one).into(),
+                ],
+                "",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap_or_else(|| length.into());
+        builder.build_return(Some(&length));
+        Ok(())
+    }
+
+    /// generates the body of `__string_append_duration(dest, capacity, length, value)` which
+    /// writes every unit of the duration that is not 0 (e.g. `-1h30m` or `2s500ms`)
+    fn generate_append_duration_helper(
+        &self,
+        function: FunctionValue<'a>,
+        context: &AstStatement,
+    ) -> Result<(), CompileError> {
+        let append_integer =
+            self.get_conversion_helper(ConversionHelper::AppendInteger, context)?;
+        let llvm_context = self.llvm.context;
+        let builder = &self.llvm.builder;
+        let destination = [get_parameter(function, 0), get_parameter(function, 1)];
+        let value = get_parameter(function, 3).into_int_value();
+        let i32_type = llvm_context.i32_type();
+        let i64_type = llvm_context.i64_type();
+
+        let entry = llvm_context.append_basic_block(function, "entry");
+        builder.position_at_end(entry);
+        let is_negative =
+            builder.build_int_compare(IntPredicate::SLT, value, i64_type.const_zero(), "");
+        let magnitude = builder
+            .build_select(is_negative, builder.build_int_neg(value, ""), value, "")
+            .into_int_value();
+        let mut length = self.append_text(
+            &destination,
+            get_parameter(function, 2).into_int_value(),
+            "-",
+            self.select_count(is_negative, i32_type.const_int(1, false)),
+            context,
+        )?;
+        let mut bigger_unit = None;
+        for (suffix, unit) in DURATION_UNITS.iter() {
+            let count =
+                builder.build_int_unsigned_div(magnitude, i64_type.const_int(*unit, false), "");
+            let count = match bigger_unit {
+                Some(bigger_unit) => builder.build_int_unsigned_rem(
+                    count,
+                    i64_type.const_int(bigger_unit / unit, false),
+                    "",
+                ),
+                None => count,
+            };
+            bigger_unit = Some(*unit);
+            //a count of 0 writes no digits
+            length = builder
+                .build_call(
+                    append_integer,
+                    &[
+                        destination[0],
+                        destination[1],
+                        length.into(),
+                        count.into(),
+                        llvm_context.bool_type().const_zero().into(),
+                        i32_type.const_zero().into(),
+                    ],
+                    "",
+                )
+                .try_as_basic_value()
+                .left()
+                .map(BasicValueEnum::into_int_value)
+                .unwrap_or(length);
+            let has_unit =
+                builder.build_int_compare(IntPredicate::NE, count, i64_type.const_zero(), "");
+            length = self.append_text(
+                &destination,
+                length,
+                suffix,
+                self.select_count(has_unit, i32_type.const_int(suffix.len() as u64, false)),
+                context,
+            )?;
+        }
+        let is_zero =
+            builder.build_int_compare(IntPredicate::EQ, magnitude, i64_type.const_zero(), "");
+        let length = self.append_text(
+            &destination,
+            length,
+            "0ms",
+            self.select_count(is_zero, i32_type.const_int(3, false)),
+            context,
+        )?;
+        builder.build_return(Some(&length));
+        Ok(())
+    }
+
+    /// generates the body of `__string_append_date(dest, capacity, length, value,
+    /// units_per_second)` which writes the date as `yyyy-mm-dd`
+    fn generate_append_date_helper(
+        &self,
+        function: FunctionValue<'a>,
+        context: &AstStatement,
+    ) -> Result<(), CompileError> {
+        let append_integer =
+            self.get_conversion_helper(ConversionHelper::AppendInteger, context)?;
+        let llvm_context = self.llvm.context;
+        let builder = &self.llvm.builder;
+        let destination = [get_parameter(function, 0), get_parameter(function, 1)];
+        let value = get_parameter(function, 3).into_int_value();
+        let units_per_second = get_parameter(function, 4).into_int_value();
+        let i32_type = llvm_context.i32_type();
+        let i64_type = llvm_context.i64_type();
+        let constant = |value: i64| i64_type.const_int(value as u64, true);
+
+        let entry = llvm_context.append_basic_block(function, "entry");
+        builder.position_at_end(entry);
+        let units_per_day = builder.build_int_mul(units_per_second, constant(86_400), "");
+        let days = self.build_floor_division(value, units_per_day);
+
+        //the civil date of the days since 1970-01-01 (http://howardhinnant.github.io/date_algorithms.html)
+        let shifted_days = builder.build_int_add(days, constant(719_468), "");
+        let era = self.build_floor_division(shifted_days, constant(146_097));
+        let day_of_era = builder.build_int_sub(
+            shifted_days,
+            builder.build_int_mul(era, constant(146_097), ""),
+            "",
+        );
+        let divide = |value: IntValue<'a>, divisor: i64| {
+            builder.build_int_signed_div(value, constant(divisor), "")
+        };
+        //(doe - doe / 1460 + doe / 36524 - doe / 146096) / 365
+        let year_of_era = divide(
+            builder.build_int_sub(
+                builder.build_int_add(
+                    builder.build_int_sub(day_of_era, divide(day_of_era, 1460), ""),
+                    divide(day_of_era, 36_524),
+                    "",
+                ),
+                divide(day_of_era, 146_096),
+                "",
+            ),
+            365,
+        );
+        //doe - (365 * yoe + yoe / 4 - yoe / 100)
+        let day_of_year = builder.build_int_sub(
+            day_of_era,
+            builder.build_int_sub(
+                builder.build_int_add(
+                    builder.build_int_mul(year_of_era, constant(365), ""),
+                    divide(year_of_era, 4),
+                    "",
+                ),
+                divide(year_of_era, 100),
+                "",
+            ),
+            "",
+        );
+        //the month starting with march
+        let shifted_month = divide(
+            builder.build_int_add(
+                builder.build_int_mul(day_of_year, constant(5), ""),
+                constant(2),
+                "",
+            ),
+            153,
+        );
+        let day = builder.build_int_add(
+            builder.build_int_sub(
+                day_of_year,
+                divide(
+                    builder.build_int_add(
+                        builder.build_int_mul(shifted_month, constant(153), ""),
+                        constant(2),
+                        "",
+                    ),
+                    5,
+                ),
+                "",
+            ),
+            constant(1),
+            "",
+        );
+        let is_next_year =
+            builder.build_int_compare(IntPredicate::SGE, shifted_month, constant(10), "");
+        let month = builder
+            .build_select(
+                is_next_year,
+                builder.build_int_sub(shifted_month, constant(9), ""),
+                builder.build_int_add(shifted_month, constant(3), ""),
+                "",
+            )
+            .into_int_value();
+        let year = builder.build_int_add(
+            builder.build_int_add(
+                year_of_era,
+                builder.build_int_mul(era, constant(400), ""),
+                "",
+            ),
+            builder.build_int_z_extend(is_next_year, i64_type, ""),
+            "",
+        );
+
+        let append_number = |length: IntValue<'a>, number: IntValue<'a>, digits: u64| {
+            let is_negative =
+                builder.build_int_compare(IntPredicate::SLT, number, i64_type.const_zero(), "");
+            builder
+                .build_call(
+                    append_integer,
+                    &[
+                        destination[0],
+                        destination[1],
+                        length.into(),
+                        builder.build_select(
+                            is_negative,
+                            builder.build_int_neg(number, ""),
+                            number,
+                            "",
+                        ),
+                        is_negative.into(),
+                        i32_type.const_int(digits, false).into(),
+                    ],
+                    "",
+                )
+                .try_as_basic_value()
+                .left()
+                .map(BasicValueEnum::into_int_value)
+                .unwrap_or(length)
+        };
+        let separator = i32_type.const_int(1, false);
+        let length = append_number(get_parameter(function, 2).into_int_value(), year, 4);
+        let length = self.append_text(&destination, length, "-", separator, context)?;
+        let length = append_number(length, month, 2);
+        let length = self.append_text(&destination, length, "-", separator, context)?;
+        let length = append_number(length, day, 2);
+        builder.build_return(Some(&length));
+        Ok(())
+    }
+
+    /// generates the body of `__string_append_time_of_day(dest, capacity, length, value,
+    /// units_per_second, digits)` which writes the time of day as `hh:mm:ss`, followed by the
+    /// fraction of the second with the given number of digits if it is not 0
+    fn generate_append_time_of_day_helper(
+        &self,
+        function: FunctionValue<'a>,
+        context: &AstStatement,
+    ) -> Result<(), CompileError> {
+        let append_integer =
+            self.get_conversion_helper(ConversionHelper::AppendInteger, context)?;
+        let llvm_context = self.llvm.context;
+        let builder = &self.llvm.builder;
+        let destination = [get_parameter(function, 0), get_parameter(function, 1)];
+        let value = get_parameter(function, 3).into_int_value();
+        let units_per_second = get_parameter(function, 4).into_int_value();
+        let fraction_digits = get_parameter(function, 5).into_int_value();
+        let i32_type = llvm_context.i32_type();
+        let i64_type = llvm_context.i64_type();
+        let constant = |value: u64| i64_type.const_int(value, false);
+
+        let entry = llvm_context.append_basic_block(function, "entry");
+        builder.position_at_end(entry);
+        let units_per_day = builder.build_int_mul(units_per_second, constant(86_400), "");
+        let time_of_day = builder.build_int_sub(
+            value,
+            builder.build_int_mul(
+                self.build_floor_division(value, units_per_day),
+                units_per_day,
+                "",
+            ),
+            "",
+        );
+        let seconds = builder.build_int_signed_div(time_of_day, units_per_second, "");
+        let fraction = builder.build_int_signed_rem(time_of_day, units_per_second, "");
+        let hours = builder.build_int_signed_div(seconds, constant(3600), "");
+        let minutes = builder.build_int_signed_rem(
+            builder.build_int_signed_div(seconds, constant(60), ""),
+            constant(60),
+            "",
+        );
+        let seconds = builder.build_int_signed_rem(seconds, constant(60), "");
+
+        let append_number = |length: IntValue<'a>, number: IntValue<'a>, digits: IntValue<'a>| {
+            builder
+                .build_call(
+                    append_integer,
+                    &[
+                        destination[0],
+                        destination[1],
+                        length.into(),
+                        number.into(),
+                        llvm_context.bool_type().const_zero().into(),
+                        digits.into(),
+                    ],
+                    "",
+                )
+                .try_as_basic_value()
+                .left()
+                .map(BasicValueEnum::into_int_value)
+                .unwrap_or(length)
+        };
+        let two_digits = i32_type.const_int(2, false);
+        let separator = i32_type.const_int(1, false);
+        let length = append_number(
+            get_parameter(function, 2).into_int_value(),
+            hours,
+            two_digits,
+        );
+        let length = self.append_text(&destination, length, ":", separator, context)?;
+        let length = append_number(length, minutes, two_digits);
+        let length = self.append_text(&destination, length, ":", separator, context)?;
+        let length = append_number(length, seconds, two_digits);
+        let has_fraction =
+            builder.build_int_compare(IntPredicate::NE, fraction, i64_type.const_zero(), "");
+        let length = self.append_text(
+            &destination,
+            length,
+            ".",
+            self.select_count(has_fraction, separator),
+            context,
+        )?;
+        let length = append_number(
+            length,
+            fraction,
+            self.select_count(has_fraction, fraction_digits),
+        );
+        builder.build_return(Some(&length));
+        Ok(())
+    }
+
+    /// generates the body of `__string_parse_integer(str, capacity, index)`
+    fn generate_parse_integer_helper(&self, function: FunctionValue<'a>) {
+        let context = self.llvm.context;
+        let builder = &self.llvm.builder;
+        let string = get_parameter(function, 0).into_pointer_value();
+        let capacity = get_parameter(function, 1).into_int_value();
+        let index = get_parameter(function, 2).into_pointer_value();
+        let i32_type = context.i32_type();
+        let i64_type = context.i64_type();
+
+        let entry = context.append_basic_block(function, "entry");
+        let condition = context.append_basic_block(function, "condition");
+        let digit = context.append_basic_block(function, "digit");
+        let exit = context.append_basic_block(function, "exit");
+
+        builder.position_at_end(entry);
+        let start = builder.build_load(index, "").into_int_value();
+        builder.build_unconditional_branch(condition);
+
+        //while str[position] is a digit
+        builder.position_at_end(condition);
+        let position_phi = builder.build_phi(i32_type, "position");
+        let result_phi = builder.build_phi(i64_type, "result");
+        let position = position_phi.as_basic_value().into_int_value();
+        let result = result_phi.as_basic_value().into_int_value();
+        let (is_digit, digit_value) = self.load_digit(string, capacity, position);
+        builder.build_conditional_branch(is_digit, digit, exit);
+
+        builder.position_at_end(digit);
+        let next_result = builder.build_int_add(
+            builder.build_int_mul(result, i64_type.const_int(10, false), ""),
+            builder.build_int_z_extend(digit_value, i64_type, ""),
+            "",
+        );
+        let next_position = builder.build_int_add(position, i32_type.const_int(1, false), "");
+        builder.build_unconditional_branch(condition);
+        position_phi.add_incoming(&[(&start, entry), (&next_position, digit)]);
+        result_phi.add_incoming(&[(&i64_type.const_zero(), entry), (&next_result, digit)]);
+
+        builder.position_at_end(exit);
+        builder.build_store(index, position);
+        builder.build_return(Some(&result));
+    }
+
+    /// generates the body of `__string_to_integer(str, capacity)` which reads an integer with
+    /// an optional sign
+    fn generate_to_integer_helper(
+        &self,
+        function: FunctionValue<'a>,
+        context: &AstStatement,
+    ) -> Result<(), CompileError> {
+        let parse_integer = self.get_conversion_helper(ConversionHelper::ParseInteger, context)?;
+        let llvm_context = self.llvm.context;
+        let builder = &self.llvm.builder;
+        let string = get_parameter(function, 0).into_pointer_value();
+        let capacity = get_parameter(function, 1).into_int_value();
+        let i32_type = llvm_context.i32_type();
+
+        let entry = llvm_context.append_basic_block(function, "entry");
+        builder.position_at_end(entry);
+        let index = builder.build_alloca(i32_type, "");
+        let position = self.build_skip_spaces(function, string, capacity);
+        let (is_negative, start) = self.build_sign(string, capacity, position);
+        builder.build_store(index, start);
+        let value = builder
+            .build_call(
+                parse_integer,
+                &[string.into(), capacity.into(), index.into()],
+                "",
+            )
+            .try_as_basic_value()
+            .left()
+            .map(BasicValueEnum::into_int_value)
+            .unwrap_or_else(|| llvm_context.i64_type().const_zero());
+        let result = builder.build_select(is_negative, builder.build_int_neg(value, ""), value, "");
+        builder.build_return(Some(&result));
+        Ok(())
+    }
+
+    /// generates the body of `__string_to_real(str, capacity)` which reads a real number with an
+    /// optional sign, fraction and exponent (e.g. `-1.5E-3`)
+    fn generate_to_real_helper(
+        &self,
+        function: FunctionValue<'a>,
+        context: &AstStatement,
+    ) -> Result<(), CompileError> {
+        let parse_integer = self.get_conversion_helper(ConversionHelper::ParseInteger, context)?;
+        let llvm_context = self.llvm.context;
+        let builder = &self.llvm.builder;
+        let string = get_parameter(function, 0).into_pointer_value();
+        let capacity = get_parameter(function, 1).into_int_value();
+        let i32_type = llvm_context.i32_type();
+        let i64_type = llvm_context.i64_type();
+        let double_type = llvm_context.f64_type();
+        let one = i32_type.const_int(1, false);
+
+        let entry = llvm_context.append_basic_block(function, "entry");
+        let integer = llvm_context.append_basic_block(function, "integer");
+        let point = llvm_context.append_basic_block(function, "point");
+        let fraction = llvm_context.append_basic_block(function, "fraction");
+        let exponent = llvm_context.append_basic_block(function, "exponent");
+
+        builder.position_at_end(entry);
+        let index = builder.build_alloca(i32_type, "");
+        let position = self.build_skip_spaces(function, string, capacity);
+        let (is_negative, start) = self.build_sign(string, capacity, position);
+        let sign_block = builder.get_insert_block().unwrap_or(entry);
+        builder.build_unconditional_branch(integer);
+
+        //the digits are collected as an integral LREAL, the fraction lowers the exponent
+        let collect_digit = |block: BasicBlock<'a>, next_block: BasicBlock<'a>| {
+            builder.position_at_end(block);
+            let position_phi = builder.build_phi(i32_type, "position");
+            let mantissa_phi = builder.build_phi(double_type, "mantissa");
+            let position = position_phi.as_basic_value().into_int_value();
+            let current_mantissa = mantissa_phi.as_basic_value().into_float_value();
+            let (is_digit, digit) = self.load_digit(string, capacity, position);
+            let next_mantissa = builder.build_float_add(
+                builder.build_float_mul(current_mantissa, double_type.const_float(10.0), ""),
+                builder.build_unsigned_int_to_float(digit, double_type, ""),
+                "",
+            );
+            let next_position = builder.build_int_add(position, one, "");
+            builder.build_conditional_branch(is_digit, block, next_block);
+            (
+                position_phi,
+                mantissa_phi,
+                position,
+                current_mantissa,
+                next_position,
+                next_mantissa,
+            )
+        };
+        let (
+            integer_position_phi,
+            integer_mantissa_phi,
+            integer_end,
+            integer_mantissa,
+            next_position,
+            next_mantissa,
+        ) = collect_digit(integer, point);
+        integer_position_phi.add_incoming(&[(&start, sign_block), (&next_position, integer)]);
+        integer_mantissa_phi.add_incoming(&[
+            (&double_type.const_zero(), sign_block),
+            (&next_mantissa, integer),
+        ]);
+
+        builder.position_at_end(point);
+        let has_point = self.is_character_at(string, capacity, integer_end, b'.');
+        let fraction_start = builder.build_int_add(
+            integer_end,
+            builder.build_int_z_extend(has_point, i32_type, ""),
+            "",
+        );
+        builder.build_unconditional_branch(fraction);
+
+        let (
+            fraction_position_phi,
+            fraction_mantissa_phi,
+            fraction_end,
+            mantissa,
+            next_position,
+            next_mantissa,
+        ) = collect_digit(fraction, exponent);
+        fraction_position_phi.add_incoming(&[(&fraction_start, point), (&next_position, fraction)]);
+        fraction_mantissa_phi
+            .add_incoming(&[(&integer_mantissa, point), (&next_mantissa, fraction)]);
+
+        //an optional exponent with a sign
+        builder.position_at_end(exponent);
+        let fraction_digits = builder.build_int_sub(fraction_end, fraction_start, "");
+        let is_exponent = builder.build_or(
+            self.is_character_at(string, capacity, fraction_end, b'e'),
+            self.is_character_at(string, capacity, fraction_end, b'E'),
+            "",
+        );
+        let sign_position = builder.build_int_add(
+            fraction_end,
+            builder.build_int_z_extend(is_exponent, i32_type, ""),
+            "",
+        );
+        let (is_negative_sign, exponent_start) = self.build_sign(string, capacity, sign_position);
+        let is_negative_exponent = builder.build_and(is_exponent, is_negative_sign, "");
+        let exponent_start = builder
+            .build_select(is_exponent, exponent_start, fraction_end, "")
+            .into_int_value();
+        builder.build_store(index, exponent_start);
+        let written_exponent = builder
+            .build_call(
+                parse_integer,
+                &[string.into(), capacity.into(), index.into()],
+                "",
+            )
+            .try_as_basic_value()
+            .left()
+            .map(BasicValueEnum::into_int_value)
+            .unwrap_or_else(|| i64_type.const_zero());
+        let written_exponent = builder
+            .build_select(
+                is_negative_exponent,
+                builder.build_int_neg(written_exponent, ""),
+                written_exponent,
+                "",
+            )
+            .into_int_value();
+        let decimal_exponent = builder.build_int_sub(
+            written_exponent,
+            builder.build_int_s_extend(fraction_digits, i64_type, ""),
+            "",
+        );
+
+        //mantissa * 10^exponent, divided by 10^-exponent for negative exponents
+        let is_negative_power = builder.build_int_compare(
+            IntPredicate::SLT,
+            decimal_exponent,
+            i64_type.const_zero(),
+            "",
+        );
+        let power_exponent = builder
+            .build_select(
+                is_negative_power,
+                builder.build_int_neg(decimal_exponent, ""),
+                decimal_exponent,
+                "",
+            )
+            .into_int_value();
+        let power = self
+            .call_float_function(
+                "llvm.pow.f64",
+                &[
+                    double_type.const_float(10.0),
+                    builder.build_signed_int_to_float(power_exponent, double_type, ""),
+                ],
+                context,
+            )?
+            .into_float_value();
+        let value = builder
+            .build_select(
+                is_negative_power,
+                builder.build_float_div(mantissa, power, ""),
+                builder.build_float_mul(mantissa, power, ""),
+                "",
+            )
+            .into_float_value();
+        //0 stays 0 even if the power overflows
+        let is_zero = builder.build_float_compare(
+            FloatPredicate::OEQ,
+            mantissa,
+            double_type.const_zero(),
+            "",
+        );
+        let value = builder
+            .build_select(is_zero, double_type.const_zero(), value, "")
+            .into_float_value();
+        let result =
+            builder.build_select(is_negative, builder.build_float_neg(value, ""), value, "");
+        builder.build_return(Some(&result));
+        Ok(())
+    }
+
+    /// generates the body of `__string_to_bool(str, capacity)` which returns TRUE for `TRUE`
+    /// (ignoring case) and numbers other than 0
+    fn generate_to_bool_helper(
+        &self,
+        function: FunctionValue<'a>,
+        context: &AstStatement,
+    ) -> Result<(), CompileError> {
+        let to_integer = self.get_conversion_helper(ConversionHelper::ToInteger, context)?;
+        let llvm_context = self.llvm.context;
+        let builder = &self.llvm.builder;
+        let string = get_parameter(function, 0).into_pointer_value();
+        let capacity = get_parameter(function, 1).into_int_value();
+        let i32_type = llvm_context.i32_type();
+
+        let entry = llvm_context.append_basic_block(function, "entry");
+        builder.position_at_end(entry);
+        let position = self.build_skip_spaces(function, string, capacity);
+        //the letters of TRUE (converted to lower case) followed by the end of the text
+        let is_true = b"true".iter().enumerate().fold(
+            llvm_context.bool_type().const_all_ones(),
+            |is_true, (offset, letter)| {
+                let index =
+                    builder.build_int_add(position, i32_type.const_int(offset as u64, false), "");
+                let character = builder.build_or(
+                    self.load_character(string, capacity, index),
+                    llvm_context.i8_type().const_int(0x20, false),
+                    "",
+                );
+                let is_letter = builder.build_int_compare(
+                    IntPredicate::EQ,
+                    character,
+                    llvm_context.i8_type().const_int(*letter as u64, false),
+                    "",
+                );
+                builder.build_and(is_true, is_letter, "")
+            },
+        );
+        let end = builder.build_int_add(position, i32_type.const_int(4, false), "");
+        let is_end = builder.build_or(
+            self.is_character_at(string, capacity, end, 0),
+            self.is_character_at(string, capacity, end, b' '),
+            "",
+        );
+        let is_true = builder.build_and(is_true, is_end, "");
+        let number = builder
+            .build_call(to_integer, &[string.into(), capacity.into()], "")
+            .try_as_basic_value()
+            .left()
+            .map(BasicValueEnum::into_int_value)
+            .unwrap_or_else(|| llvm_context.i64_type().const_zero());
+        let is_not_zero =
+            builder.build_int_compare(IntPredicate::NE, number, number.get_type().const_zero(), "");
+        builder.build_return(Some(&builder.build_or(is_true, is_not_zero, "")));
+        Ok(())
+    }
+
+    /// generates the body of `__string_to_duration(str, capacity, index)` which reads a sequence
+    /// of numbers with units (e.g. `1h30m`, `1.5s` or `-2d_12h`)
+    fn generate_to_duration_helper(
+        &self,
+        function: FunctionValue<'a>,
+        context: &AstStatement,
+    ) -> Result<(), CompileError> {
+        let parse_integer = self.get_conversion_helper(ConversionHelper::ParseInteger, context)?;
+        let llvm_context = self.llvm.context;
+        let builder = &self.llvm.builder;
+        let string = get_parameter(function, 0).into_pointer_value();
+        let capacity = get_parameter(function, 1).into_int_value();
+        let index = get_parameter(function, 2).into_pointer_value();
+        let i8_type = llvm_context.i8_type();
+        let i32_type = llvm_context.i32_type();
+        let i64_type = llvm_context.i64_type();
+        let parse = || {
+            builder
+                .build_call(
+                    parse_integer,
+                    &[string.into(), capacity.into(), index.into()],
+                    "",
+                )
+                .try_as_basic_value()
+                .left()
+                .map(BasicValueEnum::into_int_value)
+                .unwrap_or_else(|| i64_type.const_zero())
+        };
+
+        let entry = llvm_context.append_basic_block(function, "entry");
+        let component = llvm_context.append_basic_block(function, "component");
+        let exit = llvm_context.append_basic_block(function, "exit");
+
+        builder.position_at_end(entry);
+        let start = builder.build_load(index, "").into_int_value();
+        let is_negative = self.is_character_at(string, capacity, start, b'-');
+        let first = builder.build_int_add(
+            start,
+            builder.build_int_z_extend(is_negative, i32_type, ""),
+            "",
+        );
+        builder.build_unconditional_branch(component);
+
+        //while the text continues with a number followed by a unit
+        builder.position_at_end(component);
+        let position_phi = builder.build_phi(i32_type, "position");
+        let total_phi = builder.build_phi(i64_type, "total");
+        let position = position_phi.as_basic_value().into_int_value();
+        let total = total_phi.as_basic_value().into_int_value();
+        builder.build_store(index, position);
+        let whole = parse();
+        let whole_end = builder.build_load(index, "").into_int_value();
+        let has_digits = builder.build_int_compare(IntPredicate::NE, whole_end, position, "");
+        let has_point = self.is_character_at(string, capacity, whole_end, b'.');
+        let fraction_start = builder.build_int_add(
+            whole_end,
+            builder.build_int_z_extend(has_point, i32_type, ""),
+            "",
+        );
+        builder.build_store(index, fraction_start);
+        let fraction = parse();
+        let fraction_end = builder.build_load(index, "").into_int_value();
+        let fraction_digits = builder.build_int_sub(fraction_end, fraction_start, "");
+
+        //the units in lower case, a single letter unit must not be followed by an s (m or ms)
+        let lower_case = |offset: u64| {
+            let position =
+                builder.build_int_add(fraction_end, i32_type.const_int(offset, false), "");
+            builder.build_or(
+                self.load_character(string, capacity, position),
+                i8_type.const_int(0x20, false),
+                "",
+            )
+        };
+        let first_letter = lower_case(0);
+        let second_letter = lower_case(1);
+        let is_letter = |character: IntValue<'a>, letter: u8| {
+            builder.build_int_compare(
+                IntPredicate::EQ,
+                character,
+                i8_type.const_int(letter as u64, false),
+                "",
+            )
+        };
+        let (unit, unit_length) = DURATION_UNITS.iter().fold(
+            (i64_type.const_zero(), i32_type.const_zero()),
+            |(unit, unit_length), (suffix, nanos)| {
+                let letters = suffix.as_bytes();
+                let is_second_letter = is_letter(second_letter, b's');
+                let matches = builder.build_and(
+                    is_letter(first_letter, letters[0]),
+                    if letters.len() > 1 {
+                        is_second_letter
+                    } else {
+                        builder.build_not(is_second_letter, "")
+                    },
+                    "",
+                );
+                (
+                    builder
+                        .build_select(matches, i64_type.const_int(*nanos, false), unit, "")
+                        .into_int_value(),
+                    builder
+                        .build_select(
+                            matches,
+                            i32_type.const_int(letters.len() as u64, false),
+                            unit_length,
+                            "",
+                        )
+                        .into_int_value(),
+                )
+            },
+        );
+        let is_component = builder.build_and(
+            has_digits,
+            builder.build_int_compare(IntPredicate::NE, unit, i64_type.const_zero(), ""),
+            "",
+        );
+        //whole * unit + ROUND(fraction * unit / 10^digits)
+        let double_type = llvm_context.f64_type();
+        let scaled_fraction = builder.build_float_div(
+            builder.build_float_mul(
+                builder.build_unsigned_int_to_float(fraction, double_type, ""),
+                builder.build_unsigned_int_to_float(unit, double_type, ""),
+                "",
+            ),
+            self.call_float_function(
+                "llvm.pow.f64",
+                &[
+                    double_type.const_float(10.0),
+                    builder.build_signed_int_to_float(fraction_digits, double_type, ""),
+                ],
+                context,
+            )?
+            .into_float_value(),
+            "",
+        );
+        let fraction_value = builder.build_float_to_signed_int(
+            self.call_float_function("llvm.round.f64", &[scaled_fraction], context)?
+                .into_float_value(),
+            i64_type,
+            "",
+        );
+        let next_total = builder.build_int_add(
+            total,
+            builder.build_int_add(builder.build_int_mul(whole, unit, ""), fraction_value, ""),
+            "",
+        );
+        //units may be separated by an underscore
+        let unit_end = builder.build_int_add(fraction_end, unit_length, "");
+        let is_separator = self.is_character_at(string, capacity, unit_end, b'_');
+        let next_position = builder.build_int_add(
+            unit_end,
+            builder.build_int_z_extend(is_separator, i32_type, ""),
+            "",
+        );
+        builder.build_conditional_branch(is_component, component, exit);
+        position_phi.add_incoming(&[(&first, entry), (&next_position, component)]);
+        total_phi.add_incoming(&[(&i64_type.const_zero(), entry), (&next_total, component)]);
+
+        builder.position_at_end(exit);
+        builder.build_store(index, position);
+        let result = builder.build_select(is_negative, builder.build_int_neg(total, ""), total, "");
+        builder.build_return(Some(&result));
+        Ok(())
+    }
+
+    /// generates the body of `__string_to_date(str, capacity, index, units_per_second)` which
+    /// reads a date written as `yyyy-mm-dd`
+    fn generate_to_date_helper(
+        &self,
+        function: FunctionValue<'a>,
+        context: &AstStatement,
+    ) -> Result<(), CompileError> {
+        let parse_integer = self.get_conversion_helper(ConversionHelper::ParseInteger, context)?;
+        let llvm_context = self.llvm.context;
+        let builder = &self.llvm.builder;
+        let string = get_parameter(function, 0);
+        let capacity = get_parameter(function, 1);
+        let index = get_parameter(function, 2).into_pointer_value();
+        let units_per_second = get_parameter(function, 3).into_int_value();
+        let i64_type = llvm_context.i64_type();
+        let constant = |value: i64| i64_type.const_int(value as u64, true);
+        let parse = || {
+            builder
+                .build_call(parse_integer, &[string, capacity, index.into()], "")
+                .try_as_basic_value()
+                .left()
+                .map(BasicValueEnum::into_int_value)
+                .unwrap_or_else(|| i64_type.const_zero())
+        };
+
+        let entry = llvm_context.append_basic_block(function, "entry");
+        builder.position_at_end(entry);
+        let year = parse();
+        self.skip_character(index);
+        let month = parse();
+        self.skip_character(index);
+        let day = parse();
+
+        //the days since 1970-01-01 of the civil date (http://howardhinnant.github.io/date_algorithms.html)
+        let divide = |value: IntValue<'a>, divisor: i64| {
+            builder.build_int_signed_div(value, constant(divisor), "")
+        };
+        let is_before_march = builder.build_int_compare(IntPredicate::SLE, month, constant(2), "");
+        let shifted_year = builder.build_int_sub(
+            year,
+            builder.build_int_z_extend(is_before_march, i64_type, ""),
+            "",
+        );
+        let era = self.build_floor_division(shifted_year, constant(400));
+        let year_of_era = builder.build_int_sub(
+            shifted_year,
+            builder.build_int_mul(era, constant(400), ""),
+            "",
+        );
+        //the month starting with march
+        let shifted_month = builder
+            .build_select(
+                is_before_march,
+                builder.build_int_add(month, constant(9), ""),
+                builder.build_int_sub(month, constant(3), ""),
+                "",
+            )
+            .into_int_value();
+        //(153 * month + 2) / 5 + day - 1
+        let day_of_year = builder.build_int_sub(
+            builder.build_int_add(
+                divide(
+                    builder.build_int_add(
+                        builder.build_int_mul(shifted_month, constant(153), ""),
+                        constant(2),
+                        "",
+                    ),
+                    5,
+                ),
+                day,
+                "",
+            ),
+            constant(1),
+            "",
+        );
+        //yoe * 365 + yoe / 4 - yoe / 100 + doy
+        let day_of_era = builder.build_int_add(
+            builder.build_int_sub(
+                builder.build_int_add(
+                    builder.build_int_mul(year_of_era, constant(365), ""),
+                    divide(year_of_era, 4),
+                    "",
+                ),
+                divide(year_of_era, 100),
+                "",
+            ),
+            day_of_year,
+            "",
+        );
+        let days = builder.build_int_sub(
+            builder.build_int_add(
+                builder.build_int_mul(era, constant(146_097), ""),
+                day_of_era,
+                "",
+            ),
+            constant(719_468),
+            "",
+        );
+        let units_per_day = builder.build_int_mul(units_per_second, constant(86_400), "");
+        builder.build_return(Some(&builder.build_int_mul(days, units_per_day, "")));
+        Ok(())
+    }
+
+    /// generates the body of `__string_to_time_of_day(str, capacity, index, units_per_second)`
+    /// which reads a time of day written as `hh:mm:ss` with an optional fraction of the second
+    fn generate_to_time_of_day_helper(
+        &self,
+        function: FunctionValue<'a>,
+        context: &AstStatement,
+    ) -> Result<(), CompileError> {
+        let parse_integer = self.get_conversion_helper(ConversionHelper::ParseInteger, context)?;
+        let llvm_context = self.llvm.context;
+        let builder = &self.llvm.builder;
+        let string = get_parameter(function, 0);
+        let capacity = get_parameter(function, 1);
+        let index = get_parameter(function, 2).into_pointer_value();
+        let units_per_second = get_parameter(function, 3).into_int_value();
+        let i32_type = llvm_context.i32_type();
+        let i64_type = llvm_context.i64_type();
+        let double_type = llvm_context.f64_type();
+        let constant = |value: u64| i64_type.const_int(value, false);
+        let parse = || {
+            builder
+                .build_call(parse_integer, &[string, capacity, index.into()], "")
+                .try_as_basic_value()
+                .left()
+                .map(BasicValueEnum::into_int_value)
+                .unwrap_or_else(|| i64_type.const_zero())
+        };
+
+        let entry = llvm_context.append_basic_block(function, "entry");
+        builder.position_at_end(entry);
+        let hours = parse();
+        self.skip_character(index);
+        let minutes = parse();
+        self.skip_character(index);
+        let seconds = parse();
+
+        //the optional fraction is scaled to the units of the type
+        let position = builder.build_load(index, "").into_int_value();
+        let has_fraction = self.is_character_at(
+            string.into_pointer_value(),
+            capacity.into_int_value(),
+            position,
+            b'.',
+        );
+        let fraction_start = builder.build_int_add(
+            position,
+            builder.build_int_z_extend(has_fraction, i32_type, ""),
+            "",
+        );
+        builder.build_store(index, fraction_start);
+        let fraction = parse();
+        let fraction_end = builder.build_load(index, "").into_int_value();
+        let fraction_digits = builder.build_int_sub(fraction_end, fraction_start, "");
+        let scaled_fraction = builder.build_float_div(
+            builder.build_float_mul(
+                builder.build_unsigned_int_to_float(fraction, double_type, ""),
+                builder.build_unsigned_int_to_float(units_per_second, double_type, ""),
+                "",
+            ),
+            self.call_float_function(
+                "llvm.pow.f64",
+                &[
+                    double_type.const_float(10.0),
+                    builder.build_signed_int_to_float(fraction_digits, double_type, ""),
+                ],
+                context,
+            )?
+            .into_float_value(),
+            "",
+        );
+        let fraction_value = builder.build_float_to_signed_int(
+            self.call_float_function("llvm.round.f64", &[scaled_fraction], context)?
+                .into_float_value(),
+            i64_type,
+            "",
+        );
+
+        //((hours * 60 + minutes) * 60 + seconds) * units_per_second + fraction
+        let total_seconds = builder.build_int_add(
+            builder.build_int_mul(
+                builder.build_int_add(builder.build_int_mul(hours, constant(60), ""), minutes, ""),
+                constant(60),
+                "",
+            ),
+            seconds,
+            "",
+        );
+        let result = builder.build_int_add(
+            builder.build_int_mul(total_seconds, units_per_second, ""),
+            fraction_value,
+            "",
+        );
+        builder.build_return(Some(&result));
+        Ok(())
+    }
+
+    /// generates a loop skipping the spaces at the start of the string and returns the index of
+    /// the first other character, the builder is positioned behind the loop afterwards
+    fn build_skip_spaces(
+        &self,
+        function: FunctionValue<'a>,
+        string: PointerValue<'a>,
+        capacity: IntValue<'a>,
+    ) -> IntValue<'a> {
+        let context = self.llvm.context;
+        let builder = &self.llvm.builder;
+        let i32_type = context.i32_type();
+        let previous = builder
+            .get_insert_block()
+            .expect("the builder is positioned in the helper function");
+        let spaces = context.append_basic_block(function, "spaces");
+        let text = context.append_basic_block(function, "text");
+        builder.build_unconditional_branch(spaces);
+
+        builder.position_at_end(spaces);
+        let position_phi = builder.build_phi(i32_type, "position");
+        let position = position_phi.as_basic_value().into_int_value();
+        let is_space = self.is_character_at(string, capacity, position, b' ');
+        let next_position = builder.build_int_add(position, i32_type.const_int(1, false), "");
+        builder.build_conditional_branch(is_space, spaces, text);
+        position_phi.add_incoming(&[(&i32_type.const_zero(), previous), (&next_position, spaces)]);
+
+        builder.position_at_end(text);
+        position
+    }
+
+    /// returns whether the character at the given position is a `-` and the position behind an
+    /// optional `+` or `-`
+    fn build_sign(
+        &self,
+        string: PointerValue<'a>,
+        capacity: IntValue<'a>,
+        position: IntValue<'a>,
+    ) -> (IntValue<'a>, IntValue<'a>) {
+        let builder = &self.llvm.builder;
+        let is_negative = self.is_character_at(string, capacity, position, b'-');
+        let has_sign = builder.build_or(
+            is_negative,
+            self.is_character_at(string, capacity, position, b'+'),
+            "",
+        );
+        let start = builder.build_int_add(
+            position,
+            builder.build_int_z_extend(has_sign, self.llvm.context.i32_type(), ""),
+            "",
+        );
+        (is_negative, start)
+    }
+
+    /// returns whether the character at the given position is a decimal digit and its value
+    fn load_digit(
+        &self,
+        string: PointerValue<'a>,
+        capacity: IntValue<'a>,
+        position: IntValue<'a>,
+    ) -> (IntValue<'a>, IntValue<'a>) {
+        let builder = &self.llvm.builder;
+        let i8_type = self.llvm.context.i8_type();
+        let character = self.load_character(string, capacity, position);
+        let is_digit = builder.build_and(
+            builder.build_int_compare(
+                IntPredicate::UGE,
+                character,
+                i8_type.const_int(b'0' as u64, false),
+                "",
+            ),
+            builder.build_int_compare(
+                IntPredicate::ULE,
+                character,
+                i8_type.const_int(b'9' as u64, false),
+                "",
+            ),
+            "",
+        );
+        let digit = builder.build_int_sub(character, i8_type.const_int(b'0' as u64, false), "");
+        (is_digit, digit)
+    }
+
+    /// returns true if the character at the given position is the given character
+    fn is_character_at(
+        &self,
+        string: PointerValue<'a>,
+        capacity: IntValue<'a>,
+        position: IntValue<'a>,
+        character: u8,
+    ) -> IntValue<'a> {
+        self.llvm.builder.build_int_compare(
+            IntPredicate::EQ,
+            self.load_character(string, capacity, position),
+            self.llvm
+                .context
+                .i8_type()
+                .const_int(character as u64, false),
+            "",
+        )
+    }
+
+    /// loads the character at the given position of the string, positions outside of its
+    /// capacity read the terminator
+    fn load_character(
+        &self,
+        string: PointerValue<'a>,
+        capacity: IntValue<'a>,
+        position: IntValue<'a>,
+    ) -> IntValue<'a> {
+        let builder = &self.llvm.builder;
+        let i32_type = self.llvm.context.i32_type();
+        let is_in_bounds = builder.build_and(
+            builder.build_int_compare(IntPredicate::SGE, position, i32_type.const_zero(), ""),
+            builder.build_int_compare(IntPredicate::SLT, position, capacity, ""),
+            "",
+        );
+        let safe_position = builder
+            .build_select(is_in_bounds, position, i32_type.const_zero(), "")
+            .into_int_value();
+        let character_pointer =
+            unsafe { builder.build_in_bounds_gep(string, &[safe_position], "") };
+        let character = builder.build_load(character_pointer, "").into_int_value();
+        builder
+            .build_select(
+                is_in_bounds,
+                character,
+                self.llvm.context.i8_type().const_zero(),
+                "",
+            )
+            .into_int_value()
+    }
+
+    /// divides the given values and rounds the quotient towards negative infinity
+    fn build_floor_division(&self, value: IntValue<'a>, divisor: IntValue<'a>) -> IntValue<'a> {
+        let builder = &self.llvm.builder;
+        let quotient = builder.build_int_signed_div(value, divisor, "");
+        let remainder = builder.build_int_signed_rem(value, divisor, "");
+        let is_below = builder.build_int_compare(
+            IntPredicate::SLT,
+            remainder,
+            remainder.get_type().const_zero(),
+            "",
+        );
+        builder.build_int_sub(
+            quotient,
+            builder.build_int_z_extend(is_below, quotient.get_type(), ""),
+            "",
+        )
+    }
+}
+
+/// returns the prefix of the literals of the given date or time type (e.g. `T#` or `LDT#`)
+fn get_date_time_prefix(kind: DateTimeKind, is_long: bool) -> &'static str {
+    match (kind, is_long) {
+        (DateTimeKind::Time, false) => "T#",
+        (DateTimeKind::Date, false) => "D#",
+        (DateTimeKind::TimeOfDay, false) => "TOD#",
+        (DateTimeKind::DateAndTime, false) => "DT#",
+        (DateTimeKind::Time, true) => "LTIME#",
+        (DateTimeKind::Date, true) => "LDATE#",
+        (DateTimeKind::TimeOfDay, true) => "LTOD#",
+        (DateTimeKind::DateAndTime, true) => "LDT#",
+    }
+}
@@ -0,0 +1,783 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+
+//! generates the string functions LEN, LEFT, RIGHT, MID, CONCAT, INSERT, DELETE, REPLACE and
+//! FIND for STRING and WSTRING values
+//!
+//! the functions are composed of three helper functions per encoding, they are generated into
+//! the module when they are used for the first time:
+//! - `__string_length(str, capacity)` returns the number of characters before the terminator
+//! - `__string_append(dest, dest_capacity, dest_length, src, src_capacity, start, count)` appends
+//!   up to `count` characters of `src` starting at the (0-based) index `start` to `dest` and
+//!   returns the new length of `dest`. It never writes more than `dest_capacity - 1` characters
+//!   followed by the terminator
+//! - `__string_find(str, capacity, pattern, pattern_capacity)` returns the (1-based) position of
+//!   the first occurrence of `pattern` in `str` or 0
+//!
+//! the capacities are given in characters (including the terminator), the helpers of WSTRINGs
+//! are prefixed with `__wstring` and work on 16 bit characters
+use inkwell::{
+    module::Linkage,
+    types::{BasicTypeEnum, IntType},
+    values::{BasicValueEnum, FunctionValue, IntValue, PointerValue},
+    AddressSpace, IntPredicate,
+};
+
+use super::ExpressionCodeGenerator;
+use crate::{
+    ast::AstStatement,
+    builtins::BuiltIn,
+    codegen::{llvm_typesystem::cast_if_needed, TypeAndValue},
+    compile_error::CompileError,
+    typesystem::{
+        DataTypeInformation, StringEncoding, DINT_TYPE, INT_TYPE, STRING_TYPE, WSTRING_TYPE,
+    },
+};
+
+/// a string passed to a string function (or the string under construction): a pointer to its
+/// first character and the number of characters it can hold (including the terminator)
+pub(super) struct StringArgument<'a> {
+    pub(super) data_type: DataTypeInformation,
+    pub(super) encoding: StringEncoding,
+    pub(super) pointer: PointerValue<'a>,
+    pub(super) capacity: IntValue<'a>,
+}
+
+/// the helper functions the string functions are composed of
+#[derive(Clone, Copy)]
+pub(super) enum StringHelper {
+    Length,
+    Append,
+    Find,
+}
+
+impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
+    /// generates a call of a string function with the given inputs. LEN and FIND result in an
+    /// INT, all other functions result in the longest of the given strings and the default
+    /// string of their encoding (e.g. `STRING`). The results of CONCAT, INSERT and REPLACE are
+    /// extended to hold the characters of all inputs
+    pub(super) fn generate_string_function(
+        &self,
+        builtin: BuiltIn,
+        inputs: &[&AstStatement],
+        context: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let zero = self.llvm.context.i32_type().const_zero();
+        match (builtin, inputs) {
+            (BuiltIn::Len, [input]) => {
+                let string = self.generate_string_argument(input)?;
+                let length = self.call_string_helper(
+                    StringHelper::Length,
+                    &string.encoding,
+                    &[string.pointer.into(), string.capacity.into()],
+                    context,
+                )?;
+                self.generate_int_result(length, context)
+            }
+            (BuiltIn::Find, [input, pattern]) => {
+                let string = self.generate_string_argument(input)?;
+                let pattern = self.generate_string_argument(pattern)?;
+                self.check_same_encoding(builtin, &[&string, &pattern], context)?;
+                let position = self.call_string_helper(
+                    StringHelper::Find,
+                    &string.encoding,
+                    &[
+                        string.pointer.into(),
+                        string.capacity.into(),
+                        pattern.pointer.into(),
+                        pattern.capacity.into(),
+                    ],
+                    context,
+                )?;
+                self.generate_int_result(position, context)
+            }
+            (BuiltIn::Left, [input, length]) => {
+                let string = self.generate_string_argument(input)?;
+                let length = self.generate_position_argument(length)?;
+                let (result_type, result, buffer) =
+                    self.create_string_result(builtin, &[&string], context)?;
+                self.append_string(&result, zero, &string, zero, length, context)?;
+                Ok((result_type, self.llvm.builder.build_load(buffer, "")))
+            }
+            (BuiltIn::Right, [input, length]) => {
+                let string = self.generate_string_argument(input)?;
+                let length = self.generate_position_argument(length)?;
+                let (result_type, result, buffer) =
+                    self.create_string_result(builtin, &[&string], context)?;
+                let string_length = self.call_string_helper(
+                    StringHelper::Length,
+                    &string.encoding,
+                    &[string.pointer.into(), string.capacity.into()],
+                    context,
+                )?;
+                let start = self.llvm.builder.build_int_sub(string_length, length, "");
+                self.append_string(&result, zero, &string, start, length, context)?;
+                Ok((result_type, self.llvm.builder.build_load(buffer, "")))
+            }
+            (BuiltIn::Mid, [input, length, position]) => {
+                let string = self.generate_string_argument(input)?;
+                let length = self.generate_position_argument(length)?;
+                let start = self.generate_start_index(position)?;
+                let (result_type, result, buffer) =
+                    self.create_string_result(builtin, &[&string], context)?;
+                self.append_string(&result, zero, &string, start, length, context)?;
+                Ok((result_type, self.llvm.builder.build_load(buffer, "")))
+            }
+            (BuiltIn::Concat, inputs) => {
+                let strings = inputs
+                    .iter()
+                    .map(|it| self.generate_string_argument(it))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let string_references: Vec<&StringArgument> = strings.iter().collect();
+                let (result_type, result, buffer) =
+                    self.create_string_result(builtin, &string_references, context)?;
+                let mut length = zero;
+                for string in &strings {
+                    length = self.append_string(
+                        &result,
+                        length,
+                        string,
+                        zero,
+                        string.capacity,
+                        context,
+                    )?;
+                }
+                Ok((result_type, self.llvm.builder.build_load(buffer, "")))
+            }
+            (BuiltIn::Insert, [input1, input2, position]) => {
+                let string1 = self.generate_string_argument(input1)?;
+                let string2 = self.generate_string_argument(input2)?;
+                let position = self.generate_position_argument(position)?;
+                let (result_type, result, buffer) =
+                    self.create_string_result(builtin, &[&string1, &string2], context)?;
+                let length =
+                    self.append_string(&result, zero, &string1, zero, position, context)?;
+                let length =
+                    self.append_string(&result, length, &string2, zero, string2.capacity, context)?;
+                self.append_string(
+                    &result,
+                    length,
+                    &string1,
+                    position,
+                    string1.capacity,
+                    context,
+                )?;
+                Ok((result_type, self.llvm.builder.build_load(buffer, "")))
+            }
+            (BuiltIn::Delete, [input, length, position]) => {
+                let string = self.generate_string_argument(input)?;
+                let length = self.generate_position_argument(length)?;
+                let start = self.generate_start_index(position)?;
+                let (result_type, result, buffer) =
+                    self.create_string_result(builtin, &[&string], context)?;
+                let result_length =
+                    self.append_string(&result, zero, &string, zero, start, context)?;
+                let end = self.llvm.builder.build_int_add(start, length, "");
+                self.append_string(
+                    &result,
+                    result_length,
+                    &string,
+                    end,
+                    string.capacity,
+                    context,
+                )?;
+                Ok((result_type, self.llvm.builder.build_load(buffer, "")))
+            }
+            (BuiltIn::Replace, [input1, input2, length, position]) => {
+                let string1 = self.generate_string_argument(input1)?;
+                let string2 = self.generate_string_argument(input2)?;
+                let length = self.generate_position_argument(length)?;
+                let start = self.generate_start_index(position)?;
+                let (result_type, result, buffer) =
+                    self.create_string_result(builtin, &[&string1, &string2], context)?;
+                let result_length =
+                    self.append_string(&result, zero, &string1, zero, start, context)?;
+                let result_length = self.append_string(
+                    &result,
+                    result_length,
+                    &string2,
+                    zero,
+                    string2.capacity,
+                    context,
+                )?;
+                let end = self.llvm.builder.build_int_add(start, length, "");
+                self.append_string(
+                    &result,
+                    result_length,
+                    &string1,
+                    end,
+                    string1.capacity,
+                    context,
+                )?;
+                Ok((result_type, self.llvm.builder.build_load(buffer, "")))
+            }
+            _ => Err(CompileError::codegen_error(
+                format!(
+                    "Invalid number of parameters ({}) for {}",
+                    inputs.len(),
+                    builtin.get_name()
+                ),
+                context.get_location(),
+            )),
+        }
+    }
+
+    /// generates the given string and copies it into a local variable
+    fn generate_string_argument(
+        &self,
+        input: &AstStatement,
+    ) -> Result<StringArgument<'a>, CompileError> {
+        let (input_type, value) = self.generate_expression(input)?;
+        self.create_string_argument(self.get_intrinsic_type(input_type), value, input)
+    }
+
+    /// copies the given value of the generated string input into a local variable
+    pub(super) fn create_string_argument(
+        &self,
+        input_type: DataTypeInformation,
+        value: BasicValueEnum<'a>,
+        input: &AstStatement,
+    ) -> Result<StringArgument<'a>, CompileError> {
+        let encoding = match &input_type {
+            DataTypeInformation::String { encoding, .. } => encoding.clone(),
+            _ => {
+                return Err(CompileError::codegen_error(
+                    format!(
+                        "invalid type, expected a string but found {}",
+                        input_type.get_name()
+                    ),
+                    input.get_location(),
+                ))
+            }
+        };
+        let llvm_type = match (value, input) {
+            (BasicValueEnum::ArrayValue(value), _) => value.get_type(),
+            (BasicValueEnum::VectorValue(_), AstStatement::LiteralString { value, .. }) => {
+                //a string literal is a constant of its characters followed by the terminator
+                let bytes = match encoding {
+                    StringEncoding::Utf8 => value.len() + 1,
+                    StringEncoding::Utf16 => (value.encode_utf16().count() + 1) * 2,
+                };
+                self.llvm.context.i8_type().array_type(bytes as u32)
+            }
+            _ => {
+                return Err(CompileError::codegen_error(
+                    format!("cannot generate the string {:?}", input),
+                    input.get_location(),
+                ))
+            }
+        };
+        let buffer = self.llvm.create_local_variable("", &llvm_type.into());
+        self.llvm.builder.build_store(buffer, value);
+        let capacity = llvm_type.len() / encoding.get_bytes_per_char();
+        Ok(StringArgument {
+            data_type: input_type,
+            pointer: self.llvm.builder.build_pointer_cast(
+                buffer,
+                self.get_character_type(&encoding)
+                    .ptr_type(AddressSpace::Generic),
+                "",
+            ),
+            capacity: self
+                .llvm
+                .context
+                .i32_type()
+                .const_int(capacity as u64, false),
+            encoding,
+        })
+    }
+
+    /// creates the zero-initialized local variable a string function writes its result to. The
+    /// result has the longest type of the given strings and the default string of their encoding,
+    /// the results of CONCAT, INSERT and REPLACE can hold the characters of all given strings
+    fn create_string_result(
+        &self,
+        builtin: BuiltIn,
+        strings: &[&StringArgument<'a>],
+        context: &AstStatement,
+    ) -> Result<(DataTypeInformation, StringArgument<'a>, PointerValue<'a>), CompileError> {
+        self.check_same_encoding(builtin, strings, context)?;
+        let encoding = strings
+            .first()
+            .map(|it| it.encoding.clone())
+            .unwrap_or(StringEncoding::Utf8);
+        let default_type_name = match encoding {
+            StringEncoding::Utf8 => STRING_TYPE,
+            StringEncoding::Utf16 => WSTRING_TYPE,
+        };
+        let default_type = self.index.get_type_information(default_type_name)?;
+        let result_type =
+            strings
+                .iter()
+                .map(|it| &it.data_type)
+                .fold(default_type, |result_type, it| {
+                    if get_string_size(it) > get_string_size(&result_type) {
+                        it.clone()
+                    } else {
+                        result_type
+                    }
+                });
+        let result_type = if matches!(
+            builtin,
+            BuiltIn::Concat | BuiltIn::Insert | BuiltIn::Replace
+        ) {
+            //the characters of all strings followed by one terminator
+            let joined_size = strings
+                .iter()
+                .map(|it| get_string_size(&it.data_type).saturating_sub(1))
+                .sum::<u32>()
+                + 1;
+            if joined_size > get_string_size(&result_type) {
+                DataTypeInformation::String {
+                    size: joined_size,
+                    encoding: encoding.clone(),
+                }
+            } else {
+                result_type
+            }
+        } else {
+            result_type
+        };
+        let (result, buffer) = self.create_string_buffer(result_type.clone(), encoding);
+        Ok((result_type, result, buffer))
+    }
+
+    /// creates a zero-initialized local variable of the given string type and returns it with
+    /// the pointer to the variable itself
+    pub(super) fn create_string_buffer(
+        &self,
+        data_type: DataTypeInformation,
+        encoding: StringEncoding,
+    ) -> (StringArgument<'a>, PointerValue<'a>) {
+        let capacity = get_string_size(&data_type);
+        let llvm_type = self
+            .llvm
+            .context
+            .i8_type()
+            .array_type(capacity * encoding.get_bytes_per_char());
+        let buffer = self.llvm.create_local_variable("", &llvm_type.into());
+        self.llvm
+            .builder
+            .build_store(buffer, llvm_type.const_zero());
+        let string = StringArgument {
+            data_type,
+            pointer: self.llvm.builder.build_pointer_cast(
+                buffer,
+                self.get_character_type(&encoding)
+                    .ptr_type(AddressSpace::Generic),
+                "",
+            ),
+            capacity: self
+                .llvm
+                .context
+                .i32_type()
+                .const_int(capacity as u64, false),
+            encoding,
+        };
+        (string, buffer)
+    }
+
+    /// returns an error if the given strings do not share the same encoding
+    fn check_same_encoding(
+        &self,
+        builtin: BuiltIn,
+        strings: &[&StringArgument<'a>],
+        context: &AstStatement,
+    ) -> Result<(), CompileError> {
+        match strings.split_first() {
+            Some((first, others)) if others.iter().any(|it| it.encoding != first.encoding) => {
+                Err(CompileError::codegen_error(
+                    format!(
+                        "{} cannot combine STRING and WSTRING values",
+                        builtin.get_name()
+                    ),
+                    context.get_location(),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// appends `count` characters of `source` starting at the 0-based index `start` to the
+    /// given result of the current `length` and returns the result's new length
+    fn append_string(
+        &self,
+        result: &StringArgument<'a>,
+        length: IntValue<'a>,
+        source: &StringArgument<'a>,
+        start: IntValue<'a>,
+        count: IntValue<'a>,
+        context: &AstStatement,
+    ) -> Result<IntValue<'a>, CompileError> {
+        self.call_string_helper(
+            StringHelper::Append,
+            &result.encoding,
+            &[
+                result.pointer.into(),
+                result.capacity.into(),
+                length.into(),
+                source.pointer.into(),
+                source.capacity.into(),
+                start.into(),
+                count.into(),
+            ],
+            context,
+        )
+    }
+
+    /// generates a length or a position as a DINT
+    fn generate_position_argument(
+        &self,
+        input: &AstStatement,
+    ) -> Result<IntValue<'a>, CompileError> {
+        let (input_type, value) = self.generate_expression(input)?;
+        let dint_type = self.index.get_type_information(DINT_TYPE)?;
+        cast_if_needed(self.llvm, self.index, &dint_type, value, &input_type, input)
+            .map(BasicValueEnum::into_int_value)
+    }
+
+    /// generates the 0-based index of the given 1-based position
+    fn generate_start_index(&self, position: &AstStatement) -> Result<IntValue<'a>, CompileError> {
+        let position = self.generate_position_argument(position)?;
+        let one = self.llvm.context.i32_type().const_int(1, false);
+        Ok(self.llvm.builder.build_int_sub(position, one, ""))
+    }
+
+    /// converts the DINT returned by a string helper into the INT result of LEN and FIND
+    fn generate_int_result(
+        &self,
+        value: IntValue<'a>,
+        context: &AstStatement,
+    ) -> Result<TypeAndValue<'a>, CompileError> {
+        let dint_type = self.index.get_type_information(DINT_TYPE)?;
+        let int_type = self.index.get_type_information(INT_TYPE)?;
+        let value = cast_if_needed(
+            self.llvm,
+            self.index,
+            &int_type,
+            value.into(),
+            &dint_type,
+            context,
+        )?;
+        Ok((int_type, value))
+    }
+
+    /// returns the llvm type of a single character of the given encoding
+    pub(super) fn get_character_type(&self, encoding: &StringEncoding) -> IntType<'a> {
+        match encoding {
+            StringEncoding::Utf8 => self.llvm.context.i8_type(),
+            StringEncoding::Utf16 => self.llvm.context.i16_type(),
+        }
+    }
+
+    /// calls the given string helper, it is generated when it is used for the first time
+    pub(super) fn call_string_helper(
+        &self,
+        helper: StringHelper,
+        encoding: &StringEncoding,
+        arguments: &[BasicValueEnum<'a>],
+        context: &AstStatement,
+    ) -> Result<IntValue<'a>, CompileError> {
+        let function = self.get_string_helper(helper, encoding, context)?;
+        self.llvm
+            .builder
+            .build_call(function, arguments, "")
+            .try_as_basic_value()
+            .left()
+            .map(BasicValueEnum::into_int_value)
+            .ok_or_else(|| {
+                CompileError::codegen_error(
+                    format!(
+                        "{} did not return a value",
+                        get_helper_name(helper, encoding)
+                    ),
+                    context.get_location(),
+                )
+            })
+    }
+
+    /// returns the given string helper of the current module, it is generated when it is used
+    /// for the first time
+    pub(super) fn get_string_helper(
+        &self,
+        helper: StringHelper,
+        encoding: &StringEncoding,
+        context: &AstStatement,
+    ) -> Result<FunctionValue<'a>, CompileError> {
+        let module = self.get_function_context(context)?.module;
+        let name = get_helper_name(helper, encoding);
+        if let Some(function) = module.get_function(&name) {
+            return Ok(function);
+        }
+        //FIND uses the length helper, so it has to exist before FIND is generated
+        let length_helper = if let StringHelper::Find = helper {
+            Some(self.get_string_helper(StringHelper::Length, encoding, context)?)
+        } else {
+            None
+        };
+
+        let pointer_type: BasicTypeEnum = self
+            .get_character_type(encoding)
+            .ptr_type(AddressSpace::Generic)
+            .into();
+        let i32_type = self.llvm.context.i32_type();
+        let int_type: BasicTypeEnum = i32_type.into();
+        let parameters = match helper {
+            StringHelper::Length => vec![pointer_type, int_type],
+            StringHelper::Append => vec![
+                pointer_type,
+                int_type,
+                int_type,
+                pointer_type,
+                int_type,
+                int_type,
+                int_type,
+            ],
+            StringHelper::Find => vec![pointer_type, int_type, pointer_type, int_type],
+        };
+        let function = module.add_function(
+            &name,
+            i32_type.fn_type(&parameters, false),
+            Some(Linkage::Internal),
+        );
+
+        let current_block = self.llvm.builder.get_insert_block();
+        match (helper, length_helper) {
+            (StringHelper::Find, Some(length_helper)) => {
+                self.generate_find_helper(function, length_helper)
+            }
+            (StringHelper::Append, _) => self.generate_append_helper(function, encoding),
+            _ => self.generate_length_helper(function, encoding),
+        }
+        if let Some(block) = current_block {
+            self.llvm.builder.position_at_end(block);
+        }
+        Ok(function)
+    }
+
+    /// generates the body of `__string_length(str, capacity)`
+    fn generate_length_helper(&self, function: FunctionValue<'a>, encoding: &StringEncoding) {
+        let context = self.llvm.context;
+        let builder = &self.llvm.builder;
+        let string = get_parameter(function, 0).into_pointer_value();
+        let capacity = get_parameter(function, 1).into_int_value();
+        let i32_type = context.i32_type();
+        let character_type = self.get_character_type(encoding);
+
+        let entry = context.append_basic_block(function, "entry");
+        let condition = context.append_basic_block(function, "condition");
+        let body = context.append_basic_block(function, "body");
+        let exit = context.append_basic_block(function, "exit");
+
+        builder.position_at_end(entry);
+        builder.build_unconditional_branch(condition);
+
+        //while index < capacity && str[index] <> 0
+        builder.position_at_end(condition);
+        let index_phi = builder.build_phi(i32_type, "index");
+        let index = index_phi.as_basic_value().into_int_value();
+        let is_in_bounds = builder.build_int_compare(IntPredicate::SLT, index, capacity, "");
+        builder.build_conditional_branch(is_in_bounds, body, exit);
+
+        builder.position_at_end(body);
+        let character_pointer = unsafe { builder.build_in_bounds_gep(string, &[index], "") };
+        let character = builder.build_load(character_pointer, "").into_int_value();
+        let is_terminator =
+            builder.build_int_compare(IntPredicate::EQ, character, character_type.const_zero(), "");
+        let next_index = builder.build_int_add(index, i32_type.const_int(1, false), "");
+        builder.build_conditional_branch(is_terminator, exit, condition);
+        index_phi.add_incoming(&[(&i32_type.const_zero(), entry), (&next_index, body)]);
+
+        builder.position_at_end(exit);
+        builder.build_return(Some(&index));
+    }
+
+    /// generates the body of
+    /// `__string_append(dest, dest_capacity, dest_length, src, src_capacity, start, count)`
+    fn generate_append_helper(&self, function: FunctionValue<'a>, encoding: &StringEncoding) {
+        let context = self.llvm.context;
+        let builder = &self.llvm.builder;
+        let destination = get_parameter(function, 0).into_pointer_value();
+        let destination_capacity = get_parameter(function, 1).into_int_value();
+        let destination_length = get_parameter(function, 2).into_int_value();
+        let source = get_parameter(function, 3).into_pointer_value();
+        let source_capacity = get_parameter(function, 4).into_int_value();
+        let start = get_parameter(function, 5).into_int_value();
+        let count = get_parameter(function, 6).into_int_value();
+        let i32_type = context.i32_type();
+        let character_type = self.get_character_type(encoding);
+
+        let entry = context.append_basic_block(function, "entry");
+        let condition = context.append_basic_block(function, "condition");
+        let read = context.append_basic_block(function, "read");
+        let copy = context.append_basic_block(function, "copy");
+        let exit = context.append_basic_block(function, "exit");
+
+        //a negative start is treated as 0, the last character is reserved for the terminator
+        builder.position_at_end(entry);
+        let is_negative =
+            builder.build_int_compare(IntPredicate::SLT, start, i32_type.const_zero(), "");
+        let first = builder
+            .build_select(is_negative, i32_type.const_zero(), start, "")
+            .into_int_value();
+        let destination_limit =
+            builder.build_int_sub(destination_capacity, i32_type.const_int(1, false), "");
+        builder.build_unconditional_branch(condition);
+
+        //while offset < count && first + offset < src_capacity && dest_length + offset < limit
+        builder.position_at_end(condition);
+        let offset_phi = builder.build_phi(i32_type, "offset");
+        let offset = offset_phi.as_basic_value().into_int_value();
+        let destination_index = builder.build_int_add(destination_length, offset, "");
+        let source_index = builder.build_int_add(first, offset, "");
+        let is_in_count = builder.build_int_compare(IntPredicate::SLT, offset, count, "");
+        let is_in_source =
+            builder.build_int_compare(IntPredicate::SLT, source_index, source_capacity, "");
+        let is_in_destination =
+            builder.build_int_compare(IntPredicate::SLT, destination_index, destination_limit, "");
+        let is_in_bounds = builder.build_and(
+            builder.build_and(is_in_count, is_in_source, ""),
+            is_in_destination,
+            "",
+        );
+        builder.build_conditional_branch(is_in_bounds, read, exit);
+
+        //... && src[first + offset] <> 0
+        builder.position_at_end(read);
+        let source_pointer = unsafe { builder.build_in_bounds_gep(source, &[source_index], "") };
+        let character = builder.build_load(source_pointer, "").into_int_value();
+        let is_terminator =
+            builder.build_int_compare(IntPredicate::EQ, character, character_type.const_zero(), "");
+        builder.build_conditional_branch(is_terminator, exit, copy);
+
+        builder.position_at_end(copy);
+        let destination_pointer =
+            unsafe { builder.build_in_bounds_gep(destination, &[destination_index], "") };
+        builder.build_store(destination_pointer, character);
+        let next_offset = builder.build_int_add(offset, i32_type.const_int(1, false), "");
+        builder.build_unconditional_branch(condition);
+        offset_phi.add_incoming(&[(&i32_type.const_zero(), entry), (&next_offset, copy)]);
+
+        builder.position_at_end(exit);
+        let terminator_pointer =
+            unsafe { builder.build_in_bounds_gep(destination, &[destination_index], "") };
+        builder.build_store(terminator_pointer, character_type.const_zero());
+        builder.build_return(Some(&destination_index));
+    }
+
+    /// generates the body of `__string_find(str, capacity, pattern, pattern_capacity)`
+    fn generate_find_helper(&self, function: FunctionValue<'a>, length_helper: FunctionValue<'a>) {
+        let context = self.llvm.context;
+        let builder = &self.llvm.builder;
+        let string = get_parameter(function, 0);
+        let capacity = get_parameter(function, 1);
+        let pattern = get_parameter(function, 2);
+        let pattern_capacity = get_parameter(function, 3);
+        let i32_type = context.i32_type();
+        let one = i32_type.const_int(1, false);
+
+        let entry = context.append_basic_block(function, "entry");
+        let position = context.append_basic_block(function, "position");
+        let compare = context.append_basic_block(function, "compare");
+        let check = context.append_basic_block(function, "check");
+        let next_character = context.append_basic_block(function, "next_character");
+        let next_position = context.append_basic_block(function, "next_position");
+        let found = context.append_basic_block(function, "found");
+        let not_found = context.append_basic_block(function, "not_found");
+
+        //an empty pattern is never found
+        builder.position_at_end(entry);
+        let call_length = |string: BasicValueEnum<'a>, capacity: BasicValueEnum<'a>| {
+            builder
+                .build_call(length_helper, &[string, capacity], "")
+                .try_as_basic_value()
+                .left()
+                .map(BasicValueEnum::into_int_value)
+                .unwrap_or_else(|| i32_type.const_zero())
+        };
+        let length = call_length(string, capacity);
+        let pattern_length = call_length(pattern, pattern_capacity);
+        let last_start = builder.build_int_sub(length, pattern_length, "");
+        let is_empty =
+            builder.build_int_compare(IntPredicate::EQ, pattern_length, i32_type.const_zero(), "");
+        builder.build_conditional_branch(is_empty, not_found, position);
+
+        //for start := 0 to length - pattern_length
+        builder.position_at_end(position);
+        let start_phi = builder.build_phi(i32_type, "start");
+        let start = start_phi.as_basic_value().into_int_value();
+        let fits = builder.build_int_compare(IntPredicate::SLE, start, last_start, "");
+        builder.build_conditional_branch(fits, compare, not_found);
+
+        //for offset := 0 to pattern_length - 1
+        builder.position_at_end(compare);
+        let offset_phi = builder.build_phi(i32_type, "offset");
+        let offset = offset_phi.as_basic_value().into_int_value();
+        let is_complete = builder.build_int_compare(IntPredicate::SGE, offset, pattern_length, "");
+        builder.build_conditional_branch(is_complete, found, check);
+
+        //str[start + offset] = pattern[offset]
+        builder.position_at_end(check);
+        let index = builder.build_int_add(start, offset, "");
+        let character_pointer =
+            unsafe { builder.build_in_bounds_gep(string.into_pointer_value(), &[index], "") };
+        let character = builder.build_load(character_pointer, "").into_int_value();
+        let expected_pointer =
+            unsafe { builder.build_in_bounds_gep(pattern.into_pointer_value(), &[offset], "") };
+        let expected = builder.build_load(expected_pointer, "").into_int_value();
+        let is_equal = builder.build_int_compare(IntPredicate::EQ, character, expected, "");
+        builder.build_conditional_branch(is_equal, next_character, next_position);
+
+        builder.position_at_end(next_character);
+        let next_offset = builder.build_int_add(offset, one, "");
+        builder.build_unconditional_branch(compare);
+        offset_phi.add_incoming(&[
+            (&i32_type.const_zero(), position),
+            (&next_offset, next_character),
+        ]);
+
+        builder.position_at_end(next_position);
+        let next_start = builder.build_int_add(start, one, "");
+        builder.build_unconditional_branch(position);
+        start_phi.add_incoming(&[
+            (&i32_type.const_zero(), entry),
+            (&next_start, next_position),
+        ]);
+
+        //positions are 1-based
+        builder.position_at_end(found);
+        builder.build_return(Some(&builder.build_int_add(start, one, "")));
+
+        builder.position_at_end(not_found);
+        builder.build_return(Some(&i32_type.const_zero()));
+    }
+}
+
+/// returns the name of the given string helper for the given encoding
+fn get_helper_name(helper: StringHelper, encoding: &StringEncoding) -> String {
+    let prefix = match encoding {
+        StringEncoding::Utf8 => "__string",
+        StringEncoding::Utf16 => "__wstring",
+    };
+    let name = match helper {
+        StringHelper::Length => "length",
+        StringHelper::Append => "append",
+        StringHelper::Find => "find",
+    };
+    format!("{}_{}", prefix, name)
+}
+
+/// returns the number of characters (including the terminator) of the given string type
+pub(super) fn get_string_size(data_type: &DataTypeInformation) -> u32 {
+    match data_type {
+        DataTypeInformation::String { size, .. } => *size,
+        _ => 0,
+    }
+}
+
+/// returns the parameter of a string helper at the given index, the helpers are declared with
+/// all parameters they use
+pub(super) fn get_parameter(function: FunctionValue, index: u32) -> BasicValueEnum {
+    function
+        .get_nth_param(index)
+        .expect("string helpers are declared with all of their parameters")
+}
@@ -0,0 +1,417 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+
+//! generates the trigonometric functions TAN, ASIN, ACOS, ATAN and ATAN2. LLVM has no intrinsics
+//! for the arc functions, so they are calculated by helper functions instead of calling the C math
+//! library. The helpers are generated into the module when they are used for the first time:
+//! - `__atan(x)` follows fdlibm's `atan`: `|x|` is reduced into `[-7/16, 7/16]` around one of the
+//!   points 0, 0.5, 1, 1.5 or infinity and the arc tangent of the reduced argument is approximated
+//!   with a polynomial
+//! - `__atan2(y, x)` mirrors `__atan(|y| / |x|)` into the quadrant of `(x, y)`
+//!
+//! ASIN and ACOS call `__atan2`, TAN divides `llvm.sin` by `llvm.cos`. The intrinsics used here
+//! and by the other numeric functions (`llvm.sin`, `llvm.cos`, `llvm.exp`, `llvm.log`, ...) are
+//! lowered to calls of the C math library on most targets, so it still has to be linked.
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+use inkwell::{
+    module::Linkage,
+    types::BasicTypeEnum,
+    values::{BasicValueEnum, FloatValue, FunctionValue, IntValue},
+    FloatPredicate,
+};
+
+use super::ExpressionCodeGenerator;
+use crate::{ast::AstStatement, builtins::BuiltIn, compile_error::CompileError};
+
+/// the lower bounds of the reduction intervals 1 to 4, interval 0 starts at 0
+const ATAN_BOUNDS: [f64; 4] = [0.4375, 0.6875, 1.1875, 2.4375];
+/// the reduced argument of interval i is
+/// `(SCALE[i] * |x| - OFFSET[i]) / (SCALE[i] + OFFSET[i] * |x|)`, interval 4 uses `-1 / |x|`
+const ATAN_SCALE: [f64; 5] = [1.0, 2.0, 1.0, 1.0, 0.0];
+const ATAN_OFFSET: [f64; 5] = [0.0, 1.0, 1.0, 1.5, 1.0];
+/// the arc tangents of the reduction points, split into a high and a low part
+const ATAN_HI: [f64; 5] = [
+    0.0,
+    0.4636476090008061,
+    FRAC_PI_4,
+    0.982793723247329,
+    FRAC_PI_2,
+];
+const ATAN_LO: [f64; 5] = [
+    0.0,
+    2.2698777452961687e-17,
+    3.061616997868383e-17,
+    1.3903311031230998e-17,
+    6.123233995736766e-17,
+];
+/// the coefficients of the polynomial, split into the even and odd ones
+const ATAN_EVEN_COEFFICIENTS: [f64; 6] = [
+    0.3333333333333293,
+    0.14285714272503466,
+    0.09090887133436507,
+    0.06661073137387531,
+    0.049768779946159324,
+    0.016285820115365782,
+];
+const ATAN_ODD_COEFFICIENTS: [f64; 5] = [
+    -0.19999999999876483,
+    -0.11111110405462356,
+    -0.0769187620504483,
+    -0.058335701337905735,
+    -0.036531572744216916,
+];
+/// the rest of PI that does not fit into `std::f64::consts::PI`
+const PI_LO: f64 = 1.2246467991473532e-16;
+
+/// the helper functions the arc functions are composed of
+#[derive(Clone, Copy)]
+enum TrigonometricHelper {
+    Atan,
+    Atan2,
+}
+
+impl TrigonometricHelper {
+    fn get_name(&self) -> &'static str {
+        match self {
+            TrigonometricHelper::Atan => "__atan",
+            TrigonometricHelper::Atan2 => "__atan2",
+        }
+    }
+
+    fn get_parameter_count(&self) -> usize {
+        match self {
+            TrigonometricHelper::Atan => 1,
+            TrigonometricHelper::Atan2 => 2,
+        }
+    }
+}
+
+impl<'a, 'b> ExpressionCodeGenerator<'a, 'b> {
+    /// generates `TAN(IN)`, `ASIN(IN)`, `ACOS(IN)`, `ATAN(IN)` or `ATAN2(Y, X)` for the given
+    /// REAL or LREAL values, REAL values are calculated as LREAL and truncated afterwards
+    pub(super) fn generate_trigonometric_function(
+        &self,
+        builtin: BuiltIn,
+        values: &[FloatValue<'a>],
+        context: &AstStatement,
+    ) -> Result<BasicValueEnum<'a>, CompileError> {
+        let builder = &self.llvm.builder;
+        let double_type = self.llvm.context.f64_type();
+        let result_type = values.first().map(FloatValue::get_type);
+        let values: Vec<FloatValue> = values
+            .iter()
+            .map(|it| {
+                if it.get_type() == double_type {
+                    *it
+                } else {
+                    builder.build_float_ext(*it, double_type, "")
+                }
+            })
+            .collect();
+
+        let result = match (builtin, values.as_slice()) {
+            (BuiltIn::Tan, [value]) => {
+                let sine = self.call_double_function("llvm.sin.f64", &[*value], context)?;
+                let cosine = self.call_double_function("llvm.cos.f64", &[*value], context)?;
+                builder.build_float_div(sine, cosine, "")
+            }
+            (BuiltIn::Atan, [value]) => {
+                self.call_trigonometric_helper(TrigonometricHelper::Atan, &[*value], context)?
+            }
+            (BuiltIn::Atan2, [y, x]) => {
+                self.call_trigonometric_helper(TrigonometricHelper::Atan2, &[*y, *x], context)?
+            }
+            //ASIN(x) = ATAN2(x, SQRT(1 - x²)), ACOS(x) = ATAN2(SQRT(1 - x²), x)
+            (BuiltIn::Asin, [value]) => {
+                let root = self.generate_complement_root(*value, context)?;
+                self.call_trigonometric_helper(
+                    TrigonometricHelper::Atan2,
+                    &[*value, root],
+                    context,
+                )?
+            }
+            (BuiltIn::Acos, [value]) => {
+                let root = self.generate_complement_root(*value, context)?;
+                self.call_trigonometric_helper(
+                    TrigonometricHelper::Atan2,
+                    &[root, *value],
+                    context,
+                )?
+            }
+            _ => {
+                return Err(CompileError::codegen_error(
+                    format!(
+                        "invalid number of arguments, found {} for {}",
+                        values.len(),
+                        builtin.get_name()
+                    ),
+                    context.get_location(),
+                ))
+            }
+        };
+        Ok(match result_type {
+            Some(result_type) if result_type != double_type => {
+                builder.build_float_trunc(result, result_type, "tmpVar")
+            }
+            _ => result,
+        }
+        .into())
+    }
+
+    /// calls the given helper on LREAL values, it is generated when it is used for the first time
+    fn call_trigonometric_helper(
+        &self,
+        helper: TrigonometricHelper,
+        arguments: &[FloatValue<'a>],
+        context: &AstStatement,
+    ) -> Result<FloatValue<'a>, CompileError> {
+        let function = self.get_trigonometric_helper(helper, context)?;
+        let arguments: Vec<BasicValueEnum> = arguments.iter().map(|it| (*it).into()).collect();
+        self.llvm
+            .builder
+            .build_call(function, &arguments, "")
+            .try_as_basic_value()
+            .left()
+            .map(BasicValueEnum::into_float_value)
+            .ok_or_else(|| {
+                CompileError::codegen_error(
+                    format!("{} did not return a value", helper.get_name()),
+                    context.get_location(),
+                )
+            })
+    }
+
+    /// returns the given helper of the current module, it is generated when it is used for the
+    /// first time
+    fn get_trigonometric_helper(
+        &self,
+        helper: TrigonometricHelper,
+        context: &AstStatement,
+    ) -> Result<FunctionValue<'a>, CompileError> {
+        let module = self.get_function_context(context)?.module;
+        if let Some(function) = module.get_function(helper.get_name()) {
+            return Ok(function);
+        }
+        let double_type = self.llvm.context.f64_type();
+        let parameters: Vec<BasicTypeEnum> = vec![double_type.into(); helper.get_parameter_count()];
+        let function = module.add_function(
+            helper.get_name(),
+            double_type.fn_type(&parameters, false),
+            Some(Linkage::Internal),
+        );
+
+        let current_block = self.llvm.builder.get_insert_block();
+        let generated = match helper {
+            TrigonometricHelper::Atan => self.generate_atan_helper(function, context),
+            TrigonometricHelper::Atan2 => self.generate_atan2_helper(function, context),
+        };
+        if let Some(block) = current_block {
+            self.llvm.builder.position_at_end(block);
+        }
+        generated.map(|_| function)
+    }
+
+    /// generates the body of `__atan(x)`
+    fn generate_atan_helper(
+        &self,
+        function: FunctionValue<'a>,
+        context: &AstStatement,
+    ) -> Result<(), CompileError> {
+        let entry = self.llvm.context.append_basic_block(function, "entry");
+        self.llvm.builder.position_at_end(entry);
+        let result = self.generate_atan(get_float_parameter(function, 0), context)?;
+        self.llvm.builder.build_return(Some(&result));
+        Ok(())
+    }
+
+    /// generates the body of `__atan2(y, x)`
+    fn generate_atan2_helper(
+        &self,
+        function: FunctionValue<'a>,
+        context: &AstStatement,
+    ) -> Result<(), CompileError> {
+        let entry = self.llvm.context.append_basic_block(function, "entry");
+        self.llvm.builder.position_at_end(entry);
+        let result = self.generate_atan2(
+            get_float_parameter(function, 0),
+            get_float_parameter(function, 1),
+            context,
+        )?;
+        self.llvm.builder.build_return(Some(&result));
+        Ok(())
+    }
+
+    /// generates the arc tangent of the given LREAL value
+    fn generate_atan(
+        &self,
+        value: FloatValue<'a>,
+        context: &AstStatement,
+    ) -> Result<FloatValue<'a>, CompileError> {
+        let builder = &self.llvm.builder;
+        let double_type = self.llvm.context.f64_type();
+        let abs_value = self.call_double_function("llvm.fabs.f64", &[value], context)?;
+
+        //select the constants of the reduction interval that contains |x|
+        let is_in_interval: Vec<IntValue> = ATAN_BOUNDS
+            .iter()
+            .map(|bound| {
+                builder.build_float_compare(
+                    FloatPredicate::OGE,
+                    abs_value,
+                    double_type.const_float(*bound),
+                    "",
+                )
+            })
+            .collect();
+        let select_constant = |constants: &[f64; 5]| {
+            is_in_interval.iter().zip(constants.iter().skip(1)).fold(
+                double_type.const_float(constants[0]),
+                |selected, (is_in_interval, constant)| {
+                    builder
+                        .build_select(
+                            *is_in_interval,
+                            double_type.const_float(*constant),
+                            selected,
+                            "",
+                        )
+                        .into_float_value()
+                },
+            )
+        };
+        let scale = select_constant(&ATAN_SCALE);
+        let offset = select_constant(&ATAN_OFFSET);
+        let atan_hi = select_constant(&ATAN_HI);
+        let atan_lo = select_constant(&ATAN_LO);
+
+        //reduce the argument, the numerator of the last interval is -1 (0 * |x| fails for infinity)
+        let numerator =
+            builder.build_float_sub(builder.build_float_mul(scale, abs_value, ""), offset, "");
+        let numerator = builder
+            .build_select(
+                is_in_interval[ATAN_BOUNDS.len() - 1],
+                double_type.const_float(-1.0),
+                numerator,
+                "",
+            )
+            .into_float_value();
+        let denominator =
+            builder.build_float_add(scale, builder.build_float_mul(offset, abs_value, ""), "");
+        let reduced = builder.build_float_div(numerator, denominator, "");
+
+        //approximate the arc tangent of the reduced argument
+        let square = builder.build_float_mul(reduced, reduced, "");
+        let fourth_power = builder.build_float_mul(square, square, "");
+        let even_sum = builder.build_float_mul(
+            square,
+            self.generate_polynomial(fourth_power, &ATAN_EVEN_COEFFICIENTS),
+            "",
+        );
+        let odd_sum = builder.build_float_mul(
+            fourth_power,
+            self.generate_polynomial(fourth_power, &ATAN_ODD_COEFFICIENTS),
+            "",
+        );
+        let correction =
+            builder.build_float_mul(reduced, builder.build_float_add(even_sum, odd_sum, ""), "");
+        //atan_hi - ((correction - atan_lo) - reduced)
+        let result = builder.build_float_sub(
+            atan_hi,
+            builder.build_float_sub(
+                builder.build_float_sub(correction, atan_lo, ""),
+                reduced,
+                "",
+            ),
+            "",
+        );
+        self.call_double_function("llvm.copysign.f64", &[result, value], context)
+    }
+
+    /// generates the arc tangent of `y / x` in the quadrant given by the signs of `y` and `x`
+    fn generate_atan2(
+        &self,
+        y: FloatValue<'a>,
+        x: FloatValue<'a>,
+        context: &AstStatement,
+    ) -> Result<FloatValue<'a>, CompileError> {
+        let builder = &self.llvm.builder;
+        let double_type = self.llvm.context.f64_type();
+        let abs_y = self.call_double_function("llvm.fabs.f64", &[y], context)?;
+        let abs_x = self.call_double_function("llvm.fabs.f64", &[x], context)?;
+        let atan = self.call_trigonometric_helper(
+            TrigonometricHelper::Atan,
+            &[builder.build_float_div(abs_y, abs_x, "")],
+            context,
+        )?;
+
+        //the angle of y = 0 is 0 or PI, even if x is 0 as well
+        let is_y_zero =
+            builder.build_float_compare(FloatPredicate::OEQ, y, double_type.const_zero(), "");
+        let atan = builder
+            .build_select(is_y_zero, double_type.const_zero(), atan, "")
+            .into_float_value();
+
+        //mirror the angle for negative x: PI - (atan - PI_LO)
+        let mirrored = builder.build_float_sub(
+            double_type.const_float(PI),
+            builder.build_float_sub(atan, double_type.const_float(PI_LO), ""),
+            "",
+        );
+        let is_x_negative =
+            builder.build_float_compare(FloatPredicate::OLT, x, double_type.const_zero(), "");
+        let angle = builder
+            .build_select(is_x_negative, mirrored, atan, "")
+            .into_float_value();
+        self.call_double_function("llvm.copysign.f64", &[angle, y], context)
+    }
+
+    /// generates `SQRT((1 - x) * (1 + x))`, the cosine of the arc sine of x
+    fn generate_complement_root(
+        &self,
+        value: FloatValue<'a>,
+        context: &AstStatement,
+    ) -> Result<FloatValue<'a>, CompileError> {
+        let builder = &self.llvm.builder;
+        let one = self.llvm.context.f64_type().const_float(1.0);
+        let square = builder.build_float_mul(
+            builder.build_float_sub(one, value, ""),
+            builder.build_float_add(one, value, ""),
+            "",
+        );
+        self.call_double_function("llvm.sqrt.f64", &[square], context)
+    }
+
+    /// generates the polynomial `c0 + x * (c1 + x * (c2 + ...))` with Horner's method
+    fn generate_polynomial(&self, x: FloatValue<'a>, coefficients: &[f64]) -> FloatValue<'a> {
+        let builder = &self.llvm.builder;
+        let double_type = x.get_type();
+        coefficients.iter().rev().skip(1).fold(
+            double_type.const_float(coefficients[coefficients.len() - 1]),
+            |sum, coefficient| {
+                builder.build_float_add(
+                    double_type.const_float(*coefficient),
+                    builder.build_float_mul(x, sum, ""),
+                    "",
+                )
+            },
+        )
+    }
+
+    /// calls the llvm intrinsic with the given name on LREAL values
+    fn call_double_function(
+        &self,
+        function_name: &str,
+        arguments: &[FloatValue<'a>],
+        context: &AstStatement,
+    ) -> Result<FloatValue<'a>, CompileError> {
+        self.call_float_function(function_name, arguments, context)
+            .map(BasicValueEnum::into_float_value)
+    }
+}
+
+/// returns the LREAL parameter of the given helper function
+fn get_float_parameter(function: FunctionValue, index: u32) -> FloatValue {
+    function
+        .get_nth_param(index)
+        .expect("trigonometric helpers are declared with all of their parameters")
+        .into_float_value()
+}
@@ -0,0 +1,253 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+use super::llvm::Llvm;
+use crate::{
+    ast::{DirectAccess, HardwareArea},
+    codegen::llvm_index::LlvmTypedIndex,
+    compile_error::CompileError,
+    index::{Index, LocatedVariableEntry},
+};
+use inkwell::{
+    module::Module,
+    types::BasicType,
+    values::{GlobalValue, PointerValue},
+    AddressSpace,
+};
+
+/// the name of the exported function that copies the input- and memory-image into the
+/// located variables
+pub const READ_INPUTS_NAME: &str = "__read_inputs";
+/// the name of the exported function that copies the located variables into the
+/// output- and memory-image
+pub const WRITE_OUTPUTS_NAME: &str = "__write_outputs";
+
+/// returns the name of the exported byte-array holding the process image of the given area
+pub fn get_process_image_name(area: HardwareArea) -> &'static str {
+    match area {
+        HardwareArea::Input => "__input_image",
+        HardwareArea::Output => "__output_image",
+        HardwareArea::Memory => "__memory_image",
+    }
+}
+
+/// generates the process images for all located variables and the functions that copy the
+/// values between the process images and the variables.
+///
+/// a process image is an exported byte-array (e.g. `__input_image`) that is large enough to
+/// hold all variables located in its area, its size is exported as `<image>_size`.
+/// A bit `%IX<byte>.<bit>` is stored in bit `<bit>` (counted from the least significant bit)
+/// of byte `<byte>`, all other sizes are stored in the target's byte order starting at
+/// the given byte (e.g. `%QW10` occupies bytes 10 and 11).
+///
+/// `__read_inputs` copies the input- and memory-image into the variables, `__write_outputs`
+/// copies the variables into the output- and memory-image.
+pub fn generate_process_images<'ink>(
+    module: &Module<'ink>,
+    llvm: &Llvm<'ink>,
+    index: &Index,
+    llvm_index: &LlvmTypedIndex<'ink>,
+) -> Result<(), CompileError> {
+    let located_variables = index.get_located_variables();
+    if located_variables.is_empty() || module.get_function(READ_INPUTS_NAME).is_some() {
+        return Ok(());
+    }
+
+    let images = [
+        HardwareArea::Input,
+        HardwareArea::Output,
+        HardwareArea::Memory,
+    ]
+    .iter()
+    .map(|area| (*area, generate_process_image(module, llvm, index, *area)))
+    .collect::<Vec<_>>();
+    let find_image = |area: HardwareArea| {
+        images
+            .iter()
+            .find(|(it, _)| *it == area)
+            .map(|(_, image)| *image)
+            .unwrap()
+    };
+
+    let void_type = llvm.context.void_type().fn_type(&[], false);
+    let read_inputs = module.add_function(READ_INPUTS_NAME, void_type, None);
+    llvm.builder
+        .position_at_end(llvm.context.append_basic_block(read_inputs, "entry"));
+    for variable in located_variables.values() {
+        if variable.address.area != HardwareArea::Output {
+            let image = find_image(variable.address.area);
+            copy_from_image(llvm, index, llvm_index, variable, image)?;
+        }
+    }
+    llvm.builder.build_return(None);
+
+    let write_outputs = module.add_function(WRITE_OUTPUTS_NAME, void_type, None);
+    llvm.builder
+        .position_at_end(llvm.context.append_basic_block(write_outputs, "entry"));
+    for variable in located_variables.values() {
+        if variable.address.area != HardwareArea::Input {
+            let image = find_image(variable.address.area);
+            copy_to_image(llvm, index, llvm_index, variable, image)?;
+        }
+    }
+    llvm.builder.build_return(None);
+    Ok(())
+}
+
+/// generates the byte-array of the given area and its exported size
+fn generate_process_image<'ink>(
+    module: &Module<'ink>,
+    llvm: &Llvm<'ink>,
+    index: &Index,
+    area: HardwareArea,
+) -> GlobalValue<'ink> {
+    let size = index
+        .get_located_variables()
+        .values()
+        .filter(|it| it.address.area == area)
+        .filter_map(|it| it.address.get_bit_range())
+        .map(|it| (it.end + 7) / 8)
+        .max()
+        .unwrap_or_default();
+
+    let name = get_process_image_name(area);
+    let image_type = llvm.context.i8_type().array_type(size);
+    let image = module.add_global(image_type, None, name);
+    image.set_initializer(&image_type.const_zero());
+
+    let image_size = module.add_global(llvm.i32_type(), None, &format!("{}_size", name));
+    image_size.set_initializer(&llvm.i32_type().const_int(size as u64, false));
+    image_size.set_constant(true);
+    image
+}
+
+/// returns a pointer to the byte of the process image the variable is located at
+fn get_image_byte<'ink>(
+    llvm: &Llvm<'ink>,
+    variable: &LocatedVariableEntry,
+    image: GlobalValue<'ink>,
+) -> Result<PointerValue<'ink>, CompileError> {
+    let byte = variable
+        .address
+        .get_bit_range()
+        .map(|it| it.start / 8)
+        .ok_or_else(|| {
+            CompileError::codegen_error(
+                format!("Invalid address {}", variable.address),
+                variable.address.location.clone(),
+            )
+        })?;
+    let zero = llvm.i32_type().const_zero();
+    Ok(unsafe {
+        llvm.builder.build_in_bounds_gep(
+            image.as_pointer_value(),
+            &[zero, llvm.i32_type().const_int(byte as u64, false)],
+            "",
+        )
+    })
+}
+
+/// returns the bit inside its byte a bit-variable is located at
+fn get_bit_in_byte(variable: &LocatedVariableEntry) -> u64 {
+    variable
+        .address
+        .get_bit_range()
+        .map(|it| it.start % 8)
+        .unwrap_or_default() as u64
+}
+
+/// returns a pointer to the located variable, it is either a global variable or a member of
+/// a program's instance
+fn get_variable_pointer<'ink>(
+    llvm: &Llvm<'ink>,
+    index: &Index,
+    llvm_index: &LlvmTypedIndex<'ink>,
+    variable: &LocatedVariableEntry,
+) -> Result<PointerValue<'ink>, CompileError> {
+    let pointer = match variable.get_container_name() {
+        Some(program) => llvm_index
+            .find_loaded_associated_variable_value(program)
+            .zip(index.find_member(program, variable.get_variable_name()))
+            .and_then(|(instance, member)| {
+                llvm.builder
+                    .build_struct_gep(instance, member.get_location_in_parent(), member.get_name())
+                    .ok()
+            }),
+        None => llvm_index.find_loaded_associated_variable_value(variable.get_variable_name()),
+    };
+    pointer.ok_or_else(|| {
+        CompileError::codegen_error(
+            format!(
+                "Cannot locate {} at {}",
+                variable.get_qualified_name(),
+                variable.address
+            ),
+            variable.address.location.clone(),
+        )
+    })
+}
+
+/// generates the copying of the variable's value from the process image into the variable
+fn copy_from_image<'ink>(
+    llvm: &Llvm<'ink>,
+    index: &Index,
+    llvm_index: &LlvmTypedIndex<'ink>,
+    variable: &LocatedVariableEntry,
+    image: GlobalValue<'ink>,
+) -> Result<(), CompileError> {
+    let builder = &llvm.builder;
+    let target = get_variable_pointer(llvm, index, llvm_index, variable)?;
+    let byte = get_image_byte(llvm, variable, image)?;
+    let value = if variable.address.access == DirectAccess::Bit {
+        let byte_value = builder.build_load(byte, "").into_int_value();
+        let shifted = builder.build_right_shift(
+            byte_value,
+            llvm.context
+                .i8_type()
+                .const_int(get_bit_in_byte(variable), false),
+            false,
+            "",
+        );
+        let bit_type = target.get_type().get_element_type().into_int_type();
+        builder.build_int_truncate(shifted, bit_type, "").into()
+    } else {
+        let source =
+            builder.build_pointer_cast(byte, target.get_type(), variable.get_variable_name());
+        builder.build_load(source, "")
+    };
+    builder.build_store(target, value);
+    Ok(())
+}
+
+/// generates the copying of the variable's value into the process image
+fn copy_to_image<'ink>(
+    llvm: &Llvm<'ink>,
+    index: &Index,
+    llvm_index: &LlvmTypedIndex<'ink>,
+    variable: &LocatedVariableEntry,
+    image: GlobalValue<'ink>,
+) -> Result<(), CompileError> {
+    let builder = &llvm.builder;
+    let source = get_variable_pointer(llvm, index, llvm_index, variable)?;
+    let byte = get_image_byte(llvm, variable, image)?;
+    let value = builder.build_load(source, variable.get_variable_name());
+    if variable.address.access == DirectAccess::Bit {
+        //replace the bit in the image's byte
+        let byte_type = llvm.context.i8_type();
+        let bit = get_bit_in_byte(variable);
+        let bit_value = builder.build_left_shift(
+            builder.build_int_z_extend(value.into_int_value(), byte_type, ""),
+            byte_type.const_int(bit, false),
+            "",
+        );
+        let byte_value = builder.build_and(
+            builder.build_load(byte, "").into_int_value(),
+            byte_type.const_int(!(1 << bit) & 0xFF, false),
+            "",
+        );
+        builder.build_store(byte, builder.build_or(byte_value, bit_value, ""));
+    } else {
+        let target =
+            builder.build_pointer_cast(byte, value.get_type().ptr_type(AddressSpace::Generic), "");
+        builder.build_store(target, value);
+    }
+    Ok(())
+}
@@ -198,6 +198,7 @@ impl<'a> Llvm<'a> {
             name: "VOIDPtr".into(),
             inner_type_name: VOID_TYPE.into(),
             auto_deref: false,
+            is_ref_to: false,
         };
         Ok((data_type, value.into()))
     }
@@ -14,7 +14,9 @@ use crate::{
 /// - generates a struct-datatype for the POU's members
 /// - generates a function for the pou
 /// - declares a global instance if the POU is a PROGRAM
-use crate::index::{ImplementationIndexEntry, VariableIndexEntry};
+use crate::index::{
+    ImplementationIndexEntry, VariableIndexEntry, VariableType, SUPER_CLASS_MEMBER_NAME,
+};
 use crate::typesystem::*;
 use crate::{
     ast::{AstStatement, Implementation, PouType, SourceRange},
@@ -25,7 +27,7 @@ use inkwell::types::StructType;
 use inkwell::{
     module::Module,
     types::{BasicTypeEnum, FunctionType},
-    values::{BasicValueEnum, FunctionValue},
+    values::{BasicValueEnum, FunctionValue, PointerValue},
     AddressSpace,
 };
 
@@ -119,10 +121,11 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
         Ok(curr_f)
     }
 
-    /// generates a function for the given pou
+    /// generates a function for the given pou into the given module
     pub fn generate_implementation(
         &self,
         implementation: &Implementation,
+        module: &Module<'ink>,
     ) -> Result<(), CompileError> {
         let context = self.llvm.context;
         let mut local_index = LlvmTypedIndex::create_child(self.llvm_index);
@@ -145,8 +148,8 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
 
         let mut param_index = 0;
 
-        if let PouType::Method { .. } = implementation.pou_type {
-            let class_name = implementation.type_name.split('.').collect::<Vec<&str>>()[0];
+        if let PouType::Method { owner_class } = &implementation.pou_type {
+            let class_name = owner_class.as_str();
             let class_members = self.index.find_local_members(class_name);
             self.generate_local_variable_accessors(
                 param_index,
@@ -171,6 +174,7 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
         let function_context = FunctionContext {
             linking_context: implementation.into(),
             function: current_function,
+            module,
         };
         {
             let statement_gen = StatementCodeGenerator::new(
@@ -186,6 +190,7 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
                 implementation.pou_type,
                 PouType::Function | PouType::Method { .. }
             ) {
+                self.generate_initialization_of_method_tables(&pou_members, &local_index)?;
                 self.generate_initialization_of_local_vars(pou_members, &statement_gen)?;
             }
             statement_gen.generate_body(&implementation.statements)?
@@ -261,11 +266,80 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
                 )
             };
             index.associate_loaded_local_variable(type_name, name, variable)?;
+            if parameter_name == SUPER_CLASS_MEMBER_NAME {
+                self.generate_inherited_variable_accessors(index, m.get_type_name(), variable)?;
+            }
         }
 
         Ok(())
     }
 
+    /// generates the accessors for all members inherited from `super_class` and its own super classes
+    ///
+    /// - `super_instance` pointer to the super class' instance embedded in the current pou's struct
+    fn generate_inherited_variable_accessors(
+        &self,
+        index: &mut LlvmTypedIndex<'ink>,
+        super_class: &str,
+        super_instance: PointerValue<'ink>,
+    ) -> Result<(), CompileError> {
+        let mut instance = super_instance;
+        for class in self.index.get_class_hierarchy(super_class) {
+            let mut next_instance = None;
+            for (i, m) in self.index.find_local_members(class).iter().enumerate() {
+                let variable = self
+                    .llvm
+                    .builder
+                    .build_struct_gep(instance, i as u32, m.get_name())
+                    .unwrap();
+                index.associate_loaded_local_variable(class, m.get_name(), variable)?;
+                if m.get_name() == SUPER_CLASS_MEMBER_NAME {
+                    next_instance = Some(variable);
+                }
+            }
+            match next_instance {
+                Some(it) => instance = it,
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    /// initializes the local instances of extended classes, so calls to their methods
+    /// find the instance's method table
+    fn generate_initialization_of_method_tables(
+        &self,
+        variables: &[&VariableIndexEntry],
+        local_index: &LlvmTypedIndex<'ink>,
+    ) -> Result<(), CompileError> {
+        let instances = variables
+            .iter()
+            .filter(|it| {
+                matches!(
+                    it.get_variable_type(),
+                    VariableType::Local | VariableType::Temp
+                )
+            })
+            .filter(|it| it.initial_value.is_none())
+            .filter(|it| {
+                self.index
+                    .find_method_table_owner(it.get_type_name())
+                    .is_some()
+            });
+
+        for instance in instances {
+            let initial_value = self
+                .llvm_index
+                .find_associated_initial_value(instance.get_type_name());
+            let variable =
+                local_index.find_loaded_associated_variable_value(instance.get_qualified_name());
+            if let (Some(initial_value), Some(variable)) = (initial_value, variable) {
+                self.llvm.builder.build_store(variable, initial_value);
+            }
+        }
+        Ok(())
+    }
+
     /// generates assignment statements for initialized variables in the VAR-block
     ///
     /// - `blocks` - all declaration blocks of the current pou
@@ -296,7 +370,7 @@ impl<'ink, 'cg> PouGenerator<'ink, 'cg> {
     /// a function returns the value of the local variable that has the function's name
     pub fn generate_return_statement(
         &self,
-        function_context: &FunctionContext<'ink>,
+        function_context: &FunctionContext<'ink, '_>,
         local_index: &LlvmTypedIndex<'ink>,
         location: Option<SourceRange>,
     ) -> Result<(), CompileError> {
@@ -15,17 +15,20 @@ use crate::{
 };
 use inkwell::{
     basic_block::BasicBlock,
+    module::Module,
     values::{BasicValueEnum, FunctionValue},
     IntPredicate,
 };
 use std::ops::Range;
 
 /// the full context when generating statements inside a POU
-pub struct FunctionContext<'a> {
+pub struct FunctionContext<'a, 'b> {
     /// the current pou's name. This means that a variable x may refer to "`linking_context`.x"
     pub linking_context: ImplementationIndexEntry,
     /// the llvm function to generate statements into
     pub function: FunctionValue<'a>,
+    /// the module the function is generated into, used to declare llvm intrinsics
+    pub module: &'b Module<'a>,
 }
 
 /// the StatementCodeGenerator is used to generate statements (For, If, etc.) or expressions (references, literals, etc.)
@@ -35,7 +38,7 @@ pub struct StatementCodeGenerator<'a, 'b> {
     annotations: &'b AnnotationMap,
     pou_generator: &'b PouGenerator<'a, 'b>,
     llvm_index: &'b LlvmTypedIndex<'a>,
-    function_context: &'b FunctionContext<'a>,
+    function_context: &'b FunctionContext<'a, 'b>,
 
     pub load_prefix: String,
     pub load_suffix: String,
@@ -54,7 +57,7 @@ impl<'a, 'b> StatementCodeGenerator<'a, 'b> {
         annotations: &'b AnnotationMap,
         pou_generator: &'b PouGenerator<'a, 'b>,
         llvm_index: &'b LlvmTypedIndex<'a>,
-        linking_context: &'b FunctionContext<'a>,
+        linking_context: &'b FunctionContext<'a, 'b>,
     ) -> StatementCodeGenerator<'a, 'b> {
         StatementCodeGenerator {
             llvm,
@@ -114,6 +117,9 @@ impl<'a, 'b> StatementCodeGenerator<'a, 'b> {
             AstStatement::Assignment { left, right, .. } => {
                 self.generate_assignment_statement(left, right)?;
             }
+            AstStatement::ReferenceAssignment { left, right, .. } => {
+                self.generate_reference_assignment(left, right)?;
+            }
             AstStatement::ForLoopStatement {
                 start,
                 end,
@@ -217,7 +223,7 @@ impl<'a, 'b> StatementCodeGenerator<'a, 'b> {
         let (right_type, right) = if let Some(check_call) = range_checked_right_side {
             exp_gen.generate_expression(&check_call)?
         } else {
-            exp_gen.generate_expression(right_statement)?
+            exp_gen.generate_assignable_expression(left.type_entry, right_statement)?
         };
 
         let cast_value = cast_if_needed(
@@ -232,6 +238,32 @@ impl<'a, 'b> StatementCodeGenerator<'a, 'b> {
         Ok(())
     }
 
+    /// generates a `REF=` assignment which rebinds the reference on the left side to the variable
+    /// on the right side (or to NULL). A `:=` assignment writes to the variable a
+    /// `REFERENCE TO` points to instead
+    pub fn generate_reference_assignment(
+        &self,
+        left_statement: &AstStatement,
+        right_statement: &AstStatement,
+    ) -> Result<(), CompileError> {
+        let exp_gen = self.create_expr_generator();
+        let reference = exp_gen.generate_reference_pointer(left_statement)?;
+        let reference_type = self
+            .llvm_index
+            .get_associated_type(reference.get_type_information().get_name())?
+            .into_pointer_type();
+        let target = if let AstStatement::LiteralNull { .. } = right_statement {
+            reference_type.const_null()
+        } else {
+            let target = exp_gen.generate_element_pointer(right_statement)?;
+            self.llvm
+                .builder
+                .build_pointer_cast(target.ptr_value, reference_type, "")
+        };
+        self.llvm.builder.build_store(reference.ptr_value, target);
+        Ok(())
+    }
+
     /// returns the implementation of the sub-range-check-function for a variable of the given dataType
     fn find_range_check_impolementation_for(
         &self,
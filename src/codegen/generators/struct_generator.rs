@@ -1,14 +1,21 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
 use super::{expression_generator::ExpressionCodeGenerator, llvm::Llvm};
-use crate::index::Index;
+use crate::index::{
+    ImplementationIndexEntry, Index, VariableType, INTERFACE_INSTANCE_MEMBER_NAME,
+    INTERFACE_VTABLE_MEMBER_NAME, METHOD_TABLE_MEMBER_NAME, SUPER_CLASS_MEMBER_NAME,
+};
 use crate::resolver::AnnotationMap;
 use crate::{
-    codegen::llvm_index::LlvmTypedIndex, compile_error::CompileError, index::VariableIndexEntry,
+    ast::SourceRange, codegen::llvm_index::LlvmTypedIndex, compile_error::CompileError,
+    index::VariableIndexEntry, typesystem::DataTypeInformation,
 };
 use inkwell::{
+    module::Module,
     types::{BasicTypeEnum, StructType},
-    values::BasicValueEnum,
+    values::{BasicValueEnum, CallableValue, FunctionValue, GlobalValue, PointerValue},
+    AddressSpace,
 };
+use std::convert::TryFrom;
 
 /// object that offers convinient operations to create struct types and instances
 pub struct StructGenerator<'a, 'b> {
@@ -56,11 +63,19 @@ impl<'a, 'b> StructGenerator<'a, 'b> {
 
         let mut members = Vec::new();
         for member in member_variables {
-            members.push(self.create_llvm_variable_declaration_elements(member)?);
+            let (member_name, member_type, initializer) =
+                self.create_llvm_variable_declaration_elements(member)?;
+            let initializer = self
+                .generate_method_table_initializer(member, name)?
+                .or(initializer);
+            members.push((member_name, member_type, initializer));
         }
 
         let member_types: Vec<BasicTypeEnum> = members.iter().map(|(_, t, _)| *t).collect();
-        struct_type.set_body(member_types.as_slice(), false);
+        //the type may already be defined as the super class of another class
+        if struct_type.is_opaque() {
+            struct_type.set_body(member_types.as_slice(), false);
+        }
 
         //vec(member_name, initial_value)
         let struct_fields_values = members
@@ -88,6 +103,79 @@ impl<'a, 'b> StructGenerator<'a, 'b> {
         Ok(((struct_type, initial_value.into()), member_values))
     }
 
+    /// returns the initial value of a member of an instance of `class_name` that depends on
+    /// the class' method table or None if the member does not refer to the method table.
+    ///
+    /// the method table member points to the method table of `class_name`. The embedded
+    /// instances of its super classes point to it as well.
+    fn generate_method_table_initializer(
+        &self,
+        member: &VariableIndexEntry,
+        class_name: &str,
+    ) -> Result<Option<BasicValueEnum<'a>>, CompileError> {
+        if self.index.find_method_table_owner(class_name).is_none() {
+            return Ok(None);
+        }
+        match member.get_name() {
+            METHOD_TABLE_MEMBER_NAME => {
+                let method_table_name = get_method_table_name(class_name);
+                let method_table = self
+                    .llvm_index
+                    .find_associated_variable_value(&method_table_name)
+                    .ok_or_else(|| {
+                        CompileError::codegen_error(
+                            format!("Could not find method table {}", method_table_name),
+                            SourceRange::undefined(),
+                        )
+                    })?;
+                let void_ptr_type = self.llvm.context.i8_type().ptr_type(AddressSpace::Generic);
+                Ok(Some(
+                    method_table
+                        .into_pointer_value()
+                        .const_cast(void_ptr_type)
+                        .into(),
+                ))
+            }
+            SUPER_CLASS_MEMBER_NAME => self
+                .generate_embedded_instance(member.get_type_name(), class_name)
+                .map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// generates the initial value of the instance of `super_class` that is embedded in an
+    /// instance of `class_name`
+    fn generate_embedded_instance(
+        &self,
+        super_class: &str,
+        class_name: &str,
+    ) -> Result<BasicValueEnum<'a>, CompileError> {
+        let struct_type = self
+            .llvm_index
+            .get_associated_type(super_class)
+            .map(BasicTypeEnum::into_struct_type)?;
+
+        let mut member_types = vec![];
+        let mut member_values = vec![];
+        for member in self.index.find_local_members(super_class) {
+            if member.is_return() {
+                continue;
+            }
+            let (_, member_type, initializer) =
+                self.create_llvm_variable_declaration_elements(member)?;
+            let value = self
+                .generate_method_table_initializer(member, class_name)?
+                .or(initializer)
+                .unwrap_or_else(|| get_default_for(member_type));
+            member_types.push(member_type);
+            member_values.push(value);
+        }
+        if struct_type.is_opaque() {
+            struct_type.set_body(member_types.as_slice(), false);
+        }
+        Ok(struct_type.const_named_struct(&member_values).into())
+    }
+
     /// creates all declaration information for the given variable
     ///
     /// returns a tuple of the variable's name, its DataType and it's optional initial Value
@@ -139,3 +227,483 @@ pub fn get_default_for(basic_type: BasicTypeEnum) -> BasicValueEnum {
         BasicTypeEnum::VectorType(t) => t.const_zero().into(),
     }
 }
+
+/// returns the name of the vtable that maps the methods of `interface_name` to the
+/// implementations of `class_name`
+pub fn get_vtable_name(class_name: &str, interface_name: &str) -> String {
+    format!("__vtable_{}_{}", class_name, interface_name)
+}
+
+/// returns the name of the table that holds the methods of `class_name` in the order of
+/// their slots
+pub fn get_method_table_name(class_name: &str) -> String {
+    format!("__method_table_{}", class_name)
+}
+
+/// returns the name of the function that calls the implementation of the given method that
+/// is found in the method table of the called instance
+pub fn get_dispatcher_name(method: &str) -> String {
+    format!("__dispatch_{}", method)
+}
+
+/// declares the method table of the given class, its initializer is generated
+/// by `generate_vtables`
+pub fn declare_method_table<'ink>(
+    module: &Module<'ink>,
+    llvm: &Llvm<'ink>,
+    index: &Index,
+    class_name: &str,
+) -> GlobalValue<'ink> {
+    let method_table_name = get_method_table_name(class_name);
+    module.get_global(&method_table_name).unwrap_or_else(|| {
+        let void_ptr_type = llvm.context.i8_type().ptr_type(AddressSpace::Generic);
+        let size = index.get_virtual_methods(class_name).len() as u32;
+        module.add_global(void_ptr_type.array_type(size), None, &method_table_name)
+    })
+}
+
+/// returns the name of the function that forwards a call of the interface-method
+/// `interface_method` to its implementation in `class_name`
+fn get_thunk_name(class_name: &str, interface_method: &str) -> String {
+    format!("__thunk_{}_{}", class_name, interface_method)
+}
+
+/// generates the vtables for all classes and function blocks that implement an interface
+/// and the bodies of all interface-methods
+///
+/// a vtable holds a function for every method of the interface (in the order of their
+/// declaration). Such a function forwards the call to the class' implementation of the method.
+/// An interface-method looks up the function in the vtable of the referenced instance and calls it.
+pub fn generate_vtables<'ink>(
+    module: &Module<'ink>,
+    llvm: &Llvm<'ink>,
+    index: &Index,
+    llvm_index: &LlvmTypedIndex<'ink>,
+) -> Result<LlvmTypedIndex<'ink>, CompileError> {
+    let mut vtables = LlvmTypedIndex::new();
+    for interface in index.get_interfaces().values() {
+        for (slot, method) in index.find_interface_methods(interface).iter().enumerate() {
+            generate_interface_method_dispatch(llvm, llvm_index, method, slot as u64)?;
+        }
+    }
+
+    for data_type in index.get_types().values() {
+        if let DataTypeInformation::Struct { .. } = data_type.get_type_information() {
+            let class_name = data_type.get_name();
+            if let Some(root_class) = index.find_method_table_owner(class_name) {
+                generate_method_table(module, llvm, index, llvm_index, class_name)?;
+                for (slot, method) in index.get_virtual_methods(class_name).iter().enumerate() {
+                    let dispatcher = generate_virtual_method_dispatch(
+                        module,
+                        llvm,
+                        index,
+                        llvm_index,
+                        root_class,
+                        method,
+                        slot as u64,
+                    )?;
+                    vtables.associate_implementation(
+                        &get_dispatcher_name(method.get_call_name()),
+                        dispatcher,
+                    )?;
+                }
+            }
+            for interface in index.find_implemented_interfaces(class_name) {
+                //unresolved interfaces are reported by the validation
+                if index.is_interface(interface) {
+                    let vtable =
+                        generate_vtable(module, llvm, index, llvm_index, class_name, interface)?;
+                    vtables.associate_global(&get_vtable_name(class_name, interface), vtable)?;
+                }
+            }
+        }
+    }
+    Ok(vtables)
+}
+
+/// generates the body of the given interface-method.
+///
+/// the method receives a pointer to the interface-reference and looks up the function
+/// at the given `slot` of the referenced instance's vtable
+fn generate_interface_method_dispatch<'ink>(
+    llvm: &Llvm<'ink>,
+    llvm_index: &LlvmTypedIndex<'ink>,
+    method: &ImplementationIndexEntry,
+    slot: u64,
+) -> Result<(), CompileError> {
+    let function = find_implementation_stub(llvm_index, method.get_call_name())?;
+    if function.get_first_basic_block().is_some() {
+        //already generated
+        return Ok(());
+    }
+    let builder = &llvm.builder;
+    let block = llvm.context.append_basic_block(function, "entry");
+    builder.position_at_end(block);
+
+    let reference = function.get_nth_param(0).unwrap().into_pointer_value();
+    let parameters = function.get_nth_param(1).unwrap();
+    let instance = builder.build_load(
+        builder
+            .build_struct_gep(reference, 0, INTERFACE_INSTANCE_MEMBER_NAME)
+            .unwrap(),
+        "instance",
+    );
+    let vtable = builder.build_load(
+        builder
+            .build_struct_gep(reference, 1, INTERFACE_VTABLE_MEMBER_NAME)
+            .unwrap(),
+        "vtable",
+    );
+
+    //the called function receives the referenced instance instead of the interface-reference
+    let instance =
+        builder.build_pointer_cast(instance.into_pointer_value(), reference.get_type(), "");
+    generate_vtable_call(
+        llvm,
+        function,
+        vtable.into_pointer_value(),
+        slot,
+        instance.into(),
+        parameters,
+    )
+}
+
+/// generates the function that dispatches a call of the given method to the implementation
+/// in the method table of the called instance
+///
+/// - `root_class` the root of the class hierarchy that holds the method table
+/// - `method` the method's declaration in the top-most class of the hierarchy
+/// - `slot` the method's slot in the method table
+fn generate_virtual_method_dispatch<'ink>(
+    module: &Module<'ink>,
+    llvm: &Llvm<'ink>,
+    index: &Index,
+    llvm_index: &LlvmTypedIndex<'ink>,
+    root_class: &str,
+    method: &ImplementationIndexEntry,
+    slot: u64,
+) -> Result<FunctionValue<'ink>, CompileError> {
+    let dispatcher_name = get_dispatcher_name(method.get_call_name());
+    if let Some(dispatcher) = module.get_function(&dispatcher_name) {
+        return Ok(dispatcher);
+    }
+    let method_function = find_implementation_stub(llvm_index, method.get_call_name())?;
+    let dispatcher = module.add_function(&dispatcher_name, method_function.get_type(), None);
+
+    let builder = &llvm.builder;
+    let block = llvm.context.append_basic_block(dispatcher, "entry");
+    builder.position_at_end(block);
+
+    let instance = dispatcher.get_nth_param(0).unwrap().into_pointer_value();
+    let parameters = dispatcher.get_nth_param(1).unwrap();
+
+    //the root class is embedded at the start of the instance via the super class members
+    let mut root_instance = instance;
+    let declaring_class = method
+        .get_associated_class_name()
+        .map(String::as_str)
+        .unwrap_or(root_class);
+    for class in index.get_class_hierarchy(declaring_class) {
+        if class.eq_ignore_ascii_case(root_class) {
+            break;
+        }
+        if let Some(super_member) = index.find_local_member(class, SUPER_CLASS_MEMBER_NAME) {
+            root_instance = builder
+                .build_struct_gep(
+                    root_instance,
+                    super_member.get_location_in_parent(),
+                    SUPER_CLASS_MEMBER_NAME,
+                )
+                .unwrap();
+        }
+    }
+    let method_table_member = index
+        .find_local_member(root_class, METHOD_TABLE_MEMBER_NAME)
+        .ok_or_else(|| {
+            CompileError::codegen_error(
+                format!("{} has no method table", root_class),
+                SourceRange::undefined(),
+            )
+        })?;
+    let method_table = builder.build_load(
+        builder
+            .build_struct_gep(
+                root_instance,
+                method_table_member.get_location_in_parent(),
+                METHOD_TABLE_MEMBER_NAME,
+            )
+            .unwrap(),
+        "method_table",
+    );
+
+    generate_vtable_call(
+        llvm,
+        dispatcher,
+        method_table.into_pointer_value(),
+        slot,
+        instance.into(),
+        parameters,
+    )?;
+    Ok(dispatcher)
+}
+
+/// generates a call of the function at the given `slot` of the vtable and returns its result
+/// from the given function
+///
+/// - `function` the function to generate the call into, the called function has the same type
+/// - `vtable` an untyped pointer to the vtable
+fn generate_vtable_call<'ink>(
+    llvm: &Llvm<'ink>,
+    function: FunctionValue<'ink>,
+    vtable: PointerValue<'ink>,
+    slot: u64,
+    instance: BasicValueEnum<'ink>,
+    parameters: BasicValueEnum<'ink>,
+) -> Result<(), CompileError> {
+    let builder = &llvm.builder;
+    //the vtable is an array of untyped function pointers
+    let void_ptr_type = llvm.context.i8_type().ptr_type(AddressSpace::Generic);
+    let vtable =
+        builder.build_pointer_cast(vtable, void_ptr_type.ptr_type(AddressSpace::Generic), "");
+    let entry = unsafe {
+        builder.build_in_bounds_gep(vtable, &[llvm.i32_type().const_int(slot, false)], "")
+    };
+    let target = builder.build_pointer_cast(
+        builder.build_load(entry, "").into_pointer_value(),
+        function.get_type().ptr_type(AddressSpace::Generic),
+        "",
+    );
+    let target = CallableValue::try_from(target).map_err(|_| {
+        CompileError::codegen_error(
+            format!(
+                "Cannot call {} via a vtable",
+                function.get_name().to_string_lossy()
+            ),
+            SourceRange::undefined(),
+        )
+    })?;
+
+    let result = builder
+        .build_call(target, &[instance, parameters], "call")
+        .try_as_basic_value();
+    match result.left() {
+        Some(value) => builder.build_return(Some(&value)),
+        None => builder.build_return(None),
+    };
+    Ok(())
+}
+
+/// generates the initializer of the given class' method table. A method that is overridden
+/// by the class is called through a thunk that forwards the parameters to the override
+fn generate_method_table<'ink>(
+    module: &Module<'ink>,
+    llvm: &Llvm<'ink>,
+    index: &Index,
+    llvm_index: &LlvmTypedIndex<'ink>,
+    class_name: &str,
+) -> Result<(), CompileError> {
+    let method_table = declare_method_table(module, llvm, index, class_name);
+    if method_table.get_initializer().is_some() {
+        return Ok(());
+    }
+
+    let void_ptr_type = llvm.context.i8_type().ptr_type(AddressSpace::Generic);
+    let mut entries = vec![];
+    for method in index.get_virtual_methods(class_name) {
+        let method_name = method
+            .get_call_name()
+            .rsplit('.')
+            .next()
+            .unwrap_or_default();
+        let implementation = index.find_method(class_name, method_name).unwrap_or(method);
+        let function = if implementation.get_call_name() == method.get_call_name() {
+            find_implementation_stub(llvm_index, method.get_call_name())?
+        } else {
+            generate_thunk(
+                module,
+                llvm,
+                index,
+                llvm_index,
+                class_name,
+                method,
+                implementation,
+            )?
+        };
+        entries.push(
+            function
+                .as_global_value()
+                .as_pointer_value()
+                .const_cast(void_ptr_type),
+        );
+    }
+    method_table.set_initializer(&void_ptr_type.const_array(&entries));
+    method_table.set_constant(true);
+    Ok(())
+}
+
+/// generates the vtable that maps the methods of the given interface to the implementations
+/// of the given class
+fn generate_vtable<'ink>(
+    module: &Module<'ink>,
+    llvm: &Llvm<'ink>,
+    index: &Index,
+    llvm_index: &LlvmTypedIndex<'ink>,
+    class_name: &str,
+    interface: &str,
+) -> Result<GlobalValue<'ink>, CompileError> {
+    let vtable_name = get_vtable_name(class_name, interface);
+    if let Some(vtable) = module.get_global(&vtable_name) {
+        return Ok(vtable);
+    }
+
+    let void_ptr_type = llvm.context.i8_type().ptr_type(AddressSpace::Generic);
+    let mut entries = vec![];
+    for method in index.find_interface_methods(interface) {
+        let method_name = method
+            .get_call_name()
+            .rsplit('.')
+            .next()
+            .unwrap_or_default();
+        let entry = match index.find_method(class_name, method_name) {
+            Some(implementation) => {
+                let thunk = generate_thunk(
+                    module,
+                    llvm,
+                    index,
+                    llvm_index,
+                    class_name,
+                    method,
+                    implementation,
+                )?;
+                thunk
+                    .as_global_value()
+                    .as_pointer_value()
+                    .const_cast(void_ptr_type)
+            }
+            //missing implementations are reported by the validation
+            None => void_ptr_type.const_null(),
+        };
+        entries.push(entry);
+    }
+
+    let initial_value = void_ptr_type.const_array(&entries);
+    let vtable = module.add_global(initial_value.get_type(), None, &vtable_name);
+    vtable.set_initializer(&initial_value);
+    vtable.set_constant(true);
+    Ok(vtable)
+}
+
+/// generates a function of the interface-method's type that forwards its parameters to
+/// the class' implementation of the method
+///
+/// - `method` the interface-method
+/// - `implementation` the class' implementation of the interface-method
+fn generate_thunk<'ink>(
+    module: &Module<'ink>,
+    llvm: &Llvm<'ink>,
+    index: &Index,
+    llvm_index: &LlvmTypedIndex<'ink>,
+    class_name: &str,
+    method: &ImplementationIndexEntry,
+    implementation: &ImplementationIndexEntry,
+) -> Result<FunctionValue<'ink>, CompileError> {
+    let thunk_name = get_thunk_name(class_name, method.get_call_name());
+    if let Some(thunk) = module.get_function(&thunk_name) {
+        return Ok(thunk);
+    }
+    let method_function = find_implementation_stub(llvm_index, method.get_call_name())?;
+    let implementation_function =
+        find_implementation_stub(llvm_index, implementation.get_call_name())?;
+    let thunk = module.add_function(&thunk_name, method_function.get_type(), None);
+
+    let builder = &llvm.builder;
+    let block = llvm.context.append_basic_block(thunk, "entry");
+    builder.position_at_end(block);
+
+    //the implementation may be inherited, its class is embedded at the start of the instance
+    let class_type = implementation_function.get_type().get_param_types()[0].into_pointer_type();
+    let instance = builder.build_pointer_cast(
+        thunk.get_nth_param(0).unwrap().into_pointer_value(),
+        class_type,
+        "",
+    );
+    let parameters = thunk.get_nth_param(1).unwrap().into_pointer_value();
+    let implementation_parameters = llvm.create_local_variable(
+        "",
+        &llvm_index.get_associated_type(implementation.get_type_name())?,
+    );
+
+    let method_parameters = (method.get_call_name(), parameters);
+    let forwarded_parameters = (implementation.get_call_name(), implementation_parameters);
+    copy_parameters(
+        llvm,
+        index,
+        method_parameters,
+        forwarded_parameters,
+        &[VariableType::Input, VariableType::InOut],
+    );
+    let result = builder
+        .build_call(
+            implementation_function,
+            &[instance.into(), implementation_parameters.into()],
+            "call",
+        )
+        .try_as_basic_value();
+    copy_parameters(
+        llvm,
+        index,
+        forwarded_parameters,
+        method_parameters,
+        &[VariableType::Output],
+    );
+    match result.left() {
+        Some(value) => builder.build_return(Some(&value)),
+        None => builder.build_return(None),
+    };
+    Ok(thunk)
+}
+
+/// copies the parameters of the given variable types from one parameter struct into the
+/// equally named members of another one
+///
+/// - `from` the name of the source's pou and a pointer to its parameter struct
+/// - `to` the name of the target's pou and a pointer to its parameter struct
+fn copy_parameters<'ink>(
+    llvm: &Llvm<'ink>,
+    index: &Index,
+    from: (&str, PointerValue<'ink>),
+    to: (&str, PointerValue<'ink>),
+    variable_types: &[VariableType],
+) {
+    let builder = &llvm.builder;
+    let (from_pou, from_struct) = from;
+    let (to_pou, to_struct) = to;
+    for member in index.find_local_members(from_pou) {
+        if !variable_types.contains(&member.get_variable_type()) {
+            continue;
+        }
+        if let Some(target) = index.find_local_member(to_pou, member.get_name()) {
+            let source = builder
+                .build_struct_gep(from_struct, member.get_location_in_parent(), "")
+                .unwrap();
+            let target = builder
+                .build_struct_gep(to_struct, target.get_location_in_parent(), "")
+                .unwrap();
+            builder.build_store(target, builder.build_load(source, member.get_name()));
+        }
+    }
+}
+
+fn find_implementation_stub<'ink>(
+    llvm_index: &LlvmTypedIndex<'ink>,
+    call_name: &str,
+) -> Result<FunctionValue<'ink>, CompileError> {
+    llvm_index
+        .find_associated_implementation(call_name)
+        .ok_or_else(|| {
+            CompileError::codegen_error(
+                format!("Could not find generated stub for {}", call_name),
+                SourceRange::undefined(),
+            )
+        })
+}
@@ -0,0 +1,203 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+use super::{
+    expression_generator::ExpressionCodeGenerator,
+    io_generator::{READ_INPUTS_NAME, WRITE_OUTPUTS_NAME},
+    llvm::Llvm,
+    struct_generator::get_pou_instance_variable_name,
+};
+use crate::{
+    ast::evaluate_constant_int,
+    codegen::llvm_index::LlvmTypedIndex,
+    compile_error::CompileError,
+    index::{Index, TaskIndexEntry},
+    resolver::AnnotationMap,
+    typesystem::TIME_TYPE,
+};
+use inkwell::{
+    module::{Linkage, Module},
+    values::{FunctionValue, PointerValue},
+    AddressSpace,
+};
+
+/// the name of the exported table that describes all tasks
+pub const TASK_TABLE_NAME: &str = "__tasks";
+/// the name of the exported number of entries in the task table
+pub const TASK_COUNT_NAME: &str = "__tasks_count";
+/// the name of the struct type of an entry in the task table
+const TASK_TYPE_NAME: &str = "__task";
+
+/// returns the name of the function that executes one cycle of the given task
+pub fn get_task_cycle_name(task_name: &str) -> String {
+    format!("{}_cycle", task_name)
+}
+
+/// generates the task table and a cycle function for every task of the index
+///
+/// an entry of the table is a `__task` struct consisting of
+/// - the task's name
+/// - its interval in nanoseconds
+/// - its priority
+/// - the number of program instances and an array with their names
+/// - the task's cycle function which calls all of its program instances
+pub fn generate_task_table<'ink>(
+    module: &Module<'ink>,
+    llvm: &Llvm<'ink>,
+    index: &Index,
+    annotations: &AnnotationMap,
+    llvm_index: &LlvmTypedIndex<'ink>,
+) -> Result<(), CompileError> {
+    if index.get_tasks().is_empty() || module.get_global(TASK_TABLE_NAME).is_some() {
+        return Ok(());
+    }
+
+    let context = llvm.context;
+    let string_type = context.i8_type().ptr_type(AddressSpace::Generic);
+    let cycle_type = context.void_type().fn_type(&[], false);
+    let task_type = context.opaque_struct_type(TASK_TYPE_NAME);
+    task_type.set_body(
+        &[
+            string_type.into(),
+            context.i64_type().into(),
+            llvm.i32_type().into(),
+            llvm.i32_type().into(),
+            string_type.ptr_type(AddressSpace::Generic).into(),
+            cycle_type.ptr_type(AddressSpace::Generic).into(),
+        ],
+        false,
+    );
+
+    let mut entries = vec![];
+    for task in index.get_tasks().values() {
+        let task_name = task.get_name();
+        let cycle = generate_task_cycle(module, llvm, llvm_index, task)?;
+
+        let interval = match &task.interval {
+            Some(interval) => {
+                let exp_gen = ExpressionCodeGenerator::new_context_free(
+                    llvm,
+                    index,
+                    annotations,
+                    llvm_index,
+                    Some(index.get_type_information(TIME_TYPE)?),
+                );
+                exp_gen.generate_literal(interval)?.1.into_int_value()
+            }
+            None => context.i64_type().const_zero(),
+        };
+        let priority = task
+            .priority
+            .as_ref()
+            .map(evaluate_constant_int)
+            .transpose()?
+            .unwrap_or_default();
+
+        let program_names = task
+            .get_programs()
+            .iter()
+            .map(|(instance, _)| generate_string_constant(module, llvm, instance, instance))
+            .collect::<Vec<PointerValue>>();
+        let programs = module.add_global(
+            string_type.array_type(program_names.len() as u32),
+            None,
+            &format!("{}_programs", task_name),
+        );
+        programs.set_initializer(&string_type.const_array(&program_names));
+        programs.set_constant(true);
+        programs.set_linkage(Linkage::Private);
+
+        entries.push(
+            task_type.const_named_struct(&[
+                generate_string_constant(module, llvm, task_name, task_name).into(),
+                interval.into(),
+                llvm.i32_type().const_int(priority as u64, true).into(),
+                llvm.i32_type()
+                    .const_int(program_names.len() as u64, false)
+                    .into(),
+                programs
+                    .as_pointer_value()
+                    .const_cast(string_type.ptr_type(AddressSpace::Generic))
+                    .into(),
+                cycle.as_global_value().as_pointer_value().into(),
+            ]),
+        );
+    }
+
+    let table = module.add_global(
+        task_type.array_type(entries.len() as u32),
+        None,
+        TASK_TABLE_NAME,
+    );
+    table.set_initializer(&task_type.const_array(&entries));
+    table.set_constant(true);
+
+    let count = module.add_global(llvm.i32_type(), None, TASK_COUNT_NAME);
+    count.set_initializer(&llvm.i32_type().const_int(entries.len() as u64, false));
+    count.set_constant(true);
+    Ok(())
+}
+
+/// generates the function that executes one cycle of the given task by calling
+/// its program instances in the order of their declaration. Every program instance gets its own
+/// global state `<resource>.<instance>_instance`. If there are located variables,
+/// the inputs are read before and the outputs are written after the programs
+fn generate_task_cycle<'ink>(
+    module: &Module<'ink>,
+    llvm: &Llvm<'ink>,
+    llvm_index: &LlvmTypedIndex<'ink>,
+    task: &TaskIndexEntry,
+) -> Result<FunctionValue<'ink>, CompileError> {
+    let cycle_type = llvm.context.void_type().fn_type(&[], false);
+    let cycle = module.add_function(&get_task_cycle_name(task.get_name()), cycle_type, None);
+    let builder = &llvm.builder;
+    let block = llvm.context.append_basic_block(cycle, "entry");
+    builder.position_at_end(block);
+
+    if let Some(read_inputs) = module.get_function(READ_INPUTS_NAME) {
+        builder.build_call(read_inputs, &[], "");
+    }
+    for (instance, program) in task.get_programs() {
+        let implementation = llvm_index
+            .find_associated_implementation(program)
+            .ok_or_else(|| cannot_generate_program_call(program, task))?;
+        let state = llvm.create_global_variable(
+            module,
+            &get_pou_instance_variable_name(instance),
+            llvm_index.get_associated_type(program)?,
+            llvm_index.find_associated_initial_value(program),
+        );
+        builder.build_call(implementation, &[state.as_pointer_value().into()], "");
+    }
+    if let Some(write_outputs) = module.get_function(WRITE_OUTPUTS_NAME) {
+        builder.build_call(write_outputs, &[], "");
+    }
+    builder.build_return(None);
+    Ok(cycle)
+}
+
+/// generates a private, null-terminated string constant and returns a pointer to its first character
+fn generate_string_constant<'ink>(
+    module: &Module<'ink>,
+    llvm: &Llvm<'ink>,
+    name: &str,
+    value: &str,
+) -> PointerValue<'ink> {
+    let value = llvm.context.const_string(value.as_bytes(), true);
+    let global = module.add_global(value.get_type(), None, &format!("{}_name", name));
+    global.set_initializer(&value);
+    global.set_constant(true);
+    global.set_linkage(Linkage::Private);
+    global
+        .as_pointer_value()
+        .const_cast(llvm.context.i8_type().ptr_type(AddressSpace::Generic))
+}
+
+fn cannot_generate_program_call(program: &str, task: &TaskIndexEntry) -> CompileError {
+    CompileError::codegen_error(
+        format!(
+            "Cannot call program {} from task {}",
+            program,
+            task.get_name()
+        ),
+        task.source_location.clone(),
+    )
+}
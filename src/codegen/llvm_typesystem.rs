@@ -3,7 +3,8 @@ use inkwell::{
     builder::Builder,
     context::Context,
     types::{FloatType, IntType},
-    values::{BasicValueEnum, IntValue},
+    values::{ArrayValue, BasicValueEnum, IntValue},
+    AddressSpace,
 };
 
 use crate::{
@@ -11,7 +12,7 @@ use crate::{
     ast::SourceRange,
     compile_error::CompileError,
     index::Index,
-    typesystem::{get_bigger_type, DataTypeInformation},
+    typesystem::{get_bigger_type, get_long_date_type, DataTypeInformation},
 };
 
 use super::{generators::llvm::Llvm, llvm_index::LlvmTypedIndex, TypeAndValue};
@@ -28,8 +29,8 @@ pub fn promote_if_needed<'a>(
     let (rtype, rvalue) = rvalue;
 
     //TODO : We need better error handling here
-    let ltype = index.find_effective_type_information(ltype).unwrap();
-    let rtype = index.find_effective_type_information(rtype).unwrap();
+    let ltype = index.find_intrinsic_type_information(ltype).unwrap();
+    let rtype = index.find_intrinsic_type_information(rtype).unwrap();
 
     let ltype_llvm = llvm_index.find_associated_type(ltype.get_name()).unwrap();
     let rtype_llvm = llvm_index.find_associated_type(rtype.get_name()).unwrap();
@@ -39,8 +40,9 @@ pub fn promote_if_needed<'a>(
             (ltype.clone(), *lvalue, *rvalue)
         } else {
             let target_type = get_bigger_type(
-                &get_bigger_type(ltype, rtype),
+                &get_bigger_type(ltype, rtype, index),
                 &index.find_type_information("DINT").unwrap(),
+                index,
             );
 
             let promoted_lvalue =
@@ -150,6 +152,28 @@ fn create_llvm_extend_int_value<'a>(
     }
 }
 
+/// converts a value between a short date type (DATE, TOD, DT) stored in milliseconds and its
+/// long form (LDATE, LTOD, LDT) stored in nanoseconds.
+/// returns None if the given types are not the short and long form of the same date type
+fn convert_date_resolution<'ctx>(
+    llvm: &Llvm<'ctx>,
+    value: IntValue<'ctx>,
+    value_type: &DataTypeInformation,
+    target_type: &DataTypeInformation,
+) -> Option<IntValue<'ctx>> {
+    let nanos_per_milli = value.get_type().const_int(1_000_000, true);
+    if get_long_date_type(value_type.get_name()) == Some(target_type.get_name()) {
+        Some(llvm.builder.build_int_mul(value, nanos_per_milli, ""))
+    } else if get_long_date_type(target_type.get_name()) == Some(value_type.get_name()) {
+        Some(
+            llvm.builder
+                .build_int_signed_div(value, nanos_per_milli, ""),
+        )
+    } else {
+        None
+    }
+}
+
 pub fn cast_if_needed<'ctx>(
     llvm: &Llvm<'ctx>,
     index: &Index,
@@ -160,7 +184,7 @@ pub fn cast_if_needed<'ctx>(
 ) -> Result<BasicValueEnum<'ctx>, CompileError> {
     let builder = &llvm.builder;
     let target_type = index
-        .find_effective_type_information(target_type)
+        .find_intrinsic_type_information(target_type)
         .ok_or_else(|| {
             CompileError::codegen_error(
                 format!("Could not find primitive type for {:?}", target_type),
@@ -168,7 +192,7 @@ pub fn cast_if_needed<'ctx>(
             )
         })?;
     let value_type = index
-        .find_effective_type_information(value_type)
+        .find_intrinsic_type_information(value_type)
         .ok_or_else(|| {
             CompileError::codegen_error(
                 format!("Could not find primitive type for {:?}", value_type),
@@ -183,7 +207,14 @@ pub fn cast_if_needed<'ctx>(
         } => {
             match value_type {
                 DataTypeInformation::Integer { size: rsize, .. } => {
-                    if lsize < rsize {
+                    if let Some(converted) = convert_date_resolution(
+                        llvm,
+                        value.into_int_value(),
+                        value_type,
+                        target_type,
+                    ) {
+                        Ok(converted.into())
+                    } else if lsize < rsize {
                         //Truncate
                         Ok(llvm
                             .builder
@@ -280,7 +311,7 @@ pub fn cast_if_needed<'ctx>(
                 location_context.get_location(),
             )),
         },
-        DataTypeInformation::String { size, .. } => match value_type {
+        DataTypeInformation::String { size, encoding } => match value_type {
             DataTypeInformation::String {
                 size: value_size, ..
             } => {
@@ -292,6 +323,11 @@ pub fn cast_if_needed<'ctx>(
                         let new_value = &string_value[0..(*size - 1) as usize];
                         let (_, value) = llvm.create_llvm_const_vec_string(new_value)?;
                         Ok(value)
+                    } else if value.is_array_value() {
+                        Ok(
+                            truncate_string(llvm, value, *size, encoding.get_bytes_per_char())
+                                .into(),
+                        )
                     } else {
                         Err(CompileError::casting_error(
                             value_type.get_name(),
@@ -299,6 +335,8 @@ pub fn cast_if_needed<'ctx>(
                             location_context.get_location(),
                         ))
                     }
+                } else if size > value_size && value.is_array_value() {
+                    Ok(extend_string(llvm, value, *size, encoding.get_bytes_per_char()).into())
                 } else {
                     Ok(value)
                 }
@@ -313,6 +351,49 @@ pub fn cast_if_needed<'ctx>(
     }
 }
 
+/// truncates the given string value to the given number of characters (including the
+/// terminator), the last character of the truncated string is replaced by the terminator
+fn truncate_string<'ctx>(
+    llvm: &Llvm<'ctx>,
+    value: BasicValueEnum<'ctx>,
+    size: u32,
+    bytes_per_char: u32,
+) -> ArrayValue<'ctx> {
+    let builder = &llvm.builder;
+    let byte_type = llvm.context.i8_type();
+    let target_type = byte_type.array_type(size * bytes_per_char);
+    let buffer = llvm.create_local_variable("", &value.get_type());
+    builder.build_store(buffer, value);
+    let truncated_buffer =
+        builder.build_pointer_cast(buffer, target_type.ptr_type(AddressSpace::Generic), "");
+    let mut truncated = builder.build_load(truncated_buffer, "").into_array_value();
+    for index in (size - 1) * bytes_per_char..size * bytes_per_char {
+        truncated = builder
+            .build_insert_value(truncated, byte_type.const_zero(), index, "")
+            .map(|it| it.into_array_value())
+            .unwrap_or(truncated);
+    }
+    truncated
+}
+
+/// extends the given string value to the given number of characters (including the
+/// terminator), the added characters are zero
+fn extend_string<'ctx>(
+    llvm: &Llvm<'ctx>,
+    value: BasicValueEnum<'ctx>,
+    size: u32,
+    bytes_per_char: u32,
+) -> ArrayValue<'ctx> {
+    let builder = &llvm.builder;
+    let target_type = llvm.context.i8_type().array_type(size * bytes_per_char);
+    let buffer = llvm.create_local_variable("", &target_type.into());
+    builder.build_store(buffer, target_type.const_zero());
+    let value_buffer =
+        builder.build_pointer_cast(buffer, value.get_type().ptr_type(AddressSpace::Generic), "");
+    builder.build_store(value_buffer, value);
+    builder.build_load(buffer, "").into_array_value()
+}
+
 pub fn get_llvm_int_type<'a>(
     context: &'a Context,
     size: u32,
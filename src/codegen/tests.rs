@@ -7,12 +7,14 @@ mod typesystem_test;
 #[macro_export]
 macro_rules! codegen_wihout_unwrap {
     ($code:tt) => {{
-        let lexer = crate::lexer::lex($code);
+        let id_provider = crate::lexer::IdProvider::new();
+        let lexer = crate::lexer::lex_with_ids($code, id_provider.clone());
         let (mut ast, ..) = crate::parser::parse(lexer);
 
         let context = inkwell::context::Context::create();
         crate::ast::pre_process(&mut ast);
         let index = crate::index::visitor::visit(&ast);
+        crate::resolver::lower_property_accesses(&index, &mut ast, id_provider);
         let annotations = crate::resolver::TypeAnnotator::visit_unit(&index, &ast);
         let code_generator = crate::codegen::CodeGen::new(&context, "main");
         code_generator.generate(&ast, &annotations, &index)
@@ -22,18 +24,41 @@ macro_rules! codegen_wihout_unwrap {
 #[macro_export]
 macro_rules! codegen {
     ($code:tt) => {{
-        let lexer = crate::lexer::lex($code);
+        let id_provider = crate::lexer::IdProvider::new();
+        let lexer = crate::lexer::lex_with_ids($code, id_provider.clone());
         let (mut ast, ..) = crate::parser::parse(lexer);
 
         let context = inkwell::context::Context::create();
         crate::ast::pre_process(&mut ast);
         let index = crate::index::visitor::visit(&ast);
+        crate::resolver::lower_property_accesses(&index, &mut ast, id_provider);
         let annotations = crate::resolver::TypeAnnotator::visit_unit(&index, &ast);
         let code_generator = crate::codegen::CodeGen::new(&context, "main");
         code_generator.generate(&ast, &annotations, &index).unwrap()
     }};
 }
 
+/// generates the code into a module using the given data layout, e.g. `e-p:32:32` for 32-bit pointers
+#[macro_export]
+macro_rules! codegen_for_data_layout {
+    ($data_layout:expr, $code:tt) => {{
+        let id_provider = crate::lexer::IdProvider::new();
+        let lexer = crate::lexer::lex_with_ids($code, id_provider.clone());
+        let (mut ast, ..) = crate::parser::parse(lexer);
+
+        let context = inkwell::context::Context::create();
+        crate::ast::pre_process(&mut ast);
+        let index = crate::index::visitor::visit(&ast);
+        crate::resolver::lower_property_accesses(&index, &mut ast, id_provider);
+        let annotations = crate::resolver::TypeAnnotator::visit_unit(&index, &ast);
+        let code_generator = crate::codegen::CodeGen::new(&context, "main");
+        code_generator
+            .module
+            .set_data_layout(&inkwell::targets::TargetData::create($data_layout).get_data_layout());
+        code_generator.generate(&ast, &annotations, &index).unwrap()
+    }};
+}
+
 #[macro_export]
 macro_rules! generate_with_empty_program {
     ($code:tt) => {{
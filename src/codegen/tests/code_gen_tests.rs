@@ -746,6 +746,128 @@ entry:
     assert_eq!(result, expected);
 }
 
+#[test]
+fn long_date_and_time_types_have_nano_seconds_resolution() {
+    let result = codegen!(
+        r#"PROGRAM prg
+VAR
+a : LTIME;
+b : LDATE;
+c : LTOD;
+d : LDT;
+e : DATE;
+f : TIME;
+END_VAR
+a := LT#1d2ns;
+b := LDATE#1970-01-02;
+c := LTIME_OF_DAY#00:00:01.000000001;
+d := LDT#1970-01-01-00:00:01.5;
+b := e;
+e := b;
+f := a;
+b := D#1970-01-02;
+END_PROGRAM
+"#
+    );
+
+    let expected = r#"; ModuleID = 'main'
+source_filename = "main"
+
+%prg_interface = type { i64, i64, i64, i64, i64, i64 }
+
+@prg_instance = global %prg_interface zeroinitializer
+
+define void @prg(%prg_interface* %0) {
+entry:
+  %a = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 0
+  %b = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 1
+  %c = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 2
+  %d = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 3
+  %e = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 4
+  %f = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 5
+  store i64 86400000000002, i64* %a, align 4
+  store i64 86400000000000, i64* %b, align 4
+  store i64 1000000001, i64* %c, align 4
+  store i64 1500000000, i64* %d, align 4
+  %load_e = load i64, i64* %e, align 4
+  %1 = mul i64 %load_e, 1000000
+  store i64 %1, i64* %b, align 4
+  %load_b = load i64, i64* %b, align 4
+  %2 = sdiv i64 %load_b, 1000000
+  store i64 %2, i64* %e, align 4
+  %load_a = load i64, i64* %a, align 4
+  store i64 %load_a, i64* %f, align 4
+  store i64 86400000000000, i64* %b, align 4
+  ret void
+}
+"#;
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn date_and_time_arithmetic_converts_between_resolutions() {
+    let result = codegen!(
+        r#"PROGRAM prg
+VAR
+t : TIME;
+tod : TOD;
+dt : DT;
+r : REAL;
+i : INT;
+END_VAR
+t := dt - dt;
+tod := tod + t;
+t := t * i;
+t := t / r;
+END_PROGRAM
+"#
+    );
+
+    let expected = r#"; ModuleID = 'main'
+source_filename = "main"
+
+%prg_interface = type { i64, i64, i64, float, i16 }
+
+@prg_instance = global %prg_interface zeroinitializer
+
+define void @prg(%prg_interface* %0) {
+entry:
+  %t = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 0
+  %tod = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 1
+  %dt = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 2
+  %r = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 3
+  %i = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 4
+  %load_dt = load i64, i64* %dt, align 4
+  %load_dt1 = load i64, i64* %dt, align 4
+  %tmpVar = sub i64 %load_dt, %load_dt1
+  %1 = mul i64 %tmpVar, 1000000
+  store i64 %1, i64* %t, align 4
+  %load_tod = load i64, i64* %tod, align 4
+  %load_t = load i64, i64* %t, align 4
+  %2 = mul i64 %load_tod, 1000000
+  %tmpVar2 = add i64 %2, %load_t
+  %3 = sdiv i64 %tmpVar2, 1000000
+  store i64 %3, i64* %tod, align 4
+  %load_t3 = load i64, i64* %t, align 4
+  %load_i = load i16, i16* %i, align 2
+  %4 = sext i16 %load_i to i64
+  %tmpVar4 = mul i64 %load_t3, %4
+  store i64 %tmpVar4, i64* %t, align 4
+  %load_t5 = load i64, i64* %t, align 4
+  %load_r = load float, float* %r, align 4
+  %5 = sitofp i64 %load_t5 to double
+  %6 = fpext float %load_r to double
+  %tmpVar6 = fdiv double %5, %6
+  %7 = fptosi double %tmpVar6 to i64
+  store i64 %7, i64* %t, align 4
+  ret void
+}
+"#;
+
+    assert_eq!(result, expected);
+}
+
 #[test]
 fn date_comparisons() {
     let result = codegen!(
@@ -818,6 +940,54 @@ entry:
     assert_eq!(result, expected);
 }
 
+#[test]
+fn program_with_characters_and_string_access() {
+    let result = codegen!(
+        r#"PROGRAM prg
+VAR
+s : STRING;
+ws : WSTRING;
+c : CHAR;
+wc : WCHAR;
+END_VAR
+c := CHAR#'A';
+wc := WCHAR#"Ω";
+c := s[2];
+wc := ws[3];
+END_PROGRAM
+"#
+    );
+
+    let expected = r#"; ModuleID = 'main'
+source_filename = "main"
+
+%prg_interface = type { [81 x i8], [162 x i8], i8, i16 }
+
+@prg_instance = global %prg_interface zeroinitializer
+
+define void @prg(%prg_interface* %0) {
+entry:
+  %s = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 0
+  %ws = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 1
+  %c = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 2
+  %wc = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 3
+  store i8 65, i8* %c, align 1
+  store i16 937, i16* %wc, align 2
+  %1 = bitcast [81 x i8]* %s to i8*
+  %tmpVar = getelementptr inbounds i8, i8* %1, i32 1
+  %load_tmpVar = load i8, i8* %tmpVar, align 1
+  store i8 %load_tmpVar, i8* %c, align 1
+  %2 = bitcast [162 x i8]* %ws to i16*
+  %tmpVar1 = getelementptr inbounds i16, i16* %2, i32 2
+  %load_tmpVar2 = load i16, i16* %tmpVar1, align 2
+  store i16 %load_tmpVar2, i16* %wc, align 2
+  ret void
+}
+"#;
+
+    assert_eq!(result, expected);
+}
+
 #[test]
 fn different_case_references() {
     let result = codegen!(
@@ -3774,6 +3944,196 @@ entry:
     assert_eq!(result, expected);
 }
 
+#[test]
+fn pointer_arithmetic_and_comparisons_generated() {
+    let result = codegen!(
+        "
+        PROGRAM prg
+        VAR
+            p : POINTER TO INT;
+            q : POINTER TO INT;
+            i : INT;
+            b : BOOL;
+        END_VAR
+
+        p := q + 2;
+        p := p - i;
+        b := p >= q;
+
+        END_PROGRAM
+        "
+    );
+
+    let expected = r#"; ModuleID = 'main'
+source_filename = "main"
+
+%prg_interface = type { i16*, i16*, i16, i1 }
+
+@prg_instance = global %prg_interface zeroinitializer
+
+define void @prg(%prg_interface* %0) {
+entry:
+  %p = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 0
+  %q = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 1
+  %i = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 2
+  %b = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 3
+  %load_q = load i16*, i16** %q, align 8
+  %tmpVar = getelementptr inbounds i16, i16* %load_q, i64 2
+  store i16* %tmpVar, i16** %p, align 8
+  %load_p = load i16*, i16** %p, align 8
+  %load_i = load i16, i16* %i, align 2
+  %1 = sext i16 %load_i to i64
+  %2 = sub i64 0, %1
+  %tmpVar1 = getelementptr inbounds i16, i16* %load_p, i64 %2
+  store i16* %tmpVar1, i16** %p, align 8
+  %load_p2 = load i16*, i16** %p, align 8
+  %load_q3 = load i16*, i16** %q, align 8
+  %tmpVar4 = icmp uge i16* %load_p2, %load_q3
+  store i1 %tmpVar4, i1* %b, align 1
+  ret void
+}
+"#;
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn reference_assignments_generated() {
+    let result = codegen!(
+        "
+        PROGRAM prg
+        VAR
+            r : REFERENCE TO INT;
+            x : INT;
+            b : BOOL;
+        END_VAR
+
+        r REF= x;
+        r := 5;
+        b := __ISVALIDREF(r);
+        r REF= NULL;
+
+        END_PROGRAM
+        "
+    );
+
+    let expected = r#"; ModuleID = 'main'
+source_filename = "main"
+
+%prg_interface = type { i16*, i16, i1 }
+
+@prg_instance = global %prg_interface zeroinitializer
+
+define void @prg(%prg_interface* %0) {
+entry:
+  %r = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 0
+  %x = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 1
+  %b = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 2
+  store i16* %x, i16** %r, align 8
+  %deref = load i16*, i16** %r, align 8
+  store i16 5, i16* %deref, align 2
+  %1 = load i16*, i16** %r, align 8
+  %tmpVar = icmp ne i16* %1, null
+  store i1 %tmpVar, i1* %b, align 1
+  store i16* null, i16** %r, align 8
+  ret void
+}
+"#;
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn variable_length_arrays_are_passed_with_their_bounds() {
+    let result = codegen!(
+        "
+        FUNCTION foo : DINT
+        VAR_IN_OUT
+            a : ARRAY[*, *] OF DINT;
+        END_VAR
+        foo := a[1, 2] + UPPER_BOUND(a, 2);
+        END_FUNCTION
+
+        PROGRAM prg
+        VAR
+            m : ARRAY[0..1, 1..3] OF DINT;
+            x : DINT;
+        END_VAR
+        x := foo(m);
+        END_PROGRAM
+        "
+    );
+
+    let expected = r#"; ModuleID = 'main'
+source_filename = "main"
+
+%prg_interface = type { [2 x [3 x i32]], i32 }
+%foo_interface = type { { i32*, [4 x i32] } }
+
+@prg_instance = global %prg_interface zeroinitializer
+
+define i32 @foo(%foo_interface* %0) {
+entry:
+  %a = getelementptr inbounds %foo_interface, %foo_interface* %0, i32 0, i32 0
+  %foo = alloca i32, align 4
+  %1 = getelementptr inbounds { i32*, [4 x i32] }, { i32*, [4 x i32] }* %a, i32 0, i32 1
+  %2 = getelementptr inbounds [4 x i32], [4 x i32]* %1, i32 0, i32 0
+  %3 = load i32, i32* %2, align 4
+  %4 = sub i32 1, %3
+  %5 = getelementptr inbounds [4 x i32], [4 x i32]* %1, i32 0, i32 2
+  %6 = load i32, i32* %5, align 4
+  %7 = sub i32 2, %6
+  %8 = getelementptr inbounds [4 x i32], [4 x i32]* %1, i32 0, i32 3
+  %9 = load i32, i32* %8, align 4
+  %10 = sub i32 %9, %6
+  %11 = add i32 %10, 1
+  %12 = mul i32 %4, %11
+  %13 = add i32 %12, %7
+  %14 = getelementptr inbounds { i32*, [4 x i32] }, { i32*, [4 x i32] }* %a, i32 0, i32 0
+  %15 = load i32*, i32** %14, align 8
+  %tmpVar = getelementptr inbounds i32, i32* %15, i32 %13
+  %load_tmpVar = load i32, i32* %tmpVar, align 4
+  %16 = getelementptr inbounds { i32*, [4 x i32] }, { i32*, [4 x i32] }* %a, i32 0, i32 1
+  %17 = getelementptr inbounds [4 x i32], [4 x i32]* %16, i32 0, i32 3
+  %18 = load i32, i32* %17, align 4
+  %tmpVar1 = add i32 %load_tmpVar, %18
+  store i32 %tmpVar1, i32* %foo, align 4
+  %foo_ret = load i32, i32* %foo, align 4
+  ret i32 %foo_ret
+}
+
+define void @prg(%prg_interface* %0) {
+entry:
+  %m = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 0
+  %x = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 1
+  %foo_instance = alloca %foo_interface, align 8
+  br label %input
+
+input:                                            ; preds = %entry
+  %1 = getelementptr inbounds %foo_interface, %foo_interface* %foo_instance, i32 0, i32 0
+  %2 = bitcast [2 x [3 x i32]]* %m to i32*
+  %3 = getelementptr inbounds { i32*, [4 x i32] }, { i32*, [4 x i32] }* %1, i32 0, i32 0
+  store i32* %2, i32** %3, align 8
+  %4 = getelementptr inbounds { i32*, [4 x i32] }, { i32*, [4 x i32] }* %1, i32 0, i32 1
+  store [4 x i32] [i32 0, i32 1, i32 1, i32 3], [4 x i32]* %4, align 4
+  br label %call
+
+call:                                             ; preds = %input
+  %call1 = call i32 @foo(%foo_interface* %foo_instance)
+  br label %output
+
+output:                                           ; preds = %call
+  br label %continue
+
+continue:                                         ; preds = %output
+  store i32 %call1, i32* %x, align 4
+  ret void
+}
+"#;
+
+    assert_eq!(result, expected);
+}
+
 #[test]
 fn complex_pointers() {
     let result = codegen!(
@@ -4272,6 +4632,458 @@ entry:
     assert_eq!(result, expected);
 }
 
+#[test]
+fn exponentiations_are_generated_as_pow_intrinsics_or_integer_multiplications() {
+    let result = codegen!(
+        "
+        PROGRAM main
+        VAR
+          r : REAL;
+          i : DINT;
+          e : DINT;
+          l : LREAL;
+        END_VAR
+          r := r ** e;
+          i := i ** e;
+          l := EXPT(l, r);
+        END_PROGRAM
+        "
+    );
+
+    let expected = r#"; ModuleID = 'main'
+source_filename = "main"
+
+%main_interface = type { float, i32, i32, double }
+
+@main_instance = global %main_interface zeroinitializer
+
+define void @main(%main_interface* %0) {
+entry:
+  %r = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 0
+  %i = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 1
+  %e = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 2
+  %l = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 3
+  %load_r = load float, float* %r, align 4
+  %load_e = load i32, i32* %e, align 4
+  %tmpVar = call float @llvm.powi.f32(float %load_r, i32 %load_e)
+  store float %tmpVar, float* %r, align 4
+  %load_i = load i32, i32* %i, align 4
+  %load_e1 = load i32, i32* %e, align 4
+  %1 = sext i32 %load_i to i64
+  %2 = sext i32 %load_e1 to i64
+  %3 = icmp slt i64 %2, 0
+  %4 = sub i64 0, %2
+  %5 = select i1 %3, i64 %4, i64 %2
+  %6 = call i64 @__expt_lint(i64 %1, i64 %5)
+  %7 = icmp eq i64 %1, 1
+  %8 = icmp eq i64 %1, -1
+  %9 = or i1 %7, %8
+  %10 = xor i1 %9, true
+  %11 = and i1 %3, %10
+  %12 = select i1 %11, i64 0, i64 %6
+  %13 = trunc i64 %12 to i32
+  store i32 %13, i32* %i, align 4
+  %load_l = load double, double* %l, align 8
+  %load_r2 = load float, float* %r, align 4
+  %14 = fpext float %load_r2 to double
+  %tmpVar3 = call double @llvm.pow.f64(double %load_l, double %14)
+  store double %tmpVar3, double* %l, align 8
+  ret void
+}
+
+; Function Attrs: nounwind readnone speculatable willreturn
+declare float @llvm.powi.f32(float, i32) #0
+
+define internal i64 @__expt_lint(i64 %0, i64 %1) {
+entry:
+  br label %condition
+
+condition:                                        ; preds = %multiply, %entry
+  %result = phi i64 [ 1, %entry ], [ %6, %multiply ]
+  %factor = phi i64 [ %0, %entry ], [ %7, %multiply ]
+  %exponent = phi i64 [ %1, %entry ], [ %8, %multiply ]
+  %2 = icmp eq i64 %exponent, 0
+  br i1 %2, label %exit, label %multiply
+
+multiply:                                         ; preds = %condition
+  %3 = and i64 %exponent, 1
+  %4 = icmp ne i64 %3, 0
+  %5 = mul i64 %result, %factor
+  %6 = select i1 %4, i64 %5, i64 %result
+  %7 = mul i64 %factor, %factor
+  %8 = lshr i64 %exponent, 1
+  br label %condition
+
+exit:                                             ; preds = %condition
+  ret i64 %result
+}
+
+; Function Attrs: nounwind readnone speculatable willreturn
+declare double @llvm.pow.f64(double, double) #0
+
+attributes #0 = { nounwind readnone speculatable willreturn }
+"#;
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn selection_functions_are_generated_as_selects_and_branches() {
+    let result = codegen!(
+        "
+        PROGRAM main
+        VAR
+          i : INT;
+          d : DINT;
+          b : BOOL;
+        END_VAR
+          d := MAX(i, d);
+          d := SEL(b, i, d);
+          d := MUX(i, d, i);
+        END_PROGRAM
+        "
+    );
+
+    let expected = r#"; ModuleID = 'main'
+source_filename = "main"
+
+%main_interface = type { i16, i32, i1 }
+
+@main_instance = global %main_interface zeroinitializer
+
+define void @main(%main_interface* %0) {
+entry:
+  %i = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 0
+  %d = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 1
+  %b = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 2
+  %load_i = load i16, i16* %i, align 2
+  %1 = sext i16 %load_i to i32
+  %load_d = load i32, i32* %d, align 4
+  %tmpVar = icmp slt i32 %1, %load_d
+  %tmpVar1 = select i1 %tmpVar, i32 %load_d, i32 %1
+  store i32 %tmpVar1, i32* %d, align 4
+  %load_b = load i1, i1* %b, align 1
+  %load_i2 = load i16, i16* %i, align 2
+  %2 = sext i16 %load_i2 to i32
+  %load_d3 = load i32, i32* %d, align 4
+  %tmpVar4 = select i1 %load_b, i32 %load_d3, i32 %2
+  store i32 %tmpVar4, i32* %d, align 4
+  %load_i5 = load i16, i16* %i, align 2
+  %3 = sext i16 %load_i5 to i32
+  switch i32 %3, label %mux6 [
+    i32 0, label %mux
+    i32 1, label %mux6
+  ]
+
+mux:                                              ; preds = %entry
+  %load_d7 = load i32, i32* %d, align 4
+  br label %continue
+
+mux6:                                             ; preds = %entry, %entry
+  %load_i8 = load i16, i16* %i, align 2
+  %4 = sext i16 %load_i8 to i32
+  br label %continue
+
+continue:                                         ; preds = %mux6, %mux
+  %tmpVar9 = phi i32 [ %load_d7, %mux ], [ %4, %mux6 ]
+  store i32 %tmpVar9, i32* %d, align 4
+  ret void
+}
+"#;
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn bit_shifts_are_generated_as_shifts_and_funnel_shifts() {
+    let result = codegen!(
+        "
+        PROGRAM main
+        VAR
+          b : BYTE;
+          w : WORD;
+          n : BYTE;
+        END_VAR
+          b := SHL(b, n);
+          w := ROR(w, n);
+        END_PROGRAM
+        "
+    );
+
+    let expected = r#"; ModuleID = 'main'
+source_filename = "main"
+
+%main_interface = type { i8, i16, i8 }
+
+@main_instance = global %main_interface zeroinitializer
+
+define void @main(%main_interface* %0) {
+entry:
+  %b = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 0
+  %w = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 1
+  %n = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 2
+  %load_b = load i8, i8* %b, align 1
+  %load_n = load i8, i8* %n, align 1
+  %tmpVar = shl i8 %load_b, %load_n
+  %1 = icmp ult i8 %load_n, 8
+  %tmpVar1 = select i1 %1, i8 %tmpVar, i8 0
+  store i8 %tmpVar1, i8* %b, align 1
+  %load_w = load i16, i16* %w, align 2
+  %load_n2 = load i8, i8* %n, align 1
+  %2 = zext i8 %load_n2 to i16
+  %tmpVar3 = call i16 @llvm.fshr.i16(i16 %load_w, i16 %load_w, i16 %2)
+  store i16 %tmpVar3, i16* %w, align 2
+  ret void
+}
+
+; Function Attrs: nounwind readnone speculatable willreturn
+declare i16 @llvm.fshr.i16(i16, i16, i16) #0
+
+attributes #0 = { nounwind readnone speculatable willreturn }
+"#;
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn numeric_functions_are_generated_as_intrinsic_calls() {
+    let result = codegen!(
+        "
+        PROGRAM main
+        VAR
+          i : INT;
+          r : REAL;
+          l : LREAL;
+        END_VAR
+          i := ABS(i);
+          r := SQRT(r);
+          l := LN(l);
+        END_PROGRAM
+        "
+    );
+
+    let expected = r#"; ModuleID = 'main'
+source_filename = "main"
+
+%main_interface = type { i16, float, double }
+
+@main_instance = global %main_interface zeroinitializer
+
+define void @main(%main_interface* %0) {
+entry:
+  %i = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 0
+  %r = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 1
+  %l = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 2
+  %load_i = load i16, i16* %i, align 2
+  %1 = icmp slt i16 %load_i, 0
+  %2 = sub i16 0, %load_i
+  %tmpVar = select i1 %1, i16 %2, i16 %load_i
+  store i16 %tmpVar, i16* %i, align 2
+  %load_r = load float, float* %r, align 4
+  %tmpVar1 = call float @llvm.sqrt.f32(float %load_r)
+  store float %tmpVar1, float* %r, align 4
+  %load_l = load double, double* %l, align 8
+  %tmpVar2 = call double @llvm.log.f64(double %load_l)
+  store double %tmpVar2, double* %l, align 8
+  ret void
+}
+
+; Function Attrs: nounwind readnone speculatable willreturn
+declare float @llvm.sqrt.f32(float) #0
+
+; Function Attrs: nounwind readnone speculatable willreturn
+declare double @llvm.log.f64(double) #0
+
+attributes #0 = { nounwind readnone speculatable willreturn }
+"#;
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn type_conversions_are_generated_inline() {
+    let result = codegen!(
+        "
+        PROGRAM main
+        VAR
+          i : INT;
+          r : REAL;
+          b : BOOL;
+          t : TIME;
+        END_VAR
+          i := REAL_TO_INT(r);
+          i := REAL_TRUNC_INT(r);
+          b := INT_TO_BOOL(i);
+          i := BOOL_TO_INT(b);
+          i := TIME_TO_INT(t);
+        END_PROGRAM
+        "
+    );
+
+    let expected = r#"; ModuleID = 'main'
+source_filename = "main"
+
+%main_interface = type { i16, float, i1, i64 }
+
+@main_instance = global %main_interface zeroinitializer
+
+define void @main(%main_interface* %0) {
+entry:
+  %i = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 0
+  %r = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 1
+  %b = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 2
+  %t = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 3
+  %load_r = load float, float* %r, align 4
+  %1 = call float @llvm.round.f32(float %load_r)
+  %2 = fptosi float %1 to i16
+  store i16 %2, i16* %i, align 2
+  %load_r1 = load float, float* %r, align 4
+  %3 = fptosi float %load_r1 to i16
+  store i16 %3, i16* %i, align 2
+  %load_i = load i16, i16* %i, align 2
+  %tmpVar = icmp ne i16 %load_i, 0
+  store i1 %tmpVar, i1* %b, align 1
+  %load_b = load i1, i1* %b, align 1
+  %4 = zext i1 %load_b to i8
+  %5 = zext i8 %4 to i16
+  store i16 %5, i16* %i, align 2
+  %load_t = load i64, i64* %t, align 8
+  %6 = sdiv i64 %load_t, 1000000
+  %7 = trunc i64 %6 to i16
+  store i16 %7, i16* %i, align 2
+  ret void
+}
+
+; Function Attrs: nounwind readnone speculatable willreturn
+declare float @llvm.round.f32(float) #0
+
+attributes #0 = { nounwind readnone speculatable willreturn }
+"#;
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn unions_are_generated_with_the_size_of_their_biggest_member() {
+    let result = codegen!(
+        "
+        TYPE Data: UNION
+          Whole: DINT;
+          Low: INT;
+          Bytes: ARRAY[0..5] OF BYTE;
+        END_UNION
+        END_TYPE
+
+        PROGRAM main
+        VAR
+          d : Data;
+        END_VAR
+          d.Whole := 1;
+          d.Low := 2;
+        END_PROGRAM
+        "
+    );
+
+    let expected = r#"; ModuleID = 'main'
+source_filename = "main"
+
+%main_interface = type { %Data }
+%Data = type { i32, [4 x i8] }
+
+@main_instance = global %main_interface zeroinitializer
+
+define void @main(%main_interface* %0) {
+entry:
+  %d = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 0
+  %Whole = bitcast %Data* %d to i32*
+  store i32 1, i32* %Whole, align 4
+  %Low = bitcast %Data* %d to i16*
+  store i16 2, i16* %Low, align 2
+  ret void
+}
+"#;
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn unions_are_sized_by_the_data_layout_of_the_target() {
+    let source = "
+        TYPE Data: UNION
+          Address: REF_TO BYTE;
+          Bytes: ARRAY[0..4] OF BYTE;
+        END_UNION
+        END_TYPE
+
+        VAR_GLOBAL
+          d : Data;
+        END_VAR
+        ";
+
+    //8-byte pointers hold all bytes
+    let result = codegen_for_data_layout!("e-p:64:64", source);
+    let expected = r#"; ModuleID = 'main'
+source_filename = "main"
+target datalayout = "e-p:64:64"
+
+%Data = type { i8* }
+
+@d = global %Data zeroinitializer
+"#;
+    assert_eq!(result, expected);
+
+    //4-byte pointers need padding for the fifth byte
+    let result = codegen_for_data_layout!("e-p:32:32", source);
+    let expected = r#"; ModuleID = 'main'
+source_filename = "main"
+target datalayout = "e-p:32:32"
+
+%Data = type { i8*, [4 x i8] }
+
+@d = global %Data zeroinitializer
+"#;
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn variable_length_array_descriptors_are_sized_by_the_data_layout_of_the_target() {
+    let source = "
+        TYPE Data: UNION
+          Values: ARRAY[*] OF INT;
+          Bytes: ARRAY[0..15] OF BYTE;
+        END_UNION
+        END_TYPE
+
+        VAR_GLOBAL
+          d : Data;
+        END_VAR
+        ";
+
+    //a pointer and two bounds take 16 bytes with 8-byte pointers
+    let result = codegen_for_data_layout!("e-p:64:64", source);
+    let expected = r#"; ModuleID = 'main'
+source_filename = "main"
+target datalayout = "e-p:64:64"
+
+%Data = type { { i16*, [2 x i32] } }
+
+@d = global %Data zeroinitializer
+"#;
+    assert_eq!(result, expected);
+
+    //and 12 bytes with 4-byte pointers
+    let result = codegen_for_data_layout!("e-p:32:32", source);
+    let expected = r#"; ModuleID = 'main'
+source_filename = "main"
+target datalayout = "e-p:32:32"
+
+%Data = type { { i16*, [2 x i32] }, [4 x i8] }
+
+@d = global %Data zeroinitializer
+"#;
+    assert_eq!(result, expected);
+}
+
 #[test]
 fn enums_are_generated() {
     let result = codegen!(
@@ -4341,6 +5153,52 @@ entry:
     assert_eq!(result, expected);
 }
 
+#[test]
+fn enums_with_values_and_numeric_types_are_generated() {
+    let result = codegen!(
+        "
+      TYPE State: (Idle := 1, Run := 10, Fault) UINT;
+      END_TYPE
+
+      TYPE Mode: (Run, Stop);
+      END_TYPE
+
+      PROGRAM main
+      VAR
+        s : State;
+        m : Mode;
+      END_VAR
+      s := State#Fault;
+      m := Mode#Run;
+      END_PROGRAM
+      "
+    );
+
+    let expected = r#"; ModuleID = 'main'
+source_filename = "main"
+
+%main_interface = type { i16, i32 }
+
+@main_instance = global %main_interface { i16 1, i32 0 }
+@Idle = global i16 1
+@Run = global i16 10
+@Fault = global i16 11
+@Run.1 = global i32 0
+@Stop = global i32 1
+
+define void @main(%main_interface* %0) {
+entry:
+  %s = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 0
+  %m = getelementptr inbounds %main_interface, %main_interface* %0, i32 0, i32 1
+  store i16 11, i16* %s, align 2
+  store i32 0, i32* %m, align 4
+  ret void
+}
+"#;
+
+    assert_eq!(result, expected);
+}
+
 #[test]
 fn inline_structs_are_generated() {
     let result = codegen!(
@@ -5564,3 +6422,55 @@ continue:                                         ; preds = %output
 "#;
     assert_eq!(expected, result);
 }
+
+#[test]
+fn program_instances_of_the_same_program_get_their_own_state() {
+    let result = codegen!(
+        "
+        PROGRAM prg
+        VAR
+            x : DINT;
+        END_VAR
+        END_PROGRAM
+
+        CONFIGURATION config
+            RESOURCE res ON PLC
+                TASK fast(INTERVAL := T#10ms, PRIORITY := 1);
+                PROGRAM a WITH fast : prg;
+                PROGRAM b WITH fast : prg;
+            END_RESOURCE
+        END_CONFIGURATION
+        "
+    );
+
+    let expected = r#"; ModuleID = 'main'
+source_filename = "main"
+
+%prg_interface = type { i32 }
+%__task = type { i8*, i64, i32, i32, i8**, void ()* }
+
+@prg_instance = global %prg_interface zeroinitializer
+@res.a_instance = global %prg_interface zeroinitializer
+@res.b_instance = global %prg_interface zeroinitializer
+@res.a_name = private constant [6 x i8] c"res.a\00"
+@res.b_name = private constant [6 x i8] c"res.b\00"
+@res.fast_programs = private constant [2 x i8*] [i8* getelementptr inbounds ([6 x i8], [6 x i8]* @res.a_name, i32 0, i32 0), i8* getelementptr inbounds ([6 x i8], [6 x i8]* @res.b_name, i32 0, i32 0)]
+@res.fast_name = private constant [9 x i8] c"res.fast\00"
+@__tasks = constant [1 x %__task] [%__task { i8* getelementptr inbounds ([9 x i8], [9 x i8]* @res.fast_name, i32 0, i32 0), i64 10000000, i32 1, i32 2, i8** getelementptr inbounds ([2 x i8*], [2 x i8*]* @res.fast_programs, i32 0, i32 0), void ()* @res.fast_cycle }]
+@__tasks_count = constant i32 1
+
+define void @prg(%prg_interface* %0) {
+entry:
+  %x = getelementptr inbounds %prg_interface, %prg_interface* %0, i32 0, i32 0
+  ret void
+}
+
+define void @res.fast_cycle() {
+entry:
+  call void @prg(%prg_interface* @res.a_instance)
+  call void @prg(%prg_interface* @res.b_instance)
+  ret void
+}
+"#;
+    assert_eq!(result, expected);
+}
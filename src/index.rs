@@ -1,8 +1,13 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
+use std::collections::HashSet;
+
 use indexmap::IndexMap;
 
 use crate::{
-    ast::{AstStatement, Implementation, PouType, SourceRange},
+    ast::{
+        get_property_getter_name, get_property_setter_name, AccessModifier, AstStatement,
+        HardwareAddress, Implementation, PolymorphismMode, PouType, SourceRange,
+    },
     compile_error::CompileError,
     typesystem::*,
 };
@@ -11,6 +16,26 @@ use crate::{
 mod tests;
 pub mod visitor;
 
+/// the name of the member that holds the embedded instance of a POU's super class (`EXTENDS`)
+pub const SUPER_CLASS_MEMBER_NAME: &str = "__SUPER";
+/// the member of an interface-reference that points to the referenced instance
+pub const INTERFACE_INSTANCE_MEMBER_NAME: &str = "__instance";
+/// the member of an interface-reference that points to the instance's vtable
+pub const INTERFACE_VTABLE_MEMBER_NAME: &str = "__vtable";
+/// the member of the root of a class hierarchy with overridden methods that points to the
+/// instance's method table
+pub const METHOD_TABLE_MEMBER_NAME: &str = "__method_table";
+/// the implicit pointer to the current instance inside a method or function block
+pub const THIS_POINTER_NAME: &str = "THIS";
+/// the implicit pointer to the instance of the super class inside a method or function block
+pub const SUPER_POINTER_NAME: &str = "SUPER";
+
+/// returns the name of the pointer-type used for the implicit `THIS` and `SUPER` pointers
+/// to an instance of the given class
+pub fn get_instance_pointer_type_name(class_name: &str) -> String {
+    format!("__POINTER_TO_{}", class_name)
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct VariableIndexEntry {
     name: String,
@@ -51,6 +76,10 @@ impl VariableIndexEntry {
     pub fn is_local(&self) -> bool {
         self.information.variable_type == VariableType::Local
     }
+
+    pub fn get_variable_type(&self) -> VariableType {
+        self.information.variable_type
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -92,6 +121,7 @@ pub enum ImplementationType {
     FunctionBlock,
     Action,
     Class,
+    Interface,
     Method,
 }
 
@@ -101,6 +131,8 @@ pub struct ImplementationIndexEntry {
     type_name: String,
     associated_class: Option<String>,
     implementation_type: ImplementationType,
+    /// the access modifier of a method
+    access: Option<AccessModifier>,
 }
 
 impl ImplementationIndexEntry {
@@ -116,6 +148,9 @@ impl ImplementationIndexEntry {
     pub fn get_implementation_type(&self) -> &ImplementationType {
         &self.implementation_type
     }
+    pub fn get_access(&self) -> Option<&AccessModifier> {
+        self.access.as_ref()
+    }
 }
 
 impl From<&Implementation> for ImplementationIndexEntry {
@@ -126,10 +161,70 @@ impl From<&Implementation> for ImplementationIndexEntry {
             type_name: implementation.type_name.clone(),
             associated_class: pou_type.get_optional_owner_class(),
             implementation_type: pou_type.into(),
+            access: implementation.access.clone(),
+        }
+    }
+}
+
+/// a task of a resource with the program instances it executes
+#[derive(Debug, PartialEq, Clone)]
+pub struct TaskIndexEntry {
+    /// the task's name qualified with its resource (e.g. `res.fast`)
+    name: String,
+    /// the time between two cycles of the task (`INTERVAL`)
+    pub interval: Option<AstStatement>,
+    pub priority: Option<AstStatement>,
+    /// the executed program instances as (qualified instance name, program name)
+    programs: Vec<(String, String)>,
+    pub source_location: SourceRange,
+}
+
+impl TaskIndexEntry {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_programs(&self) -> &[(String, String)] {
+        &self.programs
+    }
+}
+
+/// a variable that is located at an address of a process image (e.g. `x AT %IX0.3 : BOOL`)
+#[derive(Debug, PartialEq, Clone)]
+pub struct LocatedVariableEntry {
+    /// the POU that declares the variable or None for a global variable
+    container_name: Option<String>,
+    variable_name: String,
+    pub address: HardwareAddress,
+}
+
+impl LocatedVariableEntry {
+    pub fn get_container_name(&self) -> Option<&str> {
+        self.container_name.as_deref()
+    }
+
+    pub fn get_variable_name(&self) -> &str {
+        &self.variable_name
+    }
+
+    pub fn get_qualified_name(&self) -> String {
+        match &self.container_name {
+            Some(container) => format!("{}.{}", container, self.variable_name),
+            None => self.variable_name.clone(),
         }
     }
 }
 
+/// returns the name of the given task qualified with its resource (e.g. `res.fast`)
+pub fn get_task_name(resource: &str, task: &str) -> String {
+    format!("{}.{}", resource, task)
+}
+
+/// returns the name of the given program instance qualified with its resource (e.g. `res.inst`)
+pub fn get_program_instance_name(resource: &str, instance: &str) -> String {
+    format!("{}.{}", resource, instance)
+}
+
 impl From<&PouType> for ImplementationType {
     fn from(it: &PouType) -> Self {
         match it {
@@ -138,6 +233,7 @@ impl From<&PouType> for ImplementationType {
             PouType::FunctionBlock => ImplementationType::FunctionBlock,
             PouType::Action => ImplementationType::Action,
             PouType::Class => ImplementationType::Class,
+            PouType::Interface => ImplementationType::Interface,
             PouType::Method { .. } => ImplementationType::Method,
         }
     }
@@ -168,6 +264,21 @@ pub struct Index {
     /// all implementations
     implementations: IndexMap<String, ImplementationIndexEntry>,
 
+    /// the names of all interfaces
+    interfaces: IndexMap<String, String>,
+
+    /// the interfaces implemented by a POU, grouped by the POU's name
+    implemented_interfaces: IndexMap<String, Vec<String>>,
+
+    /// all POUs and methods declared `ABSTRACT` or `FINAL`
+    polymorphism_modes: IndexMap<String, PolymorphismMode>,
+
+    /// all tasks of all resources with their qualified names <resource>.<task>
+    tasks: IndexMap<String, TaskIndexEntry>,
+
+    /// all variables located at an address of a process image by their qualified names
+    located_variables: IndexMap<String, LocatedVariableEntry>,
+
     void_type: DataType,
 }
 
@@ -180,6 +291,11 @@ impl Index {
             member_variables: IndexMap::new(),
             types: IndexMap::new(),
             implementations: IndexMap::new(),
+            interfaces: IndexMap::new(),
+            implemented_interfaces: IndexMap::new(),
+            polymorphism_modes: IndexMap::new(),
+            tasks: IndexMap::new(),
+            located_variables: IndexMap::new(),
             void_type: DataType {
                 name: VOID_TYPE.into(),
                 initial_value: None,
@@ -203,6 +319,14 @@ impl Index {
         self.member_variables.extend(other.member_variables);
         self.types.extend(other.types);
         self.implementations.extend(other.implementations);
+        self.interfaces.extend(other.interfaces);
+        self.implemented_interfaces
+            .extend(other.implemented_interfaces);
+        self.polymorphism_modes.extend(other.polymorphism_modes);
+        self.tasks.extend(other.tasks);
+        self.located_variables.extend(other.located_variables);
+        //the imported classes may extend hierarchies of the current index
+        self.register_method_tables();
     }
 
     pub fn get_void_type(&self) -> &DataType {
@@ -215,12 +339,64 @@ impl Index {
             .or_else(|| self.enum_global_variables.get(&name.to_lowercase()))
     }
 
+    /// returns the member `variable_name` of the given POU or struct. If the POU extends
+    /// another POU, members inherited from its super classes are also considered
     pub fn find_member(&self, pou_name: &str, variable_name: &str) -> Option<&VariableIndexEntry> {
+        self.get_class_hierarchy(pou_name)
+            .into_iter()
+            .find_map(|it| self.find_local_member(it, variable_name))
+    }
+
+    /// returns the member `variable_name` declared by the given POU or struct itself,
+    /// inherited members are not considered
+    pub fn find_local_member(
+        &self,
+        pou_name: &str,
+        variable_name: &str,
+    ) -> Option<&VariableIndexEntry> {
         self.member_variables
             .get(&pou_name.to_lowercase())
             .and_then(|map| map.get(&variable_name.to_lowercase()))
     }
 
+    /// returns the name of the POU the given POU extends or None if it has no super class
+    pub fn find_super_class_name(&self, pou_name: &str) -> Option<&str> {
+        self.find_local_member(pou_name, SUPER_CLASS_MEMBER_NAME)
+            .map(VariableIndexEntry::get_type_name)
+    }
+
+    /// returns the given POU followed by all of its super classes, the direct super class first
+    ///
+    /// the walk stops at the first class that was already visited, so a cyclic hierarchy
+    /// yields every class only once
+    pub fn get_class_hierarchy<'a>(&'a self, pou_name: &'a str) -> Vec<&'a str> {
+        let mut hierarchy = vec![pou_name];
+        let mut current = pou_name;
+        while let Some(super_class) = self.find_super_class_name(current) {
+            if hierarchy
+                .iter()
+                .any(|it| it.eq_ignore_ascii_case(super_class))
+            {
+                break;
+            }
+            hierarchy.push(super_class);
+            current = super_class;
+        }
+        hierarchy
+    }
+
+    /// returns the root of the given class' hierarchy if the hierarchy has a method table.
+    /// Only hierarchies in which at least one method is overridden have a method table
+    pub fn find_method_table_owner<'a>(&'a self, class_name: &'a str) -> Option<&'a str> {
+        self.get_class_hierarchy(class_name)
+            .last()
+            .copied()
+            .filter(|root| {
+                self.find_local_member(root, METHOD_TABLE_MEMBER_NAME)
+                    .is_some()
+            })
+    }
+
     /// returns the index entry of the enum-element `element_name` of the enum-type `enum_name`
     /// or None if the requested Enum-Type or -Element does not exist
     pub fn find_enum_element(
@@ -353,6 +529,20 @@ impl Index {
         }
     }
 
+    /// Retrieves the "Intrinsic" type-information behind this datatype
+    /// The intrinsic type is the effective type of the datatype, an enum is represented by its numeric type
+    pub fn find_intrinsic_type_information<'ret>(
+        &'ret self,
+        data_type: &'ret DataTypeInformation,
+    ) -> Option<&'ret DataTypeInformation> {
+        match self.find_effective_type_information(data_type)? {
+            DataTypeInformation::Enum { numeric_type, .. } => self
+                .find_type(numeric_type)
+                .and_then(|it| self.find_intrinsic_type_information(it.get_type_information())),
+            effective_type => Some(effective_type),
+        }
+    }
+
     pub fn find_return_variable(&self, pou_name: &str) -> Option<&VariableIndexEntry> {
         let members = self.member_variables.get(&pou_name.to_lowercase()); //.ok_or_else(||CompileError::unknown_type(pou_name, 0..0))?;
         if let Some(members) = members {
@@ -394,6 +584,11 @@ impl Index {
         &self.types
     }
 
+    /// Returns the names of all interfaces
+    pub fn get_interfaces(&self) -> &IndexMap<String, String> {
+        &self.interfaces
+    }
+
     pub fn get_globals(&self) -> &IndexMap<String, VariableIndexEntry> {
         &self.global_variables
     }
@@ -412,6 +607,7 @@ impl Index {
         type_name: &str,
         associated_class_name: Option<&String>,
         impl_type: ImplementationType,
+        access: Option<AccessModifier>,
     ) {
         self.implementations.insert(
             call_name.to_lowercase(),
@@ -420,6 +616,7 @@ impl Index {
                 type_name: type_name.into(),
                 associated_class: associated_class_name.map(|str| str.into()),
                 implementation_type: impl_type,
+                access,
             },
         );
     }
@@ -428,6 +625,254 @@ impl Index {
         self.implementations.get(&call_name.to_lowercase())
     }
 
+    /// returns the method (or action) `method_name` of the given class. If the class does not
+    /// declare it itself, its super classes are searched
+    pub fn find_method(
+        &self,
+        class_name: &str,
+        method_name: &str,
+    ) -> Option<&ImplementationIndexEntry> {
+        self.get_class_hierarchy(class_name)
+            .into_iter()
+            .find_map(|it| self.find_implementation(&format!("{}.{}", it, method_name)))
+    }
+
+    /// returns the type of the property `property_name` of the given class (or one of its super
+    /// classes) or None if there is no getter or setter for such a property
+    pub fn find_property_type(&self, class_name: &str, property_name: &str) -> Option<&DataType> {
+        self.find_method(class_name, &get_property_getter_name(property_name))
+            .and_then(|getter| self.find_return_type(getter.get_call_name()))
+            .or_else(|| {
+                self.find_method(class_name, &get_property_setter_name(property_name))
+                    .and_then(|setter| self.find_member(setter.get_call_name(), property_name))
+                    .and_then(|value| self.find_type(value.get_type_name()))
+            })
+    }
+
+    /// returns the declarations of all methods of the given class' method table.
+    ///
+    /// only methods that are overridden somewhere in the hierarchy are part of the method
+    /// table, all other methods are called directly. Every method is represented by its
+    /// declaration in the top-most class of the hierarchy. The methods of the root class come
+    /// first, followed by the methods added by each derived class, so a method keeps its slot
+    /// in all classes of the hierarchy.
+    /// Returns an empty list if the class' hierarchy has no method table
+    pub fn get_virtual_methods(&self, class_name: &str) -> Vec<&ImplementationIndexEntry> {
+        let root = match self.find_method_table_owner(class_name) {
+            Some(root) => root,
+            None => return vec![],
+        };
+        let overridden_methods = self.get_overridden_method_names(root);
+        let mut methods: Vec<&ImplementationIndexEntry> = vec![];
+        for class in self.get_class_hierarchy(class_name).into_iter().rev() {
+            let declared_methods = self.implementations.values().filter(|it| {
+                it.implementation_type == ImplementationType::Method
+                    && it
+                        .get_associated_class_name()
+                        .map(|owner| owner.eq_ignore_ascii_case(class))
+                        .unwrap_or(false)
+                    && overridden_methods.contains(&get_method_name(it).to_lowercase())
+            });
+            for method in declared_methods {
+                if !methods
+                    .iter()
+                    .any(|it| get_method_name(it).eq_ignore_ascii_case(get_method_name(method)))
+                {
+                    methods.push(method);
+                }
+            }
+        }
+        methods
+    }
+
+    /// returns the lowercase names of all methods that are overridden by a class of the
+    /// hierarchy below the given root class
+    fn get_overridden_method_names(&self, root: &str) -> HashSet<String> {
+        self.implementations
+            .values()
+            .filter(|it| it.implementation_type == ImplementationType::Method)
+            .filter_map(|method| {
+                let hierarchy =
+                    self.get_class_hierarchy(method.get_associated_class_name()?.as_str());
+                let method_name = get_method_name(method);
+                let is_overriding = hierarchy.last()?.eq_ignore_ascii_case(root)
+                    && hierarchy[1..].iter().any(|ancestor| {
+                        self.find_implementation(&format!("{}.{}", ancestor, method_name))
+                            .is_some()
+                    });
+                Some(method_name.to_lowercase()).filter(|_| is_overriding)
+            })
+            .collect()
+    }
+
+    /// returns the declaration of the method `method_name` in the given class' method table
+    /// or None if calls to the method are not dispatched through a method table
+    pub fn find_virtual_method(
+        &self,
+        class_name: &str,
+        method_name: &str,
+    ) -> Option<&ImplementationIndexEntry> {
+        self.get_virtual_methods(class_name)
+            .into_iter()
+            .find(|it| get_method_name(it).eq_ignore_ascii_case(method_name))
+    }
+
+    /// returns the class or function block whose instance the given implementation works on:
+    /// the owner of a method or the function block itself
+    pub fn find_instance_class(&self, pou_name: &str) -> Option<&str> {
+        let implementation = self.find_implementation(pou_name)?;
+        match implementation.get_implementation_type() {
+            ImplementationType::Method => implementation
+                .get_associated_class_name()
+                .map(String::as_str),
+            ImplementationType::FunctionBlock => Some(implementation.get_type_name()),
+            _ => None,
+        }
+    }
+
+    /// returns the pointer-type of the implicit `THIS` or `SUPER` pointer inside the given
+    /// implementation or None if `name` does not refer to such a pointer
+    pub fn find_instance_pointer_type(&self, pou_name: &str, name: &str) -> Option<&DataType> {
+        let class_name = self.find_instance_class(pou_name)?;
+        let class_name = if name.eq_ignore_ascii_case(THIS_POINTER_NAME) {
+            class_name
+        } else if name.eq_ignore_ascii_case(SUPER_POINTER_NAME) {
+            self.find_super_class_name(class_name)?
+        } else {
+            return None;
+        };
+        self.find_type(&get_instance_pointer_type_name(class_name))
+    }
+
+    /// registers the given variable as located at the given address
+    ///
+    /// - `container_name` the POU that declares the variable or None for a global variable
+    pub fn register_located_variable(
+        &mut self,
+        container_name: Option<&str>,
+        variable_name: &str,
+        address: HardwareAddress,
+    ) {
+        let entry = LocatedVariableEntry {
+            container_name: container_name.map(|it| it.into()),
+            variable_name: variable_name.into(),
+            address,
+        };
+        self.located_variables
+            .insert(entry.get_qualified_name().to_lowercase(), entry);
+    }
+
+    pub fn get_located_variables(&self) -> &IndexMap<String, LocatedVariableEntry> {
+        &self.located_variables
+    }
+
+    pub fn register_task(
+        &mut self,
+        task_name: &str,
+        interval: Option<AstStatement>,
+        priority: Option<AstStatement>,
+        source_location: SourceRange,
+    ) {
+        self.tasks.insert(
+            task_name.to_lowercase(),
+            TaskIndexEntry {
+                name: task_name.into(),
+                interval,
+                priority,
+                programs: vec![],
+                source_location,
+            },
+        );
+    }
+
+    /// registers the given program instance to be executed by the given task
+    pub fn register_program_instance(&mut self, task_name: &str, instance: &str, program: &str) {
+        if let Some(task) = self.tasks.get_mut(&task_name.to_lowercase()) {
+            task.programs.push((instance.into(), program.into()));
+        }
+    }
+
+    pub fn find_task(&self, task_name: &str) -> Option<&TaskIndexEntry> {
+        self.tasks.get(&task_name.to_lowercase())
+    }
+
+    pub fn get_tasks(&self) -> &IndexMap<String, TaskIndexEntry> {
+        &self.tasks
+    }
+
+    /// registers the given POU or method as `ABSTRACT` or `FINAL`
+    pub fn register_polymorphism_mode(&mut self, pou_name: &str, mode: PolymorphismMode) {
+        self.polymorphism_modes
+            .insert(pou_name.to_lowercase(), mode);
+    }
+
+    /// returns true if the given POU or method was declared `ABSTRACT`
+    pub fn is_abstract(&self, pou_name: &str) -> bool {
+        self.polymorphism_modes.get(&pou_name.to_lowercase()) == Some(&PolymorphismMode::Abstract)
+    }
+
+    /// returns true if the given POU or method was declared `FINAL`
+    pub fn is_final(&self, pou_name: &str) -> bool {
+        self.polymorphism_modes.get(&pou_name.to_lowercase()) == Some(&PolymorphismMode::Final)
+    }
+
+    pub fn register_interface(&mut self, interface_name: &str) {
+        self.interfaces
+            .insert(interface_name.to_lowercase(), interface_name.into());
+    }
+
+    /// returns true if the given name refers to an interface
+    pub fn is_interface(&self, name: &str) -> bool {
+        self.interfaces.contains_key(&name.to_lowercase())
+    }
+
+    /// returns the methods declared by the given interface in the order of their declaration
+    pub fn find_interface_methods(&self, interface_name: &str) -> Vec<&ImplementationIndexEntry> {
+        if !self.is_interface(interface_name) {
+            return vec![];
+        }
+        self.implementations
+            .values()
+            .filter(|it| {
+                it.get_associated_class_name()
+                    .map(|class| class.eq_ignore_ascii_case(interface_name))
+                    .unwrap_or(false)
+            })
+            .collect()
+    }
+
+    /// registers the interfaces the given POU implements (`IMPLEMENTS`)
+    pub fn register_implemented_interfaces(&mut self, pou_name: &str, interfaces: &[String]) {
+        self.implemented_interfaces
+            .insert(pou_name.to_lowercase(), interfaces.to_vec());
+    }
+
+    /// returns the interfaces the given POU declares to implement, without the ones
+    /// inherited from its super classes
+    pub fn find_local_implemented_interfaces(&self, pou_name: &str) -> &[String] {
+        self.implemented_interfaces
+            .get(&pou_name.to_lowercase())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// returns all interfaces the given POU implements, including the ones implemented
+    /// by its super classes
+    pub fn find_implemented_interfaces(&self, pou_name: &str) -> Vec<&str> {
+        let mut interfaces: Vec<&str> = vec![];
+        for class in self.get_class_hierarchy(pou_name) {
+            for interface in self.find_local_implemented_interfaces(class) {
+                if !interfaces
+                    .iter()
+                    .any(|it| it.eq_ignore_ascii_case(interface))
+                {
+                    interfaces.push(interface);
+                }
+            }
+        }
+        interfaces
+    }
+
     /// registers a member-variable of a container to be accessed in a qualified name.
     /// e.g. "POU.member", "StructName.member", etc.
     ///
@@ -542,6 +987,80 @@ impl Index {
             .insert(association_name.to_lowercase(), entry);
     }
 
+    /// registers the untyped pointer-type used for references to instances and vtables
+    pub fn register_void_pointer_type(&mut self) {
+        self.register_type(
+            VOID_POINTER_TYPE,
+            None,
+            DataTypeInformation::Pointer {
+                name: VOID_POINTER_TYPE.into(),
+                inner_type_name: BYTE_TYPE.into(),
+                auto_deref: false,
+                is_ref_to: false,
+            },
+        );
+    }
+
+    /// appends a method table member to the root of every class hierarchy in which a derived
+    /// class or function block overrides a method. Calls to the overridden methods of such a
+    /// hierarchy are dispatched through the method table of the called instance. Hierarchies
+    /// without overridden methods keep the memory layout of their declared members.
+    pub fn register_method_tables(&mut self) {
+        let mut roots: Vec<String> = vec![];
+        for container in self.member_variables.keys() {
+            if self.find_super_class_name(container).is_none() {
+                continue;
+            }
+            if let Some(root) = self.get_class_hierarchy(container).last() {
+                if self
+                    .find_local_member(root, METHOD_TABLE_MEMBER_NAME)
+                    .is_none()
+                    && !roots.iter().any(|it| it.eq_ignore_ascii_case(root))
+                    && !self.get_overridden_method_names(root).is_empty()
+                {
+                    roots.push(root.to_string());
+                }
+            }
+        }
+
+        for root in roots {
+            //the super class may not be part of this index (yet)
+            let root_name = match self.find_type(&root) {
+                Some(DataType {
+                    name,
+                    information: DataTypeInformation::Struct { .. },
+                    ..
+                }) => name.clone(),
+                _ => continue,
+            };
+            self.register_void_pointer_type();
+            let location = self.find_local_members(&root_name).len() as u32;
+            let source_location = self
+                .find_local_members(&root_name)
+                .last()
+                .map(|it| it.source_location.clone())
+                .unwrap_or_else(SourceRange::undefined);
+            self.register_member_variable(
+                &MemberInfo {
+                    container_name: &root_name,
+                    variable_name: METHOD_TABLE_MEMBER_NAME,
+                    variable_linkage: VariableType::Local,
+                    variable_type_name: VOID_POINTER_TYPE,
+                },
+                None,
+                source_location,
+                location,
+            );
+            if let Some(DataTypeInformation::Struct { member_names, .. }) = self
+                .types
+                .get_mut(&root_name.to_lowercase())
+                .map(|it| &mut it.information)
+            {
+                member_names.push(METHOD_TABLE_MEMBER_NAME.into());
+            }
+        }
+    }
+
     pub fn print_global_variables(&self) {
         println!("{:?}", self.global_variables);
     }
@@ -574,6 +1093,15 @@ impl Index {
     }
 }
 
+/// returns the name of the given method without its class' name
+fn get_method_name(method: &ImplementationIndexEntry) -> &str {
+    method
+        .get_call_name()
+        .rsplit('.')
+        .next()
+        .unwrap_or_default()
+}
+
 impl Default for Index {
     fn default() -> Self {
         Self::new()
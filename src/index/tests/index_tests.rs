@@ -3,7 +3,14 @@ use pretty_assertions::assert_eq;
 
 use crate::lexer;
 use crate::parser;
-use crate::{ast::*, index::VariableType, typesystem::DataTypeInformation};
+use crate::{
+    ast::*,
+    index::{
+        ImplementationType, VariableType, INTERFACE_INSTANCE_MEMBER_NAME,
+        INTERFACE_VTABLE_MEMBER_NAME, METHOD_TABLE_MEMBER_NAME, SUPER_CLASS_MEMBER_NAME,
+    },
+    typesystem::DataTypeInformation,
+};
 
 macro_rules! index {
     ($code:tt) => {{
@@ -287,6 +294,44 @@ fn function_with_typed_varargs_param_marked() {
     );
 }
 
+#[test]
+fn variable_length_array_parameters_are_indexed_without_pointers() {
+    let index = index!(
+        r#"
+        FUNCTION myFunc : INT
+        VAR_INPUT
+            x : ARRAY[*] OF INT;
+        END_VAR
+        VAR_IN_OUT
+            y : ARRAY[*, *] OF REAL;
+        END_VAR
+        END_FUNCTION
+        "#
+    );
+    let x = index.find_member("myFunc", "x").unwrap();
+    assert_eq!("__myFunc_x", x.get_type_name());
+    assert_eq!(
+        &DataTypeInformation::VariableLengthArray {
+            name: "__myFunc_x".into(),
+            inner_type_name: "INT".into(),
+            dimensions: 1,
+        },
+        index.get_type_information_or_void(x.get_type_name())
+    );
+
+    //the fat pointer is passed instead of a pointer to the array
+    let y = index.find_member("myFunc", "y").unwrap();
+    assert_eq!("__myFunc_y", y.get_type_name());
+    assert_eq!(
+        &DataTypeInformation::VariableLengthArray {
+            name: "__myFunc_y".into(),
+            inner_type_name: "REAL".into(),
+            dimensions: 2,
+        },
+        index.get_type_information_or_void(y.get_type_name())
+    );
+}
+
 #[test]
 fn pous_are_indexed() {
     let index = index!(
@@ -686,7 +731,9 @@ fn pre_processing_generates_inline_enums_global() {
     assert_eq!(
         &DataType::EnumType {
             name: Some("__global_inline_enum".to_string()),
-            elements: ["a".to_string(), "b".to_string(), "c".to_string()].to_vec()
+            numeric_type: "DINT".to_string(),
+            elements: ["a".to_string(), "b".to_string(), "c".to_string()].to_vec(),
+            values: vec![None, None, None],
         },
         new_enum_type
     );
@@ -749,6 +796,7 @@ fn pre_processing_generates_inline_structs_global() {
                 },
                 location: (54..55).into(),
                 initializer: None,
+                address: None,
             }]
         },
         new_struct_type
@@ -786,7 +834,9 @@ fn pre_processing_generates_inline_enums() {
     assert_eq!(
         &DataType::EnumType {
             name: Some("__foo_inline_enum".to_string()),
-            elements: ["a".to_string(), "b".to_string(), "c".to_string()].to_vec()
+            numeric_type: "DINT".to_string(),
+            elements: ["a".to_string(), "b".to_string(), "c".to_string()].to_vec(),
+            values: vec![None, None, None],
         },
         new_enum_type
     );
@@ -838,6 +888,7 @@ fn pre_processing_generates_inline_structs() {
                 },
                 location: (67..68).into(),
                 initializer: None,
+                address: None,
             }]
         },
         new_struct_type
@@ -880,6 +931,8 @@ fn pre_processing_generates_inline_pointers() {
                 referenced_type: "INT".to_string(),
                 location: SourceRange::undefined(),
             }),
+            is_ref_to: true,
+            auto_deref: false,
         },
         location: SourceRange::undefined(),
         initializer: None,
@@ -923,6 +976,8 @@ fn pre_processing_generates_inline_pointer_to_pointer() {
                 referenced_type: "INT".to_string(),
                 location: SourceRange::undefined(),
             }),
+            is_ref_to: true,
+            auto_deref: false,
         },
         location: SourceRange::undefined(),
         initializer: None,
@@ -938,6 +993,8 @@ fn pre_processing_generates_inline_pointer_to_pointer() {
                 referenced_type: "__foo_inline_pointer_".to_string(),
                 location: SourceRange::undefined(),
             }),
+            is_ref_to: true,
+            auto_deref: false,
         },
         location: SourceRange::undefined(),
         initializer: None,
@@ -1133,6 +1190,7 @@ fn pre_processing_nested_array_in_struct() {
                 },
                 location: SourceRange::undefined(),
                 initializer: None,
+                address: None,
             }],
         },
         initializer: None,
@@ -1319,3 +1377,601 @@ fn sub_range_boundaries_are_registered_at_the_index() {
 
     assert_eq!(format!("{:?}", expected), format!("{:?}", my_int));
 }
+
+#[test]
+fn super_class_is_indexed_as_first_member() {
+    // GIVEN a function block extending another function block
+    let index = index!(
+        "
+        FUNCTION_BLOCK Base
+            VAR_INPUT a : INT; END_VAR
+        END_FUNCTION_BLOCK
+
+        FUNCTION_BLOCK Derived EXTENDS Base
+            VAR_INPUT b : INT; END_VAR
+        END_FUNCTION_BLOCK
+        "
+    );
+
+    // THEN the super class is embedded as the first member of the derived block
+    let super_member = index
+        .find_local_member("Derived", SUPER_CLASS_MEMBER_NAME)
+        .unwrap();
+    assert_eq!("Base", super_member.get_type_name());
+    assert_eq!(0, super_member.get_location_in_parent());
+    assert_eq!(
+        1,
+        index
+            .find_member("Derived", "b")
+            .unwrap()
+            .get_location_in_parent()
+    );
+    assert_eq!(Some("Base"), index.find_super_class_name("Derived"));
+    assert_eq!(None, index.find_super_class_name("Base"));
+
+    if let DataTypeInformation::Struct { member_names, .. } =
+        index.find_type_information("Derived").unwrap()
+    {
+        assert_eq!(
+            vec![SUPER_CLASS_MEMBER_NAME.to_string(), "b".to_string()],
+            member_names
+        );
+    } else {
+        panic!("Derived should be a struct");
+    }
+}
+
+#[test]
+fn inherited_members_can_be_found() {
+    // GIVEN a three level class hierarchy
+    let index = index!(
+        "
+        CLASS Base
+            VAR a : INT; END_VAR
+        END_CLASS
+
+        CLASS Middle EXTENDS Base
+            VAR b : INT; END_VAR
+        END_CLASS
+
+        CLASS Leaf EXTENDS Middle
+            VAR c : INT; END_VAR
+        END_CLASS
+        "
+    );
+
+    // THEN members of all super classes can be found via the leaf class
+    assert_eq!(
+        "Base.a",
+        index.find_member("Leaf", "a").unwrap().get_qualified_name()
+    );
+    assert_eq!(
+        "Middle.b",
+        index.find_member("Leaf", "b").unwrap().get_qualified_name()
+    );
+    assert_eq!(
+        "Leaf.c",
+        index.find_member("Leaf", "c").unwrap().get_qualified_name()
+    );
+    // AND local lookups only consider the class itself
+    assert!(index.find_local_member("Leaf", "a").is_none());
+    // AND super classes do not see members of derived classes
+    assert!(index.find_member("Base", "c").is_none());
+    assert_eq!(
+        vec!["Leaf", "Middle", "Base"],
+        index.get_class_hierarchy("Leaf")
+    );
+    // AND qualified variable lookups follow the hierarchy
+    assert_eq!(
+        "Base.a",
+        index
+            .find_variable(Some("Leaf"), &["a".to_string()])
+            .unwrap()
+            .get_qualified_name()
+    );
+}
+
+#[test]
+fn inherited_methods_can_be_found() {
+    // GIVEN a class that inherits a method and overrides another one
+    let index = index!(
+        "
+        CLASS Base
+            METHOD foo END_METHOD
+            METHOD bar END_METHOD
+        END_CLASS
+
+        CLASS Derived EXTENDS Base
+            METHOD OVERRIDE bar END_METHOD
+        END_CLASS
+        "
+    );
+
+    // THEN the inherited method is found on the super class
+    assert_eq!(
+        "Base.foo",
+        index.find_method("Derived", "foo").unwrap().get_call_name()
+    );
+    // AND the overridden method is found on the derived class
+    assert_eq!(
+        "Derived.bar",
+        index.find_method("Derived", "bar").unwrap().get_call_name()
+    );
+    assert!(index.find_method("Base", "baz").is_none());
+}
+
+#[test]
+fn cyclic_class_hierarchy_does_not_loop_forever() {
+    let index = index!(
+        "
+        CLASS A EXTENDS B
+        END_CLASS
+
+        CLASS B EXTENDS A
+            VAR x : INT; END_VAR
+        END_CLASS
+        "
+    );
+
+    assert_eq!(vec!["A", "B"], index.get_class_hierarchy("A"));
+    assert_eq!(
+        "B.x",
+        index.find_member("A", "x").unwrap().get_qualified_name()
+    );
+    assert!(index.find_member("A", "y").is_none());
+}
+
+#[test]
+fn interfaces_are_indexed_as_references_with_vtable() {
+    // GIVEN an interface with two methods
+    let index = index!(
+        "
+        INTERFACE IDriver
+            METHOD read : INT END_METHOD
+            METHOD reset END_METHOD
+        END_INTERFACE
+        "
+    );
+
+    // THEN the interface is known and references an instance and a vtable
+    assert!(index.is_interface("idriver"));
+    assert!(!index.is_interface("read"));
+    if let DataTypeInformation::Struct { member_names, .. } =
+        index.find_type_information("IDriver").unwrap()
+    {
+        assert_eq!(
+            vec![
+                INTERFACE_INSTANCE_MEMBER_NAME.to_string(),
+                INTERFACE_VTABLE_MEMBER_NAME.to_string()
+            ],
+            member_names
+        );
+    } else {
+        panic!("IDriver should be a struct");
+    }
+
+    // AND its methods are listed in the order of their declaration
+    let methods: Vec<&str> = index
+        .find_interface_methods("IDriver")
+        .iter()
+        .map(|it| it.get_call_name())
+        .collect();
+    assert_eq!(vec!["IDriver.read", "IDriver.reset"], methods);
+}
+
+#[test]
+fn implemented_interfaces_are_inherited() {
+    // GIVEN a class implementing two interfaces and a class extending it
+    let index = index!(
+        "
+        INTERFACE I1 END_INTERFACE
+        INTERFACE I2 END_INTERFACE
+        INTERFACE I3 END_INTERFACE
+
+        CLASS Base IMPLEMENTS I1, I2 END_CLASS
+        CLASS Derived EXTENDS Base IMPLEMENTS I3, I1 END_CLASS
+        "
+    );
+
+    // THEN the derived class implements all interfaces exactly once
+    assert_eq!(
+        vec!["I1".to_string(), "I2".to_string()],
+        index.find_local_implemented_interfaces("Base")
+    );
+    assert_eq!(vec!["I1", "I2"], index.find_implemented_interfaces("Base"));
+    assert_eq!(
+        vec!["I3", "I1", "I2"],
+        index.find_implemented_interfaces("Derived")
+    );
+    assert!(index.find_implemented_interfaces("I1").is_empty());
+}
+
+#[test]
+fn extended_class_hierarchies_get_a_method_table() {
+    // GIVEN a class hierarchy and a class that is not extended
+    let index = index!(
+        "
+        CLASS Base
+            VAR x : INT; END_VAR
+            METHOD foo END_METHOD
+            METHOD bar END_METHOD
+        END_CLASS
+
+        CLASS Derived EXTENDS Base
+            METHOD baz END_METHOD
+            METHOD foo END_METHOD
+        END_CLASS
+
+        CLASS Single
+            METHOD foo END_METHOD
+        END_CLASS
+
+        CLASS Plain
+            METHOD foo END_METHOD
+        END_CLASS
+
+        CLASS PlainDerived EXTENDS Plain
+            METHOD bar END_METHOD
+        END_CLASS
+        "
+    );
+
+    // THEN the root of the hierarchy holds the method table after its own members
+    let method_table = index
+        .find_local_member("Base", METHOD_TABLE_MEMBER_NAME)
+        .unwrap();
+    assert_eq!(1, method_table.get_location_in_parent());
+    assert_eq!("__VOID_POINTER", method_table.get_type_name());
+    assert_eq!(
+        Some(method_table),
+        index.find_member("Derived", METHOD_TABLE_MEMBER_NAME)
+    );
+    assert_eq!(Some("Base"), index.find_method_table_owner("Derived"));
+    assert_eq!(None, index.find_method_table_owner("Single"));
+    assert_eq!(
+        None,
+        index.find_local_member("Single", METHOD_TABLE_MEMBER_NAME)
+    );
+
+    // AND a hierarchy without overridden methods keeps its layout
+    assert_eq!(None, index.find_method_table_owner("PlainDerived"));
+    assert_eq!(
+        None,
+        index.find_local_member("Plain", METHOD_TABLE_MEMBER_NAME)
+    );
+
+    // AND the overridden methods keep their slots in all classes of the hierarchy
+    let slots = |class| {
+        index
+            .get_virtual_methods(class)
+            .iter()
+            .map(|it| it.get_call_name())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(vec!["Base.foo"], slots("Base"));
+    assert_eq!(vec!["Base.foo"], slots("Derived"));
+    assert!(slots("Single").is_empty());
+    assert!(slots("PlainDerived").is_empty());
+    assert_eq!(
+        "Base.foo",
+        index
+            .find_virtual_method("Derived", "FOO")
+            .unwrap()
+            .get_call_name()
+    );
+    assert!(index.find_virtual_method("Derived", "bar").is_none());
+}
+
+#[test]
+fn method_tables_are_added_when_importing_a_derived_class() {
+    // GIVEN a base class and a derived class overriding its method in different units
+    let (mut base_unit, _) = parser::parse(lex(
+        "CLASS Base VAR x : INT; END_VAR METHOD foo END_METHOD END_CLASS",
+    ));
+    let (mut derived_unit, _) = parser::parse(lex(
+        "CLASS Derived EXTENDS Base METHOD foo END_METHOD END_CLASS",
+    ));
+    pre_process(&mut base_unit);
+    pre_process(&mut derived_unit);
+
+    // WHEN both units are imported into one index
+    let mut index = crate::index::visitor::visit(&base_unit);
+    assert_eq!(
+        None,
+        index.find_local_member("Base", METHOD_TABLE_MEMBER_NAME)
+    );
+    index.import(crate::index::visitor::visit(&derived_unit));
+
+    // THEN the base class gets a method table
+    assert_eq!(
+        1,
+        index
+            .find_local_member("Base", METHOD_TABLE_MEMBER_NAME)
+            .unwrap()
+            .get_location_in_parent()
+    );
+    if let DataTypeInformation::Struct { member_names, .. } =
+        index.find_type_information("Base").unwrap()
+    {
+        assert_eq!(
+            vec!["x".to_string(), METHOD_TABLE_MEMBER_NAME.into()],
+            member_names
+        );
+    } else {
+        unreachable!()
+    }
+}
+
+#[test]
+fn properties_are_indexed_as_getter_and_setter_methods() {
+    // GIVEN a class with a read-write property and a derived class
+    let index = index!(
+        "
+        CLASS Base
+            VAR _speed : INT; END_VAR
+            PROPERTY speed : INT
+                GET speed := _speed; END_GET
+                SET _speed := speed; END_SET
+            END_PROPERTY
+            PROPERTY enabled : BOOL
+                SET END_SET
+            END_PROPERTY
+        END_CLASS
+
+        CLASS Derived EXTENDS Base
+        END_CLASS
+        "
+    );
+
+    // THEN the accessors are methods of the class
+    let getter = index.find_implementation("Base.__get_speed").unwrap();
+    assert!(ImplementationType::Method == *getter.get_implementation_type());
+    assert_eq!(
+        Some(&"Base".to_string()),
+        getter.get_associated_class_name()
+    );
+    let setter = index.find_implementation("Base.__set_speed").unwrap();
+    assert!(ImplementationType::Method == *setter.get_implementation_type());
+
+    // AND the getter returns the value via the property's name
+    let return_value = index.find_return_variable("Base.__get_speed").unwrap();
+    assert_eq!("speed", return_value.get_name());
+    assert_eq!("INT", return_value.get_type_name());
+
+    // AND the property's type is found via its getter or its setter, also for derived classes
+    assert_eq!(
+        Some("INT"),
+        index
+            .find_property_type("Derived", "speed")
+            .map(|it| it.get_name())
+    );
+    assert_eq!(
+        Some("BOOL"),
+        index
+            .find_property_type("Base", "enabled")
+            .map(|it| it.get_name())
+    );
+    assert_eq!(None, index.find_property_type("Base", "_speed"));
+}
+
+#[test]
+fn function_block_methods_are_indexed_with_their_access_modifier() {
+    let index = index!(
+        "
+        FUNCTION_BLOCK MyFb
+            METHOD PRIVATE foo END_METHOD
+            METHOD PUBLIC bar : INT END_METHOD
+            METHOD baz END_METHOD
+        END_FUNCTION_BLOCK
+        "
+    );
+
+    let access = |method| {
+        index
+            .find_method("MyFb", method)
+            .and_then(|it| it.get_access())
+    };
+    assert_eq!(Some(&AccessModifier::Private), access("foo"));
+    assert_eq!(Some(&AccessModifier::Public), access("bar"));
+    //methods without an access modifier are public
+    assert_eq!(Some(&AccessModifier::Public), access("baz"));
+    assert_eq!(
+        Some(&"MyFb".to_string()),
+        index
+            .find_method("MyFb", "bar")
+            .and_then(|it| it.get_associated_class_name())
+    );
+    //the function block itself has no access modifier
+    assert_eq!(
+        None,
+        index
+            .find_implementation("MyFb")
+            .and_then(|it| it.get_access())
+    );
+}
+
+#[test]
+fn tasks_are_indexed_with_their_program_instances() {
+    let index = index!(
+        "
+        PROGRAM prg END_PROGRAM
+        CONFIGURATION config
+            RESOURCE res
+                TASK fast(INTERVAL := T#10ms, PRIORITY := 1);
+                TASK slow(INTERVAL := T#1s);
+                PROGRAM a WITH fast : prg;
+                PROGRAM b WITH FAST : prg;
+                PROGRAM c : prg;
+            END_RESOURCE
+        END_CONFIGURATION
+        "
+    );
+
+    let tasks = index
+        .get_tasks()
+        .values()
+        .map(|it| it.get_name())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["res.fast", "res.slow"], tasks);
+
+    let fast = index.find_task("RES.FAST").unwrap();
+    assert!(fast.interval.is_some());
+    assert!(fast.priority.is_some());
+    assert_eq!(
+        fast.get_programs(),
+        &[
+            ("res.a".to_string(), "prg".to_string()),
+            ("res.b".to_string(), "prg".to_string())
+        ]
+    );
+    assert!(index
+        .find_task("res.slow")
+        .unwrap()
+        .get_programs()
+        .is_empty());
+}
+
+#[test]
+fn external_variables_are_no_members_of_the_pou() {
+    let index = index!(
+        "
+        VAR_GLOBAL g : INT; END_VAR
+        PROGRAM prg
+            VAR_EXTERNAL g : INT; END_VAR
+            VAR x : INT; END_VAR
+        END_PROGRAM
+        "
+    );
+
+    let members = index
+        .find_local_members("prg")
+        .iter()
+        .map(|it| (it.get_name(), it.get_location_in_parent()))
+        .collect::<Vec<_>>();
+    assert_eq!(vec![("x", 0)], members);
+    assert_eq!(
+        "g",
+        index
+            .find_variable(Some("prg"), &["g".into()])
+            .unwrap()
+            .get_qualified_name()
+    );
+}
+
+#[test]
+fn located_variables_are_indexed_with_their_address() {
+    let index = index!(
+        "
+        VAR_GLOBAL
+            a AT %IX0.3 : BOOL;
+            b : INT;
+        END_VAR
+        PROGRAM prg
+            VAR
+                c AT %QW2 : INT;
+            END_VAR
+        END_PROGRAM
+        "
+    );
+
+    let located = index
+        .get_located_variables()
+        .values()
+        .map(|it| {
+            (
+                it.get_container_name(),
+                it.get_variable_name(),
+                it.address.to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            (None, "a", "%IX0.3".to_string()),
+            (Some("prg"), "c", "%QW2".to_string())
+        ],
+        located
+    );
+    //located variables of a program are members of its instance
+    assert_eq!(
+        "prg.c",
+        index.find_member("prg", "c").unwrap().get_qualified_name()
+    );
+}
+
+#[test]
+fn enum_elements_are_indexed_with_their_values() {
+    let index = index!(
+        "
+        TYPE State : (Idle := 0, Run := 10, Stopping, Fault := -1, Unknown) UINT; END_TYPE
+        TYPE Mode : (Run, Stop); END_TYPE
+        "
+    );
+
+    let values = ["Idle", "Run", "Stopping", "Fault", "Unknown"]
+        .iter()
+        .map(|it| {
+            index
+                .find_enum_element("State", it)
+                .and_then(|it| it.initial_value.as_ref())
+                .map(|it| evaluate_constant_int(it).unwrap())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(vec![Some(0), Some(10), Some(11), Some(-1), Some(0)], values);
+    assert_eq!(
+        &DataTypeInformation::Enum {
+            name: "State".into(),
+            numeric_type: "UINT".into(),
+            elements: vec![
+                "Idle".into(),
+                "Run".into(),
+                "Stopping".into(),
+                "Fault".into(),
+                "Unknown".into()
+            ],
+        },
+        index.get_type_information("State").as_ref().unwrap()
+    );
+
+    //both enums keep their own element Run
+    assert_eq!(
+        "State.Run",
+        index
+            .find_enum_element("State", "Run")
+            .unwrap()
+            .get_qualified_name()
+    );
+    assert_eq!(
+        "Mode.Run",
+        index
+            .find_enum_element("Mode", "Run")
+            .unwrap()
+            .get_qualified_name()
+    );
+}
+
+#[test]
+fn union_members_are_indexed() {
+    let index = index!(
+        "
+        TYPE Data : UNION
+            Whole : DINT;
+            Bytes : ARRAY[0..3] OF BYTE;
+        END_UNION END_TYPE
+        "
+    );
+
+    assert_eq!(
+        &DataTypeInformation::Union {
+            name: "Data".into(),
+            member_names: vec!["Whole".into(), "Bytes".into()],
+        },
+        index.get_type_information("Data").as_ref().unwrap()
+    );
+    let whole = index.find_member("Data", "Whole").unwrap();
+    assert_eq!("DINT", whole.get_type_name());
+    //inline types of union members are generated like the ones of struct members
+    let bytes = index.find_member("Data", "Bytes").unwrap();
+    assert_eq!("__Data_Bytes", bytes.get_type_name());
+    assert!(index.find_type("__Data_Bytes").is_some());
+}
@@ -1,11 +1,14 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
 use super::VariableType;
 use crate::ast::{
-    self, evaluate_constant_int, get_array_dimensions, AstStatement, CompilationUnit, DataType,
-    DataTypeDeclaration, Implementation, Pou, PouType, SourceRange, UserTypeDeclaration, Variable,
-    VariableBlock, VariableBlockType,
+    self, evaluate_constant_int, get_array_dimensions, AstStatement, CompilationUnit,
+    Configuration, DataType, DataTypeDeclaration, Implementation, PolymorphismMode, Pou, PouType,
+    SourceRange, UserTypeDeclaration, Variable, VariableBlock, VariableBlockType,
+};
+use crate::index::{
+    get_instance_pointer_type_name, get_program_instance_name, get_task_name, Index, MemberInfo,
+    INTERFACE_INSTANCE_MEMBER_NAME, INTERFACE_VTABLE_MEMBER_NAME, SUPER_CLASS_MEMBER_NAME,
 };
-use crate::index::{Index, MemberInfo};
 use crate::typesystem::*;
 
 pub fn visit(unit: &CompilationUnit) -> Index {
@@ -38,9 +41,38 @@ pub fn visit(unit: &CompilationUnit) -> Index {
         visit_implementation(&mut index, implementation);
     }
 
+    for configuration in &unit.configurations {
+        visit_configuration(&mut index, configuration);
+    }
+
+    index.register_method_tables();
     index
 }
 
+/// registers the tasks of the configuration's resources and the program instances they execute
+fn visit_configuration(index: &mut Index, configuration: &Configuration) {
+    for resource in &configuration.resources {
+        for task in &resource.tasks {
+            index.register_task(
+                &get_task_name(&resource.name, &task.name),
+                task.interval.clone(),
+                task.priority.clone(),
+                task.location.clone(),
+            );
+        }
+        for program in &resource.programs {
+            //programs without a task are not executed cyclically
+            if let Some(task) = &program.task {
+                index.register_program_instance(
+                    &get_task_name(&resource.name, task),
+                    &get_program_instance_name(&resource.name, &program.name),
+                    &program.program,
+                );
+            }
+        }
+    }
+}
+
 pub fn visit_pou(index: &mut Index, pou: &Pou) {
     let interface_name = format!("{}_interface", &pou.name);
 
@@ -56,12 +88,64 @@ pub fn visit_pou(index: &mut Index, pou: &Pou) {
         );
     }
 
+    if let Some(mode) = pou.poly_mode.filter(|it| it != &PolymorphismMode::None) {
+        index.register_polymorphism_mode(&pou.name, mode);
+    }
+    if matches!(pou.pou_type, PouType::Class | PouType::FunctionBlock) {
+        //the type of the implicit THIS and SUPER pointers
+        register_instance_pointer_type(index, &pou.name);
+    }
+
     let mut member_names = vec![];
 
     //register the pou's member variables
     let mut count = 0;
+    if let Some(super_class) = &pou.super_class {
+        //the super class' instance is embedded as the first member
+        member_names.push(SUPER_CLASS_MEMBER_NAME.into());
+        index.register_member_variable(
+            &MemberInfo {
+                container_name: &pou.name,
+                variable_name: SUPER_CLASS_MEMBER_NAME,
+                variable_linkage: VariableType::Local,
+                variable_type_name: super_class,
+            },
+            None,
+            pou.location.clone(),
+            count,
+        );
+        count += 1;
+    }
+    if !pou.interfaces.is_empty() {
+        index.register_implemented_interfaces(&pou.name, &pou.interfaces);
+    }
+    if pou.pou_type == PouType::Interface {
+        index.register_interface(&pou.name);
+        //a variable of an interface-type references an instance and its vtable
+        index.register_void_pointer_type();
+        for member in &[INTERFACE_INSTANCE_MEMBER_NAME, INTERFACE_VTABLE_MEMBER_NAME] {
+            member_names.push(member.to_string());
+            index.register_member_variable(
+                &MemberInfo {
+                    container_name: &pou.name,
+                    variable_name: member,
+                    variable_linkage: VariableType::Local,
+                    variable_type_name: VOID_POINTER_TYPE,
+                },
+                None,
+                pou.location.clone(),
+                count,
+            );
+            count += 1;
+        }
+    }
     let mut varargs = None;
-    for block in &pou.variable_blocks {
+    //external variables refer to the global variable, they are no members of the pou
+    for block in pou
+        .variable_blocks
+        .iter()
+        .filter(|it| it.variable_block_type != VariableBlockType::External)
+    {
         let block_type = get_variable_type_from_block(block);
         for var in &block.variables {
             if let DataTypeDeclaration::DataTypeDefinition {
@@ -79,13 +163,18 @@ pub fn visit_pou(index: &mut Index, pou: &Pou) {
                 continue;
             }
             member_names.push(var.name.clone());
+            if let Some(address) = &var.address {
+                index.register_located_variable(Some(&pou.name), &var.name, address.clone());
+            }
 
-            let type_name = if block_type == VariableType::InOut {
-                //register a pointer type for the var_in_out
-                register_inout_pointer_type_for(index, var)
-            } else {
-                var.data_type.get_name().unwrap().to_string()
-            };
+            let type_name =
+                if block_type == VariableType::InOut && !is_variable_length_array(index, var) {
+                    //register a pointer type for the var_in_out, variable-length arrays are passed as a
+                    //fat pointer already
+                    register_inout_pointer_type_for(index, var)
+                } else {
+                    var.data_type.get_name().unwrap().to_string()
+                };
 
             index.register_member_variable(
                 &MemberInfo {
@@ -137,6 +226,7 @@ fn visit_implementation(index: &mut Index, implementation: &Implementation) {
         &implementation.type_name,
         pou_type.get_optional_owner_class().as_ref(),
         pou_type.into(),
+        implementation.access.clone(),
     );
     //if we are registing an action, also register a datatype for it
     if pou_type == &PouType::Action {
@@ -151,6 +241,20 @@ fn visit_implementation(index: &mut Index, implementation: &Implementation) {
     }
 }
 
+fn register_instance_pointer_type(index: &mut Index, class_name: &str) {
+    let type_name = get_instance_pointer_type_name(class_name);
+    index.register_type(
+        &type_name,
+        None,
+        DataTypeInformation::Pointer {
+            name: type_name.clone(),
+            inner_type_name: class_name.into(),
+            auto_deref: false,
+            is_ref_to: false,
+        },
+    );
+}
+
 fn register_inout_pointer_type_for(index: &mut Index, var: &Variable) -> String {
     let inner_type_name = var.data_type.get_name().unwrap().to_string();
     //get unique name
@@ -164,14 +268,26 @@ fn register_inout_pointer_type_for(index: &mut Index, var: &Variable) -> String
             name: type_name.clone(),
             inner_type_name,
             auto_deref: true,
+            is_ref_to: false,
         },
     );
 
     type_name
 }
 
+/// returns true if the variable is declared as `ARRAY[*] OF ...`
+fn is_variable_length_array(index: &Index, var: &Variable) -> bool {
+    var.data_type
+        .get_name()
+        .and_then(|type_name| index.find_type_information(type_name))
+        .map_or(false, |it| it.is_variable_length_array())
+}
+
 fn visit_global_var_block(index: &mut Index, block: &VariableBlock) {
     for var in &block.variables {
+        if let Some(address) = &var.address {
+            index.register_located_variable(None, &var.name, address.clone());
+        }
         index.register_global_variable(
             &var.name,
             var.data_type.get_name().unwrap(),
@@ -189,6 +305,7 @@ fn get_variable_type_from_block(block: &VariableBlock) -> VariableType {
         VariableBlockType::Output => VariableType::Output,
         VariableBlockType::Global => VariableType::Global,
         VariableBlockType::InOut => VariableType::InOut,
+        VariableBlockType::External => VariableType::Global,
     }
 }
 
@@ -196,16 +313,23 @@ fn visit_data_type(index: &mut Index, type_declatation: &UserTypeDeclaration) {
     let data_type = &type_declatation.data_type;
     //names should not be empty
     match data_type {
-        DataType::StructType { name, variables } => {
+        DataType::StructType { name, variables } | DataType::UnionType { name, variables } => {
             let struct_name = name.as_ref().unwrap();
 
             let member_names: Vec<String> =
                 variables.iter().map(|it| it.name.to_string()).collect();
 
-            let information = DataTypeInformation::Struct {
-                name: name.clone().unwrap(),
-                member_names,
-                varargs: None,
+            let information = if let DataType::UnionType { .. } = data_type {
+                DataTypeInformation::Union {
+                    name: struct_name.clone(),
+                    member_names,
+                }
+            } else {
+                DataTypeInformation::Struct {
+                    name: struct_name.clone(),
+                    member_names,
+                    varargs: None,
+                }
             };
             index.register_type(
                 name.as_ref().unwrap(),
@@ -239,10 +363,16 @@ fn visit_data_type(index: &mut Index, type_declatation: &UserTypeDeclaration) {
             }
         }
 
-        DataType::EnumType { name, elements } => {
+        DataType::EnumType {
+            name,
+            numeric_type,
+            elements,
+            values,
+        } => {
             let enum_name = name.as_ref().unwrap();
             let information = DataTypeInformation::Enum {
                 name: enum_name.clone(),
+                numeric_type: numeric_type.clone(),
                 elements: elements.clone(),
             };
             index.register_type(
@@ -250,18 +380,36 @@ fn visit_data_type(index: &mut Index, type_declatation: &UserTypeDeclaration) {
                 type_declatation.initializer.clone(),
                 information,
             );
-            elements.iter().enumerate().for_each(|(i, v)| {
-                index.register_enum_element(
-                    v,
-                    enum_name.as_str(),
-                    Some(ast::AstStatement::LiteralInteger {
-                        value: i as i128,
+            //an element without an explicit value is one greater than its predecessor
+            let mut next_value = 0;
+            for (element, value) in elements.iter().zip(values.iter()) {
+                let initial_value = match value {
+                    Some(value) => match evaluate_enum_value(value) {
+                        Some(explicit_value) => {
+                            next_value = explicit_value;
+                            ast::AstStatement::LiteralInteger {
+                                value: explicit_value,
+                                location: value.get_location(),
+                                id: value.get_id(),
+                            }
+                        }
+                        //the validation reports values that are no integer-literals
+                        None => value.clone(),
+                    },
+                    None => ast::AstStatement::LiteralInteger {
+                        value: next_value,
                         location: SourceRange::undefined(),
                         id: 0,
-                    }),
+                    },
+                };
+                next_value += 1;
+                index.register_enum_element(
+                    element,
+                    enum_name.as_str(),
+                    Some(initial_value),
                     SourceRange::undefined(),
                 )
-            }); //TODO : Enum locations
+            } //TODO : Enum locations
         }
 
         DataType::SubRangeType {
@@ -306,16 +454,34 @@ fn visit_data_type(index: &mut Index, type_declatation: &UserTypeDeclaration) {
                 information,
             )
         }
+        DataType::VariableLengthArrayType {
+            name,
+            dimensions,
+            referenced_type,
+        } => {
+            let information = DataTypeInformation::VariableLengthArray {
+                name: name.as_ref().unwrap().clone(),
+                inner_type_name: referenced_type.get_name().unwrap().to_string(),
+                dimensions: *dimensions,
+            };
+            index.register_type(
+                name.as_ref().unwrap(),
+                type_declatation.initializer.clone(),
+                information,
+            )
+        }
         DataType::PointerType {
             name,
             referenced_type,
-            ..
+            is_ref_to,
+            auto_deref,
         } => {
             let inner_type_name = referenced_type.get_name().unwrap();
             let information = DataTypeInformation::Pointer {
                 name: name.as_ref().unwrap().clone(),
                 inner_type_name: inner_type_name.into(),
-                auto_deref: false,
+                auto_deref: *auto_deref,
+                is_ref_to: *is_ref_to,
             };
             index.register_type(
                 name.as_ref().unwrap(),
@@ -351,3 +517,17 @@ fn visit_data_type(index: &mut Index, type_declatation: &UserTypeDeclaration) {
         DataType::VarArgs { .. } => {} //Varargs are not indexed
     };
 }
+
+/// returns the value of an explicitly assigned enum-element value (e.g. `10` or `-1`)
+/// or None if the value is no integer-literal
+fn evaluate_enum_value(value: &ast::AstStatement) -> Option<i128> {
+    match value {
+        ast::AstStatement::LiteralInteger { value, .. } => Some(*value),
+        ast::AstStatement::UnaryExpression {
+            operator: ast::Operator::Minus,
+            value,
+            ..
+        } => evaluate_enum_value(value).map(|it| -it),
+        _ => None,
+    }
+}
@@ -120,6 +120,7 @@ impl<'a> ParseSession<'a> {
             | Token::KeywordVarOutput
             | Token::KeywordVarGlobal
             | Token::KeywordVarInOut
+            | Token::KeywordVarExternal
             | Token::KeywordRef
             | Token::KeywordVarTemp
             | Token::KeywordNonRetain
@@ -130,13 +131,21 @@ impl<'a> ParseSession<'a> {
             | Token::KeywordFunctionBlock
             | Token::KeywordEndFunctionBlock
             | Token::KeywordEndStruct
+            | Token::KeywordEndUnion
             | Token::KeywordEndAction
             | Token::KeywordEndActions
             | Token::KeywordEndIf
             | Token::KeywordEndFor
             | Token::KeywordEndRepeat
             | Token::KeywordEndMethod
-            | Token::KeywordEndClass => {
+            | Token::KeywordEndProperty
+            | Token::KeywordEndGet
+            | Token::KeywordEndSet
+            | Token::KeywordEndNamespace
+            | Token::KeywordEndConfiguration
+            | Token::KeywordEndResource
+            | Token::KeywordEndClass
+            | Token::KeywordEndInterface => {
                 if !self.slice().to_string().contains('_') {
                     self.accept_diagnostic(Diagnostic::ImprovementSuggestion {
                         message: format!(
@@ -224,6 +224,24 @@ fn operator_test() {
     assert_eq!(lexer.token, OperatorGreaterOrEqual);
 }
 
+#[test]
+fn exponent_operator_test() {
+    let mut lexer = lex("a**b * c ** d");
+    assert_eq!(lexer.token, Identifier);
+    lexer.advance();
+    assert_eq!(lexer.token, OperatorExponent);
+    lexer.advance();
+    assert_eq!(lexer.token, Identifier);
+    lexer.advance();
+    assert_eq!(lexer.token, OperatorMultiplication);
+    lexer.advance();
+    assert_eq!(lexer.token, Identifier);
+    lexer.advance();
+    assert_eq!(lexer.token, OperatorExponent);
+    lexer.advance();
+    assert_eq!(lexer.token, Identifier);
+}
+
 #[test]
 fn boolean_expression_test() {
     let mut lexer = lex("AND XOR OR NOT");
@@ -339,6 +357,29 @@ fn time_literals_test() {
     }
 }
 
+#[test]
+fn long_date_and_time_literals_test() {
+    let mut lexer = lex(r#"
+    LTIME#12d LT#4s12ns ldate#1984-10-01 LD#1-1-1
+    LTIME_OF_DAY#20:15:12 LTOD#1:1:1.123456789
+    LDATE_AND_TIME#1984-10-01-20:15:12 LDT#1-1-1-1:1:1.123
+    "#);
+    let expected = vec![
+        LiteralTime,
+        LiteralTime,
+        LiteralDate,
+        LiteralDate,
+        LiteralTimeOfDay,
+        LiteralTimeOfDay,
+        LiteralDateAndTime,
+        LiteralDateAndTime,
+    ];
+    for token in expected {
+        assert_eq!(lexer.token, token, "unexpected token for {}", lexer.slice());
+        lexer.advance();
+    }
+}
+
 #[test]
 fn a_full_program_generates_correct_token_sequence() {
     let mut lexer = lex(r"
@@ -488,6 +529,21 @@ fn struct_enum_datatype() {
     assert_eq!(lexer.token, KeywordEndType);
 }
 
+#[test]
+fn union_datatype() {
+    let mut lexer = lex(r"TYPE UNION END_UNION ENDUNION END_TYPE");
+
+    assert_eq!(lexer.token, KeywordType);
+    lexer.advance();
+    assert_eq!(lexer.token, KeywordUnion);
+    lexer.advance();
+    assert_eq!(lexer.token, KeywordEndUnion);
+    lexer.advance();
+    assert_eq!(lexer.token, KeywordEndUnion);
+    lexer.advance();
+    assert_eq!(lexer.token, KeywordEndType);
+}
+
 #[test]
 fn array_parsing() {
     let mut lexer = lex(r"ARRAY OF x[5]");
@@ -605,6 +661,53 @@ fn pointers_and_references_keyword() {
     lexer.advance();
 }
 
+#[test]
+fn reference_keywords() {
+    let mut lexer = lex(r#"
+    REFERENCE TO x
+    reference to x
+    r REF= x
+    r ref= x
+    r REF = x
+    "#);
+
+    for _ in 0..2 {
+        assert_eq!(lexer.token, KeywordReference);
+        lexer.advance();
+        assert_eq!(lexer.token, KeywordTo);
+        lexer.advance();
+        assert_eq!(lexer.slice(), "x");
+        lexer.advance();
+    }
+    for _ in 0..2 {
+        assert_eq!(lexer.slice(), "r");
+        lexer.advance();
+        assert_eq!(lexer.token, KeywordReferenceAssignment);
+        lexer.advance();
+        assert_eq!(lexer.slice(), "x");
+        lexer.advance();
+    }
+    //REF= must not contain whitespaces
+    assert_eq!(lexer.slice(), "r");
+    lexer.advance();
+    assert_eq!(lexer.token, Identifier);
+    lexer.advance();
+    assert_eq!(lexer.token, OperatorEqual);
+}
+
+#[test]
+fn hardware_access_test() {
+    let mut lexer = lex(r"
+        %IX0.3 %i0.3 %QW10 %qb1 %MD4 %m7
+    ");
+
+    for _ in 0..6 {
+        assert_eq!(lexer.token, HardwareAccess, "{}", lexer.slice());
+        lexer.advance();
+    }
+    assert_eq!(lexer.token, End);
+}
+
 #[test]
 fn direct_access_test() {
     let mut lexer = lex(r"
@@ -667,10 +770,14 @@ fn lowercase_keywords_accepted() {
         program class end_class endclass var_input varinput var_output
         varoutput var abstract final method constant retain non_retain 
         nonretain var_temp vartemp end_method endmethod
-        public private internal protected override
-        var_global varglobal var_in_out varinout end_var endvar
+        public private internal protected override extends
+        property end_property endproperty end_get endget end_set endset
+        interface end_interface endinterface implements
+        namespace end_namespace endnamespace using
+        configuration end_configuration endconfiguration resource end_resource endresource on task with at
+        var_global varglobal var_in_out varinout var_external varexternal end_var endvar
         end_program endprogram end_function endfunction end_function_block endfunctionblock
-        type struct end_type endtype end_struct endstruct 
+        type struct end_type endtype end_struct endstruct union end_union endunion
         actions action end_action endaction end_actions endactions 
         if then elsif else endif end_if
         for to by do end_for endfor
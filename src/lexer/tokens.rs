@@ -22,6 +22,13 @@ pub enum Token {
     #[token("ENDCLASS", ignore(case))]
     KeywordEndClass,
 
+    #[token("INTERFACE", ignore(case))]
+    KeywordInterface,
+
+    #[token("END_INTERFACE", ignore(case))]
+    #[token("ENDINTERFACE", ignore(case))]
+    KeywordEndInterface,
+
     #[token("VAR_INPUT", ignore(case))]
     #[token("VARINPUT", ignore(case))]
     KeywordVarInput,
@@ -39,6 +46,12 @@ pub enum Token {
     #[token("FINAL", ignore(case))]
     KeywordFinal,
 
+    #[token("EXTENDS", ignore(case))]
+    KeywordExtends,
+
+    #[token("IMPLEMENTS", ignore(case))]
+    KeywordImplements,
+
     #[token("METHOD", ignore(case))]
     KeywordMethod,
 
@@ -60,6 +73,57 @@ pub enum Token {
     #[token("ENDMETHOD", ignore(case))]
     KeywordEndMethod,
 
+    #[token("PROPERTY", ignore(case))]
+    KeywordProperty,
+
+    #[token("END_PROPERTY", ignore(case))]
+    #[token("ENDPROPERTY", ignore(case))]
+    KeywordEndProperty,
+
+    #[token("END_GET", ignore(case))]
+    #[token("ENDGET", ignore(case))]
+    KeywordEndGet,
+
+    #[token("END_SET", ignore(case))]
+    #[token("ENDSET", ignore(case))]
+    KeywordEndSet,
+
+    #[token("NAMESPACE", ignore(case))]
+    KeywordNamespace,
+
+    #[token("END_NAMESPACE", ignore(case))]
+    #[token("ENDNAMESPACE", ignore(case))]
+    KeywordEndNamespace,
+
+    #[token("USING", ignore(case))]
+    KeywordUsing,
+
+    #[token("CONFIGURATION", ignore(case))]
+    KeywordConfiguration,
+
+    #[token("END_CONFIGURATION", ignore(case))]
+    #[token("ENDCONFIGURATION", ignore(case))]
+    KeywordEndConfiguration,
+
+    #[token("RESOURCE", ignore(case))]
+    KeywordResource,
+
+    #[token("END_RESOURCE", ignore(case))]
+    #[token("ENDRESOURCE", ignore(case))]
+    KeywordEndResource,
+
+    #[token("ON", ignore(case))]
+    KeywordOn,
+
+    #[token("TASK", ignore(case))]
+    KeywordTask,
+
+    #[token("WITH", ignore(case))]
+    KeywordWith,
+
+    #[token("AT", ignore(case))]
+    KeywordAt,
+
     #[token("PUBLIC", ignore(case))]
     KeywordAccessPublic,
 
@@ -83,6 +147,10 @@ pub enum Token {
     #[token("VARINOUT", ignore(case))]
     KeywordVarInOut,
 
+    #[token("VAR_EXTERNAL", ignore(case))]
+    #[token("VAREXTERNAL", ignore(case))]
+    KeywordVarExternal,
+
     #[token("END_VAR", ignore(case))]
     #[token("ENDVAR", ignore(case))]
     KeywordEndVar,
@@ -120,6 +188,13 @@ pub enum Token {
     #[token("ENDSTRUCT", ignore(case))]
     KeywordEndStruct,
 
+    #[token("UNION", ignore(case))]
+    KeywordUnion,
+
+    #[token("END_UNION", ignore(case))]
+    #[token("ENDUNION", ignore(case))]
+    KeywordEndUnion,
+
     #[token("ACTIONS", ignore(case))]
     KeywordActions,
 
@@ -146,6 +221,9 @@ pub enum Token {
     #[token("=>")]
     KeywordOutputAssignment,
 
+    #[token("REF=", ignore(case))]
+    KeywordReferenceAssignment,
+
     #[token("(")]
     KeywordParensOpen,
 
@@ -239,6 +317,9 @@ pub enum Token {
     #[token("REFTO", ignore(case))]
     KeywordRef,
 
+    #[token("REFERENCE", ignore(case))]
+    KeywordReference,
+
     #[token("ARRAY", ignore(case))]
     KeywordArray,
 
@@ -265,6 +346,9 @@ pub enum Token {
     #[token("*")]
     OperatorMultiplication,
 
+    #[token("**")]
+    OperatorExponent,
+
     #[token("/")]
     OperatorDivision,
 
@@ -333,24 +417,27 @@ pub enum Token {
     #[token("FALSE", ignore(case))]
     LiteralFalse,
 
-    #[regex("D(ATE)?#\\d+-\\d+-\\d+", ignore(case))]
+    #[regex("L?D(ATE)?#\\d+-\\d+-\\d+", ignore(case))]
     LiteralDate,
 
     #[regex(
-        "(DATE_AND_TIME|DT)#\\d+-\\d+-\\d+-\\d+:\\d+:\\d+(\\.\\d+)?",
+        "(L?DATE_AND_TIME|L?DT)#\\d+-\\d+-\\d+-\\d+:\\d+:\\d+(\\.\\d+)?",
         ignore(case)
     )]
     LiteralDateAndTime,
 
-    #[regex("(TIME_OF_DAY|TOD)#\\d+:\\d+:\\d+(\\.\\d+)?", ignore(case))]
+    #[regex("(L?TIME_OF_DAY|L?TOD)#\\d+:\\d+:\\d+(\\.\\d+)?", ignore(case))]
     LiteralTimeOfDay,
 
-    #[regex("T(IME)?#-?(\\d+(\\.\\d+)?(d|h|ms|m|s|us|ns))+", ignore(case))]
+    #[regex("L?T(IME)?#-?(\\d+(\\.\\d+)?(d|h|ms|m|s|us|ns))+", ignore(case))]
     LiteralTime,
 
     #[regex("%(B|b|D|d|W|w|X|x)[0-9]+")]
     DirectAccess,
 
+    #[regex("%(I|i|Q|q|M|m)(B|b|D|d|W|w|X|x)?[0-9]+(\\.[0-9]+)*")]
+    HardwareAccess,
+
     #[regex("'((\\$.)|[^$'])*'")]
     LiteralString,
 
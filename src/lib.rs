@@ -41,6 +41,7 @@ use validation::Validator;
 use crate::ast::CompilationUnit;
 use crate::resolver::{AnnotationMap, TypeAnnotator};
 mod ast;
+mod builtins;
 pub mod cli;
 mod codegen;
 pub mod compile_error;
@@ -76,23 +77,54 @@ pub enum ErrNo {
     syntax__generic_error,
     syntax__missing_token,
     syntax__unexpected_token,
+    syntax__invalid_escape_sequence,
 
     //semantic
     // pou related
     pou__missing_return_type,
     pou__unexpected_return_type,
     pou__empty_variable_block,
+    pou__unresolved_super_class,
+    pou__cyclic_inheritance,
+    pou__unresolved_interface,
+    pou__missing_interface_method,
+    pou__interface_method_signature_mismatch,
+    pou__abstract_instantiation,
+    pou__final_method_overridden,
 
     //reference related
     reference__unresolved,
+    reference__private_access,
+    reference__protected_access,
+    reference__internal_access,
+    reference__ambiguous,
+    //call related
+    call__invalid_argument_count,
+    call__invalid_argument_types,
     //variable related
+    var__unresolved_external,
+    var__external_type_mismatch,
+    var__invalid_located_variable,
+    var__invalid_hardware_address,
+    var__incompatible_hardware_address,
+    var__overlapping_hardware_address,
+    var__invalid_variable_length_array,
+
+    //configuration related
+    task__unresolved,
+    task__program_expected,
+    task__invalid_parameter,
 
     //type related
     type__literal_out_of_range,
     type__inompatible_literal_cast,
     type__incompatible_directaccess,
     type__incompatible_directaccess_range,
+    type__string_index_out_of_range,
     type__expected_literal,
+    type__invalid_enum_base_type,
+    type__invalid_operation,
+    type__invalid_reference_assignment,
 }
 
 impl Diagnostic {
@@ -115,6 +147,14 @@ impl Diagnostic {
         }
     }
 
+    pub fn invalid_escape_sequence(sequence: &str, range: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!("Invalid escape sequence in string literal: {}", sequence),
+            range,
+            err_no: ErrNo::syntax__invalid_escape_sequence,
+        }
+    }
+
     pub fn unexpected_initializer_on_function_return(range: SourceRange) -> Diagnostic {
         Diagnostic::SyntaxError {
             message: "Return types cannot have a default value".into(),
@@ -197,6 +237,21 @@ impl Diagnostic {
         }
     }
 
+    pub fn string_index_out_of_range(
+        index: i128,
+        length: u32,
+        location: SourceRange,
+    ) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "String index {} is out of range, expected an index in 1..{}",
+                index, length
+            ),
+            range: location,
+            err_no: ErrNo::type__string_index_out_of_range,
+        }
+    }
+
     pub fn incompatible_literal_cast(
         cast_type: &str,
         literal_type: &str,
@@ -232,6 +287,92 @@ impl Diagnostic {
         }
     }
 
+    pub fn invalid_enum_base_type(type_name: &str, location: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "Invalid base type '{:}' for an enum, expected an integer type",
+                type_name
+            ),
+            range: location,
+            err_no: ErrNo::type__invalid_enum_base_type,
+        }
+    }
+
+    pub fn invalid_operation(
+        left_type: &str,
+        operator: &str,
+        right_type: &str,
+        location: SourceRange,
+    ) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "Invalid operation: {:} {:} {:}",
+                left_type, operator, right_type
+            ),
+            range: location,
+            err_no: ErrNo::type__invalid_operation,
+        }
+    }
+
+    pub fn reference_expected(location: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: "Only a REFERENCE TO variable can be rebound using REF=".into(),
+            range: location,
+            err_no: ErrNo::type__invalid_reference_assignment,
+        }
+    }
+
+    pub fn invalid_reference_target(
+        expected_type: &str,
+        found: &str,
+        location: SourceRange,
+    ) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "Invalid REF= assignment, expected a variable of type {:} but found {:}",
+                expected_type, found
+            ),
+            range: location,
+            err_no: ErrNo::type__invalid_reference_assignment,
+        }
+    }
+
+    pub fn invalid_argument_count(
+        function: &str,
+        expected: &str,
+        found: usize,
+        location: SourceRange,
+    ) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "Invalid number of arguments for {:}, expected {:} but found {:}",
+                function, expected, found
+            ),
+            range: location,
+            err_no: ErrNo::call__invalid_argument_count,
+        }
+    }
+
+    pub fn invalid_argument_types(
+        function: &str,
+        types: &str,
+        location: SourceRange,
+    ) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!("Invalid arguments for {:}, found {:}", function, types),
+            range: location,
+            err_no: ErrNo::call__invalid_argument_types,
+        }
+    }
+
+    pub fn invalid_variable_length_array(location: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: "ARRAY[*] can only be declared for VAR_INPUT and VAR_IN_OUT parameters".into(),
+            range: location,
+            err_no: ErrNo::var__invalid_variable_length_array,
+        }
+    }
+
     pub fn empty_variable_block(location: SourceRange) -> Diagnostic {
         Diagnostic::SyntaxError {
             message: "Variable block is empty".into(),
@@ -240,6 +381,237 @@ impl Diagnostic {
         }
     }
 
+    pub fn unresolved_super_class(super_class: &str, location: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!("Could not resolve super class '{:}'", super_class),
+            range: location,
+            err_no: ErrNo::pou__unresolved_super_class,
+        }
+    }
+
+    pub fn cyclic_inheritance(pou_name: &str, location: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!("'{:}' cannot extend itself (cyclic inheritance)", pou_name),
+            range: location,
+            err_no: ErrNo::pou__cyclic_inheritance,
+        }
+    }
+
+    pub fn unresolved_interface(interface: &str, location: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!("Could not resolve interface '{:}'", interface),
+            range: location,
+            err_no: ErrNo::pou__unresolved_interface,
+        }
+    }
+
+    pub fn missing_interface_method(
+        pou_name: &str,
+        method: &str,
+        location: SourceRange,
+    ) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!("'{:}' does not implement method '{:}'", pou_name, method),
+            range: location,
+            err_no: ErrNo::pou__missing_interface_method,
+        }
+    }
+
+    pub fn interface_method_signature_mismatch(
+        method: &str,
+        interface_method: &str,
+        location: SourceRange,
+    ) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "'{:}' does not match the signature of '{:}'",
+                method, interface_method
+            ),
+            range: location,
+            err_no: ErrNo::pou__interface_method_signature_mismatch,
+        }
+    }
+
+    pub fn abstract_instantiation(pou_name: &str, location: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!("Cannot instantiate abstract '{:}'", pou_name),
+            range: location,
+            err_no: ErrNo::pou__abstract_instantiation,
+        }
+    }
+
+    pub fn private_access(member: &str, class: &str, location: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "'{:}' is PRIVATE and can only be accessed from within '{:}'",
+                member, class
+            ),
+            range: location,
+            err_no: ErrNo::reference__private_access,
+        }
+    }
+
+    pub fn protected_access(member: &str, class: &str, location: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "'{:}' is PROTECTED and can only be accessed from within '{:}' or its derived types",
+                member, class
+            ),
+            range: location,
+            err_no: ErrNo::reference__protected_access,
+        }
+    }
+
+    pub fn internal_access(member: &str, location: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "'{:}' is INTERNAL and can only be accessed from within its namespace",
+                member
+            ),
+            range: location,
+            err_no: ErrNo::reference__internal_access,
+        }
+    }
+
+    pub fn ambiguous_reference(
+        reference: &str,
+        candidates: &[&str],
+        location: SourceRange,
+    ) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "'{:}' is ambiguous, it could refer to '{:}'",
+                reference,
+                candidates.join("', '")
+            ),
+            range: location,
+            err_no: ErrNo::reference__ambiguous,
+        }
+    }
+
+    pub fn final_method_overridden(
+        method: &str,
+        final_method: &str,
+        location: SourceRange,
+    ) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "'{:}' cannot override the final method '{:}'",
+                method, final_method
+            ),
+            range: location,
+            err_no: ErrNo::pou__final_method_overridden,
+        }
+    }
+
+    pub fn unresolved_external_variable(name: &str, location: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "Could not resolve VAR_EXTERNAL '{:}', no such global variable",
+                name
+            ),
+            range: location,
+            err_no: ErrNo::var__unresolved_external,
+        }
+    }
+
+    pub fn external_type_mismatch(name: &str, location: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "The type of VAR_EXTERNAL '{:}' does not match the type of the global variable",
+                name
+            ),
+            range: location,
+            err_no: ErrNo::var__external_type_mismatch,
+        }
+    }
+
+    pub fn located_variable_not_allowed(name: &str, location: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "'{:}' cannot be located, only global variables and local variables of a PROGRAM can",
+                name
+            ),
+            range: location,
+            err_no: ErrNo::var__invalid_located_variable,
+        }
+    }
+
+    pub fn invalid_hardware_address(address: &str, location: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "Invalid address '{:}', expected <byte>.<bit> for bits and <byte> for all other sizes",
+                address
+            ),
+            range: location,
+            err_no: ErrNo::var__invalid_hardware_address,
+        }
+    }
+
+    pub fn incompatible_hardware_address(
+        address: &str,
+        type_name: &str,
+        location: SourceRange,
+    ) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "A variable of type '{:}' cannot be located at '{:}'",
+                type_name, address
+            ),
+            range: location,
+            err_no: ErrNo::var__incompatible_hardware_address,
+        }
+    }
+
+    pub fn overlapping_hardware_address(
+        name: &str,
+        other: &str,
+        location: SourceRange,
+    ) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "The address of '{:}' overlaps with the address of '{:}'",
+                name, other
+            ),
+            range: location,
+            err_no: ErrNo::var__overlapping_hardware_address,
+        }
+    }
+
+    pub fn unresolved_task(task: &str, resource: &str, location: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "Could not resolve task '{:}' in resource '{:}'",
+                task, resource
+            ),
+            range: location,
+            err_no: ErrNo::task__unresolved,
+        }
+    }
+
+    pub fn program_expected(name: &str, location: SourceRange) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!("Expected a PROGRAM but found '{:}'", name),
+            range: location,
+            err_no: ErrNo::task__program_expected,
+        }
+    }
+
+    pub fn invalid_task_parameter(
+        parameter: &str,
+        expected: &str,
+        location: SourceRange,
+    ) -> Diagnostic {
+        Diagnostic::SyntaxError {
+            message: format!(
+                "Invalid task parameter '{:}', expected a literal of type {:}",
+                parameter, expected
+            ),
+            range: location,
+            err_no: ErrNo::task__invalid_parameter,
+        }
+    }
+
     pub fn get_message(&self) -> &str {
         match self {
             Diagnostic::SyntaxError { message, .. } => message.as_str(),
@@ -494,14 +866,25 @@ pub fn compile_module<'c, T: SourceContainer>(
             .map_err(|err| CompileError::io_read_error(err, location.clone()))?;
         let file_id = files.add(location.clone(), e.source.clone());
 
-        let (mut parse_result, diagnostics) =
+        let (parse_result, diagnostics) =
             parser::parse(lexer::lex_with_ids(e.source.as_str(), id_provider.clone()));
+        all_units.push((file_id, diagnostics, parse_result));
+    }
 
+    //references may point to namespace declarations of other files
+    let declarations = ast::collect_declarations(all_units.iter().map(|(_, _, unit)| unit));
+    for (_, diagnostics, parse_result) in all_units.iter_mut() {
+        //qualify all references to namespace declarations
+        diagnostics.append(&mut ast::resolve_namespaces(parse_result, &declarations));
         //pre-process the ast (create inlined types)
-        ast::pre_process(&mut parse_result);
+        ast::pre_process(parse_result);
         //index the pou
-        full_index.import(index::visitor::visit(&parse_result));
-        all_units.push((file_id, diagnostics, parse_result));
+        full_index.import(index::visitor::visit(parse_result));
+    }
+
+    //properties may be declared in other files, so they are lowered once everything is indexed
+    for (_, _, unit) in all_units.iter_mut() {
+        resolver::lower_property_accesses(&full_index, unit, id_provider.clone());
     }
 
     // ### PHASE 2 ###
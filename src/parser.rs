@@ -1,4 +1,6 @@
 // Copyright (c) 2020 Ghaith Hachem and Mathias Rieder
+use std::collections::HashMap;
+
 use crate::{
     ast::*,
     expect_token, lexer,
@@ -6,7 +8,10 @@ use crate::{
     Diagnostic,
 };
 
-use self::{control_parser::parse_control_statement, expressions_parser::parse_expression};
+use self::{
+    control_parser::parse_control_statement,
+    expressions_parser::{parse_expression, parse_hardware_address, parse_range_statement},
+};
 
 mod control_parser;
 mod expressions_parser;
@@ -18,6 +23,10 @@ pub type ParsedAst = (CompilationUnit, Vec<Diagnostic>);
 pub fn parse(mut lexer: ParseSession) -> ParsedAst {
     let mut unit = CompilationUnit::default();
 
+    // the scope of the namespace we are currently in and the scopes of its enclosing namespaces
+    let mut scope = NamespaceScope::default();
+    let mut outer_scopes = vec![];
+
     let mut linkage = LinkageType::Internal;
     loop {
         match lexer.token {
@@ -27,26 +36,49 @@ pub fn parse(mut lexer: ParseSession) -> ParsedAst {
                 //Don't reset linkage
                 continue;
             }
-            KeywordVarGlobal => unit
-                .global_vars
-                .push(parse_variable_block(&mut lexer, VariableBlockType::Global)),
-            KeywordProgram | KeywordClass | KeywordFunction | KeywordFunctionBlock => {
+            KeywordNamespace => {
+                lexer.advance();
+                let name = parse_qualified_identifier(&mut lexer).unwrap_or_default();
+                let namespace = match &scope.namespace {
+                    Some(outer) => get_qualified_name(outer, &name),
+                    None => name,
+                };
+                let inner_scope = NamespaceScope {
+                    namespace: Some(namespace),
+                    usings: scope.usings.clone(),
+                };
+                outer_scopes.push(std::mem::replace(&mut scope, inner_scope));
+            }
+            KeywordEndNamespace if !outer_scopes.is_empty() => {
+                lexer.advance();
+                scope = outer_scopes.pop().unwrap_or_default();
+            }
+            KeywordUsing => scope.usings.append(&mut parse_using_directives(&mut lexer)),
+            KeywordVarGlobal => {
+                let mut block = parse_variable_block(&mut lexer, VariableBlockType::Global);
+                for variable in block.variables.iter_mut() {
+                    declare_in_scope(&mut unit.namespace_scopes, &scope, &[], &mut variable.name);
+                }
+                unit.global_vars.push(block);
+            }
+            KeywordProgram | KeywordClass | KeywordInterface | KeywordFunction
+            | KeywordFunctionBlock => {
                 let params = match lexer.token {
                     KeywordProgram => (PouType::Program, KeywordEndProgram),
                     KeywordClass => (PouType::Class, KeywordEndClass),
+                    KeywordInterface => (PouType::Interface, KeywordEndInterface),
                     KeywordFunction => (PouType::Function, KeywordEndFunction),
                     _ => (PouType::FunctionBlock, KeywordEndFunctionBlock),
                 };
 
-                let (mut pou, mut implementation) =
+                let (pous, implementations, usings) =
                     parse_pou(&mut lexer, params.0, linkage, params.1);
 
-                unit.units.append(&mut pou);
-                unit.implementations.append(&mut implementation);
+                declare_pous(&mut unit, &scope, &usings, pous, implementations);
             }
             KeywordAction => {
                 if let Some(implementation) = parse_action(&mut lexer, linkage, None) {
-                    unit.implementations.push(implementation);
+                    declare_pous(&mut unit, &scope, &[], vec![], vec![implementation]);
                 }
             }
             KeywordActions => {
@@ -55,14 +87,49 @@ pub fn parse(mut lexer: ParseSession) -> ParsedAst {
                     .last()
                     .map(|it| it.name.as_str())
                     .unwrap_or("__unknown__");
-                let mut actions = parse_actions(&mut lexer, linkage, last_pou);
-                unit.implementations.append(&mut actions);
+                //the last pou's name is already qualified with the current namespace
+                let last_pou = scope
+                    .namespace
+                    .as_ref()
+                    .and_then(|it| last_pou.strip_prefix(format!("{}.", it).as_str()))
+                    .unwrap_or(last_pou);
+                let actions = parse_actions(&mut lexer, linkage, last_pou);
+                declare_pous(&mut unit, &scope, &[], vec![], actions);
             }
             KeywordType => {
-                if let Some(unit_type) = parse_type(&mut lexer) {
+                if let Some(mut unit_type) = parse_type(&mut lexer) {
+                    let mut name = unit_type
+                        .data_type
+                        .get_name()
+                        .unwrap_or_default()
+                        .to_string();
+                    declare_in_scope(&mut unit.namespace_scopes, &scope, &[], &mut name);
+                    unit_type.data_type.set_name(name);
                     unit.types.push(unit_type);
                 }
             }
+            KeywordConfiguration => {
+                if let Some(mut configuration) =
+                    parse_configuration(&mut lexer, &mut unit.global_vars)
+                {
+                    declare_in_scope(
+                        &mut unit.namespace_scopes,
+                        &scope,
+                        &[],
+                        &mut configuration.name,
+                    );
+                    unit.configurations.push(configuration);
+                }
+            }
+            End if !outer_scopes.is_empty() => {
+                //a namespace was not closed
+                lexer.accept_diagnostic(Diagnostic::unexpected_token_found(
+                    format!("{:?}", KeywordEndNamespace).as_str(),
+                    lexer.slice(),
+                    lexer.location(),
+                ));
+                outer_scopes.clear();
+            }
             KeywordEndActions | End => return (unit, lexer.diagnostics),
             _ => {
                 lexer.accept_diagnostic(Diagnostic::unexpected_token_found(
@@ -78,6 +145,77 @@ pub fn parse(mut lexer: ParseSession) -> ParsedAst {
     //the match in the loop will always return
 }
 
+/// moves the given POUs and implementations into the current namespace and adds them to the unit
+fn declare_pous(
+    unit: &mut CompilationUnit,
+    scope: &NamespaceScope,
+    usings: &[String],
+    mut pous: Vec<Pou>,
+    mut implementations: Vec<Implementation>,
+) {
+    for pou in pous.iter_mut() {
+        declare_in_scope(&mut unit.namespace_scopes, scope, usings, &mut pou.name);
+        qualify_owner_class(scope, &mut pou.pou_type);
+    }
+    for implementation in implementations.iter_mut() {
+        declare_in_scope(
+            &mut unit.namespace_scopes,
+            scope,
+            usings,
+            &mut implementation.name,
+        );
+        qualify_name(scope, &mut implementation.type_name);
+        qualify_owner_class(scope, &mut implementation.pou_type);
+    }
+    unit.units.append(&mut pous);
+    unit.implementations.append(&mut implementations);
+}
+
+/// qualifies the given declaration's name with the current namespace and remembers the
+/// scope it was declared in (including the declaration's own `USING` directives)
+fn declare_in_scope(
+    namespace_scopes: &mut HashMap<String, NamespaceScope>,
+    scope: &NamespaceScope,
+    usings: &[String],
+    name: &mut String,
+) {
+    qualify_name(scope, name);
+    let mut declaration_scope = scope.clone();
+    declaration_scope.usings.extend(usings.iter().cloned());
+    if !declaration_scope.is_global() {
+        namespace_scopes.insert(name.to_lowercase(), declaration_scope);
+    }
+}
+
+fn qualify_name(scope: &NamespaceScope, name: &mut String) {
+    if let Some(namespace) = &scope.namespace {
+        *name = get_qualified_name(namespace, name);
+    }
+}
+
+fn qualify_owner_class(scope: &NamespaceScope, pou_type: &mut PouType) {
+    if let PouType::Method { owner_class } = pou_type {
+        qualify_name(scope, owner_class);
+    }
+}
+
+/// parses all `USING A.B, C;` directives and returns the imported namespaces
+fn parse_using_directives(lexer: &mut ParseSession) -> Vec<String> {
+    let mut namespaces = vec![];
+    while lexer.allow(&KeywordUsing) {
+        loop {
+            if let Some(namespace) = parse_qualified_identifier(lexer) {
+                namespaces.push(namespace);
+            }
+            if !lexer.allow(&KeywordComma) {
+                break;
+            }
+        }
+        lexer.consume_or_report(KeywordSemicolon);
+    }
+    namespaces
+}
+
 fn parse_actions(
     lexer: &mut ParseSession,
     linkage: LinkageType,
@@ -129,7 +267,7 @@ fn parse_pou(
     pou_type: PouType,
     linkage: LinkageType,
     expected_end_token: lexer::Token,
-) -> (Vec<Pou>, Vec<Implementation>) {
+) -> (Vec<Pou>, Vec<Implementation>, Vec<String>) {
     let start = lexer.range().start;
     lexer.advance(); //Consume ProgramKeyword
     let closing_tokens = vec![
@@ -150,28 +288,45 @@ fn parse_pou(
 
         let name = parse_identifier(lexer).unwrap_or_else(|| "".to_string()); // parse POU name
 
-        // TODO: Parse USING directives
-        // TODO: Parse EXTENDS specifier
-        // TODO: Parse IMPLEMENTS specifier
+        let mut usings = parse_using_directives(lexer);
+        let super_class = match pou_type {
+            // classes and function blocks can extend another class or function block
+            PouType::Class | PouType::FunctionBlock if lexer.allow(&KeywordExtends) => {
+                parse_qualified_identifier(lexer)
+            }
+            _ => None,
+        };
+        let interfaces = match pou_type {
+            // classes and function blocks can implement a list of interfaces
+            PouType::Class | PouType::FunctionBlock if lexer.allow(&KeywordImplements) => {
+                parse_interface_list(lexer)
+            }
+            _ => vec![],
+        };
 
-        let return_type = if pou_type != PouType::Class {
+        let return_type = if !matches!(pou_type, PouType::Class | PouType::Interface) {
             // parse an optional return type
             parse_return_type(lexer, &pou_type)
         } else {
-            // classes do not have a return type
+            // classes and interfaces do not have a return type
             None
         };
+        // functions declare their USING directives after the return type
+        usings.append(&mut parse_using_directives(lexer));
 
         // parse variable declarations. note that var in/out/inout
         // blocks are not allowed inside of class declarations.
+        // interfaces do not declare any variables.
         let mut variable_blocks = vec![];
         let allowed_var_types = match pou_type {
             PouType::Class => vec![KeywordVar],
+            PouType::Interface => vec![],
             _ => vec![
                 KeywordVar,
                 KeywordVarInput,
                 KeywordVarOutput,
                 KeywordVarInOut,
+                KeywordVarExternal,
             ],
         };
         while allowed_var_types.contains(&lexer.token) {
@@ -188,16 +343,33 @@ fn parse_pou(
             // and an implementation part. That's why we get another (Pou, Implementation)
             // tuple out of parse_method() that has to be added to the list of Pous and
             // implementations. Note that function blocks have to start with the method
-            // declarations before their implementation.
+            // declarations before their implementation. properties are turned into a
+            // getter- and a setter-method the same way.
+            while lexer.token == KeywordMethod || lexer.token == KeywordProperty {
+                if lexer.token == KeywordProperty {
+                    for (pou, implementation) in parse_property(lexer, &name, linkage) {
+                        impl_pous.push(pou);
+                        implementations.push(implementation);
+                    }
+                } else if let Some((pou, implementation)) = parse_method(lexer, &name, linkage) {
+                    impl_pous.push(pou);
+                    implementations.push(implementation);
+                }
+            }
+        } else if pou_type == PouType::Interface {
+            // an interface only declares method prototypes. their implementations are
+            // provided by the implementing classes, so they are treated as external
             while lexer.token == KeywordMethod {
-                if let Some((pou, implementation)) = parse_method(lexer, &name, linkage) {
+                if let Some((pou, implementation)) =
+                    parse_method(lexer, &name, LinkageType::External)
+                {
                     impl_pous.push(pou);
                     implementations.push(implementation);
                 }
             }
         }
-        if pou_type != PouType::Class {
-            // a class may not contain an implementation
+        if !matches!(pou_type, PouType::Class | PouType::Interface) {
+            // classes and interfaces may not contain an implementation
             implementations.push(parse_implementation(
                 lexer,
                 linkage,
@@ -214,10 +386,12 @@ fn parse_pou(
             return_type,
             location: SourceRange::new(start..lexer.range().end),
             poly_mode,
+            super_class,
+            interfaces,
         }];
         pous.append(&mut impl_pous);
 
-        (pous, implementations)
+        (pous, implementations, usings)
     });
 
     //check if we ended on the right end-keyword
@@ -231,6 +405,19 @@ fn parse_pou(
     pou
 }
 
+/// parses a comma-separated list of interface names (e.g. `IMPLEMENTS I1, I2`)
+fn parse_interface_list(lexer: &mut ParseSession) -> Vec<String> {
+    let mut interfaces = vec![];
+    loop {
+        if let Some(interface) = parse_qualified_identifier(lexer) {
+            interfaces.push(interface);
+        }
+        if !lexer.allow(&KeywordComma) {
+            return interfaces;
+        }
+    }
+}
+
 fn parse_polymorphism_mode(
     lexer: &mut ParseSession,
     pou_type: &PouType,
@@ -297,7 +484,7 @@ fn parse_method(
         let method_start = lexer.location().get_start();
         lexer.advance(); // eat METHOD keyword
 
-        let access = Some(parse_access_modifier(lexer));
+        let access = Some(parse_access_modifier(lexer, AccessModifier::Public));
         let pou_type = PouType::Method {
             owner_class: class_name.into(),
         };
@@ -347,13 +534,160 @@ fn parse_method(
                 return_type,
                 location: SourceRange::new(method_start..method_end),
                 poly_mode,
+                super_class: None,
+                interfaces: vec![],
             },
             implementation,
         ))
     })
 }
 
-fn parse_access_modifier(lexer: &mut ParseSession) -> AccessModifier {
+fn parse_property(
+    lexer: &mut ParseSession,
+    class_name: &str,
+    linkage: LinkageType,
+) -> Vec<(Pou, Implementation)> {
+    parse_any_in_region(lexer, vec![KeywordEndProperty], |lexer| {
+        // Property declarations look like this:
+        // PROPERTY [AccessModifier] name : type
+        //    [GET [AccessModifier] ... END_GET]
+        //    [SET [AccessModifier] ... END_SET]
+        // END_PROPERTY
+
+        lexer.advance(); // eat PROPERTY keyword
+
+        let access = parse_access_modifier(lexer, AccessModifier::Public);
+        let name_location = lexer.location();
+        let name = parse_identifier(lexer);
+        if lexer.token != KeywordColon {
+            lexer.accept_diagnostic(Diagnostic::unexpected_token_found(
+                "KeywordColon",
+                lexer.slice(),
+                lexer.location(),
+            ));
+        }
+        let data_type = parse_return_type(
+            lexer,
+            &PouType::Method {
+                owner_class: class_name.into(),
+            },
+        );
+        let (name, data_type) = match (name, data_type) {
+            (Some(name), Some(data_type)) => (name, data_type),
+            _ => return vec![],
+        };
+
+        let mut accessors = vec![];
+        loop {
+            let accessor = match get_property_accessor_keyword(lexer) {
+                // the getter returns the property's value via the property's name
+                Some(KeywordEndGet) => parse_property_accessor(
+                    lexer,
+                    class_name,
+                    &get_property_getter_name(&name),
+                    linkage,
+                    access.clone(),
+                    Some(data_type.clone()),
+                    vec![],
+                ),
+                // the setter receives the new value as an input named like the property
+                Some(KeywordEndSet) => parse_property_accessor(
+                    lexer,
+                    class_name,
+                    &get_property_setter_name(&name),
+                    linkage,
+                    access.clone(),
+                    None,
+                    vec![VariableBlock {
+                        access: AccessModifier::Protected,
+                        constant: false,
+                        retain: false,
+                        variables: vec![Variable {
+                            name: name.clone(),
+                            data_type: data_type.clone(),
+                            initializer: None,
+                            address: None,
+                            location: name_location.clone(),
+                        }],
+                        variable_block_type: VariableBlockType::Input,
+                    }],
+                ),
+                _ => break,
+            };
+            accessors.push(accessor);
+        }
+        accessors
+    })
+}
+
+/// parses the GET or SET part of a property as a method with the given name, the accessor
+/// uses the property's access modifier unless it declares its own one
+fn parse_property_accessor(
+    lexer: &mut ParseSession,
+    class_name: &str,
+    name: &str,
+    linkage: LinkageType,
+    property_access: AccessModifier,
+    return_type: Option<DataTypeDeclaration>,
+    mut variable_blocks: Vec<VariableBlock>,
+) -> (Pou, Implementation) {
+    let end_token = get_property_accessor_keyword(lexer).unwrap_or(KeywordEndSet);
+    parse_any_in_region(lexer, vec![end_token], |lexer| {
+        let accessor_start = lexer.location().get_start();
+        lexer.advance(); // eat GET or SET keyword
+
+        let access = parse_access_modifier(lexer, property_access.clone());
+        while lexer.token == KeywordVar || lexer.token == KeywordVarTemp {
+            variable_blocks.push(parse_variable_block(
+                lexer,
+                parse_variable_block_type(&lexer.token),
+            ));
+        }
+
+        let call_name = format!("{}.{}", class_name, name);
+        let pou_type = PouType::Method {
+            owner_class: class_name.into(),
+        };
+        let implementation =
+            parse_implementation(lexer, linkage, pou_type.clone(), &call_name, &call_name);
+        let implementation = Implementation {
+            access: Some(access),
+            ..implementation
+        };
+
+        let accessor_end = lexer.location().get_end();
+        (
+            Pou {
+                name: call_name,
+                pou_type,
+                variable_blocks,
+                return_type,
+                location: SourceRange::new(accessor_start..accessor_end),
+                poly_mode: Some(PolymorphismMode::None),
+                super_class: None,
+                interfaces: vec![],
+            },
+            implementation,
+        )
+    })
+}
+
+/// returns the token that ends the property accessor starting at the current token. GET and SET
+/// are no reserved keywords, they only start an accessor inside of a PROPERTY
+fn get_property_accessor_keyword(lexer: &ParseSession) -> Option<Token> {
+    if lexer.token != Identifier {
+        None
+    } else if lexer.slice().eq_ignore_ascii_case("GET") {
+        Some(KeywordEndGet)
+    } else if lexer.slice().eq_ignore_ascii_case("SET") {
+        Some(KeywordEndSet)
+    } else {
+        None
+    }
+}
+
+/// parses an optional access modifier, returns the given default if there is none
+fn parse_access_modifier(lexer: &mut ParseSession, default: AccessModifier) -> AccessModifier {
     if lexer.allow(&KeywordAccessPublic) {
         AccessModifier::Public
     } else if lexer.allow(&KeywordAccessPrivate) {
@@ -363,7 +697,7 @@ fn parse_access_modifier(lexer: &mut ParseSession) -> AccessModifier {
     } else if lexer.allow(&KeywordAccessInternal) {
         AccessModifier::Internal
     } else {
-        AccessModifier::Protected
+        default
     }
 }
 
@@ -383,6 +717,15 @@ fn parse_identifier(lexer: &mut ParseSession) -> Option<String> {
     }
 }
 
+/// parse a possibly qualified identifier (e.g. `A.B.MyFb`) and advance if successful
+fn parse_qualified_identifier(lexer: &mut ParseSession) -> Option<String> {
+    let mut name = parse_identifier(lexer)?;
+    while lexer.allow(&KeywordDot) {
+        name = get_qualified_name(&name, &parse_identifier(lexer)?);
+    }
+    Some(name)
+}
+
 fn parse_implementation(
     lexer: &mut ParseSession,
     linkage: LinkageType,
@@ -470,16 +813,156 @@ fn parse_type(lexer: &mut ParseSession) -> Option<UserTypeDeclaration> {
     }
 }
 
+// CONFIGURATION ... END_CONFIGURATION
+fn parse_configuration(
+    lexer: &mut ParseSession,
+    global_vars: &mut Vec<VariableBlock>,
+) -> Option<Configuration> {
+    let start = lexer.range().start;
+    lexer.advance(); // consume the CONFIGURATION
+    parse_any_in_region(lexer, vec![KeywordEndConfiguration], |lexer| {
+        let name = parse_identifier(lexer)?;
+        let mut resources = vec![];
+        loop {
+            match lexer.token {
+                KeywordVarGlobal => {
+                    global_vars.push(parse_variable_block(lexer, VariableBlockType::Global))
+                }
+                KeywordResource => resources.extend(parse_resource(lexer, global_vars)),
+                _ => break,
+            }
+        }
+        Some(Configuration {
+            name,
+            resources,
+            location: (start..lexer.range().end).into(),
+        })
+    })
+}
+
+// RESOURCE name ON processing_unit ... END_RESOURCE
+fn parse_resource(
+    lexer: &mut ParseSession,
+    global_vars: &mut Vec<VariableBlock>,
+) -> Option<Resource> {
+    let start = lexer.range().start;
+    lexer.advance(); // consume the RESOURCE
+    parse_any_in_region(lexer, vec![KeywordEndResource], |lexer| {
+        let name = parse_identifier(lexer)?;
+        let processing_unit = if lexer.allow(&KeywordOn) {
+            parse_identifier(lexer)
+        } else {
+            None
+        };
+
+        let mut tasks = vec![];
+        let mut programs = vec![];
+        loop {
+            match lexer.token {
+                KeywordVarGlobal => {
+                    global_vars.push(parse_variable_block(lexer, VariableBlockType::Global))
+                }
+                KeywordTask => tasks.extend(parse_task(lexer)),
+                KeywordProgram => programs.extend(parse_program_instance(lexer)),
+                _ => break,
+            }
+        }
+        Some(Resource {
+            name,
+            processing_unit,
+            tasks,
+            programs,
+            location: (start..lexer.range().end).into(),
+        })
+    })
+}
+
+// TASK name(INTERVAL := ..., PRIORITY := ...);
+fn parse_task(lexer: &mut ParseSession) -> Option<Task> {
+    let start = lexer.range().start;
+    lexer.advance(); // consume the TASK
+    parse_any_in_region(lexer, vec![KeywordSemicolon], |lexer| {
+        let name = parse_identifier(lexer)?;
+        let mut interval = None;
+        let mut priority = None;
+        lexer.consume_or_report(KeywordParensOpen);
+        if lexer.token != KeywordParensClose {
+            let parameters = match parse_expression(lexer) {
+                AstStatement::ExpressionList { expressions, .. } => expressions,
+                parameter => vec![parameter],
+            };
+            for parameter in parameters {
+                let location = parameter.get_location();
+                match parameter {
+                    AstStatement::Assignment { left, right, .. } => {
+                        match get_reference_name(&left).to_uppercase().as_str() {
+                            "INTERVAL" => interval = Some(*right),
+                            "PRIORITY" => priority = Some(*right),
+                            _ => lexer.accept_diagnostic(Diagnostic::unexpected_token_found(
+                                "INTERVAL or PRIORITY",
+                                get_reference_name(&left),
+                                left.get_location(),
+                            )),
+                        }
+                    }
+                    _ => lexer.accept_diagnostic(Diagnostic::unexpected_token_found(
+                        "Task parameter assignment",
+                        lexer.slice_region(location.to_range()),
+                        location,
+                    )),
+                }
+            }
+        }
+        lexer.consume_or_report(KeywordParensClose);
+        Some(Task {
+            name,
+            interval,
+            priority,
+            location: (start..lexer.last_range.end).into(),
+        })
+    })
+}
+
+fn get_reference_name(statement: &AstStatement) -> &str {
+    if let AstStatement::Reference { name, .. } = statement {
+        name.as_str()
+    } else {
+        ""
+    }
+}
+
+// PROGRAM inst [WITH task] : program;
+fn parse_program_instance(lexer: &mut ParseSession) -> Option<ProgramInstance> {
+    let start = lexer.range().start;
+    lexer.advance(); // consume the PROGRAM
+    parse_any_in_region(lexer, vec![KeywordSemicolon], |lexer| {
+        let name = parse_identifier(lexer)?;
+        let task = if lexer.allow(&KeywordWith) {
+            parse_identifier(lexer)
+        } else {
+            None
+        };
+        lexer.consume_or_report(KeywordColon);
+        let program = parse_qualified_identifier(lexer)?;
+        Some(ProgramInstance {
+            name,
+            task,
+            program,
+            location: (start..lexer.last_range.end).into(),
+        })
+    })
+}
+
 type DataTypeWithInitializer = (DataTypeDeclaration, Option<AstStatement>);
 
 fn parse_full_data_type_definition(
     lexer: &mut ParseSession,
     name: Option<String>,
 ) -> Option<DataTypeWithInitializer> {
-    let end_keyword = if lexer.token == KeywordStruct {
-        KeywordEndStruct
-    } else {
-        KeywordSemicolon
+    let end_keyword = match lexer.token {
+        KeywordStruct => KeywordEndStruct,
+        KeywordUnion => KeywordEndUnion,
+        _ => KeywordSemicolon,
     };
     parse_any_in_region(lexer, vec![end_keyword], |lexer| {
         if lexer.allow(&KeywordDotDotDot) {
@@ -512,7 +995,7 @@ fn parse_full_data_type_definition(
     })
 }
 
-// TYPE xxx : 'STRUCT' | '(' | IDENTIFIER
+// TYPE xxx : 'STRUCT' | 'UNION' | '(' | IDENTIFIER
 fn parse_data_type_definition(
     lexer: &mut ParseSession,
     name: Option<String>,
@@ -528,6 +1011,16 @@ fn parse_data_type_definition(
             },
             None,
         ))
+    } else if lexer.allow(&KeywordUnion) {
+        // Parse union
+        let variables = parse_variable_list(lexer);
+        Some((
+            DataTypeDeclaration::DataTypeDefinition {
+                data_type: DataType::UnionType { name, variables },
+                location: (start..lexer.range().end).into(),
+            },
+            None,
+        ))
     } else if lexer.allow(&KeywordArray) {
         parse_array_type_definition(lexer, name)
     } else if lexer.allow(&KeywordPointer) {
@@ -542,9 +1035,17 @@ fn parse_data_type_definition(
         } else {
             lexer.advance();
         }
-        parse_pointer_definition(lexer, name, start_pos)
+        parse_pointer_definition(lexer, name, start_pos, false, false)
     } else if lexer.allow(&KeywordRef) {
-        parse_pointer_definition(lexer, name, lexer.last_range.start)
+        parse_pointer_definition(lexer, name, lexer.last_range.start, true, false)
+    } else if lexer.allow(&KeywordReference) {
+        let start_pos = lexer.last_range.start;
+        if let Err(diag) = lexer.expect(KeywordTo) {
+            lexer.accept_diagnostic(diag);
+        } else {
+            lexer.advance();
+        }
+        parse_pointer_definition(lexer, name, start_pos, true, true)
     } else if lexer.allow(&KeywordParensOpen) {
         parse_enum_type_definition(lexer, name)
     } else if lexer.token == KeywordString || lexer.token == KeywordWideString {
@@ -566,6 +1067,8 @@ fn parse_pointer_definition(
     lexer: &mut ParseSession,
     name: Option<String>,
     start_pos: usize,
+    is_ref_to: bool,
+    auto_deref: bool,
 ) -> Option<(DataTypeDeclaration, Option<AstStatement>)> {
     parse_data_type_definition(lexer, None).map(|(decl, initializer)| {
         (
@@ -573,6 +1076,8 @@ fn parse_pointer_definition(
                 data_type: DataType::PointerType {
                     name,
                     referenced_type: Box::new(decl),
+                    is_ref_to,
+                    auto_deref,
                 },
                 location: (start_pos..lexer.last_range.end).into(),
             },
@@ -587,7 +1092,11 @@ fn parse_type_reference_type_definition(
 ) -> Option<(DataTypeDeclaration, Option<AstStatement>)> {
     let start = lexer.location().get_start();
     //Subrange
-    let referenced_type = lexer.slice_and_advance();
+    let mut referenced_type = lexer.slice_and_advance();
+    //the referenced type may be qualified with its namespace (e.g. `A.B.MyFb`)
+    while lexer.allow(&KeywordDot) {
+        referenced_type = get_qualified_name(&referenced_type, &lexer.slice_and_advance());
+    }
 
     let bounds = if lexer.allow(&KeywordParensOpen) {
         // INT (..) :=
@@ -689,60 +1198,98 @@ fn parse_enum_type_definition(
     name: Option<String>,
 ) -> Option<(DataTypeDeclaration, Option<AstStatement>)> {
     let start = lexer.last_range.start;
-    let elements = parse_any_in_region(lexer, vec![KeywordParensClose], |lexer| {
+    let (elements, values) = parse_any_in_region(lexer, vec![KeywordParensClose], |lexer| {
         // Parse Enum - we expect at least one element
 
         let mut elements = Vec::new();
+        let mut values = Vec::new();
         //we expect at least one element
         if lexer.token == Identifier {
             elements.push(lexer.slice_and_advance());
+            values.push(parse_enum_element_value(lexer));
         }
         //parse additional elements separated by ,
         while lexer.allow(&KeywordComma) {
             expect_token!(lexer, Identifier, None);
             elements.push(lexer.slice_and_advance());
+            values.push(parse_enum_element_value(lexer));
         }
-        Some(elements)
+        Some((elements, values))
     })?;
 
+    //the enum's base type follows the elements (e.g. `(Idle, Run) UINT`), it defaults to DINT
+    let numeric_type = if lexer.token == Identifier {
+        lexer.slice_and_advance()
+    } else {
+        crate::typesystem::DINT_TYPE.to_string()
+    };
+
     Some((
         DataTypeDeclaration::DataTypeDefinition {
-            data_type: DataType::EnumType { name, elements },
+            data_type: DataType::EnumType {
+                name,
+                numeric_type,
+                elements,
+                values,
+            },
             location: (start..lexer.last_range.end).into(),
         },
         None,
     ))
 }
 
+/// parses the optional explicit value of an enum element (e.g. `:= 10`)
+fn parse_enum_element_value(lexer: &mut ParseSession) -> Option<AstStatement> {
+    if lexer.allow(&KeywordAssignment) {
+        //the elements are separated by commas, so we must not parse an expression-list here
+        Some(parse_range_statement(lexer))
+    } else {
+        None
+    }
+}
+
 fn parse_array_type_definition(
     lexer: &mut ParseSession,
     name: Option<String>,
 ) -> Option<(DataTypeDeclaration, Option<AstStatement>)> {
     let start = lexer.last_range.start;
-    let range = parse_any_in_region(lexer, vec![KeywordOf], |lexer| {
+    let bounds = parse_any_in_region(lexer, vec![KeywordOf], |lexer| {
         // Parse Array range
 
         expect_token!(lexer, KeywordSquareParensOpen, None);
         lexer.advance();
 
-        let range_statement = parse_expression(lexer);
+        let bounds = if lexer.token == OperatorMultiplication {
+            ArrayBounds::VariableLength(parse_variable_length_dimensions(lexer))
+        } else {
+            ArrayBounds::Fixed(parse_expression(lexer))
+        };
 
         expect_token!(lexer, KeywordSquareParensClose, None);
         lexer.advance();
 
-        Some(range_statement)
+        Some(bounds)
     })?;
 
     let inner_type_defintion = parse_data_type_definition(lexer, None);
     inner_type_defintion.map(|(reference, initializer)| {
         let location = SourceRange::new(start..reference.get_location().get_end());
+        let referenced_type = Box::new(reference);
+        let data_type = match bounds {
+            ArrayBounds::Fixed(bounds) => DataType::ArrayType {
+                name,
+                bounds,
+                referenced_type,
+            },
+            ArrayBounds::VariableLength(dimensions) => DataType::VariableLengthArrayType {
+                name,
+                dimensions,
+                referenced_type,
+            },
+        };
         (
             DataTypeDeclaration::DataTypeDefinition {
-                data_type: DataType::ArrayType {
-                    name,
-                    bounds: range,
-                    referenced_type: Box::new(reference),
-                },
+                data_type,
                 location,
             },
             initializer,
@@ -750,6 +1297,37 @@ fn parse_array_type_definition(
     })
 }
 
+/// the bounds of an array declaration, either fixed ranges (`[1..2, 3..4]`) or
+/// a list of `*` for a variable-length array (`[*, *]`)
+enum ArrayBounds {
+    Fixed(AstStatement),
+    VariableLength(usize),
+}
+
+/// parses the comma-separated `*` of a variable-length array and returns the number of dimensions.
+/// A fixed range between the `*` is reported and skipped until the closing bracket
+fn parse_variable_length_dimensions(lexer: &mut ParseSession) -> usize {
+    let mut dimensions = 0;
+    loop {
+        if lexer.token != OperatorMultiplication {
+            lexer.accept_diagnostic(Diagnostic::unexpected_token_found(
+                format!("{:?}", OperatorMultiplication).as_str(),
+                lexer.slice(),
+                lexer.location(),
+            ));
+            while !matches!(lexer.token, KeywordSquareParensClose | KeywordOf | End) {
+                lexer.advance();
+            }
+            return dimensions + 1;
+        }
+        lexer.advance();
+        dimensions += 1;
+        if !lexer.allow(&KeywordComma) {
+            return dimensions;
+        }
+    }
+}
+
 /// parse a body and recovers until the given `end_keywords`
 fn parse_body_in_region(lexer: &mut ParseSession, end_keywords: Vec<Token>) -> Vec<AstStatement> {
     parse_any_in_region(lexer, end_keywords, |lexer| parse_body_standalone(lexer))
@@ -820,6 +1398,7 @@ fn parse_variable_block_type(block_type: &Token) -> VariableBlockType {
         KeywordVarOutput => VariableBlockType::Output,
         KeywordVarGlobal => VariableBlockType::Global,
         KeywordVarInOut => VariableBlockType::InOut,
+        KeywordVarExternal => VariableBlockType::External,
         _ => VariableBlockType::Local,
     }
 }
@@ -836,7 +1415,7 @@ fn parse_variable_block(
     let retain = lexer.allow(&KeywordRetain);
     lexer.allow(&KeywordNonRetain);
 
-    let access = parse_access_modifier(lexer);
+    let access = parse_access_modifier(lexer, AccessModifier::Protected);
 
     let variables = parse_any_in_region(lexer, vec![KeywordEndVar], |lexer| {
         parse_variable_list(lexer)
@@ -852,7 +1431,7 @@ fn parse_variable_block(
 
 fn parse_variable_list(lexer: &mut ParseSession) -> Vec<Variable> {
     let mut variables = vec![];
-    while lexer.token == Identifier {
+    while lexer.token == Identifier || lexer.token == KeywordAt {
         let mut line_vars = parse_variable_line(lexer);
         variables.append(&mut line_vars);
     }
@@ -867,7 +1446,7 @@ fn parse_variable_line(lexer: &mut ParseSession) -> Vec<Variable> {
         let identifier_end = location.get_end();
         var_names.push((lexer.slice_and_advance(), location));
 
-        if lexer.token == KeywordColon {
+        if lexer.token == KeywordColon || lexer.token == KeywordAt {
             break;
         }
 
@@ -880,6 +1459,17 @@ fn parse_variable_line(lexer: &mut ParseSession) -> Vec<Variable> {
         }
     }
 
+    let address = if lexer.allow(&KeywordAt) {
+        let address = parse_hardware_address(lexer);
+        // a located variable without a name is named after its address
+        if let (true, Some(address)) = (var_names.is_empty(), &address) {
+            var_names.push((address.get_variable_name(), address.location.clone()));
+        }
+        address
+    } else {
+        None
+    };
+
     // colon has to come before the data type
     if !lexer.allow(&KeywordColon) {
         lexer.accept_diagnostic(Diagnostic::missing_token(
@@ -897,6 +1487,7 @@ fn parse_variable_line(lexer: &mut ParseSession) -> Vec<Variable> {
                 data_type: data_type.clone(),
                 location,
                 initializer: initializer.clone(),
+                address: address.clone(),
             });
         }
     }
@@ -170,7 +170,7 @@ fn parse_additive_expression(lexer: &mut ParseSession) -> AstStatement {
 
 // Multiplication *, /, MOD
 fn parse_multiplication_expression(lexer: &mut ParseSession) -> AstStatement {
-    let left = parse_unary_expression(lexer);
+    let left = parse_exponent_expression(lexer);
     let operator = match lexer.token {
         OperatorMultiplication => Operator::Multiplication,
         OperatorDivision => Operator::Division,
@@ -187,6 +187,22 @@ fn parse_multiplication_expression(lexer: &mut ParseSession) -> AstStatement {
     }
 }
 
+// EXPONENT ** (evaluated from left to right, a unary minus binds tighter: -2 ** 2 = 4)
+fn parse_exponent_expression(lexer: &mut ParseSession) -> AstStatement {
+    let mut left = parse_unary_expression(lexer);
+    while lexer.token == OperatorExponent {
+        lexer.advance();
+        let right = parse_unary_expression(lexer);
+        left = AstStatement::BinaryExpression {
+            operator: Operator::Exponentiation,
+            left: Box::new(left),
+            right: Box::new(right),
+            id: lexer.next_id(),
+        };
+    }
+    left
+}
+
 // UNARY -x, NOT x
 fn parse_unary_expression(lexer: &mut ParseSession) -> AstStatement {
     let operator = match lexer.token {
@@ -281,6 +297,7 @@ fn parse_leaf_expression(lexer: &mut ParseSession) -> AstStatement {
             LiteralTrue => parse_bool_literal(lexer, true),
             LiteralFalse => parse_bool_literal(lexer, false),
             LiteralNull => parse_null_literal(lexer),
+            HardwareAccess => parse_hardware_reference(lexer),
             KeywordSquareParensOpen => parse_array_literal(lexer),
             _ => Err(Diagnostic::unexpected_token_found(
                 "Literal",
@@ -323,6 +340,13 @@ fn parse_leaf_expression(lexer: &mut ParseSession) -> AstStatement {
                     right: Box::new(parse_range_statement(lexer)),
                     id: lexer.next_id(),
                 }
+            } else if lexer.token == KeywordReferenceAssignment {
+                lexer.advance();
+                AstStatement::ReferenceAssignment {
+                    left: Box::new(statement),
+                    right: Box::new(parse_range_statement(lexer)),
+                    id: lexer.next_id(),
+                }
             } else {
                 statement
             }
@@ -477,6 +501,56 @@ fn parse_direct_access(lexer: &mut ParseSession) -> Result<AstStatement, Diagnos
     })
 }
 
+/// parses a hardware address like `%IX0.3` or `%QW10`, the size defaults to a bit
+pub fn parse_hardware_address(lexer: &mut ParseSession) -> Option<HardwareAddress> {
+    if lexer.token != HardwareAccess {
+        lexer.accept_diagnostic(Diagnostic::unexpected_token_found(
+            "Hardware address",
+            lexer.slice(),
+            lexer.location(),
+        ));
+        return None;
+    }
+    let location = lexer.location();
+    let slice = lexer.slice_and_advance().to_ascii_uppercase();
+    //Percent is at position 0, followed by the area and the optional size
+    let area = match &slice[1..2] {
+        "I" => HardwareArea::Input,
+        "Q" => HardwareArea::Output,
+        _ => HardwareArea::Memory,
+    };
+    let (access, address) = match &slice[2..3] {
+        "X" => (crate::ast::DirectAccess::Bit, &slice[3..]),
+        "B" => (crate::ast::DirectAccess::Byte, &slice[3..]),
+        "W" => (crate::ast::DirectAccess::Word, &slice[3..]),
+        "D" => (crate::ast::DirectAccess::DWord, &slice[3..]),
+        _ => (crate::ast::DirectAccess::Bit, &slice[2..]),
+    };
+    let address = address
+        .split('.')
+        .map(|it| it.parse::<u32>().unwrap_or(u32::MAX))
+        .collect();
+    Some(HardwareAddress {
+        area,
+        access,
+        address,
+        location,
+    })
+}
+
+/// parses a reference to a located variable by its address (e.g. `%QW10`)
+fn parse_hardware_reference(lexer: &mut ParseSession) -> Result<AstStatement, Diagnostic> {
+    let location = lexer.location();
+    let address = parse_hardware_address(lexer).ok_or_else(|| {
+        Diagnostic::unexpected_token_found("Hardware address", "", location.clone())
+    })?;
+    Ok(AstStatement::Reference {
+        name: address.get_variable_name(),
+        location,
+        id: lexer.next_id(),
+    })
+}
+
 pub fn parse_reference_access(lexer: &mut ParseSession) -> Result<AstStatement, Diagnostic> {
     let location = lexer.location();
     let reference = AstStatement::Reference {
@@ -595,8 +669,19 @@ fn parse_number<F: FromStr>(text: &str, location: &SourceRange) -> Result<F, Dia
     })
 }
 
+/// returns true if the given date or time literal is of a long type (e.g. LTIME#1s or LDT#...)
+fn is_long_literal(literal: &str) -> bool {
+    literal.starts_with('L') || literal.starts_with('l')
+}
+
+/// returns the nanoseconds of the fraction of the given seconds (e.g. 1.5 -> 500_000_000)
+fn get_nanos_of_fraction(sec: f64) -> u32 {
+    (sec.fract() * 1e9_f64).round() as u32
+}
+
 fn parse_date_from_string(
     text: &str,
+    is_long: bool,
     location: SourceRange,
     id: AstId,
 ) -> Result<AstStatement, Diagnostic> {
@@ -620,6 +705,7 @@ fn parse_date_from_string(
         year,
         month,
         day,
+        is_long,
         location,
         id,
     })
@@ -629,6 +715,7 @@ fn parse_literal_date_and_time(lexer: &mut ParseSession) -> Result<AstStatement,
     let location = lexer.location();
     //get rid of D# or DATE#
     let slice = lexer.slice_and_advance();
+    let is_long = is_long_literal(&slice);
     let hash_location = slice.find('#').unwrap_or_default();
     let last_minus_location = slice.rfind('-').unwrap();
 
@@ -647,9 +734,6 @@ fn parse_literal_date_and_time(lexer: &mut ParseSession) -> Result<AstStatement,
     let min = parse_number::<u32>(segments.next().unwrap(), &location)?;
     let sec_fraction = parse_number::<f64>(segments.next().unwrap(), &location)?;
 
-    let sec = sec_fraction as u32;
-    let milli = ((sec_fraction - sec as f64) * 1000_f64) as u32;
-
     Ok(AstStatement::LiteralDateAndTime {
         location,
         year,
@@ -657,8 +741,9 @@ fn parse_literal_date_and_time(lexer: &mut ParseSession) -> Result<AstStatement,
         day,
         hour,
         min,
-        sec,
-        milli,
+        sec: sec_fraction as u32,
+        nano: get_nanos_of_fraction(sec_fraction),
+        is_long,
         id: lexer.next_id(),
     })
 }
@@ -667,16 +752,18 @@ fn parse_literal_date(lexer: &mut ParseSession) -> Result<AstStatement, Diagnost
     let location = lexer.location();
     //get rid of D# or DATE#
     let slice = lexer.slice_and_advance();
+    let is_long = is_long_literal(&slice);
     let hash_location = slice.find('#').unwrap_or_default();
     let (_, slice) = slice.split_at(hash_location + 1); //get rid of the prefix
 
-    parse_date_from_string(slice, location, lexer.next_id())
+    parse_date_from_string(slice, is_long, location, lexer.next_id())
 }
 
 fn parse_literal_time_of_day(lexer: &mut ParseSession) -> Result<AstStatement, Diagnostic> {
     let location = lexer.location();
     //get rid of TOD# or TIME_OF_DAY#
     let slice = lexer.slice_and_advance();
+    let is_long = is_long_literal(&slice);
     let hash_location = slice.find('#').unwrap_or_default();
     let (_, slice) = slice.split_at(hash_location + 1); //get rid of the prefix
 
@@ -685,12 +772,12 @@ fn parse_literal_time_of_day(lexer: &mut ParseSession) -> Result<AstStatement, D
     let min = parse_number::<u32>(segments.next().unwrap(), &location)?;
 
     let sec = parse_number::<f64>(segments.next().unwrap(), &location)?;
-    let milli = (sec.fract() * 1000_f64) as u32;
     Ok(AstStatement::LiteralTimeOfDay {
         hour,
         min,
         sec: sec.floor() as u32,
-        milli,
+        nano: get_nanos_of_fraction(sec),
+        is_long,
         location,
         id: lexer.next_id(),
     })
@@ -707,6 +794,7 @@ fn parse_literal_time(lexer: &mut ParseSession) -> Result<AstStatement, Diagnost
     let location = lexer.location();
     //get rid of T# or TIME#
     let slice = lexer.slice_and_advance();
+    let is_long = is_long_literal(&slice);
     let (_, slice) = slice.split_at(slice.find('#').unwrap_or_default() + 1); //get rid of the prefix
 
     let mut chars = slice.char_indices();
@@ -794,6 +882,7 @@ fn parse_literal_time(lexer: &mut ParseSession) -> Result<AstStatement, Diagnost
         micro: values[POS_US].unwrap_or_default(),
         nano: values[POS_NS].map(|it| it as u32).unwrap_or(0u32),
         negative: is_negative,
+        is_long,
         location,
         id: lexer.next_id(),
     })
@@ -803,14 +892,78 @@ fn trim_quotes(quoted_string: &str) -> String {
     quoted_string[1..quoted_string.len() - 1].to_string()
 }
 
+/// decodes the escape sequences of the current string literal (`$$`, `$'`, `$"`, `$L`, `$N`,
+/// `$P`, `$R`, `$T` and the hex codes `$hh` for STRINGs or `$hhhh` for WSTRINGs)
+///
+/// malformed escape sequences are reported and remain in the string as they are
+fn handle_escape_sequences(lexer: &mut ParseSession, is_wide: bool) -> String {
+    let literal_start = lexer.range().start;
+    let value = trim_quotes(lexer.slice());
+    let hex_digits = if is_wide { 4 } else { 2 };
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c != '$' {
+            result.push(c);
+            continue;
+        }
+        let decoded = match chars.next().map(|(_, it)| it) {
+            Some('$') => Some('$'),
+            Some('\'') => Some('\''),
+            Some('"') => Some('"'),
+            Some('L') | Some('l') | Some('N') | Some('n') => Some('\n'),
+            Some('P') | Some('p') => Some('\u{c}'),
+            Some('R') | Some('r') => Some('\r'),
+            Some('T') | Some('t') => Some('\t'),
+            Some(first) if first.is_ascii_hexdigit() => {
+                let mut code = first.to_string();
+                while code.len() < hex_digits {
+                    match chars.peek() {
+                        Some((_, it)) if it.is_ascii_hexdigit() => {
+                            code.push(*it);
+                            chars.next();
+                        }
+                        _ => break,
+                    }
+                }
+                if code.len() == hex_digits {
+                    u32::from_str_radix(code.as_str(), 16)
+                        .ok()
+                        .and_then(std::char::from_u32)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+
+        let end = chars
+            .peek()
+            .map(|(it, _)| *it)
+            .unwrap_or_else(|| value.len());
+        if let Some(decoded) = decoded {
+            result.push(decoded);
+        } else {
+            let sequence = &value[start..end];
+            //the offset of 1 skips the opening quote
+            lexer.accept_diagnostic(Diagnostic::invalid_escape_sequence(
+                sequence,
+                SourceRange::new((literal_start + 1 + start)..(literal_start + 1 + end)),
+            ));
+            result.push_str(sequence);
+        }
+    }
+    result
+}
+
 fn parse_literal_string(
     lexer: &mut ParseSession,
     is_wide: bool,
 ) -> Result<AstStatement, Diagnostic> {
-    let result = lexer.slice();
     let location = lexer.location();
+    let value = handle_escape_sequences(lexer, is_wide);
     let string_literal = Ok(AstStatement::LiteralString {
-        value: trim_quotes(result),
+        value,
         is_wide,
         location,
         id: lexer.next_id(),
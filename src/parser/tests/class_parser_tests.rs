@@ -61,7 +61,7 @@ fn method_with_defaults_can_be_parsed() {
     let method = &unit.implementations[0];
 
     assert_eq!(method_pou.name, "MyClass.testMethod");
-    assert_eq!(method.access, Some(AccessModifier::Protected));
+    assert_eq!(method.access, Some(AccessModifier::Public));
     assert_eq!(method_pou.poly_mode, Some(PolymorphismMode::None));
     assert_eq!(method_pou.return_type, None);
     assert_eq!(method.overriding, false);
@@ -295,7 +295,7 @@ fn fb_two_methods_can_be_parsed() {
 
     let method2 = &unit.implementations[1];
     assert_eq!(method2.name, "MyNewFb.otherMethod");
-    assert_eq!(method2.access, Some(AccessModifier::Protected));
+    assert_eq!(method2.access, Some(AccessModifier::Public));
 }
 
 #[test]
@@ -326,3 +326,234 @@ fn fb_method_with_return_type_can_be_parsed() {
     assert_ne!(method_pou.return_type, None);
     assert_eq!(method.overriding, true);
 }
+
+#[test]
+fn class_with_super_class_can_be_parsed() {
+    let lexer = lex("CLASS MyClass EXTENDS MyBase END_CLASS");
+    let unit = parse(lexer).0;
+
+    let class = &unit.units[0];
+    assert_eq!(class.pou_type, PouType::Class);
+    assert_eq!(class.name, "MyClass");
+    assert_eq!(class.super_class, Some("MyBase".into()));
+}
+
+#[test]
+fn abstract_class_with_super_class_can_be_parsed() {
+    let lexer = lex("CLASS ABSTRACT MyClass EXTENDS MyBase METHOD testMethod END_METHOD END_CLASS");
+    let unit = parse(lexer).0;
+
+    let class = &unit.units[0];
+    assert_eq!(class.name, "MyClass");
+    assert_eq!(class.poly_mode, Some(PolymorphismMode::Abstract));
+    assert_eq!(class.super_class, Some("MyBase".into()));
+
+    let method_pou = &unit.units[1];
+    assert_eq!(method_pou.name, "MyClass.testMethod");
+    assert_eq!(method_pou.super_class, None);
+}
+
+#[test]
+fn function_block_with_super_class_can_be_parsed() {
+    let (unit, diagnostics) = parse(lex("
+        FUNCTION_BLOCK MyFb EXTENDS MyBaseFb
+            VAR_INPUT x : INT; END_VAR
+        END_FUNCTION_BLOCK
+        "));
+
+    assert_eq!(diagnostics, vec![]);
+    let fb = &unit.units[0];
+    assert_eq!(fb.pou_type, PouType::FunctionBlock);
+    assert_eq!(fb.super_class, Some("MyBaseFb".into()));
+    assert_eq!(fb.variable_blocks.len(), 1);
+}
+
+#[test]
+fn class_without_super_class_has_no_super_class() {
+    let lexer = lex("CLASS MyClass END_CLASS");
+    let unit = parse(lexer).0;
+
+    assert_eq!(unit.units[0].super_class, None);
+}
+
+#[test]
+fn interface_with_methods_can_be_parsed() {
+    let (unit, diagnostics) = parse(lex("
+        INTERFACE IDriver
+            METHOD read : INT
+                VAR_INPUT channel : INT; END_VAR
+            END_METHOD
+            METHOD reset END_METHOD
+        END_INTERFACE
+        "));
+
+    assert_eq!(diagnostics, vec![]);
+    let interface = &unit.units[0];
+    assert_eq!(interface.pou_type, PouType::Interface);
+    assert_eq!(interface.name, "IDriver");
+    assert_eq!(interface.return_type, None);
+    assert_eq!(interface.variable_blocks.len(), 0);
+
+    let read = &unit.units[1];
+    assert_eq!(read.name, "IDriver.read");
+    assert_eq!(
+        read.pou_type,
+        PouType::Method {
+            owner_class: "IDriver".into()
+        }
+    );
+    assert_ne!(read.return_type, None);
+    assert_eq!(read.variable_blocks.len(), 1);
+    assert_eq!(unit.units[2].name, "IDriver.reset");
+
+    //method prototypes have no implementation of their own
+    assert_eq!(unit.implementations.len(), 2);
+    assert!(unit
+        .implementations
+        .iter()
+        .all(|it| it.linkage == LinkageType::External && it.statements.is_empty()));
+}
+
+#[test]
+fn class_implementing_interfaces_can_be_parsed() {
+    let lexer = lex("CLASS MyClass EXTENDS MyBase IMPLEMENTS I1, I2 END_CLASS");
+    let (unit, diagnostics) = parse(lexer);
+
+    assert_eq!(diagnostics, vec![]);
+    let class = &unit.units[0];
+    assert_eq!(class.super_class, Some("MyBase".into()));
+    assert_eq!(class.interfaces, vec!["I1".to_string(), "I2".to_string()]);
+}
+
+#[test]
+fn function_block_implementing_an_interface_can_be_parsed() {
+    let (unit, diagnostics) = parse(lex("
+        FUNCTION_BLOCK MyFb IMPLEMENTS IDriver
+            METHOD read : INT END_METHOD
+        END_FUNCTION_BLOCK
+        "));
+
+    assert_eq!(diagnostics, vec![]);
+    let fb = &unit.units[0];
+    assert_eq!(fb.pou_type, PouType::FunctionBlock);
+    assert_eq!(fb.interfaces, vec!["IDriver".to_string()]);
+    assert_eq!(unit.units[1].name, "MyFb.read");
+}
+
+#[test]
+fn properties_are_parsed_as_getter_and_setter_methods() {
+    let (unit, diagnostics) = parse(lex("
+        CLASS MyClass
+            VAR _speed : INT; END_VAR
+            PROPERTY PUBLIC speed : INT
+                GET
+                    speed := _speed;
+                END_GET
+                SET
+                    VAR tmp : INT; END_VAR
+                    _speed := speed;
+                END_SET
+            END_PROPERTY
+        END_CLASS
+        "));
+
+    assert_eq!(diagnostics, vec![]);
+    let getter = &unit.units[1];
+    assert_eq!(getter.name, "MyClass.__get_speed");
+    assert_eq!(
+        getter.pou_type,
+        PouType::Method {
+            owner_class: "MyClass".into()
+        }
+    );
+    assert_eq!(
+        getter
+            .return_type
+            .as_ref()
+            .and_then(DataTypeDeclaration::get_name),
+        Some("INT")
+    );
+    assert_eq!(getter.get_return_name(), "speed");
+    assert_eq!(getter.variable_blocks.len(), 0);
+
+    //the setter receives the new value via an input named like the property
+    let setter = &unit.units[2];
+    assert_eq!(setter.name, "MyClass.__set_speed");
+    assert_eq!(setter.return_type, None);
+    assert_eq!(setter.variable_blocks.len(), 2);
+    assert_eq!(
+        setter.variable_blocks[0].variable_block_type,
+        VariableBlockType::Input
+    );
+    assert_eq!(setter.variable_blocks[0].variables[0].name, "speed");
+    assert_eq!(
+        setter.variable_blocks[1].variable_block_type,
+        VariableBlockType::Local
+    );
+
+    assert_eq!(unit.implementations.len(), 2);
+    assert_eq!(unit.implementations[0].name, "MyClass.__get_speed");
+    assert_eq!(unit.implementations[0].access, Some(AccessModifier::Public));
+    assert_eq!(unit.implementations[1].name, "MyClass.__set_speed");
+    assert_eq!(unit.implementations[1].statements.len(), 1);
+}
+
+#[test]
+fn property_with_only_a_getter_can_be_parsed_in_a_function_block() {
+    let (unit, diagnostics) = parse(lex("
+        FUNCTION_BLOCK MyFb
+            PROPERTY speed : INT
+                GET speed := 7; END_GET
+            END_PROPERTY
+            METHOD reset END_METHOD
+        END_FUNCTION_BLOCK
+        "));
+
+    assert_eq!(diagnostics, vec![]);
+    assert_eq!(unit.units.len(), 3);
+    assert_eq!(unit.units[1].name, "MyFb.__get_speed");
+    assert_eq!(unit.units[2].name, "MyFb.reset");
+}
+
+#[test]
+fn property_accessors_can_declare_their_own_access_modifier() {
+    let (unit, diagnostics) = parse(lex("
+        CLASS MyClass
+            PROPERTY speed : INT
+                GET speed := 7; END_GET
+                SET PRIVATE END_SET
+            END_PROPERTY
+            METHOD reset END_METHOD
+        END_CLASS
+        "));
+
+    assert_eq!(diagnostics, vec![]);
+    let getter = &unit.implementations[0];
+    assert_eq!(getter.access, Some(AccessModifier::Public));
+    let setter = &unit.implementations[1];
+    assert_eq!(setter.access, Some(AccessModifier::Private));
+    let method = &unit.implementations[2];
+    assert_eq!(method.access, Some(AccessModifier::Public));
+}
+
+#[test]
+fn get_and_set_can_be_used_as_names_outside_of_properties() {
+    let (unit, diagnostics) = parse(lex("
+        CLASS MyClass
+            VAR get : INT; END_VAR
+            PROPERTY value : INT
+                GET value := get; END_GET
+                SET get := value; END_SET
+            END_PROPERTY
+            METHOD set : INT
+                set := get;
+            END_METHOD
+        END_CLASS
+        "));
+
+    assert_eq!(diagnostics, vec![]);
+    assert_eq!(unit.units[0].variable_blocks[0].variables[0].name, "get");
+    assert_eq!(unit.units[1].name, "MyClass.__get_value");
+    assert_eq!(unit.units[2].name, "MyClass.__set_value");
+    assert_eq!(unit.units[3].name, "MyClass.set");
+}
@@ -1,4 +1,5 @@
 use crate::{
+    ast::*,
     parser::{parse, tests::lex},
     Diagnostic,
 };
@@ -146,3 +147,197 @@ fn two_actions_parsed() {
     assert_eq!(prg2.name, "fuz.bar");
     assert_eq!(prg2.type_name, "fuz");
 }
+
+#[test]
+fn declarations_in_a_namespace_are_qualified_with_its_name() {
+    let lexer = lex(r"
+        NAMESPACE Lib.Math
+            VAR_GLOBAL g : INT; END_VAR
+            TYPE MyInt : INT; END_TYPE
+            FUNCTION_BLOCK fb
+                METHOD m END_METHOD
+            END_FUNCTION_BLOCK
+            ACTIONS fb
+                ACTION act END_ACTION
+            END_ACTIONS
+            NAMESPACE Nested
+                FUNCTION foo : INT END_FUNCTION
+            END_NAMESPACE
+        END_NAMESPACE
+        PROGRAM prg END_PROGRAM
+        ");
+    let (unit, diagnostics) = parse(lexer);
+
+    assert_eq!(diagnostics, vec![]);
+    assert_eq!(unit.global_vars[0].variables[0].name, "Lib.Math.g");
+    assert_eq!(unit.types[0].data_type.get_name(), Some("Lib.Math.MyInt"));
+
+    let pous = unit
+        .units
+        .iter()
+        .map(|it| it.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        pous,
+        vec!["Lib.Math.fb", "Lib.Math.fb.m", "Lib.Math.Nested.foo", "prg"]
+    );
+    assert_eq!(
+        unit.units[1].pou_type,
+        PouType::Method {
+            owner_class: "Lib.Math.fb".into()
+        }
+    );
+
+    let implementations = unit
+        .implementations
+        .iter()
+        .map(|it| (it.name.as_str(), it.type_name.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        implementations,
+        vec![
+            ("Lib.Math.fb.m", "Lib.Math.fb.m"),
+            ("Lib.Math.fb", "Lib.Math.fb"),
+            ("Lib.Math.fb.act", "Lib.Math.fb"),
+            ("Lib.Math.Nested.foo", "Lib.Math.Nested.foo"),
+            ("prg", "prg"),
+        ]
+    );
+
+    assert_eq!(
+        unit.namespace_scopes.get("lib.math.nested.foo"),
+        Some(&NamespaceScope {
+            namespace: Some("Lib.Math.Nested".into()),
+            usings: vec![],
+        })
+    );
+    assert_eq!(unit.namespace_scopes.get("prg"), None);
+}
+
+#[test]
+fn using_directives_are_parsed_for_files_and_pous() {
+    let lexer = lex(r"
+        USING Lib.Math;
+        FUNCTION_BLOCK fb USING Lib.Strings, Other; EXTENDS Lib.Base
+            VAR x : Lib.Math.MyInt; END_VAR
+        END_FUNCTION_BLOCK
+        FUNCTION foo : INT
+        USING Other;
+        END_FUNCTION
+        ");
+    let (unit, diagnostics) = parse(lexer);
+
+    assert_eq!(diagnostics, vec![]);
+    assert_eq!(unit.units[0].super_class, Some("Lib.Base".into()));
+    assert_eq!(
+        unit.units[0].variable_blocks[0].variables[0]
+            .data_type
+            .get_name(),
+        Some("Lib.Math.MyInt")
+    );
+    assert_eq!(
+        unit.namespace_scopes.get("fb").map(|it| &it.usings),
+        Some(&vec![
+            "Lib.Math".to_string(),
+            "Lib.Strings".to_string(),
+            "Other".to_string()
+        ])
+    );
+    assert_eq!(
+        unit.namespace_scopes.get("foo").map(|it| &it.usings),
+        Some(&vec!["Lib.Math".to_string(), "Other".to_string()])
+    );
+}
+
+#[test]
+fn unclosed_namespace_is_reported() {
+    let lexer = lex("NAMESPACE Lib PROGRAM prg END_PROGRAM");
+    let (unit, diagnostics) = parse(lexer);
+
+    assert_eq!(unit.units[0].name, "Lib.prg");
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::unexpected_token_found(
+            "KeywordEndNamespace",
+            "",
+            (37..37).into()
+        )]
+    );
+}
+
+#[test]
+fn configuration_with_resources_tasks_and_program_instances_is_parsed() {
+    let lexer = lex(r"
+        CONFIGURATION config
+            VAR_GLOBAL g : INT; END_VAR
+            RESOURCE res ON PLC
+                TASK fast(INTERVAL := T#10ms, PRIORITY := 1);
+                TASK background();
+                PROGRAM inst WITH fast : prg;
+                PROGRAM idle : prg;
+            END_RESOURCE
+        END_CONFIGURATION
+        ");
+    let (unit, diagnostics) = parse(lexer);
+
+    assert_eq!(diagnostics, vec![]);
+    assert_eq!(unit.global_vars[0].variables[0].name, "g");
+
+    let configuration = &unit.configurations[0];
+    assert_eq!(configuration.name, "config");
+    let resource = &configuration.resources[0];
+    assert_eq!(resource.name, "res");
+    assert_eq!(resource.processing_unit, Some("PLC".into()));
+
+    let tasks = resource
+        .tasks
+        .iter()
+        .map(|it| {
+            (
+                it.name.as_str(),
+                it.interval.is_some(),
+                it.priority.is_some(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        tasks,
+        vec![("fast", true, true), ("background", false, false)]
+    );
+    assert!(matches!(
+        resource.tasks[0].priority,
+        Some(AstStatement::LiteralInteger { value: 1, .. })
+    ));
+
+    let programs = resource
+        .programs
+        .iter()
+        .map(|it| (it.name.as_str(), it.task.as_deref(), it.program.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        programs,
+        vec![("inst", Some("fast"), "prg"), ("idle", None, "prg")]
+    );
+}
+
+#[test]
+fn unknown_task_parameters_are_reported() {
+    let lexer = lex(r"
+        CONFIGURATION config
+            RESOURCE res
+                TASK fast(CYCLE := T#10ms);
+            END_RESOURCE
+        END_CONFIGURATION
+        ");
+    let (unit, diagnostics) = parse(lexer);
+
+    assert_eq!(unit.configurations[0].resources[0].tasks[0].interval, None);
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::unexpected_token_found(
+            "INTERVAL or PRIORITY",
+            "CYCLE",
+            (81..86).into()
+        )]
+    );
+}
@@ -367,6 +367,60 @@ fn multiplication_expressions_parse() {
     assert_eq!(ast_string, expected_ast);
 }
 
+#[test]
+fn exponent_expressions_bind_stronger_than_multiplication_and_weaker_than_negation() {
+    let lexer = super::lex("PROGRAM exp 2*3**a**2; -a ** b; -2 ** 2; END_PROGRAM");
+    let result = parse(lexer).0;
+
+    let prg = &result.implementations[0];
+    let ast_string = format!("{:#?}", prg.statements);
+    let expected_ast = r#"[
+    BinaryExpression {
+        operator: Multiplication,
+        left: LiteralInteger {
+            value: 2,
+        },
+        right: BinaryExpression {
+            operator: Exponentiation,
+            left: BinaryExpression {
+                operator: Exponentiation,
+                left: LiteralInteger {
+                    value: 3,
+                },
+                right: Reference {
+                    name: "a",
+                },
+            },
+            right: LiteralInteger {
+                value: 2,
+            },
+        },
+    },
+    BinaryExpression {
+        operator: Exponentiation,
+        left: UnaryExpression {
+            operator: Minus,
+            value: Reference {
+                name: "a",
+            },
+        },
+        right: Reference {
+            name: "b",
+        },
+    },
+    BinaryExpression {
+        operator: Exponentiation,
+        left: LiteralInteger {
+            value: -2,
+        },
+        right: LiteralInteger {
+            value: 2,
+        },
+    },
+]"#;
+    assert_eq!(ast_string, expected_ast);
+}
+
 #[test]
 fn addition_ast_test() {
     let lexer = super::lex("PROGRAM exp 1+2; END_PROGRAM");
@@ -823,11 +877,13 @@ fn literal_date_test() {
         year: 1984,
         month: 10,
         day: 1,
+        is_long: false,
     },
     LiteralDate {
         year: 2021,
         month: 4,
         day: 20,
+        is_long: false,
     },
 ]"#;
     assert_eq!(ast_string, expected_ast);
@@ -862,6 +918,7 @@ fn literal_time_test() {
         micro: 0.0,
         nano: 0,
         negative: false,
+        is_long: false,
     },
     LiteralTime {
         day: 12.4,
@@ -872,6 +929,7 @@ fn literal_time_test() {
         micro: 0.0,
         nano: 0,
         negative: false,
+        is_long: false,
     },
     LiteralTime {
         day: 0.0,
@@ -882,6 +940,7 @@ fn literal_time_test() {
         micro: 0.0,
         nano: 0,
         negative: true,
+        is_long: false,
     },
     LiteralTime {
         day: 0.0,
@@ -892,6 +951,7 @@ fn literal_time_test() {
         micro: 0.0,
         nano: 0,
         negative: false,
+        is_long: false,
     },
     LiteralTime {
         day: 0.0,
@@ -902,6 +962,7 @@ fn literal_time_test() {
         micro: 0.0,
         nano: 0,
         negative: false,
+        is_long: false,
     },
     LiteralTime {
         day: 12.0,
@@ -912,6 +973,7 @@ fn literal_time_test() {
         micro: 0.0,
         nano: 0,
         negative: false,
+        is_long: false,
     },
     LiteralTime {
         day: 0.0,
@@ -922,6 +984,7 @@ fn literal_time_test() {
         micro: 0.0,
         nano: 0,
         negative: true,
+        is_long: false,
     },
     LiteralTime {
         day: 0.0,
@@ -932,6 +995,7 @@ fn literal_time_test() {
         micro: 0.0,
         nano: 0,
         negative: false,
+        is_long: false,
     },
     LiteralTime {
         day: 4.0,
@@ -942,6 +1006,7 @@ fn literal_time_test() {
         micro: 4.0,
         nano: 8,
         negative: false,
+        is_long: false,
     },
 ]"#;
     assert_eq!(ast_string, expected_ast);
@@ -968,37 +1033,43 @@ fn literal_time_of_day_test() {
         hour: 12,
         min: 0,
         sec: 0,
-        milli: 0,
+        nano: 0,
+        is_long: false,
     },
     LiteralTimeOfDay {
         hour: 0,
         min: 12,
         sec: 0,
-        milli: 0,
+        nano: 0,
+        is_long: false,
     },
     LiteralTimeOfDay {
         hour: 0,
         min: 0,
         sec: 12,
-        milli: 0,
+        nano: 0,
+        is_long: false,
     },
     LiteralTimeOfDay {
         hour: 4,
         min: 16,
         sec: 22,
-        milli: 0,
+        nano: 0,
+        is_long: false,
     },
     LiteralTimeOfDay {
         hour: 4,
         min: 16,
         sec: 22,
-        milli: 100,
+        nano: 100000000,
+        is_long: false,
     },
     LiteralTimeOfDay {
         hour: 4,
         min: 16,
         sec: 22,
-        milli: 1,
+        nano: 1000000,
+        is_long: false,
     },
 ]"#;
     assert_eq!(ast_string, expected_ast);
@@ -1025,7 +1096,18 @@ fn literal_date_and_time_test() {
         hour: 16,
         min: 40,
         sec: 22,
-        milli: 0,
+        nano: 0,
+        is_long: false,
+    },
+    LiteralDateAndTime {
+        year: 2021,
+        month: 4,
+        day: 20,
+        hour: 22,
+        min: 33,
+        sec: 14,
+        nano: 0,
+        is_long: false,
     },
     LiteralDateAndTime {
         year: 2021,
@@ -1034,7 +1116,33 @@ fn literal_date_and_time_test() {
         hour: 22,
         min: 33,
         sec: 14,
-        milli: 0,
+        nano: 999000000,
+        is_long: false,
+    },
+]"#;
+    assert_eq!(ast_string, expected_ast);
+}
+
+#[test]
+fn long_date_and_time_literals_test() {
+    let lexer = super::lex(
+        "
+        PROGRAM exp
+            LDATE#1984-10-01;
+            LDT#2021-04-20-22:33:14.123456789;
+            LTOD#04:16:22.000000001;
+            LT#-1s2ns;
+        END_PROGRAM
+        ",
+    );
+    let result = parse(lexer).0;
+    let ast_string = format!("{:#?}", &result.implementations[0].statements);
+    let expected_ast = r#"[
+    LiteralDate {
+        year: 1984,
+        month: 10,
+        day: 1,
+        is_long: true,
     },
     LiteralDateAndTime {
         year: 2021,
@@ -1043,7 +1151,26 @@ fn literal_date_and_time_test() {
         hour: 22,
         min: 33,
         sec: 14,
-        milli: 999,
+        nano: 123456789,
+        is_long: true,
+    },
+    LiteralTimeOfDay {
+        hour: 4,
+        min: 16,
+        sec: 22,
+        nano: 1,
+        is_long: true,
+    },
+    LiteralTime {
+        day: 0.0,
+        hour: 0.0,
+        min: 0.0,
+        sec: 1.0,
+        milli: 0.0,
+        micro: 0.0,
+        nano: 2,
+        negative: true,
+        is_long: true,
     },
 ]"#;
     assert_eq!(ast_string, expected_ast);
@@ -2005,6 +2132,42 @@ fn wide_string_can_be_parsed() {
     assert_eq!(ast_string, expected_ast);
 }
 
+#[test]
+fn escape_sequences_in_strings_are_decoded() {
+    let lexer = super::lex(
+        r#"
+        PROGRAM buz
+            'a$$b$'c"d$Ne$lf$Pg$rh$Ti$0D$0A';
+            "a$$b'c$"d$Ne$Tf$0041$00e4";
+        END_PROGRAM
+        "#,
+    );
+    let (result, diagnostics) = parse(lexer);
+    assert_eq!(diagnostics, vec![]);
+
+    let statements = &result.implementations[0].statements;
+    assert_eq!(
+        format!("{:?}", statements),
+        format!(
+            "{:?}",
+            vec![
+                AstStatement::LiteralString {
+                    value: "a$b'c\"d\ne\nf\u{c}g\rh\ti\r\n".to_string(),
+                    is_wide: false,
+                    location: SourceRange::undefined(),
+                    id: 0,
+                },
+                AstStatement::LiteralString {
+                    value: "a$b'c\"d\ne\tfAä".to_string(),
+                    is_wide: true,
+                    location: SourceRange::undefined(),
+                    id: 0,
+                },
+            ]
+        )
+    );
+}
+
 #[test]
 fn arrays_can_be_parsed() {
     let lexer = super::lex(
@@ -2569,6 +2732,8 @@ fn sized_string_as_function_return() {
     let expected = Pou {
         name: "foo".into(),
         poly_mode: None,
+        super_class: None,
+        interfaces: vec![],
         pou_type: crate::ast::PouType::Function,
         return_type: Some(DataTypeDeclaration::DataTypeDefinition {
             data_type: DataType::StringType {
@@ -2602,6 +2767,8 @@ fn array_type_as_function_return() {
     let expected = Pou {
         name: "foo".into(),
         poly_mode: None,
+        super_class: None,
+        interfaces: vec![],
         pou_type: crate::ast::PouType::Function,
         return_type: Some(DataTypeDeclaration::DataTypeDefinition {
             data_type: DataType::ArrayType {
@@ -2633,3 +2800,47 @@ fn array_type_as_function_return() {
     assert_eq!(format!("{:?}", ast.units[0]), format!("{:?}", expected));
     assert_eq!(diagnostics.is_empty(), true);
 }
+
+#[test]
+fn reference_assignments_are_parsed() {
+    let lexer = super::lex(
+        "
+    PROGRAM prg
+    r REF= x;
+    r REF= NULL;
+    r := x;
+    END_PROGRAM
+    ",
+    );
+    let parse_result = parse(lexer).0;
+
+    let statements = &parse_result.implementations[0].statements;
+    let ast_string = format!("{:#?}", statements);
+
+    let expected_ast = r#"[
+    ReferenceAssignment {
+        left: Reference {
+            name: "r",
+        },
+        right: Reference {
+            name: "x",
+        },
+    },
+    ReferenceAssignment {
+        left: Reference {
+            name: "r",
+        },
+        right: LiteralNull,
+    },
+    Assignment {
+        left: Reference {
+            name: "r",
+        },
+        right: Reference {
+            name: "x",
+        },
+    },
+]"#;
+
+    assert_eq!(ast_string, expected_ast);
+}
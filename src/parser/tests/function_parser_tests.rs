@@ -138,6 +138,7 @@ fn varargs_parameters_can_be_parsed() {
                     },
                     initializer: None,
                     location: SourceRange::undefined(),
+                    address: None,
                 },
                 Variable {
                     name: "args2".into(),
@@ -154,11 +155,14 @@ fn varargs_parameters_can_be_parsed() {
                     },
                     initializer: None,
                     location: SourceRange::undefined(),
+                    address: None,
                 },
             ],
         }],
         location: SourceRange::undefined(),
         poly_mode: None,
+        super_class: None,
+        interfaces: vec![],
     };
     assert_eq!(format!("{:#?}", expected), format!("{:#?}", x).as_str());
 }
@@ -46,6 +46,8 @@ fn exponent_literals_parsed_as_variables() {
         name: "E1".into(),
         pou_type: PouType::Function,
         poly_mode: None,
+        super_class: None,
+        interfaces: vec![],
         return_type: Some(DataTypeDeclaration::DataTypeReference {
             referenced_type: "E2".into(),
             location: SourceRange::undefined(),
@@ -64,6 +66,7 @@ fn exponent_literals_parsed_as_variables() {
                 },
                 initializer: None,
                 location: SourceRange::undefined(),
+                address: None,
             }],
         }],
         location: SourceRange::undefined(),
@@ -622,6 +625,7 @@ fn id_implementation_for_all_statements() {
             day: 0,
             month: 0,
             year: 0,
+            is_long: false,
             location: (1..5).into(),
             id: 7
         }
@@ -634,9 +638,10 @@ fn id_implementation_for_all_statements() {
             month: 0,
             year: 0,
             hour: 0,
-            milli: 0,
+            nano: 0,
             min: 0,
             sec: 0,
+            is_long: false,
             location: (1..5).into(),
             id: 7
         }
@@ -672,6 +677,7 @@ fn id_implementation_for_all_statements() {
             micro: 0.0,
             nano: 0,
             negative: false,
+            is_long: false,
             location: (1..5).into(),
             id: 7
         }
@@ -683,7 +689,8 @@ fn id_implementation_for_all_statements() {
             hour: 0,
             min: 0,
             sec: 0,
-            milli: 0,
+            nano: 0,
+            is_long: false,
             location: (1..5).into(),
             id: 7
         }
@@ -904,6 +911,7 @@ fn location_implementation_for_all_statements() {
             day: 0,
             month: 0,
             year: 0,
+            is_long: false,
             location: (1..5).into(),
             id: 7
         }
@@ -916,9 +924,10 @@ fn location_implementation_for_all_statements() {
             month: 0,
             year: 0,
             hour: 0,
-            milli: 0,
+            nano: 0,
             min: 0,
             sec: 0,
+            is_long: false,
             location: (1..5).into(),
             id: 7
         }
@@ -954,6 +963,7 @@ fn location_implementation_for_all_statements() {
             micro: 0.0,
             nano: 0,
             negative: false,
+            is_long: false,
             location: (1..5).into(),
             id: 7
         }
@@ -965,7 +975,8 @@ fn location_implementation_for_all_statements() {
             hour: 0,
             min: 0,
             sec: 0,
-            milli: 0,
+            nano: 0,
+            is_long: false,
             location: (1..5).into(),
             id: 7
         }
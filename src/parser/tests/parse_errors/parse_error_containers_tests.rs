@@ -269,6 +269,7 @@ fn unclosed_var_container() {
                     },
                     initializer: None,
                     location: SourceRange::undefined(),
+                    address: None,
                 }]
             }
         )
@@ -328,3 +329,23 @@ fn a_variable_declaration_block_needs_to_end_with_endvar() {
         ]
     );
 }
+
+#[test]
+fn configuration_keywords_cannot_be_used_as_variable_names() {
+    //TASK, ON and WITH are reserved keywords of the configuration, unlike GET and SET
+    let lexer = lex(r"
+            PROGRAM prg
+            VAR task : INT; END_VAR
+            END_PROGRAM
+            ");
+
+    let (_, diagnostics) = parse(lexer);
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::unexpected_token_found(
+            "KeywordEndVar",
+            "'task : INT;'",
+            (41..52).into()
+        )]
+    );
+}
@@ -219,3 +219,30 @@ fn literal_cast_with_space() {
         diagnostics
     );
 }
+
+#[test]
+fn malformed_escape_sequences_in_strings_are_reported() {
+    let lexer = lex(r#"
+        PROGRAM exp
+            'a$Xb$4';
+            "$0041$041";
+        END_PROGRAM
+        "#);
+    let (cu, diagnostics) = parse(lexer);
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::invalid_escape_sequence("$X", (35..37).into()),
+            Diagnostic::invalid_escape_sequence("$4", (38..40).into()),
+            Diagnostic::invalid_escape_sequence("$041", (61..65).into()),
+        ]
+    );
+
+    //malformed sequences remain in the string
+    if let AstStatement::LiteralString { value, .. } = &cu.implementations[0].statements[1] {
+        assert_eq!(value, "A$041");
+    } else {
+        panic!("expected a string literal");
+    }
+}
@@ -282,6 +282,7 @@ fn invalid_variable_name_error_recovery() {
                     },
                     initializer: None,
                     location: SourceRange::undefined(),
+                    address: None,
                 },],
                 variable_block_type: VariableBlockType::Local,
             }
@@ -1030,6 +1031,8 @@ fn pointer_type_without_to_test() {
                 referenced_type: "INT".to_string(),
                 location: SourceRange::undefined(),
             }),
+            is_ref_to: false,
+            auto_deref: false,
         },
         location: SourceRange::undefined(),
         initializer: None,
@@ -1066,6 +1069,8 @@ fn pointer_type_with_wrong_keyword_to_test() {
                 referenced_type: "tu".to_string(),
                 location: SourceRange::undefined(),
             }),
+            is_ref_to: false,
+            auto_deref: false,
         },
         location: SourceRange::undefined(),
         initializer: None,
@@ -101,3 +101,25 @@ fn simple_program_with_var_inout_can_be_parsed() {
 }"#;
     assert_eq!(ast_string, expected_ast);
 }
+
+#[test]
+fn simple_program_with_var_external_can_be_parsed() {
+    let lexer = lex("PROGRAM buz VAR_EXTERNAL x : INT; END_VAR END_PROGRAM");
+    let result = parse(lexer).0;
+
+    let prg = &result.units[0];
+    let variable_block = &prg.variable_blocks[0];
+    let ast_string = format!("{:#?}", variable_block);
+    let expected_ast = r#"VariableBlock {
+    variables: [
+        Variable {
+            name: "x",
+            data_type: DataTypeReference {
+                referenced_type: "INT",
+            },
+        },
+    ],
+    variable_block_type: External,
+}"#;
+    assert_eq!(ast_string, expected_ast);
+}
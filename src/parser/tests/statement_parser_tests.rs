@@ -149,12 +149,15 @@ fn inline_enum_declaration_can_be_parsed() {
         data_type: DataTypeDeclaration::DataTypeDefinition {
             data_type: DataType::EnumType {
                 name: None,
+                numeric_type: "DINT".to_string(),
                 elements: vec!["red".to_string(), "yellow".to_string(), "green".to_string()],
+                values: vec![None, None, None],
             },
             location: SourceRange::undefined(),
         },
         initializer: None,
         location: SourceRange::undefined(),
+        address: None,
     };
     let expected_ast = format!("{:#?}", &v);
     assert_eq!(ast_string, expected_ast);
@@ -185,11 +188,17 @@ fn multilevel_inline_struct_and_enum_declaration_can_be_parsed() {
                     data_type: DataTypeDefinition {
                         data_type: EnumType {
                             name: None,
+                            numeric_type: "DINT",
                             elements: [
                                 "red",
                                 "yellow",
                                 "green",
                             ],
+                            values: [
+                                None,
+                                None,
+                                None,
+                            ],
                         },
                     },
                 },
@@ -33,6 +33,7 @@ fn simple_struct_type_can_be_parsed() {
                         },
                         initializer: None,
                         location: SourceRange::undefined(),
+                        address: None,
                     },
                     Variable {
                         name: "Two".to_string(),
@@ -42,6 +43,7 @@ fn simple_struct_type_can_be_parsed() {
                         },
                         initializer: None,
                         location: SourceRange::undefined(),
+                        address: None,
                     },
                     Variable {
                         name: "Three".to_string(),
@@ -51,6 +53,75 @@ fn simple_struct_type_can_be_parsed() {
                         },
                         initializer: None,
                         location: SourceRange::undefined(),
+                        address: None,
+                    },
+                ),
+            },
+            initializer: None,
+            location: SourceRange::undefined(),
+        }
+    );
+    assert_eq!(ast_string, expected_ast);
+}
+
+#[test]
+fn simple_union_type_can_be_parsed() {
+    let (result, diagnostics) = parse(lex(r#"
+        TYPE SampleUnion :
+            UNION
+                Whole : DINT;
+                Bytes : ARRAY[0..3] OF BYTE;
+            END_UNION
+        END_TYPE 
+        "#));
+
+    assert_eq!(diagnostics, vec![]);
+    let ast_string = format!("{:#?}", &result.types[0]);
+
+    let expected_ast = format!(
+        "{:#?}",
+        &UserTypeDeclaration {
+            data_type: DataType::UnionType {
+                name: Some("SampleUnion".to_string(),),
+                variables: vec!(
+                    Variable {
+                        name: "Whole".to_string(),
+                        data_type: DataTypeDeclaration::DataTypeReference {
+                            referenced_type: "DINT".to_string(),
+                            location: SourceRange::undefined(),
+                        },
+                        initializer: None,
+                        location: SourceRange::undefined(),
+                        address: None,
+                    },
+                    Variable {
+                        name: "Bytes".to_string(),
+                        data_type: DataTypeDeclaration::DataTypeDefinition {
+                            data_type: DataType::ArrayType {
+                                name: None,
+                                bounds: AstStatement::RangeStatement {
+                                    start: Box::new(AstStatement::LiteralInteger {
+                                        value: 0,
+                                        location: SourceRange::undefined(),
+                                        id: 0,
+                                    }),
+                                    end: Box::new(AstStatement::LiteralInteger {
+                                        value: 3,
+                                        location: SourceRange::undefined(),
+                                        id: 0,
+                                    }),
+                                    id: 0,
+                                },
+                                referenced_type: Box::new(DataTypeDeclaration::DataTypeReference {
+                                    referenced_type: "BYTE".to_string(),
+                                    location: SourceRange::undefined(),
+                                }),
+                            },
+                            location: SourceRange::undefined(),
+                        },
+                        initializer: None,
+                        location: SourceRange::undefined(),
+                        address: None,
                     },
                 ),
             },
@@ -73,7 +144,45 @@ fn simple_enum_type_can_be_parsed() {
     let epxtected_ast = &UserTypeDeclaration {
         data_type: DataType::EnumType {
             name: Some("SampleEnum".to_string()),
+            numeric_type: "DINT".to_string(),
             elements: vec!["red".to_string(), "yellow".to_string(), "green".to_string()],
+            values: vec![None, None, None],
+        },
+        initializer: None,
+        location: SourceRange::undefined(),
+    };
+    let expected_string = format!("{:#?}", epxtected_ast);
+    assert_eq!(ast_string, expected_string);
+}
+
+#[test]
+fn enum_type_with_values_and_numeric_type_can_be_parsed() {
+    let (result, diagnostics) = parse(lex(r#"
+        TYPE State : (Idle := 0, Run := 10, Stopping, Fault := 16#FF) UINT;
+        END_TYPE 
+        "#));
+
+    assert_eq!(diagnostics, vec![]);
+    let ast_string = format!("{:#?}", &result.types[0]);
+
+    let literal = |value| {
+        Some(LiteralInteger {
+            value,
+            location: SourceRange::undefined(),
+            id: 0,
+        })
+    };
+    let epxtected_ast = &UserTypeDeclaration {
+        data_type: DataType::EnumType {
+            name: Some("State".to_string()),
+            numeric_type: "UINT".to_string(),
+            elements: vec![
+                "Idle".to_string(),
+                "Run".to_string(),
+                "Stopping".to_string(),
+                "Fault".to_string(),
+            ],
+            values: vec![literal(0), literal(10), None, literal(255)],
         },
         initializer: None,
         location: SourceRange::undefined(),
@@ -257,6 +366,7 @@ fn subrangetype_can_be_parsed() {
         },
         initializer: None,
         location: (0..0).into(),
+        address: None,
     };
     assert_eq!(format!("{:#?}", expected), format!("{:#?}", x).as_str());
 }
@@ -320,6 +430,8 @@ fn pointer_type_test() {
                 referenced_type: "INT".to_string(),
                 location: SourceRange::undefined(),
             }),
+            is_ref_to: false,
+            auto_deref: false,
         },
         location: SourceRange::undefined(),
         initializer: None,
@@ -351,6 +463,36 @@ fn ref_type_test() {
                 referenced_type: "INT".to_string(),
                 location: SourceRange::undefined(),
             }),
+            is_ref_to: true,
+            auto_deref: false,
+        },
+        location: SourceRange::undefined(),
+        initializer: None,
+    };
+    assert_eq!(
+        format!("{:#?}", expected),
+        format!("{:#?}", reference_type).as_str()
+    );
+    assert_eq!(diagnostics.len(), 0)
+}
+
+#[test]
+fn reference_to_type_test() {
+    let (result, diagnostics) = parse(lex(r#"
+        TYPE SampleReference :
+            REFERENCE TO INT;
+        END_TYPE
+        "#));
+    let reference_type = &result.types[0];
+    let expected = UserTypeDeclaration {
+        data_type: DataType::PointerType {
+            name: Some("SampleReference".into()),
+            referenced_type: Box::new(DataTypeDeclaration::DataTypeReference {
+                referenced_type: "INT".to_string(),
+                location: SourceRange::undefined(),
+            }),
+            is_ref_to: true,
+            auto_deref: true,
         },
         location: SourceRange::undefined(),
         initializer: None,
@@ -362,6 +504,50 @@ fn ref_type_test() {
     assert_eq!(diagnostics.len(), 0)
 }
 
+#[test]
+fn variable_length_array_type_test() {
+    let (result, diagnostics) = parse(lex(r#"
+        TYPE Matrix :
+            ARRAY[*, *] OF INT;
+        END_TYPE
+        "#));
+    let array_type = &result.types[0];
+    let expected = UserTypeDeclaration {
+        data_type: DataType::VariableLengthArrayType {
+            name: Some("Matrix".into()),
+            dimensions: 2,
+            referenced_type: Box::new(DataTypeDeclaration::DataTypeReference {
+                referenced_type: "INT".to_string(),
+                location: SourceRange::undefined(),
+            }),
+        },
+        location: SourceRange::undefined(),
+        initializer: None,
+    };
+    assert_eq!(
+        format!("{:#?}", expected),
+        format!("{:#?}", array_type).as_str()
+    );
+    assert_eq!(diagnostics.len(), 0)
+}
+
+#[test]
+fn variable_length_array_with_fixed_dimension_is_reported() {
+    let (_, diagnostics) = parse(lex(r#"
+        TYPE Matrix :
+            ARRAY[*, 1..2] OF INT;
+        END_TYPE
+        "#));
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::unexpected_token_found(
+            "OperatorMultiplication",
+            "1",
+            (44..45).into()
+        )]
+    );
+}
+
 #[test]
 fn global_pointer_declaration() {
     let (result, diagnostics) = parse(lex(r#"
@@ -380,11 +566,14 @@ fn global_pointer_declaration() {
                     referenced_type: "INT".to_string(),
                     location: SourceRange::undefined(),
                 }),
+                is_ref_to: true,
+                auto_deref: false,
             },
             location: SourceRange::undefined(),
         },
         initializer: None,
         location: (0..0).into(),
+        address: None,
     };
     assert_eq!(
         format!("{:#?}", expected),
@@ -400,11 +589,14 @@ fn global_pointer_declaration() {
                     referenced_type: "INT".to_string(),
                     location: SourceRange::undefined(),
                 }),
+                is_ref_to: false,
+                auto_deref: false,
             },
             location: SourceRange::undefined(),
         },
         initializer: None,
         location: (0..0).into(),
+        address: None,
     };
     assert_eq!(
         format!("{:#?}", expected),
@@ -1,4 +1,7 @@
-use crate::parser::{parse, tests::lex};
+use crate::{
+    ast::{DirectAccess, HardwareArea},
+    parser::{parse, tests::lex},
+};
 
 #[test]
 fn empty_global_vars_can_be_parsed() {
@@ -131,3 +134,55 @@ fn two_global_vars_can_be_parsed() {
 ]"#;
     assert_eq!(ast_string, expected_ast)
 }
+
+#[test]
+fn located_variables_are_parsed() {
+    let (result, diagnostics) = parse(lex(r"
+        VAR_GLOBAL
+            input1 AT %IX0.3 : BOOL;
+            AT %QW10 : WORD;
+            flag AT %m2 : BOOL;
+        END_VAR
+        "));
+
+    assert_eq!(diagnostics, vec![]);
+    let variables = &result.global_vars[0].variables;
+    let addresses = variables
+        .iter()
+        .map(|it| {
+            let address = it.address.as_ref().unwrap();
+            (
+                it.name.as_str(),
+                address.area,
+                address.access.clone(),
+                address.address.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        addresses,
+        vec![
+            ("input1", HardwareArea::Input, DirectAccess::Bit, vec![0, 3]),
+            ("__QW10", HardwareArea::Output, DirectAccess::Word, vec![10]),
+            ("flag", HardwareArea::Memory, DirectAccess::Bit, vec![2]),
+        ]
+    );
+    assert_eq!(
+        format!("{:?}", variables[0]),
+        r#"Variable { name: "input1", data_type: DataTypeReference { referenced_type: "BOOL" }, address: "%IX0.3" }"#
+    );
+}
+
+#[test]
+fn located_variables_can_be_referenced_by_their_address() {
+    let (result, ..) = parse(lex(r"
+        PROGRAM prg
+            %QW10 := %IW2;
+        END_PROGRAM
+        "));
+
+    assert_eq!(
+        format!("{:?}", result.implementations[0].statements[0]),
+        r#"Assignment { left: Reference { name: "__QW10" }, right: Reference { name: "__IW2" } }"#
+    );
+}
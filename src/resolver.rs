@@ -9,20 +9,25 @@ use indexmap::IndexMap;
 
 use crate::{
     ast::{
-        AstId, AstStatement, CompilationUnit, DataType, DataTypeDeclaration, Operator, Pou,
-        UserTypeDeclaration, Variable,
+        flatten_expression_list, AstId, AstStatement, CompilationUnit, DataType,
+        DataTypeDeclaration, Operator, Pou, UserTypeDeclaration, Variable,
     },
+    builtins::BuiltIn,
     index::{ImplementationIndexEntry, ImplementationType, Index, VariableIndexEntry},
     typesystem::{
-        self, get_bigger_type_borrow, DataTypeInformation, BOOL_TYPE, BYTE_TYPE,
-        DATE_AND_TIME_TYPE, DATE_TYPE, DINT_TYPE, DWORD_TYPE, LINT_TYPE, REAL_TYPE, STRING_TYPE,
+        self, get_bigger_type_borrow, DataTypeInformation, StringEncoding, BOOL_TYPE, BYTE_TYPE,
+        DATE_AND_TIME_TYPE, DATE_TYPE, DINT_TYPE, DWORD_TYPE, INT_TYPE, LDATE_AND_TIME_TYPE,
+        LDATE_TYPE, LINT_TYPE, LTIME_OF_DAY_TYPE, LTIME_TYPE, REAL_TYPE, STRING_TYPE,
         TIME_OF_DAY_TYPE, TIME_TYPE, VOID_TYPE, WORD_TYPE, WSTRING_TYPE,
     },
 };
 
+mod property_lowering;
 #[cfg(test)]
 mod tests;
 
+pub use property_lowering::lower_property_accesses;
+
 /// helper macro that calls visit_statement for all given statements
 /// use like `visit_all_statements!(self, ctx, stmt1, stmt2, stmt3, ...)`
 macro_rules! visit_all_statements {
@@ -106,6 +111,12 @@ pub enum StatementAnnotation {
     Type { type_name: String },
     /// a reference to a program call or reference (e.g. `PLC_PRG`)
     Program { qualified_name: String },
+    /// a reference to a property that is read via its getter and written via its setter
+    /// (e.g. `fb.x` --> `MyFb.x`)
+    Property {
+        resulting_type: String,
+        qualified_name: String,
+    },
 }
 
 impl StatementAnnotation {
@@ -163,6 +174,9 @@ impl AnnotationMap {
                 StatementAnnotation::Function { .. } => None,
                 StatementAnnotation::Type { .. } => None,
                 StatementAnnotation::Program { .. } => None,
+                StatementAnnotation::Property { resulting_type, .. } => {
+                    Some(resulting_type.as_str())
+                }
             })
             .and_then(|type_name| index.get_type(type_name).ok())
     }
@@ -258,11 +272,14 @@ impl<'i> TypeAnnotator<'i> {
 
     fn visit_data_type(&mut self, ctx: &VisitorContext, data_type: &DataType) {
         match data_type {
-            DataType::StructType { variables, .. } => {
+            DataType::StructType { variables, .. } | DataType::UnionType { variables, .. } => {
                 variables.iter().for_each(|v| self.visit_variable(ctx, v))
             }
             DataType::ArrayType {
                 referenced_type, ..
+            }
+            | DataType::VariableLengthArrayType {
+                referenced_type, ..
             } => self.visit_data_type_declaration(ctx, referenced_type),
             DataType::VarArgs {
                 referenced_type: Some(referenced_type),
@@ -348,6 +365,9 @@ impl<'i> TypeAnnotator<'i> {
                     .get_type_information();
                 if let DataTypeInformation::Array {
                     inner_type_name, ..
+                }
+                | DataTypeInformation::VariableLengthArray {
+                    inner_type_name, ..
                 } = array_type
                 {
                     let t = self
@@ -357,6 +377,12 @@ impl<'i> TypeAnnotator<'i> {
 
                     self.annotation_map
                         .annotate(statement, StatementAnnotation::expression(t));
+                } else if let DataTypeInformation::String { encoding, .. } = array_type {
+                    //accessing a string yields a single character
+                    self.annotation_map.annotate(
+                        statement,
+                        StatementAnnotation::expression(encoding.get_char_type_name()),
+                    );
                 }
             }
             AstStatement::PointerAccess { reference, .. } => {
@@ -403,19 +429,22 @@ impl<'i> TypeAnnotator<'i> {
                     },
                 ),
             },
-            AstStatement::BinaryExpression { left, right, .. } => {
+            AstStatement::BinaryExpression {
+                left,
+                right,
+                operator,
+                ..
+            } => {
                 visit_all_statements!(self, ctx, left, right);
-                let left = &self
-                    .annotation_map
-                    .get_type_or_void(left, self.index)
-                    .get_type_information();
-                let right = &self
-                    .annotation_map
-                    .get_type_or_void(right, self.index)
-                    .get_type_information();
-
-                if left.is_numerical() && right.is_numerical() {
-                    let bigger_name = get_bigger_type_borrow(left, right, self.index).get_name();
+                if let Some(result_name) = self.get_date_time_result_type(left, operator, right) {
+                    self.annotation_map
+                        .annotate(statement, StatementAnnotation::expression(result_name));
+                } else if let Some(result_name) =
+                    self.get_pointer_result_type(left, operator, right)
+                {
+                    self.annotation_map
+                        .annotate(statement, StatementAnnotation::expression(result_name));
+                } else if let Some(bigger_name) = self.get_numerical_result_type(left, right) {
                     self.annotation_map
                         .annotate(statement, StatementAnnotation::expression(bigger_name));
                 }
@@ -452,16 +481,38 @@ impl<'i> TypeAnnotator<'i> {
                         .find_member(qualifier, name)
                         // 2nd try an enum-element qualifier#name
                         .or_else(|| self.index.find_enum_element(qualifier, name.as_str()))
-                        // 3rd try - look for a method qualifier.name
+                        // 3rd try - look for a method qualifier.name (or one inherited from a super class)
                         .map_or_else(
                             || {
-                                find_implementation_annotation(
-                                    format!("{}.{}", qualifier, name).as_str(),
-                                    self.index,
-                                )
+                                self.index
+                                    .find_method(qualifier, name)
+                                    .and_then(|it| {
+                                        find_implementation_annotation(
+                                            it.get_call_name(),
+                                            self.index,
+                                        )
+                                    })
+                                    // 4th try - a property qualifier.name, accessed via its getter or setter
+                                    .or_else(|| {
+                                        self.index.find_property_type(qualifier, name).map(|it| {
+                                            StatementAnnotation::Property {
+                                                resulting_type: it.get_name().into(),
+                                                qualified_name: format!("{}.{}", qualifier, name),
+                                            }
+                                        })
+                                    })
                             },
                             |v| Some(to_variable_annotation(v, self.index)),
                         )
+                } else if let Some(pointer_type) = ctx
+                    .pou
+                    .and_then(|pou| self.index.find_instance_pointer_type(pou, name))
+                {
+                    // the implicit THIS or SUPER pointer inside a method or function block
+                    Some(StatementAnnotation::Variable {
+                        qualified_name: format!("{}.{}", ctx.pou.unwrap_or_default(), name),
+                        resulting_type: pointer_type.get_name().into(),
+                    })
                 } else {
                     // if we see no qualifier, we try some strategies ...
                     ctx.pou
@@ -495,16 +546,16 @@ impl<'i> TypeAnnotator<'i> {
                         })
                         .or_else(|| {
                             // ... then try if we find a pou with that name (maybe it's a call?)
-                            let class_name = self
-                                .index
-                                .find_implementation(ctx.pou.unwrap())
-                                .and_then(ImplementationIndexEntry::get_associated_class_name);
+                            // or a method of the current method's class or function block
+                            let class_name =
+                                ctx.pou.and_then(|it| self.index.find_instance_class(it));
 
                             //TODO introduce qualified names!
                             let call_name = class_name
-                                .map(|it| format!("{}.{}", it, name))
-                                .unwrap_or_else(|| name.into());
-                            find_implementation_annotation(&call_name, self.index)
+                                .and_then(|it| self.index.find_method(it, name))
+                                .map(|it| it.get_call_name())
+                                .unwrap_or(name);
+                            find_implementation_annotation(call_name, self.index)
                         })
                         .or_else(|| {
                             // ... last option is a global variable, where we ignore the current pou's name as a qualifier
@@ -512,6 +563,10 @@ impl<'i> TypeAnnotator<'i> {
                                 .find_global_variable(name)
                                 .map(|v| to_variable_annotation(v, self.index))
                         })
+                        .or_else(|| {
+                            // ... or a function built into the compiler
+                            BuiltIn::find(name).map(to_builtin_annotation)
+                        })
                 };
                 if let Some(annotation) = annotation {
                     self.annotation_map.annotate(statement, annotation)
@@ -537,6 +592,9 @@ impl<'i> TypeAnnotator<'i> {
                             StatementAnnotation::Program { qualified_name } => {
                                 qualified_name.as_str()
                             }
+                            StatementAnnotation::Property { resulting_type, .. } => {
+                                resulting_type.as_str()
+                            }
                         })
                         .unwrap_or_else(|| VOID_TYPE);
 
@@ -565,6 +623,9 @@ impl<'i> TypeAnnotator<'i> {
                     self.visit_statement(ctx, left);
                 }
             }
+            AstStatement::ReferenceAssignment { left, right, .. } => {
+                visit_all_statements!(self, ctx, left, right);
+            }
             AstStatement::OutputAssignment { left, right, .. } => {
                 visit_all_statements!(self, ctx, left, right);
                 if let Some(lhs) = ctx.call {
@@ -615,7 +676,9 @@ impl<'i> TypeAnnotator<'i> {
                     self.visit_statement(&ctx, s);
                 }
 
-                if let Some(StatementAnnotation::Function { return_type, .. }) =
+                if let Some(builtin) = self.find_builtin(operator) {
+                    self.visit_builtin_call(builtin, statement, parameters);
+                } else if let Some(StatementAnnotation::Function { return_type, .. }) =
                     self.annotation_map.get(operator)
                 {
                     if let Some(return_type) = self
@@ -663,6 +726,289 @@ impl<'i> TypeAnnotator<'i> {
         }
     }
 
+    /// returns the builtin function the given call-operator refers to
+    fn find_builtin(&self, operator: &AstStatement) -> Option<BuiltIn> {
+        match self.annotation_map.get(operator) {
+            Some(StatementAnnotation::Function { qualified_name, .. })
+                if self.index.find_implementation(qualified_name).is_none() =>
+            {
+                BuiltIn::find(qualified_name)
+            }
+            _ => None,
+        }
+    }
+
+    /// annotates the call of a builtin function with the type resulting from its parameters
+    fn visit_builtin_call(
+        &mut self,
+        builtin: BuiltIn,
+        statement: &AstStatement,
+        parameters: &Option<AstStatement>,
+    ) {
+        let parameters = parameters
+            .as_ref()
+            .map(flatten_expression_list)
+            .unwrap_or_default();
+        let result_type = match (builtin, parameters.as_slice()) {
+            (BuiltIn::Expt, [base, exponent]) => self.get_numerical_result_type(base, exponent),
+            (BuiltIn::IsValidRef, [_]) => Some(BOOL_TYPE),
+            (BuiltIn::LowerBound, [_, _]) | (BuiltIn::UpperBound, [_, _]) => Some(DINT_TYPE),
+            (BuiltIn::Conversion { target, .. }, [_]) | (BuiltIn::Trunc { target, .. }, [_]) => {
+                Some(target)
+            }
+            (BuiltIn::Min, values) | (BuiltIn::Max, values) if values.len() >= 2 => {
+                self.get_comparison_result_type(values)
+            }
+            (BuiltIn::Limit, values @ [_, _, _]) => self.get_comparison_result_type(values),
+            (BuiltIn::Sel, [_, inputs @ ..]) if inputs.len() == 2 => {
+                self.get_common_result_type(inputs)
+            }
+            (BuiltIn::Mux, [_, inputs @ ..]) if !inputs.is_empty() => {
+                self.get_common_result_type(inputs)
+            }
+            (BuiltIn::Move, [value]) => Some(
+                self.annotation_map
+                    .get_type_or_void(value, self.index)
+                    .get_name(),
+            ),
+            (BuiltIn::Shl, [input, _])
+            | (BuiltIn::Shr, [input, _])
+            | (BuiltIn::Rol, [input, _])
+            | (BuiltIn::Ror, [input, _]) => self.get_bit_string_result_type(input),
+            //the absolute value has the (intrinsic) type of the number
+            (BuiltIn::Abs, [input]) => self.get_numerical_result_type(input, input),
+            (builtin, inputs)
+                if builtin.is_real_function()
+                    && inputs.len() == builtin.get_real_function_arity() =>
+            {
+                self.get_real_result_type(inputs)
+            }
+            (BuiltIn::Len, [input]) | (BuiltIn::Find, [input, _]) => {
+                self.get_string_result_type(&[*input]).map(|_| INT_TYPE)
+            }
+            (BuiltIn::Left, [input, _])
+            | (BuiltIn::Right, [input, _])
+            | (BuiltIn::Mid, [input, _, _])
+            | (BuiltIn::Delete, [input, _, _]) => self.get_string_result_type(&[*input]),
+            (BuiltIn::Insert, [input1, input2, _]) | (BuiltIn::Replace, [input1, input2, _, _]) => {
+                self.get_string_result_type(&[*input1, *input2])
+            }
+            (BuiltIn::Concat, inputs) if !inputs.is_empty() => self.get_string_result_type(inputs),
+            _ => None,
+        };
+        if let Some(result_type) = result_type {
+            self.annotation_map
+                .annotate(statement, StatementAnnotation::expression(result_type));
+        }
+    }
+
+    /// returns the name of the type resulting from an arithmetic operation on the given
+    /// operands or None if one of them is not numerical
+    fn get_numerical_result_type(
+        &self,
+        left: &AstStatement,
+        right: &AstStatement,
+    ) -> Option<&'i str> {
+        let left = self
+            .annotation_map
+            .get_type_or_void(left, self.index)
+            .get_type_information();
+        let right = self
+            .annotation_map
+            .get_type_or_void(right, self.index)
+            .get_type_information();
+
+        if left.is_numerical() && right.is_numerical() {
+            //enums take part in binary expressions with their numeric type
+            let left = self
+                .index
+                .find_intrinsic_type_information(left)
+                .unwrap_or(left);
+            let right = self
+                .index
+                .find_intrinsic_type_information(right)
+                .unwrap_or(right);
+            Some(get_bigger_type_borrow(left, right, self.index).get_name())
+        } else {
+            None
+        }
+    }
+
+    /// returns the name of the type resulting from shifting or rotating the bits of the given
+    /// input, or None if the input is no bit-string or integer
+    fn get_bit_string_result_type(&self, input: &AstStatement) -> Option<&'i str> {
+        let data_type = self
+            .annotation_map
+            .get_type_or_void(input, self.index)
+            .get_type_information();
+        let data_type = self
+            .index
+            .find_intrinsic_type_information(data_type)
+            .unwrap_or(data_type);
+        match data_type {
+            DataTypeInformation::Integer { size, .. }
+                if *size > 1
+                    && !data_type.is_character()
+                    && !typesystem::is_date_or_time_type(data_type) =>
+            {
+                Some(data_type.get_name())
+            }
+            _ => None,
+        }
+    }
+
+    /// returns the name of the real type a real function (e.g. `SQRT`) calculates its result in.
+    /// Integer inputs are converted to REAL, or LREAL if they don't fit into a REAL
+    fn get_real_result_type(&self, inputs: &[&AstStatement]) -> Option<&'i str> {
+        let real_type = self.index.get_type_information_or_void(REAL_TYPE);
+        inputs
+            .iter()
+            .try_fold(real_type, |result_type, it| {
+                let data_type = self
+                    .annotation_map
+                    .get_type_or_void(it, self.index)
+                    .get_type_information();
+                let data_type = self
+                    .index
+                    .find_intrinsic_type_information(data_type)
+                    .unwrap_or(data_type);
+                if data_type.is_numerical() {
+                    Some(get_bigger_type_borrow(result_type, data_type, self.index))
+                } else {
+                    None
+                }
+            })
+            .map(DataTypeInformation::get_name)
+    }
+
+    /// returns the name of the string type resulting from a string function on the given
+    /// strings, the longest of the given strings and the default string of their encoding
+    /// (e.g. `STRING`). Returns None if the inputs are no strings of the same encoding
+    fn get_string_result_type(&self, inputs: &[&AstStatement]) -> Option<&'i str> {
+        let types: Vec<&typesystem::DataType> = inputs
+            .iter()
+            .map(|it| self.annotation_map.get_type_or_void(it, self.index))
+            .collect();
+        let default_type_name = match types.first()?.get_type_information() {
+            DataTypeInformation::String {
+                encoding: StringEncoding::Utf8,
+                ..
+            } => STRING_TYPE,
+            DataTypeInformation::String { .. } => WSTRING_TYPE,
+            _ => return None,
+        };
+        let default_type = self.index.find_type(default_type_name)?;
+        types
+            .iter()
+            .try_fold(default_type, |result_type, it| {
+                match (
+                    result_type.get_type_information(),
+                    it.get_type_information(),
+                ) {
+                    (
+                        DataTypeInformation::String {
+                            size: result_size,
+                            encoding: result_encoding,
+                        },
+                        DataTypeInformation::String { size, encoding },
+                    ) if encoding == result_encoding => {
+                        Some(if size > result_size { *it } else { result_type })
+                    }
+                    _ => None,
+                }
+            })
+            .map(typesystem::DataType::get_name)
+    }
+
+    /// returns the name of the type all of the given values are converted to when one of them
+    /// is selected (e.g. by `MAX` or `SEL`). Numbers result in the bigger type of all values,
+    /// other values must share the same type
+    fn get_common_result_type(&self, values: &[&AstStatement]) -> Option<&'i str> {
+        let types: Vec<&DataTypeInformation> = values
+            .iter()
+            .map(|it| {
+                let data_type = self
+                    .annotation_map
+                    .get_type_or_void(it, self.index)
+                    .get_type_information();
+                self.index
+                    .find_intrinsic_type_information(data_type)
+                    .unwrap_or(data_type)
+            })
+            .collect();
+        let (first, others) = types.split_first()?;
+        if types.iter().all(|it| it.is_numerical()) {
+            let result_type = others.iter().fold(*first, |result_type, it| {
+                get_bigger_type_borrow(result_type, it, self.index)
+            });
+            Some(result_type.get_name())
+        } else if others.iter().all(|it| it.get_name() == first.get_name()) {
+            Some(first.get_name())
+        } else {
+            None
+        }
+    }
+
+    /// returns the name of the type the given values are compared in (e.g. by `MIN`), only
+    /// numbers (including BOOL, characters, dates and times) can be compared
+    fn get_comparison_result_type(&self, values: &[&AstStatement]) -> Option<&'i str> {
+        let is_comparable = |value: &&AstStatement| {
+            let data_type = self
+                .annotation_map
+                .get_type_or_void(value, self.index)
+                .get_type_information();
+            self.index
+                .find_intrinsic_type_information(data_type)
+                .unwrap_or(data_type)
+                .is_numerical()
+        };
+        if values.iter().all(is_comparable) {
+            self.get_common_result_type(values)
+        } else {
+            None
+        }
+    }
+
+    /// returns the name of the type resulting from an arithmetic operation on date or time
+    /// operands (e.g. `DT - DT` results in `TIME`) or None if no date or time operation applies
+    fn get_date_time_result_type(
+        &self,
+        left: &AstStatement,
+        operator: &Operator,
+        right: &AstStatement,
+    ) -> Option<&'static str> {
+        let left = self
+            .annotation_map
+            .get_type_or_void(left, self.index)
+            .get_type_information();
+        let right = self
+            .annotation_map
+            .get_type_or_void(right, self.index)
+            .get_type_information();
+        typesystem::get_date_time_operation_result(left, operator, right)
+    }
+
+    /// returns the name of the type resulting from pointer arithmetic or a pointer comparison
+    /// (e.g. `ptr + 1` results in the pointer's type) or None if no pointer operation applies
+    fn get_pointer_result_type(
+        &self,
+        left: &AstStatement,
+        operator: &Operator,
+        right: &AstStatement,
+    ) -> Option<&'i str> {
+        let left_type = self
+            .annotation_map
+            .get_type_or_void(left, self.index)
+            .get_type_information();
+        let right_type = self
+            .annotation_map
+            .get_type_or_void(right, self.index)
+            .get_type_information();
+        let (left_type, right_type) =
+            typesystem::get_null_comparison_types(left, left_type, operator, right, right_type);
+        typesystem::get_pointer_operation_result(left_type, operator, right_type)
+    }
+
     /// annotate a literal statement
     fn visit_statement_literals(&mut self, ctx: &VisitorContext, statement: &AstStatement) {
         match statement {
@@ -682,22 +1028,33 @@ impl<'i> TypeAnnotator<'i> {
                     StatementAnnotation::expression(get_int_type_name_for(*value)),
                 );
             }
-            AstStatement::LiteralTime { .. } => self
-                .annotation_map
-                .annotate(statement, StatementAnnotation::expression(TIME_TYPE)),
-            AstStatement::LiteralTimeOfDay { .. } => {
+            AstStatement::LiteralTime { is_long, .. } => {
+                let type_name = if *is_long { LTIME_TYPE } else { TIME_TYPE };
+                self.annotation_map
+                    .annotate(statement, StatementAnnotation::expression(type_name));
+            }
+            AstStatement::LiteralTimeOfDay { is_long, .. } => {
+                let type_name = if *is_long {
+                    LTIME_OF_DAY_TYPE
+                } else {
+                    TIME_OF_DAY_TYPE
+                };
                 self.annotation_map
-                    .annotate(statement, StatementAnnotation::expression(TIME_OF_DAY_TYPE));
+                    .annotate(statement, StatementAnnotation::expression(type_name));
             }
-            AstStatement::LiteralDate { .. } => {
+            AstStatement::LiteralDate { is_long, .. } => {
+                let type_name = if *is_long { LDATE_TYPE } else { DATE_TYPE };
                 self.annotation_map
-                    .annotate(statement, StatementAnnotation::expression(DATE_TYPE));
+                    .annotate(statement, StatementAnnotation::expression(type_name));
             }
-            AstStatement::LiteralDateAndTime { .. } => {
-                self.annotation_map.annotate(
-                    statement,
-                    StatementAnnotation::expression(DATE_AND_TIME_TYPE),
-                );
+            AstStatement::LiteralDateAndTime { is_long, .. } => {
+                let type_name = if *is_long {
+                    LDATE_AND_TIME_TYPE
+                } else {
+                    DATE_AND_TIME_TYPE
+                };
+                self.annotation_map
+                    .annotate(statement, StatementAnnotation::expression(type_name));
             }
             AstStatement::LiteralReal { .. } => {
                 //TODO when do we need a LREAL literal?
@@ -734,6 +1091,13 @@ fn find_implementation_annotation(name: &str, index: &Index) -> Option<Statement
         })
 }
 
+fn to_builtin_annotation(builtin: BuiltIn) -> StatementAnnotation {
+    StatementAnnotation::Function {
+        qualified_name: builtin.get_name().into(),
+        return_type: VOID_TYPE.into(),
+    }
+}
+
 fn to_type_annotation(name: &str) -> StatementAnnotation {
     StatementAnnotation::Type {
         type_name: name.into(),
@@ -747,12 +1111,21 @@ fn to_programm_annotation(it: &ImplementationIndexEntry) -> StatementAnnotation
 }
 
 fn to_variable_annotation(v: &VariableIndexEntry, index: &Index) -> StatementAnnotation {
+    let v_type = index.get_effective_type_by_name(v.get_type_name());
+    //a VAR_IN_OUT or a REFERENCE TO is dereferenced automatically
+    let v_type = if let DataTypeInformation::Pointer {
+        auto_deref: true,
+        inner_type_name,
+        ..
+    } = v_type.get_type_information()
+    {
+        index.get_effective_type_by_name(inner_type_name)
+    } else {
+        v_type
+    };
     StatementAnnotation::Variable {
         qualified_name: v.get_qualified_name().into(),
-        resulting_type: index
-            .get_effective_type_by_name(v.get_type_name())
-            .get_name()
-            .into(),
+        resulting_type: v_type.get_name().into(),
     }
 }
 
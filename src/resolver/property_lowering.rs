@@ -0,0 +1,270 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+
+//! Properties are accessed by calling their accessors. This module rewrites all
+//! property accesses of a `CompilationUnit` into calls to these accessors, so the
+//! following phases only see ordinary method calls:
+//! - `m.speed := 3` becomes `m.__set_speed(speed := 3)`
+//! - `x := m.speed` becomes `x := m.__get_speed()`
+//!
+//! The created statements get new ids from the given `IdProvider`, so they can be
+//! annotated and validated like any other statement.
+
+use crate::{
+    ast::{
+        get_property_getter_name, get_property_setter_name, AstStatement, CompilationUnit,
+        SourceRange,
+    },
+    index::Index,
+    lexer::IdProvider,
+};
+
+use super::{AnnotationMap, StatementAnnotation, TypeAnnotator};
+
+/// rewrites all reads and writes of properties in the given unit into calls to the
+/// properties' getters and setters
+pub fn lower_property_accesses(index: &Index, unit: &mut CompilationUnit, id_provider: IdProvider) {
+    let annotations = TypeAnnotator::visit_unit(index, unit);
+    let mut lowering = PropertyLowering {
+        annotations: &annotations,
+        id_provider,
+    };
+    for implementation in unit.implementations.iter_mut() {
+        lowering.lower_statements(&mut implementation.statements);
+    }
+}
+
+struct PropertyLowering<'a> {
+    annotations: &'a AnnotationMap,
+    id_provider: IdProvider,
+}
+
+impl<'a> PropertyLowering<'a> {
+    fn lower_statements(&mut self, statements: &mut [AstStatement]) {
+        for statement in statements.iter_mut() {
+            self.lower_statement(statement);
+        }
+    }
+
+    fn lower_statement(&mut self, statement: &mut AstStatement) {
+        match statement {
+            AstStatement::Assignment { left, right, .. } => {
+                self.lower_statement(right);
+                match left.as_mut() {
+                    AstStatement::QualifiedReference { elements, .. }
+                        if elements.last().map_or(false, |it| self.is_property(it)) =>
+                    {
+                        //m.speed := value --> m.__set_speed(speed := value)
+                        let value = std::mem::replace(
+                            right.as_mut(),
+                            AstStatement::EmptyStatement {
+                                location: SourceRange::undefined(),
+                                id: 0,
+                            },
+                        );
+                        let mut elements = std::mem::take(elements);
+                        if let Some(property) = elements.pop() {
+                            let instance = self.lower_property_reads(elements);
+                            *statement = self.create_accessor_call(
+                                instance,
+                                &property,
+                                get_property_setter_name,
+                                Some(value),
+                            );
+                        }
+                    }
+                    _ => self.lower_statement(left),
+                }
+            }
+            AstStatement::QualifiedReference { elements, id } => {
+                for element in elements.iter_mut() {
+                    self.lower_element(element);
+                }
+                if elements.iter().any(|it| self.is_property(it)) {
+                    let mut lowered = self.lower_property_reads(std::mem::take(elements));
+                    *statement = if lowered.len() == 1 {
+                        //the whole reference was a property read (e.g. `m.speed`)
+                        lowered.remove(0)
+                    } else {
+                        AstStatement::QualifiedReference {
+                            elements: lowered,
+                            id: *id,
+                        }
+                    };
+                }
+            }
+            AstStatement::CallStatement {
+                operator,
+                parameters,
+                ..
+            } => {
+                self.lower_statement(operator);
+                if let Some(parameters) = parameters.as_mut() {
+                    self.lower_statement(parameters);
+                }
+            }
+            AstStatement::CastStatement { target: value, .. }
+            | AstStatement::MultipliedStatement { element: value, .. }
+            | AstStatement::UnaryExpression { value, .. }
+            | AstStatement::CaseCondition {
+                condition: value, ..
+            }
+            | AstStatement::ReferenceAssignment { right: value, .. }
+            | AstStatement::LiteralArray {
+                elements: Some(value),
+                ..
+            } => self.lower_statement(value),
+            AstStatement::ArrayAccess { .. } | AstStatement::PointerAccess { .. } => {
+                self.lower_element(statement)
+            }
+            AstStatement::BinaryExpression { left, right, .. }
+            | AstStatement::RangeStatement {
+                start: left,
+                end: right,
+                ..
+            } => {
+                self.lower_statement(left);
+                self.lower_statement(right);
+            }
+            AstStatement::ExpressionList { expressions, .. } => {
+                self.lower_statements(expressions);
+            }
+            AstStatement::IfStatement {
+                blocks, else_block, ..
+            } => {
+                for block in blocks.iter_mut() {
+                    self.lower_statement(&mut block.condition);
+                    self.lower_statements(&mut block.body);
+                }
+                self.lower_statements(else_block);
+            }
+            AstStatement::CaseStatement {
+                selector,
+                case_blocks,
+                else_block,
+                ..
+            } => {
+                self.lower_statement(selector);
+                for block in case_blocks.iter_mut() {
+                    self.lower_statement(&mut block.condition);
+                    self.lower_statements(&mut block.body);
+                }
+                self.lower_statements(else_block);
+            }
+            AstStatement::ForLoopStatement {
+                start,
+                end,
+                by_step,
+                body,
+                ..
+            } => {
+                self.lower_statement(start);
+                self.lower_statement(end);
+                if let Some(by_step) = by_step.as_mut() {
+                    self.lower_statement(by_step);
+                }
+                self.lower_statements(body);
+            }
+            AstStatement::WhileLoopStatement {
+                condition, body, ..
+            }
+            | AstStatement::RepeatLoopStatement {
+                condition, body, ..
+            } => {
+                self.lower_statement(condition);
+                self.lower_statements(body);
+            }
+            //the targets of output-assignments and for-loop counters must stay variables
+            _ => {}
+        }
+    }
+
+    /// lowers the property reads nested in an element of a qualified reference
+    /// (e.g. the index of an array access)
+    fn lower_element(&mut self, element: &mut AstStatement) {
+        match element {
+            AstStatement::ArrayAccess {
+                reference, access, ..
+            } => {
+                self.lower_element(reference);
+                self.lower_statement(access);
+            }
+            AstStatement::PointerAccess { reference, .. } => self.lower_element(reference),
+            _ => {}
+        }
+    }
+
+    /// replaces every property in the given elements by a call to its getter
+    /// e.g. `a.b.prop.c` --> `__get_prop(a.b).c`
+    fn lower_property_reads(&mut self, elements: Vec<AstStatement>) -> Vec<AstStatement> {
+        let mut lowered = Vec::with_capacity(elements.len());
+        for element in elements {
+            if self.is_property(&element) {
+                let instance = std::mem::take(&mut lowered);
+                let call =
+                    self.create_accessor_call(instance, &element, get_property_getter_name, None);
+                lowered.push(call);
+            } else {
+                lowered.push(element);
+            }
+        }
+        lowered
+    }
+
+    /// creates the call `instance.<accessor>(<property> := value)` for the given property
+    fn create_accessor_call(
+        &mut self,
+        mut instance: Vec<AstStatement>,
+        property: &AstStatement,
+        get_accessor_name: fn(&str) -> String,
+        value: Option<AstStatement>,
+    ) -> AstStatement {
+        let (name, property_location) = match property {
+            AstStatement::Reference { name, location, .. } => (name, location),
+            _ => return property.clone(),
+        };
+        let start = instance
+            .first()
+            .map_or(property_location.get_start(), |it| {
+                it.get_location().get_start()
+            });
+
+        instance.push(AstStatement::Reference {
+            name: get_accessor_name(name),
+            location: property_location.clone(),
+            id: self.id_provider.next_id(),
+        });
+        let parameters = value.map(|value| AstStatement::Assignment {
+            left: Box::new(AstStatement::Reference {
+                name: name.clone(),
+                location: property_location.clone(),
+                id: self.id_provider.next_id(),
+            }),
+            right: Box::new(value),
+            id: self.id_provider.next_id(),
+        });
+        AstStatement::CallStatement {
+            operator: Box::new(AstStatement::QualifiedReference {
+                elements: instance,
+                id: self.id_provider.next_id(),
+            }),
+            parameters: Box::new(parameters),
+            location: SourceRange::new(start..property_location.get_end()),
+            id: self.id_provider.next_id(),
+        }
+    }
+
+    /// returns true if the given element references a property of its qualifier
+    fn is_property(&self, element: &AstStatement) -> bool {
+        match (element, self.annotations.get(element)) {
+            (
+                AstStatement::Reference { name, .. },
+                Some(StatementAnnotation::Property { qualified_name, .. }),
+            ) => qualified_name
+                .rsplit('.')
+                .next()
+                .map(|it| it.eq_ignore_ascii_case(name))
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     ast::{self, CompilationUnit},
     index::{self, Index},
-    lexer::lex,
+    lexer::{lex_with_ids, IdProvider},
 };
 
 use super::{AnnotationMap, TypeAnnotator};
@@ -14,10 +14,14 @@ mod resolve_expressions_tests;
 mod resolve_literals_tests;
 
 fn parse(src: &str) -> (CompilationUnit, Index) {
-    let (mut unit, _) = crate::parser::parse(lex(src));
+    let id_provider = IdProvider::new();
+    let (mut unit, _) = crate::parser::parse(lex_with_ids(src, id_provider.clone()));
 
+    let declarations = ast::collect_declarations(std::iter::once(&unit));
+    ast::resolve_namespaces(&mut unit, &declarations);
     ast::pre_process(&mut unit);
     let index = index::visitor::visit(&unit);
+    super::lower_property_accesses(&index, &mut unit, id_provider);
     (unit, index)
 }
 
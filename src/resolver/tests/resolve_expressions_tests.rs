@@ -76,6 +76,336 @@ fn binary_expressions_resolves_types_with_floats() {
     }
 }
 
+#[test]
+fn date_and_time_operations_resolve_iec_result_types() {
+    let (unit, index) = parse(
+        "PROGRAM PRG
+            VAR
+                t : TIME; lt : LTIME;
+                d : DATE; ld : LDATE;
+                tod : TIME_OF_DAY;
+                dt : DATE_AND_TIME; ldt : LDATE_AND_TIME;
+                i : INT; r : REAL;
+            END_VAR
+            t + t;
+            t - lt;
+            tod + t;
+            tod - t;
+            dt + lt;
+            dt - dt;
+            ldt - dt;
+            d - d;
+            d - ld;
+            tod - tod;
+            t * i;
+            r * t;
+            lt / r;
+            dt > dt;
+        END_PROGRAM",
+    );
+    let annotations = annotate(&unit, &index);
+    let statements = &unit.implementations[0].statements;
+
+    let expected_types = vec![
+        "TIME",
+        "LTIME",
+        "TIME_OF_DAY",
+        "TIME_OF_DAY",
+        "LDATE_AND_TIME",
+        "TIME",
+        "LTIME",
+        "TIME",
+        "LTIME",
+        "TIME",
+        "TIME",
+        "TIME",
+        "LTIME",
+        "DATE_AND_TIME",
+    ];
+    let types: Vec<&str> = statements
+        .iter()
+        .map(|s| annotations.get_type_or_void(s, &index).get_name())
+        .collect();
+
+    assert_eq!(expected_types, types);
+}
+
+#[test]
+fn pointer_operations_resolve_types() {
+    let (unit, index) = parse(
+        "PROGRAM PRG
+            VAR
+                p : POINTER TO INT;
+                q : POINTER TO INT;
+                i : INT;
+            END_VAR
+            p + 1;
+            i + p;
+            p - i;
+            p - q;
+            p = q;
+            p >= q;
+            p <> NULL;
+            NULL = p;
+        END_PROGRAM",
+    );
+    let annotations = annotate(&unit, &index);
+    let statements = &unit.implementations[0].statements;
+
+    let expected_types = vec![
+        "__PRG_p", "__PRG_p", "__PRG_p", "LINT", "BOOL", "BOOL", "BOOL", "BOOL",
+    ];
+    let types: Vec<&str> = statements
+        .iter()
+        .map(|s| annotations.get_type_or_void(s, &index).get_name())
+        .collect();
+
+    assert_eq!(expected_types, types);
+}
+
+#[test]
+fn references_resolve_to_the_referenced_type() {
+    let (unit, index) = parse(
+        "
+        TYPE MyStruct : STRUCT x : INT; END_STRUCT END_TYPE
+
+        FUNCTION foo : INT
+            VAR_IN_OUT
+                s : MyStruct;
+            END_VAR
+            VAR
+                r : REFERENCE TO REAL;
+            END_VAR
+            s.x;
+            r;
+            r + 1;
+            __ISVALIDREF(r);
+        END_FUNCTION",
+    );
+    let annotations = annotate(&unit, &index);
+    let statements = &unit.implementations[0].statements;
+
+    let expected_types = vec!["INT", "REAL", "REAL", "BOOL"];
+    let types: Vec<&str> = statements
+        .iter()
+        .map(|s| annotations.get_type_or_void(s, &index).get_name())
+        .collect();
+
+    assert_eq!(expected_types, types);
+}
+
+#[test]
+fn variable_length_array_access_resolves_to_the_element_type() {
+    let (unit, index) = parse(
+        "
+        FUNCTION foo : INT
+            VAR_IN_OUT
+                a : ARRAY[*, *] OF REAL;
+            END_VAR
+            a[1, 2];
+            LOWER_BOUND(a, 1);
+            UPPER_BOUND(a, 2);
+        END_FUNCTION",
+    );
+    let annotations = annotate(&unit, &index);
+    let statements = &unit.implementations[0].statements;
+
+    let expected_types = vec!["REAL", "DINT", "DINT"];
+    let types: Vec<&str> = statements
+        .iter()
+        .map(|s| annotations.get_type_or_void(s, &index).get_name())
+        .collect();
+
+    assert_eq!(expected_types, types);
+}
+
+#[test]
+fn type_conversions_resolve_to_the_target_type() {
+    let (unit, index) = parse(
+        "
+        PROGRAM PRG
+            VAR
+                i : INT;
+                r : LREAL;
+                dt : DT;
+            END_VAR
+            INT_TO_REAL(i);
+            int_to_bool(i);
+            TO_LREAL(i);
+            LREAL_TRUNC_SINT(r);
+            TRUNC(r);
+            DT_TO_TOD(dt);
+            TIME_TO_LINT(T#1s);
+            DATE_TO_TOD(dt);
+            INT_TO_STRING(i);
+            TO_WSTRING(r);
+            STRING_TO_TIME('T#1s');
+        END_PROGRAM",
+    );
+    let annotations = annotate(&unit, &index);
+    let statements = &unit.implementations[0].statements;
+
+    let expected_types = vec![
+        "REAL",
+        "BOOL",
+        "LREAL",
+        "SINT",
+        "DINT",
+        "TIME_OF_DAY",
+        "LINT",
+        "VOID",
+        "STRING",
+        "WSTRING",
+        "TIME",
+    ];
+    let types: Vec<&str> = statements
+        .iter()
+        .map(|s| annotations.get_type_or_void(s, &index).get_name())
+        .collect();
+
+    assert_eq!(expected_types, types);
+}
+
+#[test]
+fn selection_functions_resolve_to_the_bigger_type_of_their_inputs() {
+    let (unit, index) = parse(
+        "
+        PROGRAM PRG
+            VAR
+                i : INT;
+                di : DINT;
+                r : REAL;
+                b : BOOL;
+                s : STRING;
+            END_VAR
+            MIN(i, di);
+            MAX(i, i, r, di);
+            LIMIT(0, i, 10);
+            SEL(b, i, r);
+            MUX(di, i, i, i);
+            MOVE(r);
+            SEL(b, s, s);
+            SEL(b, s, i);
+            MAX(s, s);
+        END_PROGRAM",
+    );
+    let annotations = annotate(&unit, &index);
+    let statements = &unit.implementations[0].statements;
+
+    let expected_types = vec![
+        "DINT", "REAL", "DINT", "REAL", "INT", "REAL", "STRING", "VOID", "VOID",
+    ];
+    let types: Vec<&str> = statements
+        .iter()
+        .map(|s| annotations.get_type_or_void(s, &index).get_name())
+        .collect();
+
+    assert_eq!(expected_types, types);
+}
+
+#[test]
+fn bit_shifts_resolve_to_the_type_of_their_input() {
+    let (unit, index) = parse(
+        "
+        PROGRAM PRG
+            VAR
+                b : BYTE;
+                w : WORD;
+                lw : LWORD;
+                i : INT;
+                r : REAL;
+            END_VAR
+            SHL(b, 1);
+            SHR(w, i);
+            ROL(lw, 3);
+            ROR(i, b);
+            SHL(r, 1);
+        END_PROGRAM",
+    );
+    let annotations = annotate(&unit, &index);
+    let statements = &unit.implementations[0].statements;
+
+    let expected_types = vec!["BYTE", "WORD", "LWORD", "INT", "VOID"];
+    let types: Vec<&str> = statements
+        .iter()
+        .map(|s| annotations.get_type_or_void(s, &index).get_name())
+        .collect();
+
+    assert_eq!(expected_types, types);
+}
+
+#[test]
+fn numeric_functions_resolve_to_real_types() {
+    let (unit, index) = parse(
+        "
+        PROGRAM PRG
+            VAR
+                i : INT;
+                li : LINT;
+                r : REAL;
+                l : LREAL;
+            END_VAR
+            ABS(i);
+            ABS(l);
+            SQRT(r);
+            LN(l);
+            SIN(i);
+            EXP(li);
+            ATAN2(r, l);
+            ROUND(r);
+            ATAN2(r);
+        END_PROGRAM",
+    );
+    let annotations = annotate(&unit, &index);
+    let statements = &unit.implementations[0].statements;
+
+    let expected_types = vec![
+        "INT", "LREAL", "REAL", "LREAL", "REAL", "LREAL", "LREAL", "REAL", "VOID",
+    ];
+    let types: Vec<&str> = statements
+        .iter()
+        .map(|s| annotations.get_type_or_void(s, &index).get_name())
+        .collect();
+
+    assert_eq!(expected_types, types);
+}
+
+#[test]
+fn string_functions_resolve_to_the_longest_string_type() {
+    let (unit, index) = parse(
+        r#"
+        PROGRAM PRG
+            VAR
+                s : STRING;
+                ls : STRING[200];
+                ws : WSTRING;
+                i : INT;
+            END_VAR
+            LEN(s);
+            FIND(s, 'a');
+            LEFT(s, 2);
+            RIGHT(ls, 2);
+            CONCAT(s, ls, 'x');
+            INSERT(ws, "x", 1);
+            MID(ws, 1, 1);
+            CONCAT(s, ws);
+            LEN(i);
+        END_PROGRAM"#,
+    );
+    let annotations = annotate(&unit, &index);
+    let statements = &unit.implementations[0].statements;
+
+    let expected_types = vec![
+        "INT", "INT", "STRING", "__PRG_ls", "__PRG_ls", "WSTRING", "WSTRING", "VOID", "VOID",
+    ];
+    let types: Vec<&str> = statements
+        .iter()
+        .map(|s| annotations.get_type_or_void(s, &index).get_name())
+        .collect();
+
+    assert_eq!(expected_types, types);
+}
+
 #[test]
 fn local_variables_resolves_types() {
     let (unit, index) = parse(
@@ -1084,6 +1414,139 @@ fn bitaccess_is_resolved() {
     assert_eq!(format!("{:?}", expected_types), format!("{:?}", type_names));
 }
 
+#[test]
+fn inherited_members_are_resolved() {
+    let (unit, index) = parse(
+        "
+        FUNCTION_BLOCK Base
+            VAR_INPUT a : INT; END_VAR
+        END_FUNCTION_BLOCK
+
+        FUNCTION_BLOCK Derived EXTENDS Base
+            VAR_INPUT b : DINT; END_VAR
+            a;
+            b;
+        END_FUNCTION_BLOCK
+
+        PROGRAM PRG
+            VAR fb : Derived; END_VAR
+            fb.a;
+            fb.b;
+            fb(a := 1, b := 2);
+        END_PROGRAM
+        ",
+    );
+
+    let annotations = annotate(&unit, &index);
+    //members of the super class are visible inside the derived block
+    let statements = &unit.implementations[1].statements;
+    assert_eq!(
+        Some(&StatementAnnotation::Variable {
+            qualified_name: "Base.a".into(),
+            resulting_type: "INT".into(),
+        }),
+        annotations.get_annotation(&statements[0])
+    );
+    assert_eq!(
+        Some(&StatementAnnotation::Variable {
+            qualified_name: "Derived.b".into(),
+            resulting_type: "DINT".into(),
+        }),
+        annotations.get_annotation(&statements[1])
+    );
+
+    //and via a qualified reference to an instance of the derived block
+    let statements = &unit.implementations[2].statements;
+    let type_names: Vec<&str> = statements[0..2]
+        .iter()
+        .map(|s| annotations.get_type_or_void(s, &index).get_name())
+        .collect();
+    assert_eq!(vec!["INT", "DINT"], type_names);
+
+    //inherited inputs can be assigned when calling the derived block
+    if let AstStatement::CallStatement { parameters, .. } = &statements[2] {
+        assert_parameter_assignment(parameters, 0, "INT", "DINT", &annotations, &index);
+        assert_parameter_assignment(parameters, 1, "DINT", "DINT", &annotations, &index);
+    } else {
+        panic!("call statement expected")
+    }
+}
+
+#[test]
+fn inherited_methods_are_resolved() {
+    let (unit, index) = parse(
+        "
+        CLASS Base
+            METHOD foo : INT
+            END_METHOD
+        END_CLASS
+
+        CLASS Derived EXTENDS Base
+            METHOD bar : DINT
+                foo();
+            END_METHOD
+        END_CLASS
+
+        FUNCTION buz : INT
+            VAR cl : Derived; END_VAR
+            cl.foo();
+            cl.bar();
+        END_FUNCTION
+        ",
+    );
+
+    let annotations = annotate(&unit, &index);
+    //a method of the super class can be called from within the derived class
+    let call = &unit.implementations[1].statements[0];
+    if let AstStatement::CallStatement { operator, .. } = call {
+        assert_eq!(
+            Some(&StatementAnnotation::Function {
+                return_type: "INT".into(),
+                qualified_name: "Base.foo".into(),
+            }),
+            annotations.get(operator)
+        );
+    } else {
+        panic!("Unexpcted statemet : {:?}", call);
+    }
+
+    //and on an instance of the derived class
+    let statements = &unit.implementations[2].statements;
+    let type_names: Vec<&str> = statements
+        .iter()
+        .map(|s| annotations.get_type_or_void(s, &index).get_name())
+        .collect();
+    assert_eq!(vec!["INT", "DINT"], type_names);
+}
+
+#[test]
+fn interface_method_calls_are_resolved() {
+    let (unit, index) = parse(
+        "
+        INTERFACE IDriver
+            METHOD read : INT
+                VAR_INPUT channel : SINT; offset : LREAL; END_VAR
+            END_METHOD
+        END_INTERFACE
+
+        FUNCTION buz : INT
+            VAR drv : IDriver; END_VAR
+            drv.read(channel := 1, offset := 2.0);
+        END_FUNCTION
+        ",
+    );
+
+    let annotations = annotate(&unit, &index);
+    let call = &unit.implementations[1].statements[0];
+    assert_eq!("INT", annotations.get_type_or_void(call, &index).get_name());
+    if let AstStatement::CallStatement { parameters, .. } = call {
+        assert_parameter_assignment(parameters, 0, "SINT", "DINT", &annotations, &index);
+        assert_parameter_assignment(parameters, 1, "LREAL", "REAL", &annotations, &index);
+    } else {
+        panic!("Unexpcted statemet : {:?}", call);
+    }
+}
+
 fn get_expression_from_list(stmt: &Option<AstStatement>, index: usize) -> &AstStatement {
     if let Some(AstStatement::ExpressionList { expressions, .. }) = stmt {
         &expressions[index]
@@ -1117,3 +1580,368 @@ fn assert_parameter_assignment(
         panic!("expression list expected")
     }
 }
+
+#[test]
+fn this_and_super_pointers_are_resolved_inside_methods() {
+    let (unit, index) = parse(
+        "
+        CLASS Base
+            VAR x : INT; END_VAR
+            METHOD getX : INT
+                getX := x;
+            END_METHOD
+        END_CLASS
+
+        CLASS Derived EXTENDS Base
+            VAR y : SINT; END_VAR
+            METHOD getX : INT
+                THIS^.y;
+                THIS^.x;
+                SUPER^.getX();
+            END_METHOD
+        END_CLASS
+        ",
+    );
+
+    let annotations = annotate(&unit, &index);
+    let statements = &unit.implementations[1].statements;
+    assert_eq!(
+        "SINT",
+        annotations
+            .get_type_or_void(&statements[0], &index)
+            .get_name()
+    );
+    assert_eq!(
+        "INT",
+        annotations
+            .get_type_or_void(&statements[1], &index)
+            .get_name()
+    );
+    assert_eq!(
+        "INT",
+        annotations
+            .get_type_or_void(&statements[2], &index)
+            .get_name()
+    );
+
+    if let AstStatement::QualifiedReference { elements, .. } = &statements[0] {
+        if let AstStatement::PointerAccess { reference, .. } = &elements[0] {
+            assert_eq!(
+                "__POINTER_TO_Derived",
+                annotations.get_type_or_void(reference, &index).get_name()
+            );
+        } else {
+            panic!("Unexpcted statemet : {:?}", elements[0]);
+        }
+        assert_eq!(
+            "Derived",
+            annotations
+                .get_type_or_void(&elements[0], &index)
+                .get_name()
+        );
+    } else {
+        panic!("Unexpcted statemet : {:?}", statements[0]);
+    }
+
+    if let AstStatement::CallStatement { operator, .. } = &statements[2] {
+        assert_eq!(
+            Some(&StatementAnnotation::Function {
+                qualified_name: "Base.getX".into(),
+                return_type: "INT".into(),
+            }),
+            annotations.get_annotation(operator)
+        );
+    } else {
+        panic!("Unexpcted statemet : {:?}", statements[2]);
+    }
+}
+
+#[test]
+fn property_references_are_resolved_to_their_accessors() {
+    let (unit, index) = parse(
+        "
+        FUNCTION_BLOCK Motor
+            VAR _speed : INT; END_VAR
+            PROPERTY speed : INT
+                GET speed := _speed; END_GET
+                SET _speed := speed; END_SET
+            END_PROPERTY
+        END_FUNCTION_BLOCK
+
+        PROGRAM PRG
+            VAR m : Motor; END_VAR
+            m.speed;
+            m.speed := 3;
+        END_PROGRAM
+        ",
+    );
+
+    let annotations = annotate(&unit, &index);
+    let statements = &unit.implementations[3].statements;
+    //reads and writes are lowered to calls of the accessors
+    for (call, qualified_name) in vec![
+        (&statements[0], "Motor.__get_speed"),
+        (&statements[1], "Motor.__set_speed"),
+    ] {
+        if let AstStatement::CallStatement { operator, .. } = call {
+            assert_eq!(
+                Some(qualified_name),
+                annotations.get(operator).and_then(|it| match it {
+                    StatementAnnotation::Function { qualified_name, .. } =>
+                        Some(qualified_name.as_str()),
+                    _ => None,
+                })
+            );
+        } else {
+            panic!("Unexpcted statemet : {:?}", call);
+        }
+    }
+    assert_eq!(
+        "INT",
+        annotations
+            .get_type_or_void(&statements[0], &index)
+            .get_name()
+    );
+    if let AstStatement::CallStatement { parameters, .. } = &statements[1] {
+        if let Some(AstStatement::Assignment { left, right, .. }) = parameters.as_ref() {
+            assert_eq!(
+                Some(&StatementAnnotation::Variable {
+                    resulting_type: "INT".into(),
+                    qualified_name: "Motor.__set_speed.speed".into(),
+                }),
+                annotations.get_annotation(left)
+            );
+            assert!(matches!(
+                right.as_ref(),
+                AstStatement::LiteralInteger { value: 3, .. }
+            ));
+        } else {
+            panic!("Unexpcted parameters : {:?}", parameters);
+        }
+    } else {
+        panic!("Unexpcted statemet : {:?}", statements[1]);
+    }
+
+    //inside the accessors, the property's name refers to the return value or the new value
+    let getter = &unit.implementations[0].statements;
+    if let AstStatement::Assignment { left, .. } = &getter[0] {
+        assert_eq!(
+            Some(&StatementAnnotation::Variable {
+                resulting_type: "INT".into(),
+                qualified_name: "Motor.__get_speed.speed".into(),
+            }),
+            annotations.get_annotation(left)
+        );
+    } else {
+        panic!("Unexpcted statemet : {:?}", getter[0]);
+    }
+}
+
+#[test]
+fn members_of_struct_typed_properties_are_resolved() {
+    let (unit, index) = parse(
+        "
+        TYPE Point : STRUCT x, y : SINT; END_STRUCT END_TYPE
+
+        FUNCTION_BLOCK Shape
+            VAR _origin : Point; END_VAR
+            PROPERTY origin : Point
+                GET origin := _origin; END_GET
+            END_PROPERTY
+        END_FUNCTION_BLOCK
+
+        PROGRAM PRG
+            VAR s : Shape; a : INT; END_VAR
+            s.origin.y;
+            a := s.origin.x + 1;
+        END_PROGRAM
+        ",
+    );
+
+    let annotations = annotate(&unit, &index);
+    let statements = &unit.implementations[2].statements;
+    //the member is read from the value returned by the getter
+    if let AstStatement::QualifiedReference { elements, .. } = &statements[0] {
+        assert!(matches!(elements[0], AstStatement::CallStatement { .. }));
+        assert_eq!(
+            "Point",
+            annotations
+                .get_type_or_void(&elements[0], &index)
+                .get_name()
+        );
+        assert_eq!(
+            Some(&StatementAnnotation::Variable {
+                resulting_type: "SINT".into(),
+                qualified_name: "Point.y".into(),
+            }),
+            annotations.get(&elements[1])
+        );
+    } else {
+        panic!("Unexpcted statemet : {:?}", statements[0]);
+    }
+    if let AstStatement::Assignment { right, .. } = &statements[1] {
+        assert_eq!(
+            "DINT",
+            annotations.get_type_or_void(right, &index).get_name()
+        );
+    } else {
+        panic!("Unexpcted statemet : {:?}", statements[1]);
+    }
+}
+
+#[test]
+fn function_block_methods_are_resolved() {
+    let (unit, index) = parse(
+        "
+        FUNCTION_BLOCK BaseFb
+            METHOD reset
+            END_METHOD
+        END_FUNCTION_BLOCK
+
+        FUNCTION_BLOCK MyFb EXTENDS BaseFb
+            VAR_INPUT in : INT; END_VAR
+            VAR_OUTPUT out : DINT; END_VAR
+            METHOD calc : DINT
+                calc := in + out;
+                reset();
+            END_METHOD
+            calc();
+            reset();
+        END_FUNCTION_BLOCK
+
+        PROGRAM PRG
+            VAR fb : MyFb; END_VAR
+            fb.calc();
+        END_PROGRAM
+        ",
+    );
+
+    let annotations = annotate(&unit, &index);
+    let calc = &unit.implementations[2].statements;
+    //the function block's members can be used inside its methods
+    if let AstStatement::Assignment { right, .. } = &calc[0] {
+        if let AstStatement::BinaryExpression { left, right, .. } = right.as_ref() {
+            assert_eq!(
+                Some(&StatementAnnotation::Variable {
+                    resulting_type: "INT".into(),
+                    qualified_name: "MyFb.in".into(),
+                }),
+                annotations.get(left)
+            );
+            assert_eq!(
+                Some(&StatementAnnotation::Variable {
+                    resulting_type: "DINT".into(),
+                    qualified_name: "MyFb.out".into(),
+                }),
+                annotations.get(right)
+            );
+        } else {
+            panic!("Unexpcted statemet : {:?}", right);
+        }
+    } else {
+        panic!("Unexpcted statemet : {:?}", calc[0]);
+    }
+
+    //methods can be called from other methods and from the function block's body
+    let fb_body = &unit.implementations[3].statements;
+    for (call, qualified_name) in vec![
+        (&calc[1], "BaseFb.reset"),
+        (&fb_body[0], "MyFb.calc"),
+        (&fb_body[1], "BaseFb.reset"),
+    ] {
+        if let AstStatement::CallStatement { operator, .. } = call {
+            assert_eq!(
+                Some(qualified_name),
+                annotations.get(operator).and_then(|it| match it {
+                    StatementAnnotation::Function { qualified_name, .. } =>
+                        Some(qualified_name.as_str()),
+                    _ => None,
+                })
+            );
+        } else {
+            panic!("Unexpcted statemet : {:?}", call);
+        }
+    }
+
+    //and on an instance of the function block
+    assert_eq!(
+        "DINT",
+        annotations
+            .get_type_or_void(&unit.implementations[4].statements[0], &index)
+            .get_name()
+    );
+}
+
+#[test]
+fn exponentiations_resolve_to_the_bigger_type_of_their_operands() {
+    let (unit, index) = parse(
+        "
+        FUNCTION myExpt : INT
+        VAR_INPUT a, b : INT; END_VAR
+        END_FUNCTION
+
+        PROGRAM PRG
+        VAR
+            i : INT;
+            l : LINT;
+            r : REAL;
+            lr : LREAL;
+        END_VAR
+            i ** i;
+            i ** l;
+            r ** i;
+            lr ** r;
+            i ** r;
+            EXPT(i, 2);
+            expt(r, lr);
+            myExpt(i, i);
+        END_PROGRAM",
+    );
+    let annotations = annotate(&unit, &index);
+    let statements = &unit.implementations[1].statements;
+
+    let expected_types = vec![
+        "INT", "LINT", "REAL", "LREAL", "REAL", "DINT", "LREAL", "INT",
+    ];
+    let types: Vec<&str> = statements
+        .iter()
+        .map(|s| annotations.get_type_or_void(s, &index).get_name())
+        .collect();
+    assert_eq!(expected_types, types);
+
+    //the builtin EXPT is referenced like a function
+    if let AstStatement::CallStatement { operator, .. } = &statements[5] {
+        assert_eq!(
+            Some(&StatementAnnotation::Function {
+                qualified_name: "EXPT".into(),
+                return_type: VOID_TYPE.into(),
+            }),
+            annotations.get(operator)
+        );
+    } else {
+        panic!("Unexpcted statemet : {:?}", statements[5]);
+    }
+}
+
+#[test]
+fn declared_functions_take_precedence_over_builtins() {
+    let (unit, index) = parse(
+        "
+        FUNCTION EXPT : BOOL
+        VAR_INPUT a, b : INT; END_VAR
+        END_FUNCTION
+
+        PROGRAM PRG
+            EXPT(1, 2);
+        END_PROGRAM",
+    );
+    let annotations = annotate(&unit, &index);
+    let statements = &unit.implementations[1].statements;
+
+    assert_eq!(
+        "BOOL",
+        annotations
+            .get_type_or_void(&statements[0], &index)
+            .get_name()
+    );
+}
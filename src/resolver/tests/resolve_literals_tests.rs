@@ -1,6 +1,9 @@
 use crate::{
     ast::AstStatement,
-    resolver::tests::{annotate, parse},
+    resolver::{
+        tests::{annotate, parse},
+        StatementAnnotation,
+    },
     typesystem::DataTypeInformation,
 };
 
@@ -50,6 +53,33 @@ fn string_literals_are_annotated() {
     assert_eq!(expected_types, types);
 }
 
+#[test]
+fn character_literals_and_string_elements_are_annotated() {
+    let (unit, index) = parse(
+        r#"PROGRAM PRG
+                VAR
+                    s : STRING;
+                    ws : WSTRING;
+                END_VAR
+                CHAR#'A';
+                WCHAR#"B";
+                s[2];
+                ws[2];
+            END_PROGRAM"#,
+    );
+    let annotations = annotate(&unit, &index);
+    let statements = &unit.implementations[0].statements;
+
+    let expected_types = vec!["CHAR", "WCHAR", "CHAR", "WCHAR"];
+
+    let types: Vec<&str> = statements
+        .iter()
+        .map(|s| annotations.get_type_or_void(s, &index).get_name())
+        .collect();
+
+    assert_eq!(expected_types, types);
+}
+
 #[test]
 fn int_literals_are_annotated() {
     let (unit, index) = parse(
@@ -113,6 +143,41 @@ fn date_literals_are_annotated() {
     }
 }
 
+#[test]
+fn long_date_literals_are_annotated() {
+    let (unit, index) = parse(
+        "PROGRAM PRG
+                LT#12.4d;
+                LTIME#-12m;
+                LTOD#00:00:12;
+                LTIME_OF_DAY#04:16:22.123456789;
+                LDATE_AND_TIME#1984-10-01-16:40:22;
+                LDT#2021-04-20-22:33:14;
+                LDATE#1984-10-01;
+                LD#2021-04-20;
+            END_PROGRAM",
+    );
+    let annotations = annotate(&unit, &index);
+    let statements = &unit.implementations[0].statements;
+
+    let expected_types = vec![
+        "LTIME",
+        "LTIME",
+        "LTIME_OF_DAY",
+        "LTIME_OF_DAY",
+        "LDATE_AND_TIME",
+        "LDATE_AND_TIME",
+        "LDATE",
+        "LDATE",
+    ];
+    let types: Vec<&str> = statements
+        .iter()
+        .map(|s| annotations.get_type_or_void(s, &index).get_name())
+        .collect();
+
+    assert_eq!(expected_types, types);
+}
+
 #[test]
 fn real_literals_are_annotated() {
     let (unit, index) = parse(
@@ -230,6 +295,7 @@ fn enum_literals_target_are_annotated() {
     assert_eq!(
         &DataTypeInformation::Enum {
             name: "Color".into(),
+            numeric_type: "DINT".into(),
             elements: vec!["Green".into(), "Yellow".into(), "Red".into()]
         },
         annotations
@@ -241,6 +307,7 @@ fn enum_literals_target_are_annotated() {
         assert_eq!(
             &DataTypeInformation::Enum {
                 name: "Color".into(),
+                numeric_type: "DINT".into(),
                 elements: vec!["Green".into(), "Yellow".into(), "Red".into()]
             },
             annotations
@@ -252,6 +319,60 @@ fn enum_literals_target_are_annotated() {
     }
 }
 
+#[test]
+fn qualified_enum_literals_resolve_to_the_element_of_their_enum() {
+    let (unit, index) = parse(
+        "
+            TYPE State: (Idle := 0, Run := 10) UINT; END_TYPE
+            TYPE Mode: (Run, Stop); END_TYPE
+
+            PROGRAM PRG
+                State#Run;
+                Mode#Run;
+                State#Run = 10;
+            END_PROGRAM",
+    );
+    let annotations = annotate(&unit, &index);
+    let statements = &unit.implementations[0].statements;
+
+    let targets: Vec<Option<&StatementAnnotation>> = statements[0..2]
+        .iter()
+        .map(|it| match it {
+            AstStatement::CastStatement { target, .. } => annotations.get_annotation(target),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            Some(&StatementAnnotation::Variable {
+                qualified_name: "State.Run".into(),
+                resulting_type: "State".into(),
+            }),
+            Some(&StatementAnnotation::Variable {
+                qualified_name: "Mode.Run".into(),
+                resulting_type: "Mode".into(),
+            }),
+        ],
+        targets
+    );
+
+    //the enum takes part in the binary expression with its numeric type (UINT < DINT)
+    if let AstStatement::BinaryExpression { left, .. } = &statements[2] {
+        assert_eq!(
+            "State",
+            annotations.get_type_or_void(left, &index).get_name()
+        );
+    } else {
+        panic!("no binary expression")
+    }
+    assert_eq!(
+        "DINT",
+        annotations
+            .get_type_or_void(&statements[2], &index)
+            .get_name()
+    );
+}
+
 #[test]
 fn casted_inner_literals_are_annotated() {
     let (unit, index) = parse(
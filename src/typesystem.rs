@@ -2,7 +2,7 @@
 use std::ops::Range;
 
 use crate::{
-    ast::{AstStatement, Dimension},
+    ast::{AstStatement, Dimension, Operator},
     index::Index,
 };
 
@@ -36,13 +36,25 @@ pub const DATE_AND_TIME_TYPE: &str = "DATE_AND_TIME";
 pub const SHORT_DATE_AND_TIME_TYPE: &str = "DT";
 pub const TIME_OF_DAY_TYPE: &str = "TIME_OF_DAY";
 pub const SHORT_TIME_OF_DAY_TYPE: &str = "TOD";
+pub const LDATE_TYPE: &str = "LDATE";
+pub const SHORT_LDATE_TYPE: &str = "LD";
+pub const LTIME_TYPE: &str = "LTIME";
+pub const SHORT_LTIME_TYPE: &str = "LT";
+pub const LDATE_AND_TIME_TYPE: &str = "LDATE_AND_TIME";
+pub const SHORT_LDATE_AND_TIME_TYPE: &str = "LDT";
+pub const LTIME_OF_DAY_TYPE: &str = "LTIME_OF_DAY";
+pub const SHORT_LTIME_OF_DAY_TYPE: &str = "LTOD";
 pub const ULINT_TYPE: &str = "ULINT";
 pub const REAL_TYPE: &str = "REAL";
 pub const LREAL_TYPE: &str = "LREAL";
 pub const STRING_TYPE: &str = "STRING";
 pub const WSTRING_TYPE: &str = "WSTRING";
+pub const CHAR_TYPE: &str = "CHAR";
+pub const WCHAR_TYPE: &str = "WCHAR";
 
 pub const VOID_TYPE: &str = "VOID";
+/// an untyped pointer, used for the members of interface-references
+pub const VOID_POINTER_TYPE: &str = "__VOID_POINTER";
 
 #[derive(Debug, PartialEq)]
 pub struct DataType {
@@ -82,6 +94,14 @@ impl StringEncoding {
             StringEncoding::Utf16 => 2,
         }
     }
+
+    /// returns the name of the type of a single character (CHAR or WCHAR)
+    pub fn get_char_type_name(&self) -> &'static str {
+        match self {
+            StringEncoding::Utf8 => CHAR_TYPE,
+            StringEncoding::Utf16 => WCHAR_TYPE,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -91,15 +111,27 @@ pub enum DataTypeInformation {
         member_names: Vec<String>,
         varargs: Option<VarArgs>,
     },
+    Union {
+        name: String,
+        member_names: Vec<String>,
+    },
     Array {
         name: String,
         inner_type_name: String,
         dimensions: Vec<Dimension>,
     },
+    /// an array parameter with bounds that are only known at runtime (`ARRAY[*] OF INT`). It is
+    /// passed as a pointer to its first element and the lower and upper bound of every dimension
+    VariableLengthArray {
+        name: String,
+        inner_type_name: String,
+        dimensions: usize,
+    },
     Pointer {
         name: String,
         inner_type_name: String,
         auto_deref: bool,
+        is_ref_to: bool,
     },
     Integer {
         name: String,
@@ -108,6 +140,7 @@ pub enum DataTypeInformation {
     },
     Enum {
         name: String,
+        numeric_type: String,
         elements: Vec<String>,
     },
     Float {
@@ -134,7 +167,9 @@ impl DataTypeInformation {
     pub fn get_name(&self) -> &str {
         match self {
             DataTypeInformation::Struct { name, .. } => name,
+            DataTypeInformation::Union { name, .. } => name,
             DataTypeInformation::Array { name, .. } => name,
+            DataTypeInformation::VariableLengthArray { name, .. } => name,
             DataTypeInformation::Pointer { name, .. } => name,
             DataTypeInformation::Integer { name, .. } => name,
             DataTypeInformation::Float { name, .. } => name,
@@ -154,7 +189,7 @@ impl DataTypeInformation {
     }
 
     pub fn is_int(&self) -> bool {
-        // internally an enum is represented as a DINT
+        // internally an enum is represented as its numeric type
         matches!(
             self,
             DataTypeInformation::Integer { .. } | DataTypeInformation::Enum { .. }
@@ -173,12 +208,37 @@ impl DataTypeInformation {
         matches!(self, DataTypeInformation::Float { .. })
     }
 
+    /// returns true for pointers that are not dereferenced automatically (e.g. `REF_TO INT` but
+    /// not the pointer of a VAR_IN_OUT)
+    pub fn is_pointer(&self) -> bool {
+        matches!(
+            self,
+            DataTypeInformation::Pointer {
+                auto_deref: false,
+                ..
+            }
+        )
+    }
+
+    pub fn is_variable_length_array(&self) -> bool {
+        matches!(self, DataTypeInformation::VariableLengthArray { .. })
+    }
+
     pub fn is_numerical(&self) -> bool {
         matches!(
             self,
             DataTypeInformation::Integer { .. }
                 | DataTypeInformation::Float { .. }
-                | &DataTypeInformation::Enum { .. } // internally an enum is represented as a DINT
+                | &DataTypeInformation::Enum { .. } // internally an enum is represented as its numeric type
+        )
+    }
+
+    /// returns true for the single character types CHAR and WCHAR, they are represented
+    /// as unsigned integers holding the character's code
+    pub fn is_character(&self) -> bool {
+        matches!(
+            self,
+            DataTypeInformation::Integer { name, .. } if name == CHAR_TYPE || name == WCHAR_TYPE
         )
     }
 
@@ -204,18 +264,23 @@ impl DataTypeInformation {
         }
     }
 
-    pub fn get_size(&self) -> u32 {
+    pub fn get_size(&self, index: &Index) -> u32 {
         match self {
             DataTypeInformation::Integer { size, .. } => *size,
             DataTypeInformation::Float { size, .. } => *size,
             DataTypeInformation::String { size, .. } => *size,
             DataTypeInformation::Struct { .. } => 0, //TODO : Should we fill in the struct members here for size calculation or save the struct size.
+            DataTypeInformation::Union { .. } => 0,  //the size of its biggest member
             DataTypeInformation::Array { .. } => unimplemented!("array"), //Propably length * inner type size
+            DataTypeInformation::VariableLengthArray { .. } => unimplemented!("array"),
             DataTypeInformation::Pointer { .. } => unimplemented!("pointer"),
             DataTypeInformation::SubRange { .. } => unimplemented!("subrange"),
             DataTypeInformation::Alias { .. } => unimplemented!("alias"),
             DataTypeInformation::Void => 0,
-            DataTypeInformation::Enum { .. } => DINT_SIZE,
+            DataTypeInformation::Enum { numeric_type, .. } => index
+                .find_type(numeric_type)
+                .map(|it| it.get_type_information().get_size(index))
+                .unwrap_or(DINT_SIZE),
         }
     }
 }
@@ -371,6 +436,42 @@ pub fn get_builtin_types() -> Vec<DataType> {
                 size: 64,
             },
         },
+        DataType {
+            name: LDATE_TYPE.into(),
+            initial_value: None,
+            information: DataTypeInformation::Integer {
+                name: LDATE_TYPE.into(),
+                signed: true,
+                size: 64,
+            },
+        },
+        DataType {
+            name: LTIME_TYPE.into(),
+            initial_value: None,
+            information: DataTypeInformation::Integer {
+                name: LTIME_TYPE.into(),
+                signed: true,
+                size: 64,
+            },
+        },
+        DataType {
+            name: LDATE_AND_TIME_TYPE.into(),
+            initial_value: None,
+            information: DataTypeInformation::Integer {
+                name: LDATE_AND_TIME_TYPE.into(),
+                signed: true,
+                size: 64,
+            },
+        },
+        DataType {
+            name: LTIME_OF_DAY_TYPE.into(),
+            initial_value: None,
+            information: DataTypeInformation::Integer {
+                name: LTIME_OF_DAY_TYPE.into(),
+                signed: true,
+                size: 64,
+            },
+        },
         DataType {
             name: ULINT_TYPE.into(),
             initial_value: None,
@@ -412,6 +513,24 @@ pub fn get_builtin_types() -> Vec<DataType> {
                 encoding: StringEncoding::Utf16,
             },
         },
+        DataType {
+            name: CHAR_TYPE.into(),
+            initial_value: None,
+            information: DataTypeInformation::Integer {
+                name: CHAR_TYPE.into(),
+                signed: false,
+                size: 8,
+            },
+        },
+        DataType {
+            name: WCHAR_TYPE.into(),
+            initial_value: None,
+            information: DataTypeInformation::Integer {
+                name: WCHAR_TYPE.into(),
+                signed: false,
+                size: 16,
+            },
+        },
         DataType {
             name: SHORT_DATE_AND_TIME_TYPE.into(),
             initial_value: None,
@@ -444,9 +563,52 @@ pub fn get_builtin_types() -> Vec<DataType> {
                 referenced_type: TIME_TYPE.into(),
             },
         },
+        DataType {
+            name: SHORT_LDATE_AND_TIME_TYPE.into(),
+            initial_value: None,
+            information: DataTypeInformation::Alias {
+                name: SHORT_LDATE_AND_TIME_TYPE.into(),
+                referenced_type: LDATE_AND_TIME_TYPE.into(),
+            },
+        },
+        DataType {
+            name: SHORT_LDATE_TYPE.into(),
+            initial_value: None,
+            information: DataTypeInformation::Alias {
+                name: SHORT_LDATE_TYPE.into(),
+                referenced_type: LDATE_TYPE.into(),
+            },
+        },
+        DataType {
+            name: SHORT_LTIME_OF_DAY_TYPE.into(),
+            initial_value: None,
+            information: DataTypeInformation::Alias {
+                name: SHORT_LTIME_OF_DAY_TYPE.into(),
+                referenced_type: LTIME_OF_DAY_TYPE.into(),
+            },
+        },
+        DataType {
+            name: SHORT_LTIME_TYPE.into(),
+            initial_value: None,
+            information: DataTypeInformation::Alias {
+                name: SHORT_LTIME_TYPE.into(),
+                referenced_type: LTIME_TYPE.into(),
+            },
+        },
     ]
 }
 
+/// returns the long form (e.g. LDATE) of the given short date type (e.g. DATE). The short forms
+/// DATE, TIME_OF_DAY and DATE_AND_TIME are stored in milliseconds, their long forms in nanoseconds
+pub fn get_long_date_type(short_type_name: &str) -> Option<&'static str> {
+    match short_type_name {
+        DATE_TYPE => Some(LDATE_TYPE),
+        TIME_OF_DAY_TYPE => Some(LTIME_OF_DAY_TYPE),
+        DATE_AND_TIME_TYPE => Some(LDATE_AND_TIME_TYPE),
+        _ => None,
+    }
+}
+
 pub fn new_string_information(len: u32) -> DataTypeInformation {
     DataTypeInformation::String {
         size: len + 1,
@@ -461,7 +623,7 @@ pub fn new_wide_string_information(len: u32) -> DataTypeInformation {
     }
 }
 
-fn get_rank(type_information: &DataTypeInformation) -> u32 {
+fn get_rank(type_information: &DataTypeInformation, index: &Index) -> u32 {
     match type_information {
         DataTypeInformation::Integer { signed, size, .. } => {
             if *signed {
@@ -471,6 +633,12 @@ fn get_rank(type_information: &DataTypeInformation) -> u32 {
             }
         }
         DataTypeInformation::Float { size, .. } => size + 1000,
+        //an enum ranks like its numeric type
+        DataTypeInformation::Enum { .. } => index
+            .find_intrinsic_type_information(type_information)
+            .filter(|it| !matches!(it, DataTypeInformation::Enum { .. }))
+            .map(|it| get_rank(it, index))
+            .unwrap_or(DINT_SIZE + 1),
         _ => unreachable!(),
     }
 }
@@ -497,17 +665,18 @@ fn get_lreal_type() -> DataTypeInformation {
 pub fn get_bigger_type(
     ltype: &DataTypeInformation,
     rtype: &DataTypeInformation,
+    index: &Index,
 ) -> DataTypeInformation {
     if is_same_type_nature(ltype, rtype) {
-        if get_rank(ltype) < get_rank(rtype) {
+        if get_rank(ltype, index) < get_rank(rtype, index) {
             rtype.clone()
         } else {
             ltype.clone()
         }
     } else {
         let real_type = get_real_type();
-        let real_size = real_type.get_size();
-        if ltype.get_size() > real_size || rtype.get_size() > real_size {
+        let real_size = real_type.get_size(index);
+        if ltype.get_size(index) > real_size || rtype.get_size(index) > real_size {
             get_lreal_type()
         } else {
             real_type
@@ -521,7 +690,7 @@ pub fn get_bigger_type_borrow<'t>(
     index: &'t Index,
 ) -> &'t DataTypeInformation {
     if is_same_type_nature(ltype, rtype) {
-        if get_rank(ltype) < get_rank(rtype) {
+        if get_rank(ltype, index) < get_rank(rtype, index) {
             rtype
         } else {
             ltype
@@ -531,8 +700,8 @@ pub fn get_bigger_type_borrow<'t>(
             .get_type(REAL_TYPE)
             .map(|it| it.get_type_information())
             .unwrap();
-        let real_size = real_type.get_size();
-        if ltype.get_size() > real_size || rtype.get_size() > real_size {
+        let real_size = real_type.get_size(index);
+        if ltype.get_size(index) > real_size || rtype.get_size(index) > real_size {
             index.get_type(LREAL_TYPE).unwrap().get_type_information()
         } else {
             real_type
@@ -566,14 +735,202 @@ pub fn get_signed_type<'t>(
     None
 }
 
+/// the kinds of date and time types, every kind has a short and a long type (e.g. TIME and LTIME)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateTimeKind {
+    Time,
+    Date,
+    TimeOfDay,
+    DateAndTime,
+}
+
+/// returns the kind of the given date or time type and whether it is a long type
+pub fn get_date_time_kind(type_name: &str) -> Option<(DateTimeKind, bool)> {
+    match type_name {
+        TIME_TYPE => Some((DateTimeKind::Time, false)),
+        DATE_TYPE => Some((DateTimeKind::Date, false)),
+        TIME_OF_DAY_TYPE => Some((DateTimeKind::TimeOfDay, false)),
+        DATE_AND_TIME_TYPE => Some((DateTimeKind::DateAndTime, false)),
+        LTIME_TYPE => Some((DateTimeKind::Time, true)),
+        LDATE_TYPE => Some((DateTimeKind::Date, true)),
+        LTIME_OF_DAY_TYPE => Some((DateTimeKind::TimeOfDay, true)),
+        LDATE_AND_TIME_TYPE => Some((DateTimeKind::DateAndTime, true)),
+        _ => None,
+    }
+}
+
+fn get_date_time_type_name(kind: DateTimeKind, is_long: bool) -> &'static str {
+    match (kind, is_long) {
+        (DateTimeKind::Time, false) => TIME_TYPE,
+        (DateTimeKind::Date, false) => DATE_TYPE,
+        (DateTimeKind::TimeOfDay, false) => TIME_OF_DAY_TYPE,
+        (DateTimeKind::DateAndTime, false) => DATE_AND_TIME_TYPE,
+        (DateTimeKind::Time, true) => LTIME_TYPE,
+        (DateTimeKind::Date, true) => LDATE_TYPE,
+        (DateTimeKind::TimeOfDay, true) => LTIME_OF_DAY_TYPE,
+        (DateTimeKind::DateAndTime, true) => LDATE_AND_TIME_TYPE,
+    }
+}
+
+/// returns true if the given type is one of the date and time types (e.g. TIME, LDATE or DT)
+pub fn is_date_or_time_type(data_type: &DataTypeInformation) -> bool {
+    get_date_time_kind(data_type.get_name()).is_some()
+}
+
+/// returns the nanoseconds of one unit of the given date or time type. TIME and the long types
+/// are stored in nanoseconds, DATE, TIME_OF_DAY and DATE_AND_TIME in milliseconds
+pub fn get_date_time_resolution(data_type: &DataTypeInformation) -> Option<i64> {
+    get_date_time_kind(data_type.get_name()).map(|(kind, is_long)| {
+        if kind == DateTimeKind::Time || is_long {
+            1
+        } else {
+            1_000_000
+        }
+    })
+}
+
+/// returns the nanoseconds of one unit of a date or time value that is converted to or from a
+/// number (e.g. `TIME_TO_DINT`). The long types convert in nanoseconds, all others in milliseconds
+pub fn get_date_time_conversion_resolution(data_type: &DataTypeInformation) -> Option<i64> {
+    get_date_time_kind(data_type.get_name()).map(|(_, is_long)| if is_long { 1 } else { 1_000_000 })
+}
+
+/// returns true if a date or time value can be converted into the given date or time type.
+/// Every type converts into its short or long form (e.g. `TIME_TO_LTIME`), a DATE_AND_TIME
+/// also converts into its date or its time of day (e.g. `DT_TO_TOD`)
+pub fn is_valid_date_time_conversion(source: &str, target: &str) -> bool {
+    use DateTimeKind::*;
+    match (get_date_time_kind(source), get_date_time_kind(target)) {
+        (Some((source_kind, _)), Some((target_kind, _))) => {
+            source_kind == target_kind
+                || (source_kind == DateAndTime && matches!(target_kind, Date | TimeOfDay))
+        }
+        _ => false,
+    }
+}
+
+/// returns the name of the type resulting from the given arithmetic operation on a date or time
+/// value according to IEC 61131-3:
+/// - `TIME + TIME`, `TIME - TIME` -> `TIME`
+/// - `TOD + TIME`, `TOD - TIME` -> `TOD`, `DT + TIME`, `DT - TIME` -> `DT`
+/// - `DATE - DATE`, `TOD - TOD`, `DT - DT` -> `TIME`
+/// - `TIME * ANY_NUM`, `ANY_NUM * TIME`, `TIME / ANY_NUM` -> `TIME`
+///
+/// the result is a long type if one of the date or time operands is a long type.
+/// Returns None if the operation is not defined for the given types (e.g. `DATE + DATE`)
+pub fn get_date_time_operation_result(
+    left: &DataTypeInformation,
+    operator: &Operator,
+    right: &DataTypeInformation,
+) -> Option<&'static str> {
+    use DateTimeKind::*;
+    match (
+        get_date_time_kind(left.get_name()),
+        operator,
+        get_date_time_kind(right.get_name()),
+    ) {
+        (Some((left_kind, left_long)), Operator::Plus, Some((Time, right_long)))
+        | (Some((left_kind, left_long)), Operator::Minus, Some((Time, right_long)))
+            if left_kind != Date =>
+        {
+            Some(get_date_time_type_name(left_kind, left_long || right_long))
+        }
+        (Some((left_kind, left_long)), Operator::Minus, Some((right_kind, right_long)))
+            if left_kind == right_kind =>
+        {
+            Some(get_date_time_type_name(Time, left_long || right_long))
+        }
+        (Some((Time, is_long)), Operator::Multiplication, None)
+        | (Some((Time, is_long)), Operator::Division, None)
+            if right.is_numerical() =>
+        {
+            Some(get_date_time_type_name(Time, is_long))
+        }
+        (None, Operator::Multiplication, Some((Time, is_long))) if left.is_numerical() => {
+            Some(get_date_time_type_name(Time, is_long))
+        }
+        _ => None,
+    }
+}
+
+/// returns false if the given types are date or time types of different kinds which cannot be
+/// compared with each other (e.g. a DATE with a TIME). DT can be compared with DT or LDT
+pub fn is_date_time_comparison_valid(
+    left: &DataTypeInformation,
+    right: &DataTypeInformation,
+) -> bool {
+    match (
+        get_date_time_kind(left.get_name()),
+        get_date_time_kind(right.get_name()),
+    ) {
+        (Some((left_kind, _)), Some((right_kind, _))) => left_kind == right_kind,
+        _ => true,
+    }
+}
+
+/// returns the name of the type resulting from the given operation on a pointer:
+/// - `POINTER + ANY_INT`, `ANY_INT + POINTER`, `POINTER - ANY_INT` -> `POINTER`
+/// - `POINTER - POINTER` -> `LINT`
+/// - comparisons of two pointers -> `BOOL`
+///
+/// Returns None if the operation is not defined for the given types (e.g. `POINTER * 2`)
+pub fn get_pointer_operation_result<'t>(
+    left: &'t DataTypeInformation,
+    operator: &Operator,
+    right: &'t DataTypeInformation,
+) -> Option<&'t str> {
+    match operator {
+        Operator::Plus | Operator::Minus if left.is_pointer() && right.is_int() => {
+            Some(left.get_name())
+        }
+        Operator::Plus if left.is_int() && right.is_pointer() => Some(right.get_name()),
+        Operator::Minus if left.is_pointer() && right.is_pointer() => Some(LINT_TYPE),
+        Operator::Equal
+        | Operator::NotEqual
+        | Operator::Less
+        | Operator::Greater
+        | Operator::LessOrEqual
+        | Operator::GreaterOrEqual
+            if left.is_pointer() && right.is_pointer() =>
+        {
+            Some(BOOL_TYPE)
+        }
+        _ => None,
+    }
+}
+
+/// returns the types a comparison of the given operands is validated and generated with. NULL
+/// has no type of its own, so it takes the type of the pointer it is compared to with `=` or `<>`
+pub fn get_null_comparison_types<'t>(
+    left: &AstStatement,
+    left_type: &'t DataTypeInformation,
+    operator: &Operator,
+    right: &AstStatement,
+    right_type: &'t DataTypeInformation,
+) -> (&'t DataTypeInformation, &'t DataTypeInformation) {
+    if !matches!(operator, Operator::Equal | Operator::NotEqual) {
+        return (left_type, right_type);
+    }
+    match (left, right) {
+        (AstStatement::LiteralNull { .. }, _) if right_type.is_pointer() => {
+            (right_type, right_type)
+        }
+        (_, AstStatement::LiteralNull { .. }) if left_type.is_pointer() => (left_type, left_type),
+        _ => (left_type, right_type),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
         ast::CompilationUnit,
         index::visitor::visit,
+        lexer::lex,
+        parser::parse,
         typesystem::{
-            get_signed_type, BYTE_TYPE, DINT_TYPE, DWORD_TYPE, INT_TYPE, LINT_TYPE, LWORD_TYPE,
-            SINT_TYPE, UDINT_TYPE, UINT_TYPE, ULINT_TYPE, USINT_TYPE, WORD_TYPE,
+            get_bigger_type, get_signed_type, BYTE_TYPE, DINT_TYPE, DWORD_TYPE, INT_TYPE,
+            LINT_TYPE, LREAL_TYPE, LWORD_TYPE, SINT_TYPE, UDINT_TYPE, UINT_TYPE, ULINT_TYPE,
+            USINT_TYPE, WORD_TYPE,
         },
     };
 
@@ -610,4 +967,33 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    pub fn enums_have_the_size_and_rank_of_their_numeric_type() {
+        let (unit, _) = parse(lex("
+            TYPE Small : (a, b) SINT; END_TYPE
+            TYPE Large : (c, d) LINT; END_TYPE
+            TYPE Default : (e, f); END_TYPE
+            "));
+        let index = visit(&unit);
+        let get_type = |name| index.find_type_information(name).unwrap();
+
+        assert_eq!(8, get_type("Small").get_size(&index));
+        assert_eq!(64, get_type("Large").get_size(&index));
+        assert_eq!(32, get_type("Default").get_size(&index));
+
+        //mixed with other numbers, an enum behaves like its numeric type
+        assert_eq!(
+            LINT_TYPE,
+            get_bigger_type(&get_type("Small"), &get_type(LINT_TYPE), &index).get_name()
+        );
+        assert_eq!(
+            "Large",
+            get_bigger_type(&get_type("Large"), &get_type(SINT_TYPE), &index).get_name()
+        );
+        assert_eq!(
+            LREAL_TYPE,
+            get_bigger_type(&get_type("Large"), &get_type("REAL"), &index).get_name()
+        );
+    }
 }
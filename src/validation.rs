@@ -9,10 +9,11 @@ use crate::{
 };
 
 use self::{
-    pou_validator::PouValidator, stmt_validator::StatementValidator,
-    variable_validator::VariableValidator,
+    configuration_validator::ConfigurationValidator, pou_validator::PouValidator,
+    stmt_validator::StatementValidator, variable_validator::VariableValidator,
 };
 
+mod configuration_validator;
 mod pou_validator;
 mod stmt_validator;
 mod variable_validator;
@@ -34,6 +35,8 @@ macro_rules! visit_all_statements {
 pub struct ValidationContext<'s> {
     ast_annotation: &'s AnnotationMap,
     index: &'s Index,
+    /// the implementation whose statements are validated
+    implementation: Option<&'s str>,
 }
 
 pub struct Validator {
@@ -41,6 +44,7 @@ pub struct Validator {
     pou_validator: PouValidator,
     variable_validator: VariableValidator,
     stmt_validator: StatementValidator,
+    configuration_validator: ConfigurationValidator,
 }
 
 impl Validator {
@@ -49,6 +53,7 @@ impl Validator {
             pou_validator: PouValidator::new(),
             variable_validator: VariableValidator::new(),
             stmt_validator: StatementValidator::new(),
+            configuration_validator: ConfigurationValidator::new(),
         }
     }
 
@@ -57,6 +62,7 @@ impl Validator {
         all_diagnostics.append(&mut self.pou_validator.diagnostics);
         all_diagnostics.append(&mut self.variable_validator.diagnostics);
         all_diagnostics.append(&mut self.stmt_validator.diagnostics);
+        all_diagnostics.append(&mut self.configuration_validator.diagnostics);
         all_diagnostics
     }
 
@@ -69,37 +75,67 @@ impl Validator {
         let context = ValidationContext {
             ast_annotation: annotations,
             index,
+            implementation: None,
         };
 
         for pou in &unit.units {
             self.visit_pou(&context, pou);
         }
 
+        for block in &unit.global_vars {
+            self.variable_validator
+                .validate_variable_block(block, &context);
+            for variable in &block.variables {
+                self.variable_validator
+                    .validate_located_variable(None, block, variable, &context);
+            }
+        }
+
         for t in &unit.types {
             self.visit_user_type_declaration(&context, t);
         }
 
         for i in &unit.implementations {
+            let context = ValidationContext {
+                implementation: Some(i.name.as_str()),
+                ..context
+            };
             i.statements
                 .iter()
                 .for_each(|s| self.visit_statement(&context, s));
         }
+
+        for configuration in &unit.configurations {
+            self.configuration_validator
+                .validate_configuration(configuration, &context);
+        }
     }
 
     pub fn visit_user_type_declaration(
         &mut self,
-        _context: &ValidationContext,
+        context: &ValidationContext,
         user_data_type: &UserTypeDeclaration,
     ) {
-        self.variable_validator
-            .validate_data_type(&user_data_type.data_type, &user_data_type.location);
+        self.variable_validator.validate_data_type(
+            &user_data_type.data_type,
+            &user_data_type.location,
+            context,
+        );
     }
 
     pub fn visit_pou(&mut self, context: &ValidationContext, pou: &Pou) {
-        self.pou_validator.validate_pou(pou);
+        self.pou_validator.validate_pou(pou, context);
 
         for block in &pou.variable_blocks {
             self.visit_variable_container(context, block);
+            for variable in &block.variables {
+                self.variable_validator.validate_located_variable(
+                    Some(pou),
+                    block,
+                    variable,
+                    context,
+                );
+            }
         }
     }
 
@@ -108,7 +144,8 @@ impl Validator {
         context: &ValidationContext,
         container: &VariableBlock,
     ) {
-        self.variable_validator.validate_variable_block(container);
+        self.variable_validator
+            .validate_variable_block(container, context);
 
         for variable in &container.variables {
             self.visit_variable(context, variable);
@@ -145,14 +182,19 @@ impl Validator {
         location: &SourceRange,
     ) {
         self.variable_validator
-            .validate_data_type(data_type, location);
+            .validate_data_type(data_type, location, context);
 
         match data_type {
-            DataType::StructType { variables, .. } => variables
-                .iter()
-                .for_each(|v| self.visit_variable(context, v)),
+            DataType::StructType { variables, .. } | DataType::UnionType { variables, .. } => {
+                variables
+                    .iter()
+                    .for_each(|v| self.visit_variable(context, v))
+            }
             DataType::ArrayType {
                 referenced_type, ..
+            }
+            | DataType::VariableLengthArrayType {
+                referenced_type, ..
             } => self.visit_data_type_declaration(context, referenced_type),
             DataType::VarArgs {
                 referenced_type: Some(referenced_type),
@@ -198,6 +240,10 @@ impl Validator {
                 self.visit_statement(context, left);
                 self.visit_statement(context, right);
             }
+            AstStatement::ReferenceAssignment { left, right, .. } => {
+                self.visit_statement(context, left);
+                self.visit_statement(context, right);
+            }
             AstStatement::CallStatement {
                 parameters,
                 operator,
@@ -0,0 +1,92 @@
+use super::ValidationContext;
+use crate::{
+    ast::{AstStatement, Configuration, ProgramInstance, Resource, Task},
+    index::{get_task_name, ImplementationType},
+    Diagnostic,
+};
+
+/// validates CONFIGURATIONs with their resources, tasks and program instances
+pub struct ConfigurationValidator {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ConfigurationValidator {
+    pub fn new() -> ConfigurationValidator {
+        ConfigurationValidator {
+            diagnostics: Vec::new(),
+        }
+    }
+
+    pub fn validate_configuration(
+        &mut self,
+        configuration: &Configuration,
+        context: &ValidationContext,
+    ) {
+        for resource in &configuration.resources {
+            for task in &resource.tasks {
+                self.validate_task(task);
+            }
+            for program in &resource.programs {
+                self.validate_program_instance(resource, program, context);
+            }
+        }
+    }
+
+    /// validates that the task's INTERVAL is a time- and its PRIORITY an integer-literal
+    fn validate_task(&mut self, task: &Task) {
+        if let Some(interval) = &task.interval {
+            if !matches!(interval, AstStatement::LiteralTime { .. }) {
+                self.diagnostics.push(Diagnostic::invalid_task_parameter(
+                    "INTERVAL",
+                    "TIME",
+                    interval.get_location(),
+                ));
+            }
+        }
+        if let Some(priority) = &task.priority {
+            if !matches!(priority, AstStatement::LiteralInteger { .. }) {
+                self.diagnostics.push(Diagnostic::invalid_task_parameter(
+                    "PRIORITY",
+                    "ANY_INT",
+                    priority.get_location(),
+                ));
+            }
+        }
+    }
+
+    /// validates that the instance's task is declared in the same resource and that
+    /// the instantiated POU is a PROGRAM
+    fn validate_program_instance(
+        &mut self,
+        resource: &Resource,
+        program: &ProgramInstance,
+        context: &ValidationContext,
+    ) {
+        let index = context.index;
+        if let Some(task) = &program.task {
+            if index
+                .find_task(&get_task_name(&resource.name, task))
+                .is_none()
+            {
+                self.diagnostics.push(Diagnostic::unresolved_task(
+                    task,
+                    &resource.name,
+                    program.location.clone(),
+                ));
+            }
+        }
+
+        match index.find_implementation(&program.program) {
+            Some(implementation)
+                if implementation.get_implementation_type() == &ImplementationType::Program => {}
+            Some(_) => self.diagnostics.push(Diagnostic::program_expected(
+                &program.program,
+                program.location.clone(),
+            )),
+            None => self.diagnostics.push(Diagnostic::unrseolved_reference(
+                &program.program,
+                program.location.clone(),
+            )),
+        }
+    }
+}
@@ -1,4 +1,10 @@
-use crate::{ast::Pou, Diagnostic};
+use super::ValidationContext;
+use crate::{
+    ast::{PolymorphismMode, Pou, PouType},
+    index::{Index, VariableType},
+    typesystem::DataTypeInformation,
+    Diagnostic,
+};
 
 /// validates POUs
 pub struct PouValidator {
@@ -12,5 +18,140 @@ impl PouValidator {
         }
     }
 
-    pub fn validate_pou(&mut self, _pou: &Pou) {}
+    pub fn validate_pou(&mut self, pou: &Pou, context: &ValidationContext) {
+        if let Some(super_class) = &pou.super_class {
+            self.validate_super_class(pou, super_class, context);
+        }
+        for interface in &pou.interfaces {
+            self.validate_implemented_interface(pou, interface, context);
+        }
+        if let PouType::Method { owner_class } = &pou.pou_type {
+            self.validate_overridden_method(pou, owner_class, context);
+        }
+    }
+
+    /// validates that the method does not override a FINAL method of a super class
+    fn validate_overridden_method(
+        &mut self,
+        pou: &Pou,
+        owner_class: &str,
+        context: &ValidationContext,
+    ) {
+        let index = context.index;
+        let method_name = pou.name.rsplit('.').next().unwrap_or_default();
+        let overridden_method = index
+            .find_super_class_name(owner_class)
+            .and_then(|super_class| index.find_method(super_class, method_name))
+            .map(|it| it.get_call_name());
+        if let Some(overridden_method) = overridden_method {
+            if index.is_final(overridden_method) {
+                self.diagnostics.push(Diagnostic::final_method_overridden(
+                    &pou.name,
+                    overridden_method,
+                    pou.location.clone(),
+                ));
+            }
+        }
+    }
+
+    /// validates that the interface exists and that the pou implements all of its methods
+    /// with matching signatures. Abstract classes may leave methods unimplemented.
+    fn validate_implemented_interface(
+        &mut self,
+        pou: &Pou,
+        interface: &str,
+        context: &ValidationContext,
+    ) {
+        let index = context.index;
+        if !index.is_interface(interface) {
+            self.diagnostics.push(Diagnostic::unresolved_interface(
+                interface,
+                pou.location.clone(),
+            ));
+            return;
+        }
+
+        for method in index.find_interface_methods(interface) {
+            let interface_method = method.get_call_name();
+            let method_name = interface_method.rsplit('.').next().unwrap_or_default();
+            match index.find_method(&pou.name, method_name) {
+                Some(implementation) => {
+                    let implementation = implementation.get_call_name();
+                    if get_signature(index, implementation)
+                        != get_signature(index, interface_method)
+                    {
+                        self.diagnostics
+                            .push(Diagnostic::interface_method_signature_mismatch(
+                                implementation,
+                                interface_method,
+                                pou.location.clone(),
+                            ));
+                    }
+                }
+                None if pou.poly_mode != Some(PolymorphismMode::Abstract) => {
+                    self.diagnostics.push(Diagnostic::missing_interface_method(
+                        &pou.name,
+                        interface_method,
+                        pou.location.clone(),
+                    ))
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// validates that the super class exists and that the class hierarchy is not cyclic
+    fn validate_super_class(&mut self, pou: &Pou, super_class: &str, context: &ValidationContext) {
+        let is_struct = matches!(
+            context
+                .index
+                .find_type(super_class)
+                .map(|it| it.get_type_information()),
+            Some(DataTypeInformation::Struct { .. })
+        );
+        if !is_struct {
+            self.diagnostics.push(Diagnostic::unresolved_super_class(
+                super_class,
+                pou.location.clone(),
+            ));
+            return;
+        }
+
+        //the hierarchy stops before revisiting a class, so a remaining super class means a cycle
+        let hierarchy = context.index.get_class_hierarchy(&pou.name);
+        let is_cyclic = hierarchy
+            .last()
+            .and_then(|it| context.index.find_super_class_name(it))
+            .is_some();
+        if is_cyclic {
+            self.diagnostics.push(Diagnostic::cyclic_inheritance(
+                &pou.name,
+                pou.location.clone(),
+            ));
+        }
+    }
+}
+
+/// returns the return type and the parameters (variable type, name and type) of the given method
+fn get_signature(index: &Index, method: &str) -> Vec<(VariableType, String, String)> {
+    index
+        .find_local_members(method)
+        .into_iter()
+        .filter(|it| {
+            matches!(
+                it.get_variable_type(),
+                VariableType::Input
+                    | VariableType::Output
+                    | VariableType::InOut
+                    | VariableType::Return
+            )
+        })
+        .map(|it| {
+            (
+                it.get_variable_type(),
+                it.get_name().to_lowercase(),
+                it.get_type_name().to_lowercase(),
+            )
+        })
+        .collect()
 }
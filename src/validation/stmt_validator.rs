@@ -2,11 +2,20 @@ use std::mem::discriminant;
 
 use super::ValidationContext;
 use crate::{
-    ast::{AstStatement, SourceRange},
+    ast::{
+        flatten_expression_list, AccessModifier, AstStatement, Operator, SourceRange,
+        PROPERTY_GETTER_PREFIX, PROPERTY_SETTER_PREFIX,
+    },
+    builtins::BuiltIn,
+    index::{ImplementationIndexEntry, ImplementationType, Index, VariableType},
+    resolver::StatementAnnotation,
     typesystem::{
-        BOOL_TYPE, DATE_AND_TIME_TYPE, DATE_TYPE, DINT_TYPE, INT_TYPE, LINT_TYPE, LREAL_TYPE,
-        SINT_TYPE, STRING_TYPE, TIME_OF_DAY_TYPE, TIME_TYPE, UDINT_TYPE, UINT_TYPE, ULINT_TYPE,
-        USINT_TYPE, VOID_TYPE, WSTRING_TYPE,
+        get_date_time_operation_result, get_null_comparison_types, get_pointer_operation_result,
+        is_date_or_time_type, is_date_time_comparison_valid, DataTypeInformation, BOOL_TYPE,
+        DATE_AND_TIME_TYPE, DATE_TYPE, DINT_TYPE, INT_TYPE, LDATE_AND_TIME_TYPE, LDATE_TYPE,
+        LINT_TYPE, LREAL_TYPE, LTIME_OF_DAY_TYPE, LTIME_TYPE, SINT_TYPE, STRING_TYPE,
+        TIME_OF_DAY_TYPE, TIME_TYPE, UDINT_TYPE, UINT_TYPE, ULINT_TYPE, USINT_TYPE, VOID_TYPE,
+        WCHAR_TYPE, WSTRING_TYPE,
     },
     Diagnostic,
 };
@@ -46,6 +55,23 @@ impl StatementValidator {
             } => {
                 self.validate_cast_literal(target, type_name, location, context);
             }
+            AstStatement::CallStatement {
+                operator,
+                parameters,
+                location,
+                id,
+            } => {
+                if let Some(StatementAnnotation::Function { qualified_name, .. }) =
+                    context.ast_annotation.get_annotation(operator)
+                {
+                    if let Some(method) = context.index.find_implementation(qualified_name) {
+                        let member_name = get_member_name(qualified_name);
+                        self.validate_access(method, &member_name, location, context);
+                    } else if let Some(builtin) = BuiltIn::find(qualified_name) {
+                        self.validate_builtin_call(builtin, id, parameters, location, context);
+                    }
+                }
+            }
             AstStatement::QualifiedReference { elements, .. } => {
                 let mut i = elements.iter().rev();
                 if let Some((
@@ -62,19 +88,23 @@ impl StatementValidator {
                         .ast_annotation
                         .get_type_or_void(reference, context.index)
                         .get_type_information();
+                    let target_type = context
+                        .index
+                        .find_intrinsic_type_information(target_type)
+                        .unwrap_or(target_type);
                     if target_type.is_int() {
-                        if !access.is_compatible(target_type) {
+                        if !access.is_compatible(target_type, context.index) {
                             self.diagnostics.push(Diagnostic::incompatible_directaccess(
                                 &format!("{:?}", access),
                                 access.get_bit_witdh(),
                                 location.clone(),
                             ))
-                        } else if !access.is_in_range(*index, target_type) {
+                        } else if !access.is_in_range(*index, target_type, context.index) {
                             self.diagnostics
                                 .push(Diagnostic::incompatible_directaccess_range(
                                     &format!("{:?}", access),
                                     target_type.get_name(),
-                                    access.get_range(target_type),
+                                    access.get_range(target_type, context.index),
                                     location.clone(),
                                 ))
                         }
@@ -88,10 +118,207 @@ impl StatementValidator {
                     }
                 }
             }
+            AstStatement::ArrayAccess {
+                reference, access, ..
+            } => {
+                self.validate_string_access(reference, access, context);
+            }
+            AstStatement::BinaryExpression {
+                left,
+                operator,
+                right,
+                ..
+            } => {
+                self.validate_binary_expression(left, operator, right, statement, context);
+            }
+            AstStatement::ReferenceAssignment { left, right, .. } => {
+                self.validate_reference_assignment(left, right, context);
+            }
             _ => (),
         }
     }
 
+    /// validates that date and time values and pointers only take part in the operations defined
+    /// for them (e.g. `DT - DT` is valid but `DATE + DATE` is not)
+    fn validate_binary_expression(
+        &mut self,
+        left: &AstStatement,
+        operator: &Operator,
+        right: &AstStatement,
+        statement: &AstStatement,
+        context: &ValidationContext,
+    ) {
+        let left_type = context
+            .ast_annotation
+            .get_type_or_void(left, context.index)
+            .get_type_information();
+        let right_type = context
+            .ast_annotation
+            .get_type_or_void(right, context.index)
+            .get_type_information();
+        let (left_type, right_type) =
+            get_null_comparison_types(left, left_type, operator, right, right_type);
+        let is_valid = if is_date_or_time_type(left_type) || is_date_or_time_type(right_type) {
+            is_valid_date_time_operation(left_type, operator, right_type)
+        } else if left_type.is_pointer() || right_type.is_pointer() {
+            is_valid_pointer_operation(left_type, operator, right_type)
+        } else {
+            true
+        };
+        if !is_valid {
+            self.diagnostics.push(Diagnostic::invalid_operation(
+                left_type.get_name(),
+                &operator.to_string(),
+                right_type.get_name(),
+                statement.get_location(),
+            ));
+        }
+    }
+
+    /// validates that a constant index into a string addresses one of its characters,
+    /// the first character has the index 1
+    fn validate_string_access(
+        &mut self,
+        reference: &AstStatement,
+        access: &AstStatement,
+        context: &ValidationContext,
+    ) {
+        let reference_type = context
+            .ast_annotation
+            .get_type_or_void(reference, context.index)
+            .get_type_information();
+        let reference_type = context
+            .index
+            .find_intrinsic_type_information(reference_type)
+            .unwrap_or(reference_type);
+        if let (
+            DataTypeInformation::String { size, .. },
+            AstStatement::LiteralInteger {
+                value, location, ..
+            },
+        ) = (reference_type, access)
+        {
+            //the size includes the terminator
+            let length = size.saturating_sub(1);
+            if *value < 1 || *value > length as i128 {
+                self.diagnostics.push(Diagnostic::string_index_out_of_range(
+                    *value,
+                    length,
+                    location.clone(),
+                ));
+            }
+        }
+    }
+
+    /// validates that `REF=` rebinds a `REFERENCE TO` variable to a variable of the referenced
+    /// type (or to NULL)
+    fn validate_reference_assignment(
+        &mut self,
+        left: &AstStatement,
+        right: &AstStatement,
+        context: &ValidationContext,
+    ) {
+        let reference = match context.ast_annotation.get_annotation(left) {
+            Some(StatementAnnotation::Variable { qualified_name, .. }) => {
+                match qualified_name.rfind('.') {
+                    Some(separator) => context.index.find_member(
+                        &qualified_name[..separator],
+                        &qualified_name[separator + 1..],
+                    ),
+                    None => context.index.find_global_variable(qualified_name),
+                }
+            }
+            _ => None,
+        };
+        let referenced_type = reference
+            .filter(|it| it.get_variable_type() != VariableType::InOut)
+            .map(|it| {
+                context
+                    .index
+                    .get_effective_type_by_name(it.get_type_name())
+                    .get_type_information()
+            })
+            .and_then(|it| match it {
+                DataTypeInformation::Pointer {
+                    auto_deref: true,
+                    inner_type_name,
+                    ..
+                } => Some(context.index.get_effective_type_by_name(inner_type_name)),
+                _ => None,
+            });
+
+        if let Some(referenced_type) = referenced_type {
+            let found = match right {
+                AstStatement::LiteralNull { .. } => return,
+                AstStatement::Reference { .. }
+                | AstStatement::QualifiedReference { .. }
+                | AstStatement::ArrayAccess { .. }
+                | AstStatement::PointerAccess { .. } => context
+                    .ast_annotation
+                    .get_type_or_void(right, context.index)
+                    .get_name(),
+                _ => "an expression",
+            };
+            if found != referenced_type.get_name() {
+                self.diagnostics.push(Diagnostic::invalid_reference_target(
+                    referenced_type.get_name(),
+                    found,
+                    right.get_location(),
+                ));
+            }
+        } else {
+            self.diagnostics
+                .push(Diagnostic::reference_expected(left.get_location()));
+        }
+    }
+
+    /// validates the number of arguments passed to a builtin function and whether the
+    /// function accepts their types (the resolver only types a call with valid arguments)
+    fn validate_builtin_call(
+        &mut self,
+        builtin: BuiltIn,
+        id: &usize,
+        parameters: &Option<AstStatement>,
+        location: &SourceRange,
+        context: &ValidationContext,
+    ) {
+        let arguments = parameters
+            .as_ref()
+            .map(flatten_expression_list)
+            .unwrap_or_default();
+        let (min, max) = builtin.get_input_count();
+        if arguments.len() < min || max.map_or(false, |max| arguments.len() > max) {
+            let expected = match max {
+                Some(max) if max == min => min.to_string(),
+                Some(max) => format!("{} to {}", min, max),
+                None => format!("at least {}", min),
+            };
+            self.diagnostics.push(Diagnostic::invalid_argument_count(
+                &builtin.get_name(),
+                &expected,
+                arguments.len(),
+                location.clone(),
+            ));
+        } else if !context.ast_annotation.has_type_annotation(id) {
+            let types = arguments
+                .iter()
+                .map(|it| {
+                    context
+                        .ast_annotation
+                        .get_type_or_void(it, context.index)
+                        .get_type_information()
+                        .get_name()
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            self.diagnostics.push(Diagnostic::invalid_argument_types(
+                &builtin.get_name(),
+                &types,
+                location.clone(),
+            ));
+        }
+    }
+
     fn validate_reference(
         &mut self,
         id: &usize,
@@ -105,6 +332,66 @@ impl StatementValidator {
         }
     }
 
+    /// validates that the given method may be accessed from the current implementation
+    ///
+    /// - PRIVATE methods can only be accessed from within their class
+    /// - PROTECTED methods can only be accessed from within their class or a derived class
+    /// - INTERNAL methods can only be accessed from within their namespace
+    fn validate_access(
+        &mut self,
+        method: &ImplementationIndexEntry,
+        member_name: &str,
+        location: &SourceRange,
+        context: &ValidationContext,
+    ) {
+        let declaring_class = match method.get_associated_class_name() {
+            // an interface's methods are always accessible
+            Some(class) if !context.index.is_interface(class) => class.as_str(),
+            _ => return,
+        };
+        let calling_class = context
+            .implementation
+            .and_then(|it| find_calling_class(it, context));
+        match method.get_access() {
+            Some(AccessModifier::Private)
+                if !calling_class
+                    .map(|it| it.eq_ignore_ascii_case(declaring_class))
+                    .unwrap_or(false) =>
+            {
+                self.diagnostics.push(Diagnostic::private_access(
+                    member_name,
+                    declaring_class,
+                    location.clone(),
+                ))
+            }
+            Some(AccessModifier::Protected)
+                if !calling_class
+                    .map(|it| {
+                        context
+                            .index
+                            .get_class_hierarchy(it)
+                            .iter()
+                            .any(|class| class.eq_ignore_ascii_case(declaring_class))
+                    })
+                    .unwrap_or(false) =>
+            {
+                self.diagnostics.push(Diagnostic::protected_access(
+                    member_name,
+                    declaring_class,
+                    location.clone(),
+                ))
+            }
+            Some(AccessModifier::Internal) => {
+                let calling_scope = calling_class.or(context.implementation).unwrap_or_default();
+                if !is_in_namespace(get_namespace(calling_scope), get_namespace(declaring_class)) {
+                    self.diagnostics
+                        .push(Diagnostic::internal_access(member_name, location.clone()))
+                }
+            }
+            _ => {}
+        }
+    }
+
     /// validates a literal statement with a dedicated type-prefix (e.g. INT#3)
     ///
     /// checks whether ...
@@ -120,6 +407,10 @@ impl StatementValidator {
             .index
             .get_effective_type_by_name(type_name)
             .get_type_information();
+        if matches!(cast_type, DataTypeInformation::Enum { .. }) {
+            //an enum-element qualified with its enum (e.g. Color#Red) is validated as a reference
+            return;
+        }
 
         let literal_type = context.index.get_type_information_or_void(
             StatementValidator::get_literal_actual_signed_type_name(
@@ -136,6 +427,15 @@ impl StatementValidator {
         if !is_typable_literal(literal) {
             self.diagnostics
                 .push(Diagnostic::literal_expected(location.clone()))
+        } else if cast_type.is_character() {
+            //CHAR#'A' or WCHAR#"A" need a single character that fits into the character type
+            if !is_character_literal(literal, cast_type, context.index) {
+                self.diagnostics.push(Diagnostic::incompatible_literal_cast(
+                    cast_type.get_name(),
+                    StatementValidator::get_literal_value(literal).as_str(),
+                    location.clone(),
+                ));
+            }
         } else if is_date_or_time_type(cast_type) || is_date_or_time_type(literal_type) {
             self.diagnostics.push(Diagnostic::incompatible_literal_cast(
                 cast_type.get_name(),
@@ -145,7 +445,7 @@ impl StatementValidator {
             //see if target and cast_type are compatible
         } else if cast_type.is_int() && literal_type.is_int() {
             //INTs with INTs
-            if cast_type.get_size() < literal_type.get_size() {
+            if cast_type.get_size(context.index) < literal_type.get_size(context.index) {
                 self.diagnostics.push(Diagnostic::literal_out_of_range(
                     StatementValidator::get_literal_value(literal).as_str(),
                     cast_type.get_name(),
@@ -209,20 +509,83 @@ impl StatementValidator {
             AstStatement::LiteralString { is_wide: true, .. } => Some(WSTRING_TYPE),
             AstStatement::LiteralString { is_wide: false, .. } => Some(STRING_TYPE),
             AstStatement::LiteralReal { .. } => Some(LREAL_TYPE),
-            AstStatement::LiteralDate { .. } => Some(DATE_TYPE),
-            AstStatement::LiteralDateAndTime { .. } => Some(DATE_AND_TIME_TYPE),
-            AstStatement::LiteralTime { .. } => Some(TIME_TYPE),
-            AstStatement::LiteralTimeOfDay { .. } => Some(TIME_OF_DAY_TYPE),
+            AstStatement::LiteralDate { is_long: false, .. } => Some(DATE_TYPE),
+            AstStatement::LiteralDate { is_long: true, .. } => Some(LDATE_TYPE),
+            AstStatement::LiteralDateAndTime { is_long: false, .. } => Some(DATE_AND_TIME_TYPE),
+            AstStatement::LiteralDateAndTime { is_long: true, .. } => Some(LDATE_AND_TIME_TYPE),
+            AstStatement::LiteralTime { is_long: false, .. } => Some(TIME_TYPE),
+            AstStatement::LiteralTime { is_long: true, .. } => Some(LTIME_TYPE),
+            AstStatement::LiteralTimeOfDay { is_long: false, .. } => Some(TIME_OF_DAY_TYPE),
+            AstStatement::LiteralTimeOfDay { is_long: true, .. } => Some(LTIME_OF_DAY_TYPE),
             _ => None,
         }
     }
 }
 
-fn is_date_or_time_type(cast_type: &crate::typesystem::DataTypeInformation) -> bool {
-    return cast_type.get_name() == DATE_TYPE
-        || cast_type.get_name() == DATE_AND_TIME_TYPE
-        || cast_type.get_name() == TIME_OF_DAY_TYPE
-        || cast_type.get_name() == TIME_TYPE;
+/// returns true if the given literal is a string literal with a single character that can be
+/// stored in the given character type (CHAR needs a single-byte, WCHAR a wide string literal)
+fn is_character_literal(
+    literal: &AstStatement,
+    char_type: &DataTypeInformation,
+    index: &Index,
+) -> bool {
+    if let AstStatement::LiteralString { value, is_wide, .. } = literal {
+        let mut chars = value.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => {
+                *is_wide == (char_type.get_name() == WCHAR_TYPE)
+                    && (c as u64) < (1 << char_type.get_size(index))
+            }
+            _ => false,
+        }
+    } else {
+        false
+    }
+}
+
+/// returns true if the given operation is defined for the given date or time operands
+fn is_valid_date_time_operation(
+    left: &DataTypeInformation,
+    operator: &Operator,
+    right: &DataTypeInformation,
+) -> bool {
+    match operator {
+        Operator::Plus | Operator::Minus | Operator::Multiplication | Operator::Division => {
+            get_date_time_operation_result(left, operator, right).is_some()
+        }
+        Operator::Equal
+        | Operator::NotEqual
+        | Operator::Less
+        | Operator::Greater
+        | Operator::LessOrEqual
+        | Operator::GreaterOrEqual => is_date_time_comparison_valid(left, right),
+        _ => true,
+    }
+}
+
+/// returns true if the given operation is defined for the given pointer operands. Pointer
+/// arithmetic is not allowed on a `REF_TO`, it can only be compared for (in)equality
+fn is_valid_pointer_operation(
+    left: &DataTypeInformation,
+    operator: &Operator,
+    right: &DataTypeInformation,
+) -> bool {
+    let is_ref_to = |it: &DataTypeInformation| {
+        matches!(
+            it,
+            DataTypeInformation::Pointer {
+                is_ref_to: true,
+                ..
+            }
+        )
+    };
+    if is_ref_to(left) || is_ref_to(right) {
+        matches!(operator, Operator::Equal | Operator::NotEqual)
+            && left.is_pointer()
+            && right.is_pointer()
+    } else {
+        get_pointer_operation_result(left, operator, right).is_some()
+    }
 }
 
 /// returns true if this AST Statement is a literal that can be
@@ -241,3 +604,53 @@ fn is_typable_literal(literal: &AstStatement) -> bool {
             | AstStatement::Reference { .. }
     )
 }
+
+/// returns the class or function block the given implementation belongs to (the owner of a
+/// method, a function block itself or the container of an action)
+fn find_calling_class<'i>(
+    implementation: &str,
+    context: &ValidationContext<'i>,
+) -> Option<&'i str> {
+    let entry = context.index.find_implementation(implementation)?;
+    if entry.get_implementation_type() == &ImplementationType::Action {
+        Some(entry.get_type_name())
+    } else {
+        context.index.find_instance_class(implementation)
+    }
+}
+
+/// returns the name the given method is accessed by, property accessors are accessed via
+/// their property (e.g. `MyFb.x` for `MyFb.__get_x`)
+fn get_member_name(qualified_method_name: &str) -> String {
+    let (class_name, method) = qualified_method_name
+        .rfind('.')
+        .map(|it| qualified_method_name.split_at(it + 1))
+        .unwrap_or(("", qualified_method_name));
+    let member = method
+        .strip_prefix(PROPERTY_GETTER_PREFIX)
+        .or_else(|| method.strip_prefix(PROPERTY_SETTER_PREFIX))
+        .unwrap_or(method);
+    format!("{}{}", class_name, member)
+}
+
+/// returns true if the given scope is the given namespace or nested inside of it. The names are
+/// compared segment by segment, so `ns10.x` is not inside of `ns1`
+fn is_in_namespace(scope: &str, namespace: &str) -> bool {
+    let mut scope_segments = scope.split('.');
+    namespace
+        .split('.')
+        .filter(|it| !it.is_empty())
+        .all(|segment| {
+            scope_segments
+                .next()
+                .map_or(false, |it| it.eq_ignore_ascii_case(segment))
+        })
+}
+
+/// returns the namespace of the given qualified name (e.g. `A.B` for `A.B.MyFb`)
+fn get_namespace(qualified_name: &str) -> &str {
+    qualified_name
+        .rfind('.')
+        .map(|it| &qualified_name[..it])
+        .unwrap_or_default()
+}
@@ -3,26 +3,36 @@ use super::Validator;
 use crate::{
     ast,
     index::{self, Index},
-    lexer::lex,
+    lexer::{lex_with_ids, IdProvider},
     parser::parse,
-    resolver::TypeAnnotator,
+    resolver::{self, TypeAnnotator},
     Diagnostic,
 };
 
+mod binary_expression_validation_tests;
+mod builtin_validation_tests;
+mod configuration_validation_tests;
 mod literals_validation_tests;
+mod pou_validation_tests;
+mod reference_assignment_validation_tests;
 mod reference_resolve_tests;
 
 mod bitaccess_validation_test;
 
 pub fn parse_and_validate(src: &str) -> Vec<Diagnostic> {
     let mut idx = Index::new();
-    let (mut ast, _) = parse(lex(src));
+    let id_provider = IdProvider::new();
+    let (mut ast, _) = parse(lex_with_ids(src, id_provider.clone()));
+    let declarations = ast::collect_declarations(std::iter::once(&ast));
+    let mut diagnostics = ast::resolve_namespaces(&mut ast, &declarations);
     ast::pre_process(&mut ast);
     idx.import(index::visitor::visit(&ast));
+    resolver::lower_property_accesses(&idx, &mut ast, id_provider);
 
     let annotations = TypeAnnotator::visit_unit(&idx, &ast);
 
     let mut validator = Validator::new();
     validator.visit_unit(&annotations, &idx, &ast);
-    validator.diagnostics()
+    diagnostics.append(&mut validator.diagnostics());
+    diagnostics
 }
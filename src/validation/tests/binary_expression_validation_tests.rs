@@ -0,0 +1,128 @@
+use crate::{validation::tests::parse_and_validate, Diagnostic};
+
+#[test]
+fn valid_date_and_time_operations_are_accepted() {
+    let diagnostics = parse_and_validate(
+        "
+            PROGRAM prg
+                VAR
+                    t : TIME; d : DATE; tod : TOD; dt : DT; ldt : LDT;
+                END_VAR
+                t := t + t;
+                tod := tod - T#1s;
+                dt := dt + LT#1s;
+                t := dt - ldt;
+                t := d - d;
+                t := t * 2;
+                t := 2.5 * t;
+                t := t / 4;
+                dt < ldt;
+                t = T#0s;
+                t > 0;
+           END_PROGRAM
+       ",
+    );
+
+    assert_eq!(diagnostics, vec![]);
+}
+
+#[test]
+fn meaningless_date_and_time_operations_are_reported() {
+    let diagnostics = parse_and_validate(
+        "
+            PROGRAM prg
+                VAR
+                    t : TIME; d : DATE; tod : TOD; dt : DT;
+                END_VAR
+                d + d;
+                dt + dt;
+                t * t;
+                d - t;
+                2 / t;
+                t + 1;
+                d < t;
+                tod = dt;
+           END_PROGRAM
+       ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::invalid_operation("DATE", "+", "DATE", (145..150).into()),
+            Diagnostic::invalid_operation("DATE_AND_TIME", "+", "DATE_AND_TIME", (168..175).into()),
+            Diagnostic::invalid_operation("TIME", "*", "TIME", (193..198).into()),
+            Diagnostic::invalid_operation("DATE", "-", "TIME", (216..221).into()),
+            Diagnostic::invalid_operation("DINT", "/", "TIME", (239..244).into()),
+            Diagnostic::invalid_operation("TIME", "+", "DINT", (262..267).into()),
+            Diagnostic::invalid_operation("DATE", "<", "TIME", (285..290).into()),
+            Diagnostic::invalid_operation("TIME_OF_DAY", "=", "DATE_AND_TIME", (308..316).into()),
+        ]
+    );
+}
+
+#[test]
+fn pointer_arithmetic_is_only_allowed_on_pointers() {
+    let diagnostics = parse_and_validate(
+        "
+            PROGRAM prg
+                VAR
+                    p : POINTER TO INT; q : POINTER TO INT; i : INT; distance : LINT;
+                    r : REF_TO INT; s : REF_TO INT; b : BOOL;
+                END_VAR
+                p := p + 1;
+                p := 2 + p;
+                p := p - i;
+                distance := p - q;
+                b := p < q;
+                b := r = s;
+                b := r <> s;
+                p * 2;
+                p + q;
+                1 - p;
+                r + 1;
+                r - s;
+                r < s;
+           END_PROGRAM
+       ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::invalid_operation("__prg_p", "*", "DINT", (437..442).into()),
+            Diagnostic::invalid_operation("__prg_p", "+", "__prg_q", (460..465).into()),
+            Diagnostic::invalid_operation("DINT", "-", "__prg_p", (483..488).into()),
+            Diagnostic::invalid_operation("__prg_r", "+", "DINT", (506..511).into()),
+            Diagnostic::invalid_operation("__prg_r", "-", "__prg_s", (529..534).into()),
+            Diagnostic::invalid_operation("__prg_r", "<", "__prg_s", (552..557).into()),
+        ]
+    );
+}
+
+#[test]
+fn pointers_can_be_compared_to_null() {
+    let diagnostics = parse_and_validate(
+        "
+            PROGRAM prg
+                VAR
+                    p : POINTER TO INT; r : REF_TO INT; b : BOOL;
+                END_VAR
+                b := r = NULL;
+                b := NULL <> r;
+                b := p <> NULL;
+                b := NULL = p;
+                r < NULL;
+                p + NULL;
+           END_PROGRAM
+       ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::invalid_operation("__prg_r", "<", "VOID", (277..285).into()),
+            Diagnostic::invalid_operation("__prg_p", "+", "VOID", (303..311).into()),
+        ]
+    );
+}
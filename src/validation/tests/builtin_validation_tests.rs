@@ -0,0 +1,87 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+use crate::{validation::tests::parse_and_validate, Diagnostic};
+
+#[test]
+fn string_conversions_are_resolved_like_other_conversions() {
+    let diagnostics = parse_and_validate(
+        "
+        PROGRAM prg
+            VAR i : INT; s : STRING; w : WSTRING; END_VAR
+            s := INT_TO_STRING(i);
+            i := STRING_TO_INT(s);
+            w := TO_WSTRING(s);
+            i := int_to_real(i);
+            i := INT_TO_FOO(i);
+        END_PROGRAM
+        ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::unrseolved_reference(
+            "INT_TO_FOO",
+            (231..241).into()
+        )]
+    );
+}
+
+#[test]
+fn builtin_calls_with_a_wrong_number_of_arguments_are_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        PROGRAM prg
+            VAR i : INT; s : STRING; END_VAR
+            i := MIN(i);
+            i := LIMIT(0, i);
+            s := CONCAT();
+            i := INT_TO_DINT(i, i);
+            i := MAX(i, 1, 2, 3);
+        END_PROGRAM
+        ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::invalid_argument_count("MIN", "at least 2", 1, (83..89).into()),
+            Diagnostic::invalid_argument_count("LIMIT", "3", 2, (108..119).into()),
+            Diagnostic::invalid_argument_count("CONCAT", "at least 1", 0, (138..147).into()),
+            Diagnostic::invalid_argument_count("INT_TO_DINT", "1", 2, (165..182).into()),
+        ]
+    );
+}
+
+#[test]
+fn builtin_calls_with_invalid_argument_types_are_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        PROGRAM prg
+            VAR i : INT; r : REAL; s : STRING; w : WSTRING; END_VAR
+            s := MAX(s, s);
+            s := LIMIT(s, s, s);
+            i := SHL(r, 2);
+            r := SQRT(s);
+            i := LEN(i);
+            s := CONCAT(s, w);
+            s := SEL(TRUE, s, s);
+            r := MIN(i, r);
+        END_PROGRAM
+        ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::invalid_argument_types("MAX", "STRING, STRING", (106..115).into()),
+            Diagnostic::invalid_argument_types(
+                "LIMIT",
+                "STRING, STRING, STRING",
+                (134..148).into()
+            ),
+            Diagnostic::invalid_argument_types("SHL", "REAL, DINT", (167..176).into()),
+            Diagnostic::invalid_argument_types("SQRT", "STRING", (195..202).into()),
+            Diagnostic::invalid_argument_types("LEN", "INT", (221..227).into()),
+            Diagnostic::invalid_argument_types("CONCAT", "STRING, WSTRING", (246..258).into()),
+        ]
+    );
+}
@@ -0,0 +1,68 @@
+use crate::{validation::tests::parse_and_validate, Diagnostic};
+
+#[test]
+fn valid_configuration_has_no_diagnostics() {
+    let diagnostics = parse_and_validate(
+        "
+        PROGRAM prg END_PROGRAM
+        CONFIGURATION config
+            RESOURCE res
+                TASK fast(INTERVAL := T#10ms, PRIORITY := 1);
+                PROGRAM inst WITH fast : prg;
+                PROGRAM idle : prg;
+            END_RESOURCE
+        END_CONFIGURATION
+        ",
+    );
+
+    assert_eq!(diagnostics, vec![]);
+}
+
+#[test]
+fn program_instances_of_unknown_tasks_and_non_programs_are_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        FUNCTION_BLOCK fb END_FUNCTION_BLOCK
+        PROGRAM prg END_PROGRAM
+        CONFIGURATION config
+            RESOURCE res
+                TASK fast(INTERVAL := T#10ms);
+                PROGRAM a WITH slow : prg;
+                PROGRAM b WITH fast : fb;
+                PROGRAM c WITH fast : unknown;
+            END_RESOURCE
+        END_CONFIGURATION
+        ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::unresolved_task("slow", "res", (195..220).into()),
+            Diagnostic::program_expected("fb", (238..262).into()),
+            Diagnostic::unrseolved_reference("unknown", (280..309).into()),
+        ]
+    );
+}
+
+#[test]
+fn task_parameters_must_be_literals() {
+    let diagnostics = parse_and_validate(
+        "
+        VAR_GLOBAL p : INT; END_VAR
+        CONFIGURATION config
+            RESOURCE res
+                TASK fast(INTERVAL := 10, PRIORITY := p);
+            END_RESOURCE
+        END_CONFIGURATION
+        ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::invalid_task_parameter("INTERVAL", "TIME", (129..131).into()),
+            Diagnostic::invalid_task_parameter("PRIORITY", "ANY_INT", (145..146).into()),
+        ]
+    );
+}
@@ -96,6 +96,72 @@ fn string_literal_casts_are_validated() {
     );
 }
 
+#[test]
+fn char_literal_casts_are_validated() {
+    let diagnostics = parse_and_validate(
+        r#"
+        PROGRAM prg
+            CHAR#'A';
+            WCHAR#"Ω";
+            CHAR#'$0D';
+
+            CHAR#'AB';
+            CHAR#'';
+            CHAR#"A";
+            WCHAR#'A';
+            CHAR#'Ω';
+            CHAR#65;
+        END_PROGRAM
+       "#,
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::incompatible_literal_cast("CHAR", "'AB'", (104..113).into()),
+            Diagnostic::incompatible_literal_cast("CHAR", "''", (127..134).into()),
+            Diagnostic::incompatible_literal_cast("CHAR", r#""A""#, (148..156).into()),
+            Diagnostic::incompatible_literal_cast("WCHAR", "'A'", (170..179).into()),
+            Diagnostic::incompatible_literal_cast("CHAR", "'Ω'", (193..202).into()),
+            Diagnostic::incompatible_literal_cast("CHAR", "65", (216..223).into()),
+        ]
+    );
+}
+
+#[test]
+fn constant_string_indices_must_address_a_character() {
+    let diagnostics = parse_and_validate(
+        r#"
+        PROGRAM prg
+        VAR
+            s : STRING[5];
+            ws : WSTRING;
+            c : CHAR;
+            wc : WCHAR;
+        END_VAR
+            c := s[1];
+            c := s[5];
+            wc := ws[80];
+
+            c := s[0];
+            c := s[6];
+            c := s[-1];
+            wc := ws[81];
+        END_PROGRAM
+       "#,
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::string_index_out_of_range(0, 5, (240..241).into()),
+            Diagnostic::string_index_out_of_range(6, 5, (263..264).into()),
+            Diagnostic::string_index_out_of_range(-1, 5, (286..288).into()),
+            Diagnostic::string_index_out_of_range(81, 80, (312..314).into()),
+        ]
+    );
+}
+
 #[test]
 fn real_literal_casts_are_validated() {
     let diagnostics = parse_and_validate(
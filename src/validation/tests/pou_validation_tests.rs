@@ -0,0 +1,213 @@
+use crate::{validation::tests::parse_and_validate, Diagnostic};
+
+#[test]
+fn extending_a_declared_pou_is_valid() {
+    let diagnostics = parse_and_validate(
+        "
+        FUNCTION_BLOCK Base
+            VAR_INPUT a : INT; END_VAR
+        END_FUNCTION_BLOCK
+
+        FUNCTION_BLOCK Derived EXTENDS Base
+            a := 3;
+        END_FUNCTION_BLOCK
+
+        PROGRAM prg
+            VAR fb : Derived; END_VAR
+            fb.a := 4;
+            fb(a := 5);
+        END_PROGRAM
+        ",
+    );
+
+    assert_eq!(diagnostics, vec![]);
+}
+
+#[test]
+fn unknown_super_class_is_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        CLASS MyClass EXTENDS Unknown
+        END_CLASS
+        ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::unresolved_super_class(
+            "Unknown",
+            (9..56).into()
+        )]
+    );
+}
+
+#[test]
+fn extending_a_non_pou_type_is_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        CLASS MyClass EXTENDS INT
+        END_CLASS
+        ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::unresolved_super_class("INT", (9..52).into())]
+    );
+}
+
+#[test]
+fn cyclic_inheritance_is_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        CLASS A EXTENDS B
+        END_CLASS
+
+        CLASS B EXTENDS A
+        END_CLASS
+        ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::cyclic_inheritance("A", (9..44).into()),
+            Diagnostic::cyclic_inheritance("B", (54..89).into()),
+        ]
+    );
+}
+
+#[test]
+fn implementing_all_interface_methods_is_valid() {
+    let diagnostics = parse_and_validate(
+        "
+        INTERFACE IDriver
+            METHOD read : INT VAR_INPUT channel : INT; END_VAR END_METHOD
+            METHOD reset END_METHOD
+        END_INTERFACE
+
+        CLASS BaseDriver
+            METHOD reset END_METHOD
+        END_CLASS
+
+        FUNCTION_BLOCK MyDriver EXTENDS BaseDriver IMPLEMENTS IDriver
+            METHOD read : INT VAR_INPUT channel : INT; END_VAR END_METHOD
+        END_FUNCTION_BLOCK
+
+        CLASS ABSTRACT PartialDriver IMPLEMENTS IDriver
+            METHOD read : INT VAR_INPUT channel : INT; END_VAR END_METHOD
+        END_CLASS
+        ",
+    );
+
+    assert_eq!(diagnostics, vec![]);
+}
+
+#[test]
+fn unknown_interface_is_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        CLASS MyClass IMPLEMENTS Unknown
+        END_CLASS
+        ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::unresolved_interface("Unknown", (9..59).into())]
+    );
+}
+
+#[test]
+fn missing_and_mismatching_interface_methods_are_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        INTERFACE IDriver
+            METHOD read : INT VAR_INPUT channel : INT; END_VAR END_METHOD
+            METHOD reset END_METHOD
+        END_INTERFACE
+
+        CLASS MyDriver IMPLEMENTS IDriver
+            METHOD read : INT VAR_INPUT channel : DINT; END_VAR END_METHOD
+        END_CLASS
+        ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::interface_method_signature_mismatch(
+                "MyDriver.read",
+                "IDriver.read",
+                (168..294).into()
+            ),
+            Diagnostic::missing_interface_method("MyDriver", "IDriver.reset", (168..294).into()),
+        ]
+    );
+}
+
+#[test]
+fn instantiating_an_abstract_class_is_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        CLASS ABSTRACT Shape
+            METHOD ABSTRACT area : REAL END_METHOD
+        END_CLASS
+
+        CLASS Square EXTENDS Shape
+            METHOD area : REAL END_METHOD
+        END_CLASS
+
+        FUNCTION_BLOCK ABSTRACT Base
+        END_FUNCTION_BLOCK
+
+        PROGRAM prg
+            VAR
+                square : Square;
+                shape : Shape;
+                base : Base;
+            END_VAR
+            VAR_IN_OUT
+                ref : Shape;
+            END_VAR
+        END_PROGRAM
+        ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::abstract_instantiation("Shape", (346..351).into()),
+            Diagnostic::abstract_instantiation("Base", (377..381).into()),
+        ]
+    );
+}
+
+#[test]
+fn overriding_a_final_method_is_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        CLASS Base
+            METHOD FINAL foo END_METHOD
+            METHOD bar END_METHOD
+        END_CLASS
+
+        CLASS Middle EXTENDS Base
+        END_CLASS
+
+        CLASS Derived EXTENDS Middle
+            METHOD foo END_METHOD
+            METHOD bar END_METHOD
+        END_CLASS
+        ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::final_method_overridden(
+            "Derived.foo",
+            "Base.foo",
+            (215..236).into()
+        )]
+    );
+}
@@ -0,0 +1,63 @@
+use crate::{validation::tests::parse_and_validate, Diagnostic};
+
+#[test]
+fn references_can_be_rebound_to_variables_of_their_type() {
+    let diagnostics = parse_and_validate(
+        "
+            TYPE MyStruct : STRUCT x : INT; END_STRUCT END_TYPE
+
+            PROGRAM prg
+                VAR
+                    r : REFERENCE TO INT;
+                    x : INT;
+                    s : MyStruct;
+                    a : ARRAY[0..2] OF INT;
+                END_VAR
+                r REF= x;
+                r REF= s.x;
+                r REF= a[1];
+                r REF= NULL;
+                r := 5;
+           END_PROGRAM
+       ",
+    );
+
+    assert_eq!(diagnostics, vec![]);
+}
+
+#[test]
+fn invalid_reference_assignments_are_reported() {
+    let diagnostics = parse_and_validate(
+        "
+            FUNCTION foo : INT
+                VAR_IN_OUT
+                    io : INT;
+                END_VAR
+                VAR
+                    r : REFERENCE TO INT;
+                    p : REF_TO INT;
+                    x : INT;
+                    y : REAL;
+                END_VAR
+                r REF= y;
+                r REF= 5;
+                r REF= x + 1;
+                p REF= x;
+                x REF= x;
+                io REF= x;
+           END_FUNCTION
+       ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::invalid_reference_target("INT", "REAL", (317..318).into()),
+            Diagnostic::invalid_reference_target("INT", "an expression", (343..344).into()),
+            Diagnostic::invalid_reference_target("INT", "an expression", (369..374).into()),
+            Diagnostic::reference_expected((392..393).into()),
+            Diagnostic::reference_expected((418..419).into()),
+            Diagnostic::reference_expected((444..446).into()),
+        ]
+    );
+}
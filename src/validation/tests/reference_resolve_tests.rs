@@ -235,3 +235,271 @@ fn resolve_function_members_via_qualifier() {
         ]
     );
 }
+
+#[test]
+fn calls_to_private_and_protected_methods_are_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        CLASS Base
+            METHOD PRIVATE secret END_METHOD
+            METHOD PROTECTED helper END_METHOD
+            METHOD PUBLIC run
+                secret();
+                helper();
+            END_METHOD
+        END_CLASS
+
+        FUNCTION_BLOCK Derived EXTENDS Base
+            METHOD PUBLIC runDerived
+                helper();
+                secret();
+            END_METHOD
+            helper();
+        END_FUNCTION_BLOCK
+
+        PROGRAM prg
+            VAR b : Base; END_VAR
+            b.run();
+            b.secret();
+            b.helper();
+        END_PROGRAM
+        ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::private_access("Base.secret", "Base", (359..368).into()),
+            Diagnostic::private_access("Base.secret", "Base", (529..540).into()),
+            Diagnostic::protected_access("Base.helper", "Base", (553..564).into()),
+        ]
+    );
+}
+
+#[test]
+fn accessing_a_protected_property_is_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        FUNCTION_BLOCK Motor
+            VAR _speed : INT; END_VAR
+            PROPERTY PROTECTED speed : INT
+                GET speed := _speed; END_GET
+            END_PROPERTY
+            PROPERTY limit : INT
+                SET _speed := limit; END_SET
+            END_PROPERTY
+        END_FUNCTION_BLOCK
+
+        PROGRAM prg
+            VAR m : Motor; x : INT; END_VAR
+            x := m.speed;
+            m.limit := 3;
+        END_PROGRAM
+        ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::protected_access(
+            "Motor.speed",
+            "Motor",
+            (393..400).into()
+        )]
+    );
+}
+
+#[test]
+fn writing_a_property_is_checked_against_its_setter() {
+    let src = "
+        FUNCTION_BLOCK Motor
+            VAR _speed : INT; END_VAR
+            PROPERTY speed : INT
+                GET speed := _speed; END_GET
+                SET PRIVATE _speed := speed; END_SET
+            END_PROPERTY
+        END_FUNCTION_BLOCK
+
+        PROGRAM prg
+            VAR m : Motor; x : INT; END_VAR
+            x := m.speed;
+            m.speed := 3;
+        END_PROGRAM
+        ";
+    let diagnostics = parse_and_validate(src);
+
+    //only the write is reported, the getter is public
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::private_access(
+            "Motor.speed",
+            "Motor",
+            (354..361).into()
+        )]
+    );
+}
+
+#[test]
+fn internal_methods_can_be_called_from_the_same_namespace() {
+    let diagnostics = parse_and_validate(
+        "
+        CLASS Base
+            METHOD INTERNAL shared END_METHOD
+        END_CLASS
+
+        PROGRAM prg
+            VAR b : Base; END_VAR
+            b.shared();
+        END_PROGRAM
+        ",
+    );
+
+    assert_eq!(diagnostics, vec![]);
+}
+
+#[test]
+fn internal_methods_cannot_be_called_from_another_namespace() {
+    let diagnostics = parse_and_validate(
+        "
+        NAMESPACE Lib
+            CLASS Base
+                METHOD INTERNAL shared END_METHOD
+            END_CLASS
+
+            PROGRAM inside
+                VAR b : Base; END_VAR
+                b.shared();
+            END_PROGRAM
+        END_NAMESPACE
+
+        PROGRAM outside
+            VAR b : Lib.Base; END_VAR
+            b.shared();
+        END_PROGRAM
+        ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::internal_access(
+            "Lib.Base.shared",
+            (333..344).into()
+        )]
+    );
+}
+
+#[test]
+fn internal_methods_cannot_be_called_from_a_namespace_with_the_same_prefix() {
+    let diagnostics = parse_and_validate(
+        "
+        NAMESPACE ns1
+            CLASS Base
+                METHOD INTERNAL shared END_METHOD
+            END_CLASS
+        END_NAMESPACE
+
+        NAMESPACE ns10
+            PROGRAM prg
+                VAR b : ns1.Base; END_VAR
+                b.shared();
+            END_PROGRAM
+        END_NAMESPACE
+        ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::internal_access(
+            "ns1.Base.shared",
+            (246..257).into()
+        )]
+    );
+}
+
+#[test]
+fn references_into_namespaces_are_resolved() {
+    let diagnostics = parse_and_validate(
+        "
+        NAMESPACE Lib.Math
+            VAR_GLOBAL
+                offset : INT;
+            END_VAR
+
+            FUNCTION inc : INT
+                VAR_INPUT x : INT; END_VAR
+                inc := x + offset;
+            END_FUNCTION
+
+            NAMESPACE Nested
+                FUNCTION inc2 : INT
+                    VAR_INPUT x : INT; END_VAR
+                    inc2 := inc(inc(x));
+                END_FUNCTION
+            END_NAMESPACE
+        END_NAMESPACE
+
+        USING Lib.Math;
+
+        PROGRAM prg
+            VAR a, b : INT; END_VAR
+            a := inc(1) + offset;
+            b := Lib.Math.Nested.inc2(Lib.Math.offset);
+        END_PROGRAM
+        ",
+    );
+
+    assert_eq!(diagnostics, vec![]);
+}
+
+#[test]
+fn namespace_declarations_are_not_visible_without_using_or_qualifier() {
+    let diagnostics = parse_and_validate(
+        "
+        NAMESPACE Lib
+            FUNCTION inc : INT
+                VAR_INPUT x : INT; END_VAR
+                inc := x + 1;
+            END_FUNCTION
+        END_NAMESPACE
+
+        PROGRAM prg
+            VAR a : INT; END_VAR
+            a := inc(1);
+        END_PROGRAM
+        ",
+    );
+
+    assert_eq!(
+        diagnostics,
+        vec![Diagnostic::unrseolved_reference("inc", (245..248).into())]
+    );
+}
+
+#[test]
+fn ambiguous_references_into_namespaces_are_reported() {
+    let diagnostics = parse_and_validate(
+        "
+        NAMESPACE A
+            FUNCTION foo : INT END_FUNCTION
+        END_NAMESPACE
+
+        NAMESPACE B
+            FUNCTION foo : INT END_FUNCTION
+        END_NAMESPACE
+
+        PROGRAM prg
+            USING A, B;
+            VAR x : INT; END_VAR
+            x := foo();
+        END_PROGRAM
+        ",
+    );
+
+    // the ambiguous reference is not resolved to either of the candidates
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::ambiguous_reference("foo", &["A.foo", "B.foo"], (269..272).into()),
+            Diagnostic::unrseolved_reference("foo", (269..272).into()),
+        ]
+    );
+}
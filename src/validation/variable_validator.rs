@@ -1,5 +1,11 @@
+use super::ValidationContext;
 use crate::{
-    ast::{DataType, DataTypeDeclaration, SourceRange, Variable, VariableBlock},
+    ast::{
+        AstStatement, DataType, DataTypeDeclaration, Pou, PouType, SourceRange, Variable,
+        VariableBlock, VariableBlockType,
+    },
+    index::Index,
+    typesystem::{DataTypeInformation, BOOL_TYPE},
     Diagnostic,
 };
 
@@ -16,29 +22,306 @@ impl VariableValidator {
         }
     }
 
-    pub fn validate_variable_block(&self, _block: &VariableBlock) {}
+    pub fn validate_variable_block(&mut self, block: &VariableBlock, context: &ValidationContext) {
+        if !matches!(
+            block.variable_block_type,
+            VariableBlockType::Input | VariableBlockType::InOut
+        ) {
+            self.validate_no_variable_length_arrays(&block.variables, context);
+        }
+        //in-out variables only reference an instance
+        if block.variable_block_type == VariableBlockType::InOut {
+            return;
+        }
+        if block.variable_block_type == VariableBlockType::External {
+            self.validate_external_variables(block, context);
+            return;
+        }
+        for variable in &block.variables {
+            if let Some(type_name) = variable.data_type.get_name() {
+                if context.index.is_abstract(type_name) {
+                    self.diagnostics.push(Diagnostic::abstract_instantiation(
+                        type_name,
+                        variable.location.clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// validates that none of the given variables is declared as `ARRAY[*]`, variable-length
+    /// arrays can only be passed as parameters
+    fn validate_no_variable_length_arrays(
+        &mut self,
+        variables: &[Variable],
+        context: &ValidationContext,
+    ) {
+        for variable in variables {
+            let is_variable_length_array = variable
+                .data_type
+                .get_name()
+                .and_then(|type_name| context.index.find_effective_type_by_name(type_name))
+                .map_or(false, |it| {
+                    it.get_type_information().is_variable_length_array()
+                });
+            if is_variable_length_array {
+                self.diagnostics
+                    .push(Diagnostic::invalid_variable_length_array(
+                        variable.location.clone(),
+                    ));
+            }
+        }
+    }
+
+    /// validates that every external variable refers to a global variable of the same type
+    fn validate_external_variables(&mut self, block: &VariableBlock, context: &ValidationContext) {
+        for variable in &block.variables {
+            let global = match context.index.find_global_variable(&variable.name) {
+                Some(global) => global,
+                None => {
+                    self.diagnostics
+                        .push(Diagnostic::unresolved_external_variable(
+                            &variable.name,
+                            variable.location.clone(),
+                        ));
+                    continue;
+                }
+            };
+            let declared_type = variable.data_type.get_name().unwrap_or_default();
+            if !is_same_type(context.index, declared_type, global.get_type_name()) {
+                self.diagnostics.push(Diagnostic::external_type_mismatch(
+                    &variable.name,
+                    variable.location.clone(),
+                ));
+            }
+        }
+    }
+
+    /// validates that a located variable is a global or local variable of a PROGRAM, that its
+    /// address fits its type and that it does not overlap with another located variable
+    ///
+    /// - `pou` the POU declaring the variable or None for a global variable
+    pub fn validate_located_variable(
+        &mut self,
+        pou: Option<&Pou>,
+        block: &VariableBlock,
+        variable: &Variable,
+        context: &ValidationContext,
+    ) {
+        let address = match &variable.address {
+            Some(address) => address,
+            None => return,
+        };
+        let location = variable.location.clone();
+        let is_program_local = pou
+            .map(|it| {
+                it.pou_type == PouType::Program
+                    && block.variable_block_type == VariableBlockType::Local
+            })
+            .unwrap_or(true);
+        if !is_program_local {
+            self.diagnostics
+                .push(Diagnostic::located_variable_not_allowed(
+                    &variable.name,
+                    location,
+                ));
+            return;
+        }
+        let bits = match address.get_bit_range() {
+            Some(bits) => bits,
+            None => {
+                self.diagnostics.push(Diagnostic::invalid_hardware_address(
+                    &address.to_string(),
+                    address.location.clone(),
+                ));
+                return;
+            }
+        };
+
+        let type_name = variable.data_type.get_name().unwrap_or_default();
+        let type_size = context
+            .index
+            .find_effective_type_by_name(type_name)
+            .map(|it| it.get_type_information())
+            .and_then(|it| match it {
+                DataTypeInformation::Integer { size, .. }
+                | DataTypeInformation::Float { size, .. } => Some(*size),
+                _ => None,
+            });
+        if type_size != Some(address.access.get_bit_witdh()) {
+            self.diagnostics
+                .push(Diagnostic::incompatible_hardware_address(
+                    &address.to_string(),
+                    type_name,
+                    location.clone(),
+                ));
+        }
+
+        let qualified_name = pou
+            .map(|it| format!("{}.{}", it.name, variable.name))
+            .unwrap_or_else(|| variable.name.clone());
+        //every pair is compared once: a variable is only compared to the ones indexed before it
+        for other in context
+            .index
+            .get_located_variables()
+            .values()
+            .take_while(|it| {
+                !it.get_qualified_name()
+                    .eq_ignore_ascii_case(&qualified_name)
+            })
+        {
+            let other_name = other.get_qualified_name();
+            if other.address.area != address.area {
+                continue;
+            }
+            if let Some(other_bits) = other.address.get_bit_range() {
+                if other_bits.start < bits.end && bits.start < other_bits.end {
+                    self.diagnostics
+                        .push(Diagnostic::overlapping_hardware_address(
+                            &qualified_name,
+                            &other_name,
+                            location.clone(),
+                        ));
+                }
+            }
+        }
+    }
 
     pub fn validate_variable(&self, _variable: &Variable) {}
 
     pub fn validate_data_type_declaration(&self, _declaration: &DataTypeDeclaration) {}
 
-    pub fn validate_data_type(&mut self, declaration: &DataType, location: &SourceRange) {
+    pub fn validate_data_type(
+        &mut self,
+        declaration: &DataType,
+        location: &SourceRange,
+        context: &ValidationContext,
+    ) {
         match declaration {
-            DataType::StructType { variables, .. } => {
+            DataType::StructType { variables, .. } | DataType::UnionType { variables, .. } => {
                 if variables.is_empty() {
                     self.diagnostics
                         .push(Diagnostic::empty_variable_block(location.clone()));
                 }
+                self.validate_no_variable_length_arrays(variables, context);
             }
             DataType::EnumType { elements, .. } => {
                 if elements.is_empty() {
                     self.diagnostics
                         .push(Diagnostic::empty_variable_block(location.clone()));
                 }
+                self.validate_enum_values(declaration, location, context);
             }
             _ => {}
         }
     }
+
+    /// validates that the enum's numeric type is an integer type and that all element values
+    /// are integer-literals in the range of the numeric type
+    fn validate_enum_values(
+        &mut self,
+        declaration: &DataType,
+        location: &SourceRange,
+        context: &ValidationContext,
+    ) {
+        if let DataType::EnumType {
+            name: Some(name),
+            numeric_type,
+            elements,
+            values,
+        } = declaration
+        {
+            let range = match context
+                .index
+                .find_effective_type_by_name(numeric_type)
+                .map(|it| it.get_type_information())
+            {
+                Some(DataTypeInformation::Integer {
+                    name: type_name,
+                    signed,
+                    size,
+                }) if type_name != BOOL_TYPE => {
+                    if *signed {
+                        -(1_i128 << (size - 1))..=(1_i128 << (size - 1)) - 1
+                    } else {
+                        0..=(1_i128 << size) - 1
+                    }
+                }
+                _ => {
+                    self.diagnostics.push(Diagnostic::invalid_enum_base_type(
+                        numeric_type,
+                        location.clone(),
+                    ));
+                    return;
+                }
+            };
+
+            for (element, value) in elements.iter().zip(values.iter()) {
+                //elements without an explicit value are reported at the enum's declaration
+                let value_location = value
+                    .as_ref()
+                    .map(|it| it.get_location())
+                    .unwrap_or_else(|| location.clone());
+                match context
+                    .index
+                    .find_enum_element(name, element)
+                    .and_then(|it| it.initial_value.as_ref())
+                {
+                    Some(AstStatement::LiteralInteger { value, .. }) => {
+                        if !range.contains(value) {
+                            self.diagnostics.push(Diagnostic::literal_out_of_range(
+                                &value.to_string(),
+                                numeric_type,
+                                value_location,
+                            ));
+                        }
+                    }
+                    _ => self
+                        .diagnostics
+                        .push(Diagnostic::literal_expected(value_location)),
+                }
+            }
+        }
+    }
+}
+
+/// returns true if both types are the same after resolving their aliases.
+/// Arrays and pointers declared inline get generated names, so they are compared by their structure
+fn is_same_type(index: &Index, left: &str, right: &str) -> bool {
+    if left.eq_ignore_ascii_case(right) {
+        return true;
+    }
+    let left = index.find_effective_type_by_name(left);
+    let right = index.find_effective_type_by_name(right);
+    match (
+        left.map(|it| it.get_type_information()),
+        right.map(|it| it.get_type_information()),
+    ) {
+        (
+            Some(DataTypeInformation::Array {
+                inner_type_name: left_inner,
+                dimensions: left_dimensions,
+                ..
+            }),
+            Some(DataTypeInformation::Array {
+                inner_type_name: right_inner,
+                dimensions: right_dimensions,
+                ..
+            }),
+        ) => left_dimensions == right_dimensions && is_same_type(index, left_inner, right_inner),
+        (
+            Some(DataTypeInformation::Pointer {
+                inner_type_name: left_inner,
+                ..
+            }),
+            Some(DataTypeInformation::Pointer {
+                inner_type_name: right_inner,
+                ..
+            }),
+        ) => is_same_type(index, left_inner, right_inner),
+        (Some(left), Some(right)) => left == right,
+        _ => false,
+    }
 }
 
 #[cfg(test)]
@@ -91,4 +374,230 @@ mod variable_validator_tests {
             ]
         );
     }
+
+    #[test]
+    fn enums_with_values_in_the_range_of_their_numeric_type_are_valid() {
+        let diagnostics = parse_and_validate(
+            "
+        TYPE State : (Idle := 0, Run := 10, Fault := 16#FF) USINT; END_TYPE
+        TYPE Mode : (Run := -1, Stop) SINT; END_TYPE
+
+        PROGRAM prg
+            VAR
+                s : State;
+                m : Mode;
+            END_VAR
+            s := State#Run;
+            m := Mode#Run;
+        END_PROGRAM
+        ",
+        );
+
+        assert_eq!(diagnostics, vec![]);
+    }
+
+    #[test]
+    fn invalid_enum_values_and_numeric_types_are_reported() {
+        let diagnostics = parse_and_validate(
+            "
+        TYPE State : (Idle := 0, Run := 256) USINT; END_TYPE
+        TYPE Mode : (Run := -1, Stop := 16#FF, Fault) UINT; END_TYPE
+        TYPE Color : (Red := 1 + 1) INT; END_TYPE
+        TYPE Animal : (Dog, Cat) REAL; END_TYPE
+        ",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::literal_out_of_range("256", "USINT", (41..44).into()),
+                Diagnostic::literal_out_of_range("-1", "UINT", (90..92).into()),
+                Diagnostic::literal_expected((160..165).into()),
+                Diagnostic::invalid_enum_base_type("REAL", (194..219).into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn external_variables_matching_a_global_variable_are_valid() {
+        let diagnostics = parse_and_validate(
+            "
+        TYPE MyInt : INT; END_TYPE
+        VAR_GLOBAL
+            a : INT;
+            b : ARRAY[0..3] OF MyInt;
+        END_VAR
+
+        FUNCTION foo : INT
+            VAR_EXTERNAL
+                A : MyInt;
+                b : ARRAY[0..3] OF INT;
+            END_VAR
+            foo := a + b[1];
+        END_FUNCTION
+        ",
+        );
+
+        assert_eq!(diagnostics, vec![]);
+    }
+
+    #[test]
+    fn external_variables_without_matching_global_variable_are_reported() {
+        let diagnostics = parse_and_validate(
+            "
+        VAR_GLOBAL
+            a : INT;
+            b : ARRAY[0..3] OF INT;
+        END_VAR
+
+        PROGRAM prg
+            VAR_EXTERNAL
+                a : DINT;
+                b : ARRAY[1..4] OF INT;
+                c : INT;
+            END_VAR
+        END_PROGRAM
+        ",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::external_type_mismatch("a", (155..156).into()),
+                Diagnostic::external_type_mismatch("b", (181..182).into()),
+                Diagnostic::unresolved_external_variable("c", (221..222).into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn located_variables_with_matching_addresses_are_valid() {
+        let diagnostics = parse_and_validate(
+            "
+        VAR_GLOBAL
+            a AT %IX0.3 : BOOL;
+            b AT %IX0.4 : BOOL;
+            c AT %IB1 : BYTE;
+            d AT %IW2 : INT;
+            e AT %ID4 : REAL;
+            f AT %QX0.3 : BOOL;
+        END_VAR
+
+        PROGRAM prg
+            VAR
+                g AT %MW0 : WORD;
+            END_VAR
+        END_PROGRAM
+        ",
+        );
+
+        assert_eq!(diagnostics, vec![]);
+    }
+
+    #[test]
+    fn invalid_located_variables_are_reported() {
+        let diagnostics = parse_and_validate(
+            "
+        VAR_GLOBAL
+            a AT %IX0 : BOOL;
+            b AT %IW2.1 : INT;
+            c AT %IX0.8 : BOOL;
+            d AT %IW4 : DINT;
+            e AT %IX6.0 : INT;
+        END_VAR
+
+        FUNCTION_BLOCK fb
+            VAR
+                f AT %QW0 : WORD;
+            END_VAR
+        END_FUNCTION_BLOCK
+
+        PROGRAM prg
+            VAR_INPUT
+                g AT %QW0 : WORD;
+            END_VAR
+        END_PROGRAM
+        ",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::located_variable_not_allowed("f", (249..250).into()),
+                Diagnostic::located_variable_not_allowed("g", (373..374).into()),
+                Diagnostic::invalid_hardware_address("%IX0", (37..41).into()),
+                Diagnostic::invalid_hardware_address("%IW2.1", (67..73).into()),
+                Diagnostic::invalid_hardware_address("%IX0.8", (98..104).into()),
+                Diagnostic::incompatible_hardware_address("%IW4", "DINT", (125..126).into()),
+                Diagnostic::incompatible_hardware_address("%IX6.0", "INT", (155..156).into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn overlapping_located_variables_are_reported() {
+        let diagnostics = parse_and_validate(
+            "
+        VAR_GLOBAL
+            a AT %IX1.3 : BOOL;
+            b AT %IB1 : BYTE;
+            c AT %QB1 : BYTE;
+        END_VAR
+
+        PROGRAM prg
+            VAR
+                d AT %QW0 : WORD;
+            END_VAR
+        END_PROGRAM
+        ",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::overlapping_hardware_address("prg.d", "c", (181..182).into()),
+                Diagnostic::overlapping_hardware_address("b", "a", (64..65).into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn variable_length_arrays_are_only_allowed_as_input_and_in_out_parameters() {
+        let diagnostics = parse_and_validate(
+            "
+        TYPE MyStruct : STRUCT
+            a : ARRAY[*] OF INT;
+        END_STRUCT END_TYPE
+
+        FUNCTION foo : INT
+            VAR_INPUT
+                in : ARRAY[*] OF INT;
+            END_VAR
+            VAR_IN_OUT
+                io : ARRAY[*, *] OF INT;
+            END_VAR
+            VAR_OUTPUT
+                out : ARRAY[*] OF INT;
+            END_VAR
+            VAR
+                local : ARRAY[*] OF INT;
+            END_VAR
+        END_FUNCTION
+
+        VAR_GLOBAL
+            global : ARRAY[*] OF INT;
+        END_VAR
+        ",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::invalid_variable_length_array((324..327).into()),
+                Diagnostic::invalid_variable_length_array((399..404).into()),
+                Diagnostic::invalid_variable_length_array((497..503).into()),
+                Diagnostic::invalid_variable_length_array((44..45).into()),
+            ]
+        );
+    }
 }
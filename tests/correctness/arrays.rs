@@ -177,3 +177,61 @@ fn cube_array_assignments2() {
         }
     }
 }
+
+#[test]
+fn variable_length_arrays_use_the_bounds_of_the_passed_array() {
+    let function = r"
+            FUNCTION array_sum : INT
+            VAR_INPUT
+                values : ARRAY[*] OF INT;
+            END_VAR
+            VAR
+                i : DINT;
+            END_VAR
+            array_sum := 0;
+            FOR i := LOWER_BOUND(values, 1) TO UPPER_BOUND(values, 1) DO
+                array_sum := array_sum + values[i];
+            END_FOR
+            END_FUNCTION
+
+            FUNCTION fill : DINT
+            VAR_IN_OUT
+                m : ARRAY[*, *] OF INT;
+            END_VAR
+            VAR
+                i : DINT;
+                j : DINT;
+            END_VAR
+            FOR i := LOWER_BOUND(m, 1) TO UPPER_BOUND(m, 1) DO
+                FOR j := LOWER_BOUND(m, 2) TO UPPER_BOUND(m, 2) DO
+                    m[i, j] := i * 10 + j;
+                END_FOR
+            END_FOR
+            fill := UPPER_BOUND(m, 1) - LOWER_BOUND(m, 1) + 1;
+            END_FUNCTION
+
+            PROGRAM main
+            VAR
+            x: INT;
+            y: INT;
+            z: INT;
+            int_array   : ARRAY[0..4] OF INT;
+            matrix      : ARRAY[1..5, 0..4] OF INT;
+            cube        : ARRAY[0..4, 0..4, 0..4] OF DINT;
+            END_VAR
+            x := array_sum(int_array);
+            y := fill(matrix);
+            END_PROGRAM
+            ";
+
+    let mut maintype = new();
+
+    compile_and_run(function.to_string(), &mut maintype);
+    assert_eq!(15, maintype.x);
+    assert_eq!(5, maintype.y);
+    for i in 0..5 {
+        for j in 0..5 {
+            assert_eq!(((i + 1) * 10 + j) as i16, maintype.matrix[i][j]);
+        }
+    }
+}
@@ -58,3 +58,327 @@ fn class_reference_in_pou() {
     );
     assert_eq!(res, 10);
 }
+
+#[test]
+fn inherited_members_and_methods_in_pou() {
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MyBase {
+        x: i16,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MyClass {
+        base: MyBase,
+        y: i16,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MainType {
+        cl: MyClass,
+    }
+
+    let source = "
+        CLASS MyBase
+            VAR
+                x : INT;
+            END_VAR
+
+            METHOD getX : INT
+                getX := x;
+            END_METHOD
+        END_CLASS
+
+        CLASS MyClass EXTENDS MyBase
+            VAR
+                y : INT;
+            END_VAR
+
+            METHOD sum : INT
+                sum := x + y;
+            END_METHOD
+        END_CLASS
+
+        FUNCTION main : DINT
+        VAR
+          cl : MyClass;
+        END_VAR
+        cl.x := 3;
+        cl.y := 4;
+        main := cl.getX() * 10 + cl.sum();
+        END_FUNCTION
+        "
+    .into();
+
+    let (res, _) = compile_and_run(
+        source,
+        &mut MainType {
+            cl: MyClass {
+                base: MyBase { x: 0 },
+                y: 0,
+            },
+        },
+    );
+    assert_eq!(res, 37);
+}
+
+#[test]
+fn overridden_methods_are_called_through_the_method_table() {
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct Shape {
+        scale: i16,
+        method_table: usize,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct Square {
+        base: Shape,
+        side: i16,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct Cube {
+        base: Square,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MainType {
+        square: Square,
+        cube: Cube,
+    }
+
+    let source = "
+        CLASS Shape
+            VAR scale : INT; END_VAR
+
+            METHOD area : INT
+                area := 0;
+            END_METHOD
+
+            METHOD scaledArea : INT
+                scaledArea := THIS^.area() * scale;
+            END_METHOD
+        END_CLASS
+
+        CLASS Square EXTENDS Shape
+            VAR side : INT; END_VAR
+
+            METHOD OVERRIDE area : INT
+                area := side * side;
+            END_METHOD
+        END_CLASS
+
+        CLASS Cube EXTENDS Square
+            METHOD OVERRIDE area : INT
+                area := SUPER^.area() * 6;
+            END_METHOD
+        END_CLASS
+
+        FUNCTION main : DINT
+        VAR
+          square : Square;
+          cube : Cube;
+        END_VAR
+        square.scale := 1;
+        square.side := 3;
+        cube.scale := 2;
+        cube.side := 2;
+        main := square.scaledArea() * 1000 + cube.scaledArea();
+        END_FUNCTION
+        "
+    .into();
+
+    let new_square = || Square {
+        base: Shape {
+            scale: 0,
+            method_table: 0,
+        },
+        side: 0,
+    };
+    let (res, _) = compile_and_run(
+        source,
+        &mut MainType {
+            square: new_square(),
+            cube: Cube { base: new_square() },
+        },
+    );
+    assert_eq!(res, 9048);
+}
+
+#[test]
+fn properties_are_read_and_written_through_their_accessors() {
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct Motor {
+        speed: i16,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MainType {
+        motor: Motor,
+    }
+
+    let source = "
+        CLASS Motor
+            VAR _speed : INT; END_VAR
+
+            PROPERTY speed : INT
+                GET
+                    speed := _speed;
+                END_GET
+                SET
+                    IF speed > 100 THEN
+                        _speed := 100;
+                    ELSE
+                        _speed := speed;
+                    END_IF
+                END_SET
+            END_PROPERTY
+        END_CLASS
+
+        FUNCTION main : DINT
+        VAR
+          motor : Motor;
+        END_VAR
+        motor.speed := 42;
+        main := motor.speed * 1000;
+        motor.speed := 250;
+        main := main + motor.speed;
+        END_FUNCTION
+        "
+    .into();
+
+    let (res, _) = compile_and_run(
+        source,
+        &mut MainType {
+            motor: Motor { speed: 0 },
+        },
+    );
+    assert_eq!(res, 42100);
+}
+
+#[test]
+fn members_of_struct_typed_properties_can_be_read() {
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct Point {
+        x: i16,
+        y: i16,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MainType {
+        shape: Point,
+        p: Point,
+        values: [i32; 4],
+    }
+
+    let source = "
+        TYPE Point : STRUCT x, y : INT; END_STRUCT END_TYPE
+
+        CLASS Shape
+            VAR _origin : Point; END_VAR
+
+            PROPERTY origin : Point
+                GET
+                    origin := _origin;
+                END_GET
+                SET
+                    _origin := origin;
+                END_SET
+            END_PROPERTY
+        END_CLASS
+
+        FUNCTION main : DINT
+        VAR
+          shape : Shape;
+          p : Point;
+          values : ARRAY[0..3] OF DINT := [10, 20, 30, 40];
+        END_VAR
+        p.x := 2;
+        p.y := 3;
+        shape.origin := p;
+        main := shape.origin.x * 100 + values[shape.origin.y];
+        END_FUNCTION
+        "
+    .into();
+
+    let (res, _) = compile_and_run(
+        source,
+        &mut MainType {
+            shape: Point { x: 0, y: 0 },
+            p: Point { x: 0, y: 0 },
+            values: [0; 4],
+        },
+    );
+    assert_eq!(res, 240);
+}
+
+#[test]
+fn interface_method_calls_are_dispatched_to_the_referenced_instance() {
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MainType {
+        a: i16,
+        b: i16,
+        counter: [usize; 2],
+    }
+
+    let source = "
+        INTERFACE ICounter
+            METHOD inc : INT
+                VAR_INPUT by : INT; END_VAR
+            END_METHOD
+        END_INTERFACE
+
+        FUNCTION_BLOCK CounterA IMPLEMENTS ICounter
+            VAR x : INT; END_VAR
+            METHOD inc : INT
+                VAR_INPUT by : INT; END_VAR
+                x := x + by;
+                inc := x;
+            END_METHOD
+        END_FUNCTION_BLOCK
+
+        FUNCTION_BLOCK CounterB IMPLEMENTS ICounter
+            VAR y : INT; END_VAR
+            METHOD inc : INT
+                VAR_INPUT by : INT; END_VAR
+                y := y + by * 10;
+                inc := y;
+            END_METHOD
+        END_FUNCTION_BLOCK
+
+        FUNCTION main : DINT
+        VAR
+          a : CounterA;
+          b : CounterB;
+          counter : ICounter;
+        END_VAR
+        counter := a;
+        counter.inc(by := 1);
+        counter.inc(by := 2);
+        counter := b;
+        main := counter.inc(by := 4) + a.x;
+        END_FUNCTION
+        "
+    .into();
+
+    let (res, _) = compile_and_run(
+        source,
+        &mut MainType {
+            a: 0,
+            b: 0,
+            counter: [0, 0],
+        },
+    );
+    assert_eq!(res, 43);
+}
@@ -259,6 +259,133 @@ fn using_inline_enums_in_structs() {
     assert_eq!(2, data.tf3); //yellow
 }
 
+#[test]
+fn using_enums_with_values_and_numeric_types() {
+    #[repr(C)]
+    struct MainType {
+        state: u16,
+        mode: i32,
+        is_running: bool,
+        next_state: u16,
+    }
+    let mut data = MainType {
+        state: 0,
+        mode: 0,
+        is_running: false,
+        next_state: 0,
+    };
+
+    let testcode = r#"
+    TYPE State : (Idle := 1, Run := 10, Fault := 16#FF) UINT;
+    END_TYPE
+
+    TYPE Mode : (Run, Stop);
+    END_TYPE
+
+    PROGRAM main
+    VAR
+        state : State;
+        mode : Mode;
+        is_running : BOOL;
+        next_state : State;
+    END_VAR
+        state := State#Run;
+        mode := Mode#Stop;
+        is_running := state = State#Run;
+        next_state := State#Fault;
+    END_PROGRAM
+    "#;
+
+    compile_and_run(testcode.to_string(), &mut data);
+    assert_eq!(10, data.state);
+    assert_eq!(1, data.mode);
+    assert_eq!(true, data.is_running);
+    assert_eq!(255, data.next_state);
+}
+
+#[test]
+fn enums_can_be_mixed_with_other_integer_types() {
+    #[repr(C)]
+    struct MainType {
+        big: i64,
+        small: i8,
+        is_fault: bool,
+        sum: i64,
+        state: u16,
+    }
+    let mut data = MainType {
+        big: 0,
+        small: 0,
+        is_fault: false,
+        sum: 0,
+        state: 0,
+    };
+
+    let testcode = r#"
+    TYPE State : (Idle := 1, Fault := 16#FF) UINT;
+    END_TYPE
+
+    PROGRAM main
+    VAR
+        big : LINT;
+        small : SINT;
+        is_fault : BOOL;
+        sum : LINT;
+        state : State;
+    END_VAR
+        state := State#Fault;
+        big := 5000000000;
+        small := -1;
+        is_fault := state = LINT#255;
+        sum := big + state + small;
+    END_PROGRAM
+    "#;
+
+    compile_and_run(testcode.to_string(), &mut data);
+    assert_eq!(true, data.is_fault);
+    assert_eq!(5000000254, data.sum);
+}
+
+#[test]
+fn union_members_share_their_memory() {
+    #[repr(C)]
+    struct MainType {
+        data: u32,
+        low_byte: u8,
+        high_byte: u8,
+    }
+    let mut data = MainType {
+        data: 0,
+        low_byte: 0,
+        high_byte: 0,
+    };
+
+    let testcode = r#"
+    TYPE Data : UNION
+        Whole : DWORD;
+        Bytes : ARRAY[0..3] OF BYTE;
+    END_UNION
+    END_TYPE
+
+    PROGRAM main
+    VAR
+        data : Data;
+        low_byte : BYTE;
+        high_byte : BYTE;
+    END_VAR
+        data.Whole := 16#12345678;
+        low_byte := data.Bytes[0];
+        high_byte := data.Bytes[3];
+        data.Bytes[1] := 16#FF;
+    END_PROGRAM
+    "#;
+
+    compile_and_run(testcode.to_string(), &mut data);
+    assert_eq!(0x1234FF78, data.data);
+    assert_eq!(0x78, data.low_byte);
+    assert_eq!(0x12, data.high_byte);
+}
+
 #[test]
 fn using_inline_arrays_in_structs() {
     #[repr(C)]
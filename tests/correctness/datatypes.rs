@@ -728,6 +728,88 @@ fn assign_string_to_string() {
     });
 }
 
+#[test]
+fn escape_sequences_in_string_literals() {
+    #[repr(C)]
+    struct Type {
+        text: [u8; 81],
+        wide: [u16; 81],
+    }
+
+    let program = r#"
+        PROGRAM main
+        VAR
+            text : STRING;
+            wide : WSTRING;
+        END_VAR
+
+        text := '$'a$$$T$0D$0A';
+        wide := "$"$N$00e4";
+
+        END_PROGRAM
+        "#;
+
+    let mut maintype = Type {
+        text: [0; 81],
+        wide: [0; 81],
+    };
+    compile_and_run(program.to_string(), &mut maintype);
+
+    assert_eq!(&maintype.text[0..7], b"'a$\t\r\n\0");
+    assert_eq!(&maintype.wide[0..4], &[0x22, 0x0A, 0xE4, 0]);
+}
+
+#[test]
+fn characters_of_strings_can_be_read_written_and_converted() {
+    #[repr(C)]
+    struct Type {
+        text: [u8; 81],
+        wide: [u16; 81],
+        c: u8,
+        wc: u16,
+        b: u8,
+        w: u16,
+    }
+
+    let program = r#"
+        PROGRAM main
+        VAR
+            text : STRING := 'Hello';
+            wide : WSTRING := "Ωmega";
+            c : CHAR;
+            wc : WCHAR;
+            b : BYTE;
+            w : WORD;
+        END_VAR
+
+        c := text[2];
+        wc := wide[1];
+        b := c;
+        w := wc;
+        text[1] := CHAR#'J';
+        wide[2] := WCHAR#"Ω";
+
+        END_PROGRAM
+        "#;
+
+    let mut maintype = Type {
+        text: [0; 81],
+        wide: [0; 81],
+        c: 0,
+        wc: 0,
+        b: 0,
+        w: 0,
+    };
+    compile_and_run(program.to_string(), &mut maintype);
+
+    assert_eq!(b'e', maintype.c);
+    assert_eq!(0x3A9, maintype.wc);
+    assert_eq!(b'e', maintype.b);
+    assert_eq!(0x3A9, maintype.w);
+    assert_eq!(&maintype.text[0..6], b"Jello\0");
+    assert_eq!(&maintype.wide[0..3], &[0x3A9, 0x3A9, u16::from(b'e')]);
+}
+
 #[test]
 fn assign_long_string_to_short_string_variable() {
     #[repr(C)]
@@ -932,3 +1014,266 @@ fn lreal_to_int_assignment() {
     let (res, _) = compile_and_run(function.to_string(), &mut Type {});
     assert_eq!(1, res);
 }
+
+#[test]
+fn long_date_and_time_values_are_converted_to_and_from_their_short_forms() {
+    #[repr(C)]
+    struct Type {
+        long_date: i64,
+        short_date: i64,
+        long_time: i64,
+        short_time: i64,
+        long_tod: i64,
+        short_tod: i64,
+    }
+
+    let program = r#"
+        PROGRAM main
+        VAR
+            long_date : LDATE;
+            short_date : DATE;
+            long_time : LTIME;
+            short_time : TIME;
+            long_tod : LTOD;
+            short_tod : TOD;
+        END_VAR
+
+        short_date := D#1970-01-02;
+        long_date := short_date;
+
+        long_time := LT#1s2ns;
+        short_time := long_time;
+
+        long_tod := LTOD#00:00:01.002003004;
+        short_tod := long_tod;
+
+        END_PROGRAM
+        "#;
+
+    let mut maintype = Type {
+        long_date: 0,
+        short_date: 0,
+        long_time: 0,
+        short_time: 0,
+        long_tod: 0,
+        short_tod: 0,
+    };
+    compile_and_run(program.to_string(), &mut maintype);
+
+    assert_eq!(86_400_000, maintype.short_date);
+    assert_eq!(86_400_000_000_000, maintype.long_date);
+    assert_eq!(1_000_000_002, maintype.long_time);
+    assert_eq!(1_000_000_002, maintype.short_time);
+    assert_eq!(1_002_003_004, maintype.long_tod);
+    assert_eq!(1_002, maintype.short_tod);
+}
+
+#[test]
+fn date_and_time_arithmetic_results_in_the_iec_result_types() {
+    #[repr(C)]
+    struct Type {
+        elapsed: i64,
+        later: i64,
+        days: i64,
+        half: i64,
+        tripled: i64,
+        quarter: i64,
+        is_before: bool,
+    }
+
+    let program = r#"
+        PROGRAM main
+        VAR
+            elapsed : TIME;
+            later : TOD;
+            days : TIME;
+            half : TIME;
+            tripled : TIME;
+            quarter : LTIME;
+            is_before : BOOL;
+        END_VAR
+
+        elapsed := DT#1970-01-02-00:00:00 - DT#1970-01-01-00:00:00;
+        later := TOD#10:00:00 + T#1h30m;
+        days := D#1970-01-03 - D#1970-01-01;
+        half := T#3s * 0.5;
+        tripled := 3 * T#1s;
+        quarter := LT#1s / 4;
+        is_before := TOD#10:00:00 < LTOD#10:00:00.000000001;
+
+        END_PROGRAM
+        "#;
+
+    let mut maintype = Type {
+        elapsed: 0,
+        later: 0,
+        days: 0,
+        half: 0,
+        tripled: 0,
+        quarter: 0,
+        is_before: false,
+    };
+    compile_and_run(program.to_string(), &mut maintype);
+
+    assert_eq!(86_400_000_000_000, maintype.elapsed);
+    assert_eq!(41_400_000, maintype.later);
+    assert_eq!(172_800_000_000_000, maintype.days);
+    assert_eq!(1_500_000_000, maintype.half);
+    assert_eq!(3_000_000_000, maintype.tripled);
+    assert_eq!(250_000_000, maintype.quarter);
+    assert!(maintype.is_before);
+}
+
+#[test]
+fn type_conversions_round_truncate_and_scale() {
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct Type {
+        rounded: i16,
+        rounded_negative: i16,
+        truncated: i32,
+        from_bool: i16,
+        to_bool: bool,
+        millis: i32,
+        time: i64,
+        time_of_day: i64,
+    }
+
+    let program = r#"
+        PROGRAM main
+        VAR
+            rounded : INT;
+            rounded_negative : INT;
+            truncated : DINT;
+            from_bool : INT;
+            to_bool : BOOL;
+            millis : DINT;
+            time : TIME;
+            time_of_day : TOD;
+        END_VAR
+
+        rounded := REAL_TO_INT(2.5);
+        rounded_negative := LREAL_TO_INT(-2.5);
+        truncated := TRUNC(-2.7);
+        from_bool := BOOL_TO_INT(TRUE);
+        to_bool := DINT_TO_BOOL(5);
+        millis := TIME_TO_DINT(T#1s);
+        time := TO_TIME(1500);
+        time_of_day := DT_TO_TOD(DT#1970-01-02-10:00:00);
+
+        END_PROGRAM
+        "#;
+
+    let mut maintype = Type {
+        rounded: 0,
+        rounded_negative: 0,
+        truncated: 0,
+        from_bool: 0,
+        to_bool: false,
+        millis: 0,
+        time: 0,
+        time_of_day: 0,
+    };
+    compile_and_run(program.to_string(), &mut maintype);
+
+    assert_eq!(3, maintype.rounded);
+    assert_eq!(-3, maintype.rounded_negative);
+    assert_eq!(-2, maintype.truncated);
+    assert_eq!(1, maintype.from_bool);
+    assert!(maintype.to_bool);
+    assert_eq!(1000, maintype.millis);
+    assert_eq!(1_500_000_000, maintype.time);
+    assert_eq!(36_000_000, maintype.time_of_day);
+}
+
+#[test]
+fn string_conversions_write_and_read_literals() {
+    #[repr(C)]
+    struct Type {
+        time: i64,
+        date_and_time: i64,
+        real: f64,
+        int: i16,
+        to_bool: bool,
+        negative: [u8; 81],
+        half: [u8; 81],
+        big: [u8; 81],
+        flag: [u8; 81],
+        duration: [u8; 81],
+        date: [u8; 81],
+        character: [u8; 81],
+        narrowed: [u8; 81],
+        widened: [u16; 81],
+    }
+
+    let program = r#"
+        PROGRAM main
+        VAR
+            time : TIME;
+            date_and_time : DT;
+            real : LREAL;
+            int : INT;
+            to_bool : BOOL;
+            negative : STRING;
+            half : STRING;
+            big : STRING;
+            flag : STRING;
+            duration : STRING;
+            date : STRING;
+            character : STRING;
+            narrowed : STRING;
+            widened : WSTRING;
+        END_VAR
+
+        time := STRING_TO_TIME('T#1s500ms');
+        date_and_time := STRING_TO_DT('DT#1970-01-02-10:00:00');
+        real := STRING_TO_LREAL('3.25');
+        int := STRING_TO_INT(' -123');
+        to_bool := STRING_TO_BOOL('true');
+        negative := INT_TO_STRING(-42);
+        half := REAL_TO_STRING(2.5);
+        big := LREAL_TO_STRING(1.0E20);
+        flag := BOOL_TO_STRING(TRUE);
+        duration := TIME_TO_STRING(T#1h30m);
+        date := DATE_TO_STRING(D#2021-10-17);
+        character := CHAR_TO_STRING(CHAR#'x');
+        narrowed := WSTRING_TO_STRING("narrow");
+        widened := TO_WSTRING('wide');
+
+        END_PROGRAM
+        "#;
+
+    let mut maintype = Type {
+        time: 0,
+        date_and_time: 0,
+        real: 0.0,
+        int: 0,
+        to_bool: false,
+        negative: [0; 81],
+        half: [0; 81],
+        big: [0; 81],
+        flag: [0; 81],
+        duration: [0; 81],
+        date: [0; 81],
+        character: [0; 81],
+        narrowed: [0; 81],
+        widened: [0; 81],
+    };
+    compile_and_run(program.to_string(), &mut maintype);
+
+    assert_eq!(1_500_000_000, maintype.time);
+    assert_eq!(122_400_000, maintype.date_and_time);
+    assert_almost_eq!(3.25, maintype.real, f64::EPSILON);
+    assert_eq!(-123, maintype.int);
+    assert!(maintype.to_bool);
+    assert_eq!(&maintype.negative[0..4], b"-42\0");
+    assert_eq!(&maintype.half[0..4], b"2.5\0");
+    assert_eq!(&maintype.big[0..7], b"1.0E20\0");
+    assert_eq!(&maintype.flag[0..5], b"TRUE\0");
+    assert_eq!(&maintype.duration[0..8], b"T#1h30m\0");
+    assert_eq!(&maintype.date[0..13], b"D#2021-10-17\0");
+    assert_eq!(&maintype.character[0..2], b"x\0");
+    assert_eq!(&maintype.narrowed[0..7], b"narrow\0");
+    let wide: Vec<u16> = "wide\0".encode_utf16().collect();
+    assert_eq!(&maintype.widened[0..5], wide.as_slice());
+}
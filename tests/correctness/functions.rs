@@ -435,3 +435,128 @@ fn inouts_behave_like_pointers() {
     assert_eq!(8, interface.p2);
     assert_eq!(9, interface.p3);
 }
+
+#[test]
+fn function_block_methods_work_on_the_instance() {
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct CounterType {
+        step: i16,
+        count: i16,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MainType {
+        c: CounterType,
+        r: i16,
+    }
+
+    let function = r#"
+    FUNCTION_BLOCK Counter
+    VAR_INPUT
+        step : INT;
+    END_VAR
+    VAR_OUTPUT
+        count : INT;
+    END_VAR
+        METHOD PUBLIC increment : INT
+            count := count + step;
+            increment := count;
+        END_METHOD
+
+        METHOD PUBLIC incrementTwice : INT
+            increment();
+            incrementTwice := increment();
+        END_METHOD
+
+    increment();
+    END_FUNCTION_BLOCK
+
+    PROGRAM main
+    VAR
+        c : Counter;
+        r : INT;
+    END_VAR
+    c(step := 2);
+    r := c.incrementTwice() * 100 + c.increment();
+    END_PROGRAM
+    "#;
+
+    let mut interface = MainType {
+        c: CounterType { step: 0, count: 0 },
+        r: 0,
+    };
+    let (_, _) = compile_and_run(function.to_string(), &mut interface);
+
+    assert_eq!(interface.c.count, 8);
+    assert_eq!(interface.r, 608);
+}
+
+#[test]
+fn pous_in_namespaces_can_be_called_qualified_or_through_using() {
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct CounterType {
+        count: i16,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MainType {
+        c: CounterType,
+        a: i16,
+        b: i16,
+    }
+
+    let function = r#"
+    NAMESPACE Lib.Math
+        FUNCTION twice : INT
+        VAR_INPUT
+            x : INT;
+        END_VAR
+            twice := x * 2;
+        END_FUNCTION
+
+        FUNCTION fourTimes : INT
+        VAR_INPUT
+            x : INT;
+        END_VAR
+            fourTimes := twice(twice(x));
+        END_FUNCTION
+    END_NAMESPACE
+
+    NAMESPACE Lib.Counting
+        FUNCTION_BLOCK Counter
+        VAR_OUTPUT
+            count : INT;
+        END_VAR
+            count := count + 1;
+        END_FUNCTION_BLOCK
+    END_NAMESPACE
+
+    PROGRAM main
+    USING Lib.Counting;
+    VAR
+        c : Counter;
+        a : INT;
+        b : INT;
+    END_VAR
+    c();
+    c();
+    a := Lib.Math.twice(5);
+    b := Lib.Math.fourTimes(c.count);
+    END_PROGRAM
+    "#;
+
+    let mut interface = MainType {
+        c: CounterType { count: 0 },
+        a: 0,
+        b: 0,
+    };
+    let (_, _) = compile_and_run(function.to_string(), &mut interface);
+
+    assert_eq!(interface.c.count, 2);
+    assert_eq!(interface.a, 10);
+    assert_eq!(interface.b, 8);
+}
@@ -108,3 +108,46 @@ fn global_variables_with_initialization() {
         }
     );
 }
+
+#[test]
+fn external_variables_refer_to_the_global_variable() {
+    #[repr(C)]
+    struct MainType {
+        x: i16,
+        y: i16,
+    }
+
+    let function = r"
+    VAR_GLOBAL
+        gX : INT := 77;
+    END_VAR
+    PROGRAM main
+        VAR
+            x : INT;
+        END_VAR
+        VAR_EXTERNAL
+            gX : INT;
+        END_VAR
+        VAR
+            y : INT;
+        END_VAR
+        gX := gX + 1;
+        x := gX;
+        y := other();
+    END_PROGRAM
+    FUNCTION other : INT
+        VAR_EXTERNAL
+            gX : INT;
+        END_VAR
+        other := gX * 2;
+    END_FUNCTION
+    ";
+    let context = inkwell::context::Context::create();
+    let exec_engine = compile(&context, function.to_string());
+
+    let mut params = MainType { x: 0, y: 0 };
+    run(&exec_engine, "main", &mut params);
+    run(&exec_engine, "main", &mut params);
+    assert_eq!(79, params.x);
+    assert_eq!(158, params.y);
+}
@@ -0,0 +1,56 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+use super::super::*;
+use inkwell::execution_engine::JitFunction;
+
+#[test]
+fn located_variables_are_copied_from_and_to_the_process_images() {
+    let source = r"
+    VAR_GLOBAL
+        start AT %IX0.1 : BOOL;
+        value AT %IW2 : INT;
+    END_VAR
+
+    PROGRAM main
+    VAR
+        result AT %QW0 : INT;
+        running AT %QX2.3 : BOOL;
+    END_VAR
+        IF start THEN
+            result := value * 2;
+        END_IF
+        running := start;
+    END_PROGRAM
+
+    CONFIGURATION config
+        RESOURCE res
+            TASK cyclic(INTERVAL := T#10ms);
+            PROGRAM inst WITH cyclic : main;
+        END_RESOURCE
+    END_CONFIGURATION
+    ";
+
+    let context: Context = Context::create();
+    let engine = compile(&context, source.to_string());
+    unsafe {
+        let cycle: JitFunction<unsafe extern "C" fn()> =
+            engine.get_function("res.cyclic_cycle").unwrap();
+        let inputs = engine.get_function_address("__input_image").unwrap() as *mut [u8; 4];
+        let outputs = engine.get_function_address("__output_image").unwrap() as *mut [u8; 3];
+        let input_size = engine.get_function_address("__input_image_size").unwrap() as *const i32;
+        let output_size = engine.get_function_address("__output_image_size").unwrap() as *const i32;
+        assert_eq!(4, *input_size);
+        assert_eq!(3, *output_size);
+
+        //start is not set, only the running-bit is written
+        (*outputs)[2] = 0b1111_1111;
+        (*inputs)[2..4].copy_from_slice(&21i16.to_ne_bytes());
+        cycle.call();
+        assert_eq!(0, i16::from_ne_bytes([(*outputs)[0], (*outputs)[1]]));
+        assert_eq!(0b1111_0111, (*outputs)[2]);
+
+        (*inputs)[0] = 0b0000_0010;
+        cycle.call();
+        assert_eq!(42, i16::from_ne_bytes([(*outputs)[0], (*outputs)[1]]));
+        assert_eq!(0b1111_1111, (*outputs)[2]);
+    }
+}
@@ -39,3 +39,69 @@ END_FUNCTION
 
     assert_eq!(36, res);
 }
+
+#[test]
+fn pointer_arithmetic_moves_by_whole_elements() {
+    let function = r"
+FUNCTION main : DINT
+	main := foo();
+END_FUNCTION
+
+FUNCTION foo : DINT
+VAR
+				buffer : ARRAY[0..9] OF DINT;
+				first, current : POINTER TO DINT;
+				i : INT;
+END_VAR
+FOR i := 0 TO 9 DO
+	buffer[i] := i * 10;
+END_FOR
+first := &buffer[0];
+current := first + 7;
+current := current - 2;
+
+IF current > first AND current <> first THEN
+	foo := current^ * 100 + (current - first);
+END_IF
+END_FUNCTION
+ ";
+
+    let mut maintype = MainType {};
+
+    let (res, _) = compile_and_run(function.to_string(), &mut maintype);
+
+    assert_eq!(5005, res);
+}
+
+#[test]
+fn references_can_be_rebound_and_written_through() {
+    let function = r"
+FUNCTION main : DINT
+	main := foo();
+END_FUNCTION
+
+FUNCTION foo : DINT
+VAR
+				a, b : DINT;
+				r : REFERENCE TO DINT;
+				valid_before, valid_after : BOOL;
+END_VAR
+valid_before := __ISVALIDREF(r);
+r REF= a;
+r := 3;
+r REF= b;
+r := 4;
+valid_after := __ISVALIDREF(r);
+
+IF NOT valid_before AND valid_after THEN
+	foo := a * 10 + b;
+END_IF
+END_FUNCTION
+ ";
+
+    let mut maintype = MainType {};
+
+    let (res, _) = compile_and_run(function.to_string(), &mut maintype);
+
+    assert_eq!(34, res);
+}
@@ -0,0 +1,418 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+use super::super::*;
+
+#[test]
+fn selection_functions_select_one_of_their_inputs() {
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MainType {
+        minimum: i32,
+        maximum: f64,
+        limited: i16,
+        unsigned_maximum: u32,
+        selected: i32,
+        multiplexed: i32,
+        out_of_range: i32,
+        moved: i32,
+    }
+
+    let prog = "
+    PROGRAM main
+    VAR
+        minimum : DINT;
+        maximum : LREAL;
+        limited : INT;
+        unsigned_maximum : UDINT;
+        selected : DINT;
+        multiplexed : DINT;
+        out_of_range : DINT;
+        moved : DINT;
+    END_VAR
+        minimum := MIN(7, -3, 5);
+        maximum := MAX(1, LREAL#2.5, 2);
+        limited := LIMIT(0, 42, 10);
+        unsigned_maximum := MAX(UDINT#1, UDINT#4000000000);
+        selected := SEL(TRUE, 1, 2);
+        multiplexed := MUX(1, 10, 20, 30);
+        out_of_range := MUX(5, 10, 20, 30);
+        moved := MOVE(multiplexed);
+    END_PROGRAM
+    ";
+
+    let mut main = MainType {
+        minimum: 0,
+        maximum: 0.0,
+        limited: 0,
+        unsigned_maximum: 0,
+        selected: 0,
+        multiplexed: 0,
+        out_of_range: 0,
+        moved: 0,
+    };
+    compile_and_run(prog.to_string(), &mut main);
+    assert_eq!(-3, main.minimum);
+    assert_almost_eq!(2.5, main.maximum, f64::EPSILON);
+    assert_eq!(10, main.limited);
+    assert_eq!(4_000_000_000, main.unsigned_maximum);
+    assert_eq!(2, main.selected);
+    assert_eq!(20, main.multiplexed);
+    assert_eq!(30, main.out_of_range);
+    assert_eq!(20, main.moved);
+}
+
+#[test]
+fn bit_shifts_shift_and_rotate_by_the_bit_width_of_their_input() {
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MainType {
+        shifted_left: u8,
+        shifted_right: u16,
+        shifted_out: u32,
+        shifted_out_wide: u8,
+        rotated_left: u8,
+        rotated_right: u16,
+        rotated_long: u64,
+    }
+
+    let prog = "
+    PROGRAM main
+    VAR
+        shifted_left : BYTE;
+        shifted_right : WORD;
+        shifted_out : DWORD;
+        shifted_out_wide : BYTE;
+        rotated_left : BYTE;
+        rotated_right : WORD;
+        rotated_long : LWORD;
+    END_VAR
+        shifted_left := SHL(BYTE#16#81, 1);
+        shifted_right := SHR(WORD#16#8001, 4);
+        shifted_out := SHL(DWORD#1, 32);
+        shifted_out_wide := SHL(BYTE#1, 256);
+        rotated_left := ROL(BYTE#16#81, 1);
+        rotated_right := ROR(WORD#16#0001, 4);
+        rotated_long := ROL(LWORD#16#1000000000000001, 68);
+    END_PROGRAM
+    ";
+
+    let mut main = MainType {
+        shifted_left: 0,
+        shifted_right: 0,
+        shifted_out: 1,
+        shifted_out_wide: 1,
+        rotated_left: 0,
+        rotated_right: 0,
+        rotated_long: 0,
+    };
+    compile_and_run(prog.to_string(), &mut main);
+    assert_eq!(0x02, main.shifted_left);
+    assert_eq!(0x0800, main.shifted_right);
+    assert_eq!(0, main.shifted_out);
+    assert_eq!(0, main.shifted_out_wide);
+    assert_eq!(0x03, main.rotated_left);
+    assert_eq!(0x1000, main.rotated_right);
+    assert_eq!(0x0000_0000_0000_0011, main.rotated_long);
+}
+
+#[test]
+fn numeric_functions_are_calculated_without_external_declarations() {
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MainType {
+        absolute: i16,
+        absolute_real: f64,
+        root: f32,
+        natural_logarithm: f64,
+        logarithm: f64,
+        exponential: f64,
+        sine: f64,
+        cosine: f64,
+        tangent: f64,
+        arc_sine: f64,
+        arc_cosine: f64,
+        arc_tangent: f64,
+        arc_tangent2: f64,
+        rounded: f64,
+    }
+
+    let prog = "
+    PROGRAM main
+    VAR
+        absolute : INT;
+        absolute_real : LREAL;
+        root : REAL;
+        natural_logarithm : LREAL;
+        logarithm : LREAL;
+        exponential : LREAL;
+        sine : LREAL;
+        cosine : LREAL;
+        tangent : LREAL;
+        arc_sine : LREAL;
+        arc_cosine : LREAL;
+        arc_tangent : LREAL;
+        arc_tangent2 : LREAL;
+        rounded : LREAL;
+    END_VAR
+        absolute := ABS(-5);
+        absolute_real := ABS(-2.5);
+        root := SQRT(REAL#16.0);
+        natural_logarithm := LN(LREAL#1.0);
+        logarithm := LOG(LREAL#1000.0);
+        exponential := EXP(LREAL#0.0);
+        sine := SIN(LREAL#0.0);
+        cosine := COS(LREAL#0.0);
+        tangent := TAN(LREAL#0.0);
+        arc_sine := ASIN(LREAL#1.0);
+        arc_cosine := ACOS(LREAL#1.0);
+        arc_tangent := ATAN(LREAL#1.0);
+        arc_tangent2 := ATAN2(LREAL#1.0, -1.0);
+        rounded := ROUND(-2.5);
+    END_PROGRAM
+    ";
+
+    let mut main = MainType {
+        absolute: 0,
+        absolute_real: 0.0,
+        root: 0.0,
+        natural_logarithm: 1.0,
+        logarithm: 0.0,
+        exponential: 0.0,
+        sine: 1.0,
+        cosine: 0.0,
+        tangent: 1.0,
+        arc_sine: 0.0,
+        arc_cosine: 1.0,
+        arc_tangent: 0.0,
+        arc_tangent2: 0.0,
+        rounded: 0.0,
+    };
+    compile_and_run(prog.to_string(), &mut main);
+    assert_eq!(5, main.absolute);
+    assert_almost_eq!(2.5, main.absolute_real, f64::EPSILON);
+    assert_almost_eq!(4.0, main.root, f32::EPSILON);
+    assert_almost_eq!(0.0, main.natural_logarithm, f64::EPSILON);
+    assert_almost_eq!(3.0, main.logarithm, f64::EPSILON);
+    assert_almost_eq!(1.0, main.exponential, f64::EPSILON);
+    assert_almost_eq!(0.0, main.sine, f64::EPSILON);
+    assert_almost_eq!(1.0, main.cosine, f64::EPSILON);
+    assert_almost_eq!(0.0, main.tangent, f64::EPSILON);
+    assert_almost_eq!(std::f64::consts::FRAC_PI_2, main.arc_sine, f64::EPSILON);
+    assert_almost_eq!(0.0, main.arc_cosine, f64::EPSILON);
+    assert_almost_eq!(std::f64::consts::FRAC_PI_4, main.arc_tangent, f64::EPSILON);
+    assert_almost_eq!(
+        3.0 * std::f64::consts::FRAC_PI_4,
+        main.arc_tangent2,
+        f64::EPSILON
+    );
+    assert_almost_eq!(-3.0, main.rounded, f64::EPSILON);
+}
+
+#[test]
+fn trigonometric_functions_are_calculated_in_all_quadrants() {
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MainType {
+        tangent: f64,
+        arc_tangent: f64,
+        arc_tangent2: f64,
+        arc_tangent2_of_zero: f64,
+        arc_sine: f64,
+        arc_cosine: f64,
+        single_arc_sine: f32,
+        single_arc_cosine: f32,
+    }
+
+    let prog = "
+    PROGRAM main
+    VAR
+        tangent : LREAL;
+        arc_tangent : LREAL;
+        arc_tangent2 : LREAL;
+        arc_tangent2_of_zero : LREAL;
+        arc_sine : LREAL;
+        arc_cosine : LREAL;
+        single_arc_sine : REAL;
+        single_arc_cosine : REAL;
+    END_VAR
+        tangent := TAN(LREAL#0.5);
+        arc_tangent := ATAN(LREAL#-3.0);
+        arc_tangent2 := ATAN2(LREAL#-1.0, LREAL#-2.0);
+        arc_tangent2_of_zero := ATAN2(LREAL#0.0, LREAL#-2.0);
+        arc_sine := ASIN(LREAL#-1.0);
+        arc_cosine := ACOS(LREAL#-0.25);
+        single_arc_sine := ASIN(REAL#0.5);
+        single_arc_cosine := ACOS(REAL#-0.5);
+    END_PROGRAM
+    ";
+
+    let mut main = MainType {
+        tangent: 0.0,
+        arc_tangent: 0.0,
+        arc_tangent2: 0.0,
+        arc_tangent2_of_zero: 0.0,
+        arc_sine: 0.0,
+        arc_cosine: 0.0,
+        single_arc_sine: 0.0,
+        single_arc_cosine: 0.0,
+    };
+    compile_and_run(prog.to_string(), &mut main);
+    assert_almost_eq!(0.5f64.tan(), main.tangent, 2.0 * f64::EPSILON);
+    assert_almost_eq!((-3.0f64).atan(), main.arc_tangent, 2.0 * f64::EPSILON);
+    assert_almost_eq!((-1.0f64).atan2(-2.0), main.arc_tangent2, 4.0 * f64::EPSILON);
+    assert_almost_eq!(
+        std::f64::consts::PI,
+        main.arc_tangent2_of_zero,
+        f64::EPSILON
+    );
+    assert_almost_eq!(-std::f64::consts::FRAC_PI_2, main.arc_sine, f64::EPSILON);
+    assert_almost_eq!((-0.25f64).acos(), main.arc_cosine, 4.0 * f64::EPSILON);
+    assert_almost_eq!(0.5f32.asin(), main.single_arc_sine, f32::EPSILON);
+    assert_almost_eq!((-0.5f32).acos(), main.single_arc_cosine, 2.0 * f32::EPSILON);
+}
+
+#[test]
+fn string_functions_respect_the_length_of_their_target() {
+    #[repr(C)]
+    struct MainType {
+        length: i16,
+        position: i16,
+        missing: i16,
+        wide_length: i16,
+        wide: [u16; 81],
+        left: [u8; 81],
+        right: [u8; 81],
+        middle: [u8; 81],
+        joined: [u8; 81],
+        inserted: [u8; 81],
+        deleted: [u8; 81],
+        replaced: [u8; 81],
+        short: [u8; 6],
+    }
+
+    let prog = "
+    PROGRAM main
+    VAR
+        length : INT;
+        position : INT;
+        missing : INT;
+        wide_length : INT;
+        wide : WSTRING;
+        left : STRING;
+        right : STRING;
+        middle : STRING;
+        joined : STRING;
+        inserted : STRING;
+        deleted : STRING;
+        replaced : STRING;
+        short : STRING[5];
+        text : STRING;
+    END_VAR
+        text := 'Hello World';
+        length := LEN(text);
+        position := FIND(text, 'World');
+        missing := FIND(text, 'xyz');
+        left := LEFT(text, 5);
+        right := RIGHT(text, 5);
+        middle := MID(text, 3, 2);
+        joined := CONCAT('a', 'b', 'c', LEFT(text, 2));
+        inserted := INSERT('abc', 'XY', 2);
+        deleted := DELETE(text, 6, 6);
+        replaced := REPLACE(text, 'there', 5, 7);
+        short := CONCAT(text, '!');
+        wide := CONCAT(\"ab\", \"cd\");
+        wide_length := LEN(wide);
+    END_PROGRAM
+    ";
+
+    let mut main = MainType {
+        length: 0,
+        position: 0,
+        missing: -1,
+        wide_length: 0,
+        wide: [0; 81],
+        left: [0; 81],
+        right: [0; 81],
+        middle: [0; 81],
+        joined: [0; 81],
+        inserted: [0; 81],
+        deleted: [0; 81],
+        replaced: [0; 81],
+        short: [1; 6],
+    };
+    compile_and_run(prog.to_string(), &mut main);
+    let as_str = |text: &[u8]| {
+        let end = text.iter().position(|it| *it == 0).unwrap_or(text.len());
+        String::from_utf8_lossy(&text[..end]).to_string()
+    };
+    assert_eq!(11, main.length);
+    assert_eq!(7, main.position);
+    assert_eq!(0, main.missing);
+    assert_eq!("Hello", as_str(&main.left));
+    assert_eq!("World", as_str(&main.right));
+    assert_eq!("ell", as_str(&main.middle));
+    assert_eq!("abcHe", as_str(&main.joined));
+    assert_eq!("abXYc", as_str(&main.inserted));
+    assert_eq!("Hello", as_str(&main.deleted));
+    assert_eq!("Hello there", as_str(&main.replaced));
+    assert_eq!([b'H', b'e', b'l', b'l', b'o', 0], main.short);
+    assert_eq!(4, main.wide_length);
+    assert_eq!(
+        "abcd",
+        String::from_utf16_lossy(&main.wide[..main.wide_length as usize])
+    );
+    assert_eq!(0, main.wide[4]);
+}
+
+#[test]
+fn joined_strings_keep_all_characters_if_the_target_is_long_enough() {
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MainType {
+        joined: [u8; 201],
+        inserted: [u8; 201],
+        replaced: [u8; 201],
+        first: [u8; 81],
+        second: [u8; 81],
+    }
+
+    let prog = format!(
+        "
+    PROGRAM main
+    VAR
+        joined : STRING[200];
+        inserted : STRING[200];
+        replaced : STRING[200];
+        first : STRING;
+        second : STRING;
+    END_VAR
+        first := '{}';
+        second := '{}';
+        joined := CONCAT(first, second);
+        inserted := INSERT(first, second, 40);
+        replaced := REPLACE(first, second, 10, 41);
+    END_PROGRAM
+    ",
+        "a".repeat(80),
+        "b".repeat(80)
+    );
+
+    let mut main = MainType {
+        joined: [0; 201],
+        inserted: [0; 201],
+        replaced: [0; 201],
+        first: [0; 81],
+        second: [0; 81],
+    };
+    compile_and_run(prog, &mut main);
+    let as_str = |text: &[u8]| {
+        let end = text.iter().position(|it| *it == 0).unwrap_or(text.len());
+        String::from_utf8_lossy(&text[..end]).to_string()
+    };
+    let (a, b) = ("a".repeat(40), "b".repeat(80));
+    assert_eq!(format!("{}{}{}", a, a, b), as_str(&main.joined));
+    assert_eq!(format!("{}{}{}", a, b, a), as_str(&main.inserted));
+    assert_eq!(
+        format!("{}{}{}", a, b, "a".repeat(30)),
+        as_str(&main.replaced)
+    );
+}
@@ -44,3 +44,85 @@ fn real_division_in_result() {
     let (res, _) = compile_and_run(prog.to_string(), &mut MainType { ret: 0 });
     assert_eq!(res, 333)
 }
+
+#[test]
+fn exponentiations_of_integers_and_reals() {
+    #[repr(C)]
+    struct MainType {
+        i: i32,
+        r: f32,
+        l: f64,
+        e: i32,
+    }
+
+    let prog = "
+    PROGRAM main
+    VAR
+        i : DINT;
+        r : REAL;
+        l : LREAL;
+        e : DINT;
+    END_VAR
+        i := 2 ** 10;
+        r := REAL#1.5 ** 2;
+        l := EXPT(LREAL#2.0, -1) ** 0.5;
+        e := -2 ** 2 + EXPT(3, 3);
+    END_PROGRAM
+    ";
+
+    let mut main = MainType {
+        i: 0,
+        r: 0.0,
+        l: 0.0,
+        e: 0,
+    };
+    compile_and_run(prog.to_string(), &mut main);
+    assert_eq!(1024, main.i);
+    assert_almost_eq!(2.25, main.r, f32::EPSILON);
+    assert_almost_eq!(0.5_f64.sqrt(), main.l, f64::EPSILON);
+    assert_eq!(31, main.e);
+}
+
+#[test]
+fn integer_exponentiations_are_exact_up_to_the_overflow_boundary() {
+    #[repr(C)]
+    struct MainType {
+        below: i64,
+        negative: i64,
+        wrapped: i64,
+        fraction: i64,
+        unit: i64,
+    }
+
+    let prog = "
+    PROGRAM main
+    VAR
+        below : LINT;
+        negative : LINT;
+        wrapped : LINT;
+        fraction : LINT;
+        unit : LINT;
+    END_VAR
+        below := LINT#3 ** 39;
+        negative := -LINT#3 ** 39;
+        wrapped := LINT#2 ** 63;
+        fraction := LINT#2 ** -1;
+        unit := -LINT#1 ** -3;
+    END_PROGRAM
+    ";
+
+    let mut main = MainType {
+        below: 0,
+        negative: 0,
+        wrapped: 0,
+        fraction: 1,
+        unit: 0,
+    };
+    compile_and_run(prog.to_string(), &mut main);
+    //an LREAL would round 3 ** 39 to 4052555153018976256
+    assert_eq!(4052555153018976267, main.below);
+    assert_eq!(-4052555153018976267, main.negative);
+    assert_eq!(i64::MIN, main.wrapped);
+    assert_eq!(0, main.fraction);
+    assert_eq!(-1, main.unit);
+}
@@ -0,0 +1,99 @@
+// Copyright (c) 2021 Ghaith Hachem and Mathias Rieder
+use super::super::*;
+use inkwell::execution_engine::JitFunction;
+
+#[allow(dead_code)]
+#[repr(C)]
+struct MainType {
+    ret: i32,
+}
+
+#[test]
+fn task_cycles_call_their_program_instances() {
+    let source = r"
+    VAR_GLOBAL
+        counted : DINT;
+    END_VAR
+
+    PROGRAM counter
+    VAR
+        calls : DINT;
+    END_VAR
+        calls := calls + 1;
+        counted := calls;
+    END_PROGRAM
+
+    FUNCTION main : DINT
+        main := counted;
+    END_FUNCTION
+
+    CONFIGURATION config
+        RESOURCE res ON PLC
+            TASK fast(INTERVAL := T#10ms, PRIORITY := 1);
+            TASK slow(INTERVAL := T#1s, PRIORITY := 2);
+            PROGRAM inst WITH fast : counter;
+        END_RESOURCE
+    END_CONFIGURATION
+    ";
+
+    let context: Context = Context::create();
+    let engine = compile(&context, source.to_string());
+    unsafe {
+        let fast: JitFunction<unsafe extern "C" fn()> =
+            engine.get_function("res.fast_cycle").unwrap();
+        let slow: JitFunction<unsafe extern "C" fn()> =
+            engine.get_function("res.slow_cycle").unwrap();
+        fast.call();
+        fast.call();
+        slow.call();
+        fast.call();
+    }
+    let (res, _) = run(&engine, "main", &mut MainType { ret: 0 });
+    assert_eq!(res, 3);
+}
+
+#[test]
+fn program_instances_of_the_same_program_keep_their_own_state() {
+    let source = r"
+    VAR_GLOBAL
+        total : DINT;
+    END_VAR
+
+    PROGRAM counter
+    VAR
+        calls : DINT;
+    END_VAR
+        calls := calls + 1;
+        total := total + calls;
+    END_PROGRAM
+
+    FUNCTION main : DINT
+        main := total;
+    END_FUNCTION
+
+    CONFIGURATION config
+        RESOURCE res ON PLC
+            TASK fast(INTERVAL := T#10ms, PRIORITY := 1);
+            TASK slow(INTERVAL := T#1s, PRIORITY := 2);
+            PROGRAM a WITH fast : counter;
+            PROGRAM b WITH slow : counter;
+        END_RESOURCE
+    END_CONFIGURATION
+    ";
+
+    let context: Context = Context::create();
+    let engine = compile(&context, source.to_string());
+    unsafe {
+        let fast: JitFunction<unsafe extern "C" fn()> =
+            engine.get_function("res.fast_cycle").unwrap();
+        let slow: JitFunction<unsafe extern "C" fn()> =
+            engine.get_function("res.slow_cycle").unwrap();
+        fast.call();
+        fast.call();
+        fast.call();
+        slow.call();
+    }
+    //a counted 1 + 2 + 3, b starts counting at 1 again
+    let (res, _) = run(&engine, "main", &mut MainType { ret: 0 });
+    assert_eq!(res, 7);
+}
@@ -15,9 +15,12 @@ mod correctness {
     mod functions;
     mod global_variables;
     mod initial_values;
+    mod located_variables;
     mod pointers;
+    mod standard_functions;
     mod sub_range_types;
     mod sums;
+    mod tasks;
 }
 
 mod integration {